@@ -3,7 +3,7 @@
 //! gRPC server implementation for VPR.
 //!
 //! Handles:
-//! - gRPC service setup and authentication
+//! - gRPC service setup, TLS and authentication
 //! - Service implementations using `vpr-core` for data operations
 //! - gRPC-specific concerns (interceptors, tonic integration)
 //!
@@ -11,6 +11,6 @@
 
 #![warn(rust_2018_idioms)]
 
-pub use service::{auth_interceptor, pb, VprService};
+pub use service::{auth_interceptor, pb, server_tls_config, VprService};
 
 pub mod service;
@@ -13,12 +13,16 @@ use tonic::transport::Server;
 use tonic_reflection::server::Builder;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use api_grpc::{auth_interceptor, pb::vpr_server::VprServer, VprService};
+use api_grpc::{auth_interceptor, pb::vpr_server::VprServer, server_tls_config, VprService};
 use api_shared::FILE_DESCRIPTOR_SET;
+use api_shared::{policy::AccessPolicy, tls::TlsSettings};
 use std::path::Path;
 use std::sync::Arc;
-use vpr_core::config::rm_system_version_from_env_value;
-use vpr_core::CoreConfig;
+use vpr_core::config::{
+    mirror_root_from_env_value, revocation_list_from_env_value, rm_system_version_from_env_value,
+    timestamp_authority_from_env_value, trust_anchors_from_env_value,
+};
+use vpr_core::{CoreConfig, PatientService};
 
 /// Main entry point for the VPR gRPC server
 ///
@@ -29,6 +33,19 @@ use vpr_core::CoreConfig;
 /// - `VPR_ADDR`: Server address (default: "0.0.0.0:50051")
 /// - `VPR_ENABLE_REFLECTION`: Enable gRPC reflection (default: "false")
 /// - `API_KEY`: API key for authentication
+/// - `ACCESS_POLICY_PATH`: JSON file overriding the default role rules (optional)
+/// - `JWT_JWKS_PATH` / `JWT_PUBLIC_KEY_PATH`: Keys that verify bearer tokens (optional)
+/// - `JWT_ISSUER` / `JWT_AUDIENCE`: Required bearer token `iss` / `aud` claims (optional)
+/// - `VPR_TLS_CERT_PATH` / `VPR_TLS_KEY_PATH`: Server certificate and key; enables TLS (optional)
+/// - `VPR_TLS_CLIENT_CA_PATH`: CA bundle for client certificates; enables mutual TLS (optional)
+/// - `VPR_TLS_CLIENT_AUTH_REQUIRED`: Refuse clients without a certificate (default: "false")
+/// - `VPR_TRUST_ANCHORS_PATH`: CA bundle that commit signing certificates must chain to (optional)
+/// - `VPR_REVOCATION_CRL_PATH`: CRL of revoked commit signing certificates (optional)
+/// - `VPR_TSA_URL`: RFC 3161 timestamp authority that timestamps every commit (optional)
+/// - `VPR_TSA_TRUST_ANCHORS_PATH`: CA bundle that timestamp authority certificates must chain to
+///   (optional)
+/// - `VPR_MIRROR_URL`: Directory or `file://` URL of the bare repositories every commit is
+///   pushed to (optional)
 ///
 /// # Returns
 /// * `Ok(())` - If server starts and runs successfully
@@ -37,6 +54,7 @@ use vpr_core::CoreConfig;
 /// Returns an error if:
 /// - the logging/tracing configuration cannot be initialised,
 /// - the server address cannot be parsed,
+/// - the TLS configuration cannot be read or is invalid,
 /// - the gRPC server cannot be bound or started.
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -63,26 +81,78 @@ async fn main() -> anyhow::Result<()> {
         .and_then(|s| vpr_core::NonEmptyText::new(s).ok())
         .unwrap_or_else(|| vpr_core::NonEmptyText::new("vpr.dev.1").unwrap());
 
-    let cfg = Arc::new(CoreConfig::new(
+    let mut cfg = CoreConfig::new(
         patient_data_path.to_path_buf(),
         rm_system_version,
         vpr_namespace,
-    )?);
+    )?;
+    if let Some(anchors) =
+        trust_anchors_from_env_value(std::env::var("VPR_TRUST_ANCHORS_PATH").ok().as_deref())?
+    {
+        cfg = cfg.with_trust_anchors(anchors);
+    }
+    if let Some(revocations) =
+        revocation_list_from_env_value(std::env::var("VPR_REVOCATION_CRL_PATH").ok().as_deref())?
+    {
+        cfg = cfg.with_revocation_list(revocations);
+    }
+    if let Some(tsa) =
+        timestamp_authority_from_env_value(std::env::var("VPR_TSA_URL").ok().as_deref())?
+    {
+        cfg = cfg.with_timestamp_authority(tsa);
+    }
+    if let Some(anchors) =
+        trust_anchors_from_env_value(std::env::var("VPR_TSA_TRUST_ANCHORS_PATH").ok().as_deref())?
+    {
+        cfg = cfg.with_timestamp_trust_anchors(anchors);
+    }
+    if let Some(mirror_root) =
+        mirror_root_from_env_value(std::env::var("VPR_MIRROR_URL").ok().as_deref())?
+    {
+        cfg = cfg.with_mirror(mirror_root);
+    }
+    let cfg = Arc::new(cfg);
+    let policy = Arc::new(AccessPolicy::from_env().map_err(anyhow::Error::msg)?);
+    let tls = TlsSettings::from_env().map_err(anyhow::Error::msg)?;
 
     tracing_subscriber::registry()
         .with(tracing_subscriber::EnvFilter::from_default_env().add_directive("vpr=info".parse()?))
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    // Finish or discard full records whose creation was interrupted by a crash.
+    match PatientService::new(cfg.clone()).recover_interrupted_records() {
+        Ok(report) if !report.completed.is_empty() || !report.rolled_back.is_empty() => {
+            tracing::info!(
+                "Recovered interrupted records: {} completed, {} rolled back",
+                report.completed.len(),
+                report.rolled_back.len()
+            );
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to recover interrupted records: {}", e),
+    }
+
     let addr: SocketAddr = std::env::var("VPR_ADDR")
         .unwrap_or_else(|_| "0.0.0.0:50051".into())
         .parse()?;
 
     tracing::info!("-- Starting VPR gRPC on {}", addr);
 
-    let svc = VprService::new(cfg);
-    let mut server_builder =
-        Server::builder().add_service(VprServer::with_interceptor(svc, auth_interceptor));
+    let svc = VprService::new(cfg, policy);
+    let mut server = Server::builder();
+    if let Some(tls) = &tls {
+        server = server.tls_config(server_tls_config(tls))?;
+        tracing::info!(
+            "gRPC TLS enabled (client certificates: {})",
+            match (&tls.client_ca_pem, tls.client_auth_required) {
+                (None, _) => "disabled",
+                (Some(_), false) => "optional",
+                (Some(_), true) => "required",
+            }
+        );
+    }
+    let mut server_builder = server.add_service(VprServer::with_interceptor(svc, auth_interceptor));
 
     if std::env::var("VPR_ENABLE_REFLECTION").unwrap_or_else(|_| "false".to_string()) == "true" {
         let reflection_service = Builder::configure()
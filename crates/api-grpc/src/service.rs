@@ -2,7 +2,9 @@
 //!
 //! ## Purpose
 //! This module provides the gRPC implementation of the VPR API, including:
-//! - An `x-api-key` authentication interceptor.
+//! - An authentication interceptor accepting bearer tokens, client certificates or an
+//!   `x-api-key` header.
+//! - TLS configuration, including mutual TLS, for the tonic server.
 //! - The `VprService` implementation for protobuf-generated `Vpr` trait methods.
 //!
 //! ## Intended use
@@ -13,54 +15,99 @@
 // reference `api::service::pb`.
 pub use api_shared::pb;
 
-use api_shared::{auth, HealthService};
-use chrono::NaiveDate;
+use api_shared::{
+    auth::{self, api_key_identity, Principal, UserIdentity},
+    policy::{AccessPolicy, Operation, ThreadAccess},
+    tls::{CertificateIdentity, TlsSettings},
+    HealthService,
+};
+use chrono::{DateTime, NaiveDate, Utc};
 use fhir::{
     coordination_status::LifecycleState, messaging::SensitivityLevel,
-    messaging::ThreadStatus as FhirThreadStatus, AuthorRole, MessageAuthor as FhirMessageAuthor,
+    messaging::ThreadStatus as FhirThreadStatus, AuthorRole, LedgerData,
+    MessageAuthor as FhirMessageAuthor,
 };
 use std::sync::Arc;
-use tonic::{Request, Response, Status};
+use tonic::{
+    transport::{Certificate, Identity, ServerTlsConfig},
+    Request, Response, Status,
+};
 use vpr_core::{
-    repositories::clinical::ClinicalService,
+    error::{PatientError, PatientResult},
+    repositories::clinical::{ClinicalService, ListLettersQuery},
     repositories::coordination::{
-        CoordinationService, CoordinationStatusUpdate, LedgerUpdate, MessageContent,
+        CoordinationService, CoordinationStatusUpdate, Initialised as CoordinationInitialised,
+        LedgerUpdate, ListCommunicationsQuery, MessageContent,
+    },
+    repositories::demographics::{
+        patient_details_from_pb, patient_to_pb, DemographicsService,
+        Uninitialised as DemographicsUninitialised,
     },
-    repositories::demographics::{DemographicsService, Uninitialised as DemographicsUninitialised},
     Author, AuthorRegistration, CoreConfig, EmailAddress, NonEmptyText, PatientService,
     ShardableUuid, TimestampId,
 };
 
 /// Authentication interceptor for gRPC requests.
 ///
-/// This interceptor checks for the presence of an `x-api-key` header in incoming
-/// gRPC requests and validates it against the expected API key from environment
-/// variables. Requests without a valid API key are rejected with an
-/// UNAUTHENTICATED status.
+/// This interceptor accepts a bearer token in the `authorization` header, a client
+/// certificate verified during the TLS handshake, or an `x-api-key` header, validating them
+/// with the shared checks in `api_shared::auth`.
+/// The authenticated [`Principal`] is stored in the request extensions. Requests
+/// without valid credentials are rejected with an UNAUTHENTICATED status.
 ///
 /// # Arguments
 /// * `req` - The incoming gRPC request
 ///
 /// # Returns
 /// * `Ok(Request<()>)` - The request with authentication validated
-/// * `Err(Status)` - UNAUTHENTICATED status if API key is missing or invalid
+/// * `Err(Status)` - UNAUTHENTICATED status if credentials are missing or invalid
 ///
 /// # Errors
 /// Returns `UNAUTHENTICATED` if:
-/// - the `x-api-key` header is missing, or
+/// - no bearer token, client certificate or `x-api-key` header is present,
+/// - the bearer token fails verification,
+/// - the client certificate carries no professional registration, or
 /// - the provided API key does not match `API_KEY`.
 #[allow(clippy::result_large_err)]
-pub fn auth_interceptor(req: Request<()>) -> Result<Request<()>, Status> {
-    let api_key = req
-        .metadata()
-        .get("x-api-key")
-        .and_then(|v| v.to_str().ok())
-        .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-
-    auth::validate_api_key(api_key)?;
+pub fn auth_interceptor(mut req: Request<()>) -> Result<Request<()>, Status> {
+    let principal = authenticate(&req)?;
+    req.extensions_mut().insert(principal);
     Ok(req)
 }
 
+/// Authenticates a request from its `authorization` or `x-api-key` metadata, or the client
+/// certificate presented over mutual TLS.
+#[allow(clippy::result_large_err)]
+fn authenticate<T>(req: &Request<T>) -> Result<Principal, Status> {
+    let header = |name: &str| req.metadata().get(name).and_then(|v| v.to_str().ok());
+    let peer_certs = req.peer_certs();
+    let client_certificate = peer_certs
+        .as_deref()
+        .and_then(|certs| certs.first())
+        .map(|cert| cert.as_ref());
+    auth::authenticate(
+        header("authorization"),
+        client_certificate,
+        header("x-api-key"),
+    )
+}
+
+/// Builds the tonic TLS configuration for the server.
+///
+/// When a client CA bundle is configured, client certificates are verified against it during
+/// the handshake. Unless client authentication is required, clients may connect without a
+/// certificate and authenticate with a bearer token or API key instead.
+pub fn server_tls_config(settings: &TlsSettings) -> ServerTlsConfig {
+    let config =
+        ServerTlsConfig::new().identity(Identity::from_pem(&settings.cert_pem, &settings.key_pem));
+    match &settings.client_ca_pem {
+        Some(ca_pem) => config
+            .client_ca_root(Certificate::from_pem(ca_pem))
+            .client_auth_optional(!settings.client_auth_required),
+        None => config,
+    }
+}
+
 // Use the shared api-shared crate for generated protobuf types.
 use api_shared::pb::{vpr_server::Vpr, CreatePatientReq, CreatePatientRes, HealthRes};
 
@@ -73,16 +120,39 @@ use api_shared::pb::{vpr_server::Vpr, CreatePatientReq, CreatePatientRes, Health
 #[derive(Clone)]
 pub struct VprService {
     cfg: Arc<CoreConfig>,
+    policy: Arc<AccessPolicy>,
     demographics_service: DemographicsService<DemographicsUninitialised>,
 }
 
 impl VprService {
-    pub fn new(cfg: Arc<CoreConfig>) -> Self {
+    pub fn new(cfg: Arc<CoreConfig>, policy: Arc<AccessPolicy>) -> Self {
         Self {
             demographics_service: DemographicsService::new(cfg.clone()),
             cfg,
+            policy,
         }
     }
+
+    /// Checks the caller may access a thread, using the thread's current ledger.
+    ///
+    /// Returns the commit the ledger was read at. Writes pin themselves to it as their expected
+    /// commit, so a ledger change that lands between this check and the write (removing the
+    /// caller, say) makes the write fail instead of being bypassed.
+    #[allow(clippy::result_large_err)]
+    fn authorize_thread(
+        &self,
+        principal: &Principal,
+        coordination_service: &CoordinationService<CoordinationInitialised>,
+        thread_id: &TimestampId,
+        access: ThreadAccess,
+    ) -> Result<String, Status> {
+        let communication = coordination_service
+            .read_communication(thread_id)
+            .map_err(|e| Status::internal(format!("Failed to read communication: {}", e)))?;
+        self.policy
+            .authorize_thread(principal, &communication.ledger, access)?;
+        Ok(communication.commit_id)
+    }
 }
 
 #[tonic::async_trait]
@@ -105,58 +175,40 @@ impl Vpr for VprService {
 
     /// Creates a new patient record via gRPC
     ///
-    /// This endpoint requires authentication via the `x-api-key` header.
-    /// It validates the API key, then delegates to the PatientService to
+    /// This endpoint requires authentication via a bearer token or the `x-api-key` header.
+    /// It validates the credentials, then delegates to the PatientService to
     /// create and store the patient record.
     ///
     /// # Arguments
-    /// * `req` - CreatePatientReq containing first_name, last_name, author_name, and author_email
+    /// * `req` - CreatePatientReq containing first_name, last_name, author_name, and author_email.
+    ///   The author fields are ignored for bearer-token callers.
     ///
     /// # Required Headers
+    /// * `authorization` - `Bearer <jwt>`, or
     /// * `x-api-key` - Valid API key for authentication
     ///
     /// # Returns
     /// * `Ok(Response<CreatePatientRes>)` - Patient creation result with ID and metadata
-    /// * `Err(Status)` - UNAUTHENTICATED if API key invalid, INTERNAL_ERROR for other failures
+    /// * `Err(Status)` - UNAUTHENTICATED if credentials invalid, INTERNAL_ERROR for other failures
     async fn create_patient(
         &self,
         req: Request<CreatePatientReq>,
     ) -> Result<Response<CreatePatientRes>, Status> {
-        // Check API key
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy.authorize(&principal, Operation::CreateRecord)?;
 
         let req = req.into_inner();
 
-        let registrations: Vec<AuthorRegistration> = req
-            .author_registrations
-            .into_iter()
-            .map(|r| AuthorRegistration::new(r.authority, r.number).expect("valid registration"))
-            .collect();
-
-        let author = Author {
-            name: NonEmptyText::new(&req.author_name)
-                .map_err(|_| Status::invalid_argument("Invalid author name"))?,
-            role: NonEmptyText::new(&req.author_role)
-                .map_err(|_| Status::invalid_argument("Invalid author role"))?,
-            email: EmailAddress::parse(&req.author_email)
-                .map_err(|_| Status::invalid_argument("Invalid author email"))?,
-            registrations,
-            signature: if req.author_signature.is_empty() {
-                None
-            } else {
-                Some(req.author_signature.into_bytes())
-            },
-            certificate: None,
-        };
+        let author = build_author(
+            &principal,
+            req.author_email,
+            req.author_role,
+            req.author_signature,
+        )?;
         let care_location = NonEmptyText::new(&req.care_location)
             .map_err(|e| Status::invalid_argument(format!("Invalid care_location: {}", e)))?;
         let clinical_service = ClinicalService::new(self.cfg.clone());
-        match clinical_service.initialise(author, care_location) {
+        match blocking_write(move || clinical_service.initialise(author, care_location)).await? {
             Ok(service) => {
                 let resp = pb::CreatePatientRes {
                     filename: "".to_string(), // No filename for initialise
@@ -166,20 +218,18 @@ impl Vpr for VprService {
                         last_name: "".to_string(),
                         created_at: "".to_string(), // Could set to now, but empty for now
                         national_id: "".to_string(),
+                        ..Default::default()
                     }),
                 };
                 Ok(Response::new(resp))
             }
-            Err(e) => Err(Status::internal(format!(
-                "Failed to initialise clinical: {}",
-                e
-            ))),
+            Err(e) => Err(write_error("Failed to initialise clinical", e)),
         }
     }
 
     /// Lists all patient records via gRPC
     ///
-    /// This endpoint requires authentication via the `x-api-key` header.
+    /// This endpoint requires authentication via a bearer token or the `x-api-key` header.
     /// It retrieves all patient records from the file system and returns them.
     ///
     /// # Arguments
@@ -187,18 +237,13 @@ impl Vpr for VprService {
     ///
     /// # Returns
     /// * `Ok(Response<ListPatientsRes>)` - List of all patient records
-    /// * `Err(Status)` - UNAUTHENTICATED if API key invalid
+    /// * `Err(Status)` - UNAUTHENTICATED if credentials invalid
     async fn list_patients(
         &self,
         req: Request<()>,
     ) -> Result<Response<pb::ListPatientsRes>, Status> {
-        // Check API key
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy.authorize(&principal, Operation::ListPatients)?;
 
         let patients = self.demographics_service.list_patients();
         Ok(Response::new(pb::ListPatientsRes { patients }))
@@ -208,19 +253,14 @@ impl Vpr for VprService {
         &self,
         req: Request<pb::InitialiseFullRecordReq>,
     ) -> Result<Response<pb::InitialiseFullRecordRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy.authorize(&principal, Operation::CreateRecord)?;
 
         let req = req.into_inner();
         let author = build_author(
-            req.author_name,
+            &principal,
             req.author_email,
             req.author_role,
-            req.author_registrations,
             req.author_signature,
         )?;
 
@@ -253,23 +293,24 @@ impl Vpr for VprService {
             )
         };
 
-        match patient_service.initialise_full_record(
-            author,
-            care_location,
-            given_names,
-            last_name,
-            birth_date,
-            namespace,
-        ) {
+        match blocking_write(move || {
+            patient_service.initialise_full_record(
+                author,
+                care_location,
+                given_names,
+                last_name,
+                birth_date,
+                namespace,
+            )
+        })
+        .await?
+        {
             Ok(record) => Ok(Response::new(pb::InitialiseFullRecordRes {
                 demographics_uuid: record.demographics_uuid.to_string(),
                 clinical_uuid: record.clinical_uuid.to_string(),
                 coordination_uuid: record.coordination_uuid.to_string(),
             })),
-            Err(e) => Err(Status::internal(format!(
-                "Failed to initialise full record: {}",
-                e
-            ))),
+            Err(e) => Err(write_error("Failed to initialise full record", e)),
         }
     }
 
@@ -277,19 +318,14 @@ impl Vpr for VprService {
         &self,
         req: Request<pb::InitialiseDemographicsReq>,
     ) -> Result<Response<pb::InitialiseDemographicsRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy.authorize(&principal, Operation::CreateRecord)?;
 
         let req = req.into_inner();
         let author = build_author(
-            req.author_name,
+            &principal,
             req.author_email,
             req.author_role,
-            req.author_registrations,
             req.author_signature,
         )?;
 
@@ -297,12 +333,35 @@ impl Vpr for VprService {
             .map_err(|e| Status::invalid_argument(format!("Invalid care_location: {}", e)))?;
 
         let demographics_service = DemographicsService::new(self.cfg.clone());
-        match demographics_service.initialise(author, care_location) {
+        match blocking_write(move || demographics_service.initialise(author, care_location)).await?
+        {
             Ok(service) => Ok(Response::new(pb::InitialiseDemographicsRes {
                 demographics_uuid: service.demographics_id().to_string(),
             })),
+            Err(e) => Err(write_error("Failed to initialise demographics", e)),
+        }
+    }
+
+    async fn read_demographics(
+        &self,
+        req: Request<pb::ReadDemographicsReq>,
+    ) -> Result<Response<pb::ReadDemographicsRes>, Status> {
+        let principal = authenticate(&req)?;
+        self.policy.authorize(&principal, Operation::ListPatients)?;
+
+        let req = req.into_inner();
+        let demographics_service =
+            DemographicsService::with_id(self.cfg.clone(), &req.demographics_uuid).map_err(
+                |e| Status::invalid_argument(format!("Invalid demographics UUID: {}", e)),
+            )?;
+
+        match demographics_service.read() {
+            Ok(result) => Ok(Response::new(pb::ReadDemographicsRes {
+                patient: Some(patient_to_pb(&result.patient)),
+                commit_id: result.commit_id,
+            })),
             Err(e) => Err(Status::internal(format!(
-                "Failed to initialise demographics: {}",
+                "Failed to read demographics: {}",
                 e
             ))),
         }
@@ -312,18 +371,28 @@ impl Vpr for VprService {
         &self,
         req: Request<pb::UpdateDemographicsReq>,
     ) -> Result<Response<pb::UpdateDemographicsRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy
+            .authorize(&principal, Operation::UpdateDemographics)?;
 
         let req = req.into_inner();
+        let author = build_author(
+            &principal,
+            req.author_email,
+            req.author_role,
+            req.author_signature,
+        )?;
+
+        let care_location = NonEmptyText::new(&req.care_location)
+            .map_err(|e| Status::invalid_argument(format!("Invalid care_location: {}", e)))?;
+
         let demographics_service =
-            DemographicsService::with_id(self.cfg.clone(), &req.demographics_uuid).map_err(
-                |e| Status::invalid_argument(format!("Invalid demographics UUID: {}", e)),
-            )?;
+            DemographicsService::with_id(self.cfg.clone(), &req.demographics_uuid)
+                .map_err(|e| Status::invalid_argument(format!("Invalid demographics UUID: {}", e)))?
+                .with_expected_commit(expected_commit(&req.expected_commit_id))
+                .map_err(|e| {
+                    Status::invalid_argument(format!("Invalid expected_commit_id: {}", e))
+                })?;
 
         let given_names: Vec<NonEmptyText> = req
             .given_names
@@ -342,12 +411,60 @@ impl Vpr for VprService {
 
         let birth_date_str = birth_date.format("%Y-%m-%d").to_string();
 
-        match demographics_service.update(given_names, last_name.as_str(), &birth_date_str) {
+        match blocking_write(move || {
+            demographics_service.update(
+                &author,
+                care_location,
+                given_names,
+                last_name.as_str(),
+                &birth_date_str,
+            )
+        })
+        .await?
+        {
             Ok(()) => Ok(Response::new(pb::UpdateDemographicsRes { success: true })),
-            Err(e) => Err(Status::internal(format!(
-                "Failed to update demographics: {}",
-                e
-            ))),
+            Err(e) => Err(write_error("Failed to update demographics", e)),
+        }
+    }
+
+    async fn update_patient_details(
+        &self,
+        req: Request<pb::UpdatePatientDetailsReq>,
+    ) -> Result<Response<pb::UpdatePatientDetailsRes>, Status> {
+        let principal = authenticate(&req)?;
+        self.policy
+            .authorize(&principal, Operation::UpdateDemographics)?;
+
+        let req = req.into_inner();
+        let author = build_author(
+            &principal,
+            req.author_email,
+            req.author_role,
+            req.author_signature,
+        )?;
+
+        let care_location = NonEmptyText::new(&req.care_location)
+            .map_err(|e| Status::invalid_argument(format!("Invalid care_location: {}", e)))?;
+
+        let demographics_service =
+            DemographicsService::with_id(self.cfg.clone(), &req.demographics_uuid)
+                .map_err(|e| Status::invalid_argument(format!("Invalid demographics UUID: {}", e)))?
+                .with_expected_commit(expected_commit(&req.expected_commit_id))
+                .map_err(|e| {
+                    Status::invalid_argument(format!("Invalid expected_commit_id: {}", e))
+                })?;
+
+        let details =
+            patient_details_from_pb(req.identifiers, &req.gender, req.telecom, req.addresses)
+                .map_err(|e| Status::invalid_argument(format!("Invalid patient details: {}", e)))?;
+
+        match blocking_write(move || {
+            demographics_service.update_details(&author, care_location, details)
+        })
+        .await?
+        {
+            Ok(()) => Ok(Response::new(pb::UpdatePatientDetailsRes { success: true })),
+            Err(e) => Err(write_error("Failed to update patient details", e)),
         }
     }
 
@@ -355,19 +472,14 @@ impl Vpr for VprService {
         &self,
         req: Request<pb::InitialiseClinicalReq>,
     ) -> Result<Response<pb::InitialiseClinicalRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy.authorize(&principal, Operation::CreateRecord)?;
 
         let req = req.into_inner();
         let author = build_author(
-            req.author_name,
+            &principal,
             req.author_email,
             req.author_role,
-            req.author_registrations,
             req.author_signature,
         )?;
 
@@ -375,14 +487,11 @@ impl Vpr for VprService {
             .map_err(|e| Status::invalid_argument(format!("Invalid care_location: {}", e)))?;
 
         let clinical_service = ClinicalService::new(self.cfg.clone());
-        match clinical_service.initialise(author, care_location) {
+        match blocking_write(move || clinical_service.initialise(author, care_location)).await? {
             Ok(service) => Ok(Response::new(pb::InitialiseClinicalRes {
                 clinical_uuid: service.clinical_id().simple().to_string(),
             })),
-            Err(e) => Err(Status::internal(format!(
-                "Failed to initialise clinical: {}",
-                e
-            ))),
+            Err(e) => Err(write_error("Failed to initialise clinical", e)),
         }
     }
 
@@ -390,19 +499,15 @@ impl Vpr for VprService {
         &self,
         req: Request<pb::LinkToDemographicsReq>,
     ) -> Result<Response<pb::LinkToDemographicsRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy
+            .authorize(&principal, Operation::LinkToDemographics)?;
 
         let req = req.into_inner();
         let author = build_author(
-            req.author_name,
+            &principal,
             req.author_email,
             req.author_role,
-            req.author_registrations,
             req.author_signature,
         )?;
 
@@ -413,7 +518,9 @@ impl Vpr for VprService {
         let care_location = NonEmptyText::new(&req.care_location)
             .map_err(|e| Status::invalid_argument(format!("Invalid care_location: {}", e)))?;
 
-        let clinical_service = ClinicalService::with_id(self.cfg.clone(), clinical_uuid);
+        let clinical_service = ClinicalService::with_id(self.cfg.clone(), clinical_uuid)
+            .with_expected_commit(expected_commit(&req.expected_commit_id))
+            .map_err(|e| Status::invalid_argument(format!("Invalid expected_commit_id: {}", e)))?;
 
         let namespace = if req.namespace.is_empty() {
             None
@@ -424,17 +531,18 @@ impl Vpr for VprService {
             )
         };
 
-        match clinical_service.link_to_demographics(
-            &author,
-            care_location,
-            &req.demographics_uuid,
-            namespace,
-        ) {
+        match blocking_write(move || {
+            clinical_service.link_to_demographics(
+                &author,
+                care_location,
+                &req.demographics_uuid,
+                namespace,
+            )
+        })
+        .await?
+        {
             Ok(()) => Ok(Response::new(pb::LinkToDemographicsRes { success: true })),
-            Err(e) => Err(Status::internal(format!(
-                "Failed to link to demographics: {}",
-                e
-            ))),
+            Err(e) => Err(write_error("Failed to link to demographics", e)),
         }
     }
 
@@ -442,19 +550,14 @@ impl Vpr for VprService {
         &self,
         req: Request<pb::NewLetterReq>,
     ) -> Result<Response<pb::NewLetterRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy.authorize(&principal, Operation::WriteLetter)?;
 
         let req = req.into_inner();
         let author = build_author(
-            req.author_name,
+            &principal,
             req.author_email,
             req.author_role,
-            req.author_registrations,
             req.author_signature,
         )?;
 
@@ -465,16 +568,22 @@ impl Vpr for VprService {
         let care_location = NonEmptyText::new(&req.care_location)
             .map_err(|e| Status::invalid_argument(format!("Invalid care_location: {}", e)))?;
 
-        let clinical_service = ClinicalService::with_id(self.cfg.clone(), clinical_uuid);
+        let clinical_service = ClinicalService::with_id(self.cfg.clone(), clinical_uuid)
+            .with_expected_commit(expected_commit(&req.expected_commit_id))
+            .map_err(|e| Status::invalid_argument(format!("Invalid expected_commit_id: {}", e)))?;
 
         let content = NonEmptyText::new(req.content)
             .map_err(|e| Status::invalid_argument(format!("Invalid content: {}", e)))?;
 
-        match clinical_service.new_letter(&author, care_location, content, None) {
+        match blocking_write(move || {
+            clinical_service.new_letter(&author, care_location, content, None)
+        })
+        .await?
+        {
             Ok(timestamp_id) => Ok(Response::new(pb::NewLetterRes {
                 timestamp_id: timestamp_id.to_string(),
             })),
-            Err(e) => Err(Status::internal(format!("Failed to create letter: {}", e))),
+            Err(e) => Err(write_error("Failed to create letter", e)),
         }
     }
 
@@ -482,12 +591,8 @@ impl Vpr for VprService {
         &self,
         req: Request<pb::ReadLetterReq>,
     ) -> Result<Response<pb::ReadLetterRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy.authorize(&principal, Operation::ReadLetter)?;
 
         let req = req.into_inner();
         let clinical_uuid = ShardableUuid::parse(&req.clinical_uuid)
@@ -511,28 +616,82 @@ impl Vpr for VprService {
                         kind: list.kind,
                     })
                     .collect(),
+                letter_timestamp_id: result.letter_data.uid.to_string(),
+                versions: result.versions.iter().map(|v| v.to_string()).collect(),
+                commit_id: result.commit_id,
             })),
             Err(e) => Err(Status::internal(format!("Failed to read letter: {}", e))),
         }
     }
 
+    async fn list_letters(
+        &self,
+        req: Request<pb::ListLettersReq>,
+    ) -> Result<Response<pb::ListLettersRes>, Status> {
+        let principal = authenticate(&req)?;
+        self.policy.authorize(&principal, Operation::ReadLetter)?;
+
+        let req = req.into_inner();
+        let clinical_uuid = ShardableUuid::parse(&req.clinical_uuid)
+            .map_err(|e| Status::invalid_argument(format!("Invalid clinical UUID: {}", e)))?
+            .uuid();
+
+        let query = ListLettersQuery {
+            cursor: if req.cursor.is_empty() {
+                None
+            } else {
+                Some(
+                    req.cursor
+                        .parse::<TimestampId>()
+                        .map_err(|e| Status::invalid_argument(format!("Invalid cursor: {}", e)))?,
+                )
+            },
+            limit: (req.limit > 0).then_some(req.limit as usize),
+            from: parse_optional_rfc3339("from", &req.from)?,
+            to: parse_optional_rfc3339("to", &req.to)?,
+        };
+
+        let clinical_service = ClinicalService::with_id(self.cfg.clone(), clinical_uuid);
+        match clinical_service.list_letters(&query) {
+            Ok(page) => Ok(Response::new(pb::ListLettersRes {
+                letters: page
+                    .letters
+                    .into_iter()
+                    .map(|letter| pb::LetterSummary {
+                        letter_timestamp_id: letter.uid.to_string(),
+                        composer_name: letter.composer_name,
+                        composer_role: letter.composer_role,
+                        start_time: letter.start_time.to_rfc3339(),
+                        has_body: letter.has_body,
+                        attachment_count: letter.attachment_count as u32,
+                        superseded_by: letter
+                            .superseded_by
+                            .map(|id| id.to_string())
+                            .unwrap_or_default(),
+                    })
+                    .collect(),
+                next_cursor: page
+                    .next_cursor
+                    .map(|id| id.to_string())
+                    .unwrap_or_default(),
+            })),
+            Err(PatientError::InvalidInput(msg)) => Err(Status::invalid_argument(msg)),
+            Err(e) => Err(Status::internal(format!("Failed to list letters: {}", e))),
+        }
+    }
+
     async fn new_letter_with_attachments(
         &self,
         req: Request<pb::NewLetterWithAttachmentsReq>,
     ) -> Result<Response<pb::NewLetterWithAttachmentsRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy.authorize(&principal, Operation::WriteLetter)?;
 
         let req = req.into_inner();
         let author = build_author(
-            req.author_name,
+            &principal,
             req.author_email,
             req.author_role,
-            req.author_registrations,
             req.author_signature,
         )?;
 
@@ -562,25 +721,27 @@ impl Vpr for VprService {
         let care_location = NonEmptyText::new(&req.care_location)
             .map_err(|e| Status::invalid_argument(format!("Invalid care_location: {}", e)))?;
 
-        let clinical_service = ClinicalService::with_id(self.cfg.clone(), clinical_uuid);
-        let result = clinical_service.new_letter_with_attachments(
-            &author,
-            care_location,
-            &attachment_paths,
-            None,
-        );
+        let clinical_service = ClinicalService::with_id(self.cfg.clone(), clinical_uuid)
+            .with_expected_commit(expected_commit(&req.expected_commit_id))
+            .map_err(|e| Status::invalid_argument(format!("Invalid expected_commit_id: {}", e)))?;
+        let result = blocking_write(move || {
+            clinical_service.new_letter_with_attachments(
+                &author,
+                care_location,
+                &attachment_paths,
+                None,
+            )
+        })
+        .await;
 
         // Clean up temp files
         let _ = std::fs::remove_dir_all(&temp_dir);
 
-        match result {
+        match result? {
             Ok(timestamp_id) => Ok(Response::new(pb::NewLetterWithAttachmentsRes {
                 timestamp_id: timestamp_id.to_string(),
             })),
-            Err(e) => Err(Status::internal(format!(
-                "Failed to create letter with attachments: {}",
-                e
-            ))),
+            Err(e) => Err(write_error("Failed to create letter with attachments", e)),
         }
     }
 
@@ -588,19 +749,14 @@ impl Vpr for VprService {
         &self,
         req: Request<pb::NewLetterCompleteReq>,
     ) -> Result<Response<pb::NewLetterCompleteRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy.authorize(&principal, Operation::WriteLetter)?;
 
         let req = req.into_inner();
         let author = build_author(
-            req.author_name,
+            &principal,
             req.author_email,
             req.author_role,
-            req.author_registrations,
             req.author_signature,
         )?;
 
@@ -630,30 +786,32 @@ impl Vpr for VprService {
         let care_location = NonEmptyText::new(&req.care_location)
             .map_err(|e| Status::invalid_argument(format!("Invalid care_location: {}", e)))?;
 
-        let clinical_service = ClinicalService::with_id(self.cfg.clone(), clinical_uuid);
+        let clinical_service = ClinicalService::with_id(self.cfg.clone(), clinical_uuid)
+            .with_expected_commit(expected_commit(&req.expected_commit_id))
+            .map_err(|e| Status::invalid_argument(format!("Invalid expected_commit_id: {}", e)))?;
 
         let content = NonEmptyText::new(req.content)
             .map_err(|e| Status::invalid_argument(format!("Invalid content: {}", e)))?;
 
-        let result = clinical_service.create_letter(
-            &author,
-            care_location,
-            Some(content),
-            &attachment_paths,
-            None,
-        );
+        let result = blocking_write(move || {
+            clinical_service.create_letter(
+                &author,
+                care_location,
+                Some(content),
+                &attachment_paths,
+                None,
+            )
+        })
+        .await;
 
         // Clean up temp files
         let _ = std::fs::remove_dir_all(&temp_dir);
 
-        match result {
+        match result? {
             Ok(timestamp_id) => Ok(Response::new(pb::NewLetterCompleteRes {
                 timestamp_id: timestamp_id.to_string(),
             })),
-            Err(e) => Err(Status::internal(format!(
-                "Failed to create complete letter: {}",
-                e
-            ))),
+            Err(e) => Err(write_error("Failed to create complete letter", e)),
         }
     }
 
@@ -661,12 +819,8 @@ impl Vpr for VprService {
         &self,
         req: Request<pb::GetLetterAttachmentsReq>,
     ) -> Result<Response<pb::GetLetterAttachmentsRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy.authorize(&principal, Operation::ReadLetter)?;
 
         let req = req.into_inner();
         let clinical_uuid = ShardableUuid::parse(&req.clinical_uuid)
@@ -706,19 +860,14 @@ impl Vpr for VprService {
         &self,
         req: Request<pb::InitialiseCoordinationReq>,
     ) -> Result<Response<pb::InitialiseCoordinationRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy.authorize(&principal, Operation::CreateRecord)?;
 
         let req = req.into_inner();
         let author = build_author(
-            req.author_name,
+            &principal,
             req.author_email,
             req.author_role,
-            req.author_registrations,
             req.author_signature,
         )?;
 
@@ -730,14 +879,15 @@ impl Vpr for VprService {
         let care_location = NonEmptyText::new(req.care_location)
             .map_err(|e| Status::invalid_argument(format!("Invalid care_location: {}", e)))?;
 
-        match coordination_service.initialise(author, care_location, clinical_uuid) {
+        match blocking_write(move || {
+            coordination_service.initialise(author, care_location, clinical_uuid)
+        })
+        .await?
+        {
             Ok(service) => Ok(Response::new(pb::InitialiseCoordinationRes {
                 coordination_uuid: service.coordination_id().to_string(),
             })),
-            Err(e) => Err(Status::internal(format!(
-                "Failed to initialise coordination: {}",
-                e
-            ))),
+            Err(e) => Err(write_error("Failed to initialise coordination", e)),
         }
     }
 
@@ -745,19 +895,14 @@ impl Vpr for VprService {
         &self,
         req: Request<pb::CreateThreadReq>,
     ) -> Result<Response<pb::CreateThreadRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy.authorize(&principal, Operation::CreateThread)?;
 
         let req = req.into_inner();
         let author = build_author(
-            req.author_name,
+            &principal,
             req.author_email,
             req.author_role,
-            req.author_registrations,
             req.author_signature,
         )?;
 
@@ -781,22 +926,12 @@ impl Vpr for VprService {
             })
             .collect::<Result<Vec<_>, Status>>()?;
 
-        let initial_message_author = req
-            .initial_message_author
-            .ok_or_else(|| Status::invalid_argument("Missing initial message author"))?;
-
         let initial_message_body = NonEmptyText::new(req.initial_message_body).map_err(|e| {
             Status::invalid_argument(format!("Invalid initial message body: {}", e))
         })?;
 
         let message = MessageContent::new(
-            FhirMessageAuthor {
-                id: uuid::Uuid::parse_str(&initial_message_author.id)
-                    .map_err(|e| Status::invalid_argument(format!("Invalid author UUID: {}", e)))?,
-                name: NonEmptyText::new(initial_message_author.name)
-                    .map_err(|e| Status::invalid_argument(format!("Invalid author name: {}", e)))?,
-                role: parse_author_role(&initial_message_author.role)?,
-            },
+            message_author(&principal, req.initial_message_author)?,
             initial_message_body,
             None,
         )
@@ -806,17 +941,20 @@ impl Vpr for VprService {
             .map_err(|e| Status::invalid_argument(format!("Invalid care_location: {}", e)))?;
 
         let coordination_service =
-            CoordinationService::with_id(self.cfg.clone(), coordination_uuid);
-        match coordination_service.communication_create(
-            &author,
-            care_location,
-            participants,
-            message,
-        ) {
+            CoordinationService::with_id(self.cfg.clone(), coordination_uuid)
+                .with_expected_commit(expected_commit(&req.expected_commit_id))
+                .map_err(|e| {
+                    Status::invalid_argument(format!("Invalid expected_commit_id: {}", e))
+                })?;
+        match blocking_write(move || {
+            coordination_service.communication_create(&author, care_location, participants, message)
+        })
+        .await?
+        {
             Ok(thread_id) => Ok(Response::new(pb::CreateThreadRes {
                 thread_id: thread_id.to_string(),
             })),
-            Err(e) => Err(Status::internal(format!("Failed to create thread: {}", e))),
+            Err(e) => Err(write_error("Failed to create thread", e)),
         }
     }
 
@@ -824,19 +962,14 @@ impl Vpr for VprService {
         &self,
         req: Request<pb::AddMessageReq>,
     ) -> Result<Response<pb::AddMessageRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy.authorize(&principal, Operation::PostMessage)?;
 
         let req = req.into_inner();
         let author = build_author(
-            req.author_name,
+            &principal,
             req.author_email,
             req.author_role,
-            req.author_registrations,
             req.author_signature,
         )?;
 
@@ -849,10 +982,6 @@ impl Vpr for VprService {
             .parse()
             .map_err(|e| Status::invalid_argument(format!("Invalid thread ID: {}", e)))?;
 
-        let message_author = req
-            .message_author
-            .ok_or_else(|| Status::invalid_argument("Missing message author"))?;
-
         let corrects =
             if req.corrects.is_empty() {
                 None
@@ -866,13 +995,7 @@ impl Vpr for VprService {
             .map_err(|e| Status::invalid_argument(format!("Invalid message body: {}", e)))?;
 
         let message = MessageContent::new(
-            FhirMessageAuthor {
-                id: uuid::Uuid::parse_str(&message_author.id)
-                    .map_err(|e| Status::invalid_argument(format!("Invalid author UUID: {}", e)))?,
-                name: NonEmptyText::new(message_author.name)
-                    .map_err(|e| Status::invalid_argument(format!("Invalid author name: {}", e)))?,
-                role: parse_author_role(&message_author.role)?,
-            },
+            message_author(&principal, req.message_author)?,
             message_body,
             corrects,
         )
@@ -883,11 +1006,26 @@ impl Vpr for VprService {
 
         let coordination_service =
             CoordinationService::with_id(self.cfg.clone(), coordination_uuid);
-        match coordination_service.message_add(&author, care_location, &thread_id, message) {
+        let ledger_commit = self.authorize_thread(
+            &principal,
+            &coordination_service,
+            &thread_id,
+            ThreadAccess::Write,
+        )?;
+        let coordination_service = coordination_service
+            .with_expected_commit(Some(
+                expected_commit(&req.expected_commit_id).unwrap_or(&ledger_commit),
+            ))
+            .map_err(|e| Status::invalid_argument(format!("Invalid expected_commit_id: {}", e)))?;
+        match blocking_write(move || {
+            coordination_service.message_add(&author, care_location, &thread_id, message)
+        })
+        .await?
+        {
             Ok(message_id) => Ok(Response::new(pb::AddMessageRes {
                 message_id: message_id.to_string(),
             })),
-            Err(e) => Err(Status::internal(format!("Failed to add message: {}", e))),
+            Err(e) => Err(write_error("Failed to add message", e)),
         }
     }
 
@@ -895,12 +1033,9 @@ impl Vpr for VprService {
         &self,
         req: Request<pb::ReadCommunicationReq>,
     ) -> Result<Response<pb::ReadCommunicationRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy
+            .authorize(&principal, Operation::ReadCommunication)?;
 
         let req = req.into_inner();
         let coordination_uuid = ShardableUuid::parse(&req.coordination_uuid)
@@ -915,48 +1050,101 @@ impl Vpr for VprService {
         let coordination_service =
             CoordinationService::with_id(self.cfg.clone(), coordination_uuid);
         match coordination_service.read_communication(&thread_id) {
-            Ok(comm) => Ok(Response::new(pb::ReadCommunicationRes {
-                communication_id: comm.communication_id.to_string(),
-                ledger: Some(pb::Ledger {
+            Ok(comm) => {
+                self.policy
+                    .authorize_thread(&principal, &comm.ledger, ThreadAccess::Read)?;
+                Ok(Response::new(pb::ReadCommunicationRes {
                     communication_id: comm.communication_id.to_string(),
-                    status: format!("{:?}", comm.ledger.status).to_lowercase(),
-                    participants: comm
-                        .ledger
-                        .participants
+                    ledger: Some(ledger_to_pb(comm.ledger)),
+                    messages: comm
+                        .messages
                         .into_iter()
-                        .map(|p| pb::LedgerParticipant {
-                            id: p.id.to_string(),
-                            name: p.name.to_string(),
-                            role: format!("{:?}", p.role).to_lowercase(),
+                        .map(|msg| pb::Message {
+                            metadata: Some(pb::MessageMetadata {
+                                message_id: msg.metadata.message_id.to_string(),
+                                author: Some(pb::MessageAuthor {
+                                    id: msg.metadata.author.id.to_string(),
+                                    name: msg.metadata.author.name.to_string(),
+                                    role: format!("{:?}", msg.metadata.author.role).to_lowercase(),
+                                }),
+                                timestamp: msg.metadata.timestamp.to_rfc3339(),
+                                corrects: msg.corrects.map(|id| id.to_string()).unwrap_or_default(),
+                            }),
+                            body: msg.body.to_string(),
                         })
                         .collect(),
-                    sensitivity: format!("{:?}", comm.ledger.sensitivity).to_lowercase(),
-                    restricted: comm.ledger.restricted,
-                    allow_patient_participation: comm.ledger.allow_patient_participation,
-                    allow_external_organisations: comm.ledger.allow_external_organisations,
-                    created_at: comm.ledger.created_at.to_rfc3339(),
-                    last_updated_at: comm.ledger.last_updated_at.to_rfc3339(),
-                }),
-                messages: comm
-                    .messages
+                    commit_id: comm.commit_id,
+                }))
+            }
+            Err(e) => Err(Status::internal(format!(
+                "Failed to read communication: {}",
+                e
+            ))),
+        }
+    }
+
+    async fn list_communications(
+        &self,
+        req: Request<pb::ListCommunicationsReq>,
+    ) -> Result<Response<pb::ListCommunicationsRes>, Status> {
+        let principal = authenticate(&req)?;
+        self.policy
+            .authorize(&principal, Operation::ReadCommunication)?;
+
+        let req = req.into_inner();
+        let coordination_uuid = ShardableUuid::parse(&req.coordination_uuid)
+            .map_err(|e| Status::invalid_argument(format!("Invalid coordination UUID: {}", e)))?
+            .uuid();
+
+        let query = ListCommunicationsQuery {
+            cursor: if req.cursor.is_empty() {
+                None
+            } else {
+                Some(
+                    req.cursor
+                        .parse::<TimestampId>()
+                        .map_err(|e| Status::invalid_argument(format!("Invalid cursor: {}", e)))?,
+                )
+            },
+            limit: (req.limit > 0).then_some(req.limit as usize),
+            status: if req.status.is_empty() {
+                None
+            } else {
+                Some(parse_thread_status(&req.status)?)
+            },
+            participant_id: if req.participant_id.is_empty() {
+                None
+            } else {
+                Some(uuid::Uuid::parse_str(&req.participant_id).map_err(|e| {
+                    Status::invalid_argument(format!("Invalid participant ID: {}", e))
+                })?)
+            },
+            sensitivity: if req.sensitivity.is_empty() {
+                None
+            } else {
+                Some(parse_sensitivity_level(&req.sensitivity)?)
+            },
+        };
+
+        let coordination_service =
+            CoordinationService::with_id(self.cfg.clone(), coordination_uuid);
+        match coordination_service.list_communications(&query) {
+            Ok(page) => Ok(Response::new(pb::ListCommunicationsRes {
+                // Threads the caller may not read are left out of the page.
+                communications: page
+                    .communications
                     .into_iter()
-                    .map(|msg| pb::Message {
-                        metadata: Some(pb::MessageMetadata {
-                            message_id: msg.metadata.message_id.to_string(),
-                            author: Some(pb::MessageAuthor {
-                                id: msg.metadata.author.id.to_string(),
-                                name: msg.metadata.author.name.to_string(),
-                                role: format!("{:?}", msg.metadata.author.role).to_lowercase(),
-                            }),
-                            timestamp: msg.metadata.timestamp.to_rfc3339(),
-                            corrects: msg.corrects.map(|id| id.to_string()).unwrap_or_default(),
-                        }),
-                        body: msg.body.to_string(),
-                    })
+                    .filter(|ledger| self.policy.can_read_thread(&principal, ledger))
+                    .map(ledger_to_pb)
                     .collect(),
+                next_cursor: page
+                    .next_cursor
+                    .map(|id| id.to_string())
+                    .unwrap_or_default(),
             })),
+            Err(PatientError::InvalidInput(msg)) => Err(Status::invalid_argument(msg)),
             Err(e) => Err(Status::internal(format!(
-                "Failed to read communication: {}",
+                "Failed to list communications: {}",
                 e
             ))),
         }
@@ -966,19 +1154,15 @@ impl Vpr for VprService {
         &self,
         req: Request<pb::UpdateCommunicationLedgerReq>,
     ) -> Result<Response<pb::UpdateCommunicationLedgerRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy
+            .authorize(&principal, Operation::UpdateCommunicationLedger)?;
 
         let req = req.into_inner();
         let author = build_author(
-            req.author_name,
+            &principal,
             req.author_email,
             req.author_role,
-            req.author_registrations,
             req.author_signature,
         )?;
 
@@ -1061,19 +1245,31 @@ impl Vpr for VprService {
 
         let coordination_service =
             CoordinationService::with_id(self.cfg.clone(), coordination_uuid);
-        match coordination_service.update_communication_ledger(
-            &author,
-            care_location,
+        let ledger_commit = self.authorize_thread(
+            &principal,
+            &coordination_service,
             &thread_id,
-            ledger_update,
-        ) {
+            ThreadAccess::Write,
+        )?;
+        let coordination_service = coordination_service
+            .with_expected_commit(Some(
+                expected_commit(&req.expected_commit_id).unwrap_or(&ledger_commit),
+            ))
+            .map_err(|e| Status::invalid_argument(format!("Invalid expected_commit_id: {}", e)))?;
+        match blocking_write(move || {
+            coordination_service.update_communication_ledger(
+                &author,
+                care_location,
+                &thread_id,
+                ledger_update,
+            )
+        })
+        .await?
+        {
             Ok(()) => Ok(Response::new(pb::UpdateCommunicationLedgerRes {
                 success: true,
             })),
-            Err(e) => Err(Status::internal(format!(
-                "Failed to update communication ledger: {}",
-                e
-            ))),
+            Err(e) => Err(write_error("Failed to update communication ledger", e)),
         }
     }
 
@@ -1081,19 +1277,15 @@ impl Vpr for VprService {
         &self,
         req: Request<pb::UpdateCoordinationStatusReq>,
     ) -> Result<Response<pb::UpdateCoordinationStatusRes>, Status> {
-        let api_key = req
-            .metadata()
-            .get("x-api-key")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing x-api-key header"))?;
-        auth::validate_api_key(api_key)?;
+        let principal = authenticate(&req)?;
+        self.policy
+            .authorize(&principal, Operation::UpdateCoordinationStatus)?;
 
         let req = req.into_inner();
         let author = build_author(
-            req.author_name,
+            &principal,
             req.author_email,
             req.author_role,
-            req.author_registrations,
             req.author_signature,
         )?;
 
@@ -1118,53 +1310,193 @@ impl Vpr for VprService {
             .map_err(|e| Status::invalid_argument(format!("Invalid care_location: {}", e)))?;
 
         let coordination_service =
-            CoordinationService::with_id(self.cfg.clone(), coordination_uuid);
-        match coordination_service.update_coordination_status(&author, care_location, status_update)
+            CoordinationService::with_id(self.cfg.clone(), coordination_uuid)
+                .with_expected_commit(expected_commit(&req.expected_commit_id))
+                .map_err(|e| {
+                    Status::invalid_argument(format!("Invalid expected_commit_id: {}", e))
+                })?;
+        match blocking_write(move || {
+            coordination_service.update_coordination_status(&author, care_location, status_update)
+        })
+        .await?
         {
             Ok(()) => Ok(Response::new(pb::UpdateCoordinationStatusRes {
                 success: true,
             })),
-            Err(e) => Err(Status::internal(format!(
-                "Failed to update coordination status: {}",
-                e
-            ))),
+            Err(e) => Err(write_error("Failed to update coordination status", e)),
         }
     }
 }
 
 // Helper functions
+fn ledger_to_pb(ledger: LedgerData) -> pb::Ledger {
+    pb::Ledger {
+        communication_id: ledger.communication_id.to_string(),
+        status: format!("{:?}", ledger.status).to_lowercase(),
+        participants: ledger
+            .participants
+            .into_iter()
+            .map(|p| pb::LedgerParticipant {
+                id: p.id.to_string(),
+                name: p.name.to_string(),
+                role: format!("{:?}", p.role).to_lowercase(),
+            })
+            .collect(),
+        sensitivity: format!("{:?}", ledger.sensitivity).to_lowercase(),
+        restricted: ledger.restricted,
+        allow_patient_participation: ledger.allow_patient_participation,
+        allow_external_organisations: ledger.allow_external_organisations,
+        created_at: ledger.created_at.to_rfc3339(),
+        last_updated_at: ledger.last_updated_at.to_rfc3339(),
+    }
+}
+
+/// Builds the commit author for a request.
+///
+/// A bearer-token caller is always recorded as the user named in the token; the author fields
+/// in the request body are ignored. A client-certificate caller is recorded under the
+/// certificate's name and registration, with role and email from the body. API-key callers
+/// are recorded as the fixed service identity from [`api_key_identity`], whatever the body
+/// says.
 #[allow(clippy::result_large_err)]
 fn build_author(
-    name: String,
+    principal: &Principal,
     email: String,
     role: String,
-    registrations: Vec<pb::AuthorRegistration>,
     signature: String,
 ) -> Result<Author, Status> {
-    let name =
-        NonEmptyText::new(&name).map_err(|_| Status::invalid_argument("Invalid author name"))?;
+    let signature = if signature.is_empty() {
+        None
+    } else {
+        Some(signature.into_bytes())
+    };
+
+    let identity = match principal {
+        Principal::User(identity) => return author_from_identity(identity, signature),
+        Principal::ApiKey => return author_from_identity(&api_key_identity(), signature),
+        Principal::Certificate(identity) => identity,
+    };
+
     let role =
         NonEmptyText::new(&role).map_err(|_| Status::invalid_argument("Invalid author role"))?;
     let email = EmailAddress::parse(&email)
         .map_err(|_| Status::invalid_argument("Invalid author email"))?;
+    let (name, registration) = certificate_author(identity)?;
 
     Ok(Author {
         name,
         email,
         role,
-        registrations: registrations
-            .into_iter()
-            .map(|r| AuthorRegistration::new(r.authority, r.number).expect("valid registration"))
-            .collect(),
-        signature: if signature.is_empty() {
-            None
-        } else {
-            Some(signature.into_bytes())
-        },
+        registrations: vec![registration],
+        signature,
         certificate: None,
     })
 }
 
+/// Returns the author name and registration carried by a client certificate.
+#[allow(clippy::result_large_err)]
+fn certificate_author(
+    identity: &CertificateIdentity,
+) -> Result<(NonEmptyText, AuthorRegistration), Status> {
+    let invalid = |_| Status::unauthenticated("Client certificate identity is invalid");
+    Ok((
+        NonEmptyText::new(&identity.common_name).map_err(invalid)?,
+        AuthorRegistration::new(
+            &identity.registration.authority,
+            &identity.registration.number,
+        )
+        .map_err(|_| Status::unauthenticated("Client certificate identity is invalid"))?,
+    ))
+}
+
+#[allow(clippy::result_large_err)]
+fn author_from_identity(
+    identity: &UserIdentity,
+    signature: Option<Vec<u8>>,
+) -> Result<Author, Status> {
+    let invalid_claim = |claim: &str| Status::unauthenticated(format!("Invalid {claim} claim"));
+
+    Ok(Author {
+        name: NonEmptyText::new(&identity.name).map_err(|_| invalid_claim("name"))?,
+        role: NonEmptyText::new(&identity.role).map_err(|_| invalid_claim("role"))?,
+        email: EmailAddress::parse(&identity.email).map_err(|_| invalid_claim("email"))?,
+        registrations: identity
+            .registrations
+            .iter()
+            .map(|r| AuthorRegistration::new(&r.authority, &r.number))
+            .collect::<Result<_, _>>()
+            .map_err(|_| invalid_claim("registrations"))?,
+        signature,
+        certificate: None,
+    })
+}
+
+/// Returns the author a caller's message is recorded under.
+///
+/// The author always comes from the caller's credentials; an author named in the request is
+/// only checked against them (see [`auth::message_author`]).
+#[allow(clippy::result_large_err)]
+fn message_author(
+    principal: &Principal,
+    requested: Option<pb::MessageAuthor>,
+) -> Result<FhirMessageAuthor, Status> {
+    let requested = requested
+        .map(|author| {
+            Ok::<_, Status>(FhirMessageAuthor {
+                id: uuid::Uuid::parse_str(&author.id)
+                    .map_err(|e| Status::invalid_argument(format!("Invalid author UUID: {}", e)))?,
+                name: NonEmptyText::new(author.name)
+                    .map_err(|e| Status::invalid_argument(format!("Invalid author name: {}", e)))?,
+                role: parse_author_role(&author.role)?,
+            })
+        })
+        .transpose()?;
+    auth::message_author(principal, requested.as_ref())
+}
+
+/// Returns the commit a conditional write expects, or `None` if the request names none.
+fn expected_commit(commit_id: &str) -> Option<&str> {
+    (!commit_id.is_empty()).then_some(commit_id)
+}
+
+/// Runs a core write on Tokio's blocking pool.
+///
+/// Writes wait for the repository write lock by sleeping, which must not stall the async
+/// workers. Failures from the write itself are returned for [`write_error`] to map.
+#[allow(clippy::result_large_err)]
+async fn blocking_write<T, F>(write: F) -> Result<PatientResult<T>, Status>
+where
+    T: Send + 'static,
+    F: FnOnce() -> PatientResult<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(write)
+        .await
+        .map_err(|e| Status::internal(format!("Write task failed: {}", e)))
+}
+
+/// Maps a failed core write to a gRPC status.
+///
+/// A write against a record that moved on is ABORTED and a write that timed out waiting for
+/// the repository lock is UNAVAILABLE; the client may retry either. Anything else is INTERNAL,
+/// prefixed with `context`.
+fn write_error(context: &str, e: PatientError) -> Status {
+    match e {
+        PatientError::ConcurrentModification { .. } => Status::aborted(e.to_string()),
+        PatientError::RepositoryLockTimeout(_) => Status::unavailable(e.to_string()),
+        e => Status::internal(format!("{}: {}", context, e)),
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn parse_optional_rfc3339(field: &str, value: &str) -> Result<Option<DateTime<Utc>>, Status> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| Some(dt.with_timezone(&Utc)))
+        .map_err(|e| Status::invalid_argument(format!("Invalid {}: {}", field, e)))
+}
+
 #[allow(clippy::result_large_err)]
 fn parse_author_role(role: &str) -> Result<AuthorRole, Status> {
     match role.to_lowercase().as_str() {
@@ -1219,3 +1551,55 @@ fn parse_lifecycle_state(state: &str) -> Result<LifecycleState, Status> {
         ))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATIENT_ID: &str = "7b3f4c2e-9a1d-4e8b-a6c5-0d2f1e3b4a5c";
+
+    fn patient() -> Principal {
+        Principal::User(UserIdentity {
+            subject: PATIENT_ID.into(),
+            name: "Pat Patient".into(),
+            role: "Patient".into(),
+            email: "pat@example.org".into(),
+            registrations: vec![],
+            roles: vec!["patient".into()],
+        })
+    }
+
+    #[test]
+    fn message_author_is_the_caller() {
+        let author = message_author(&patient(), None).unwrap();
+        assert_eq!(author.id.to_string(), PATIENT_ID);
+        assert_eq!(author.name.as_str(), "Pat Patient");
+        assert_eq!(author.role, AuthorRole::Patient);
+
+        let matching = pb::MessageAuthor {
+            id: PATIENT_ID.into(),
+            name: "Any display name".into(),
+            role: "patient".into(),
+        };
+        assert_eq!(message_author(&patient(), Some(matching)).unwrap(), author);
+    }
+
+    #[test]
+    fn message_author_rejects_a_spoofed_body_author() {
+        let as_clinician = pb::MessageAuthor {
+            id: PATIENT_ID.into(),
+            name: "Dr Pat".into(),
+            role: "clinician".into(),
+        };
+        let err = message_author(&patient(), Some(as_clinician)).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+
+        let as_other = pb::MessageAuthor {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: "Pat Patient".into(),
+            role: "patient".into(),
+        };
+        let err = message_author(&patient(), Some(as_other)).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+    }
+}
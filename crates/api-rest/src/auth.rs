@@ -0,0 +1,475 @@
+//! Authentication and authorisation for the REST API.
+//!
+//! ## Purpose
+//! Applies the same credential checks to REST requests that `api-grpc` applies to gRPC
+//! requests through its interceptor: a bearer token in the `authorization` header, a client
+//! certificate verified by [`crate::tls`], or the shared `x-api-key`. Validation itself is delegated to [`api_shared::auth::authenticate`]
+//! so both front-ends accept the same credentials.
+//!
+//! ## Intended use
+//! Add [`require_authentication`] to an axum router with `axum::middleware::from_fn`. Handlers
+//! read the caller's [`Principal`] with `Extension<Principal>` and build commit authors with
+//! [`author_from_identity`] when the caller is a user, or [`author_from_certificate`] when
+//! the caller presented a client certificate. API-key callers are built with
+//! [`author_from_identity`] from [`api_shared::auth::api_key_identity`]. Only the health
+//! check and the Swagger
+//! UI (with the OpenAPI document it loads) are reachable without credentials.
+//!
+//! Handlers then check the caller's roles with [`authorize`] and, for communication threads,
+//! [`authorize_thread`]. Both apply the shared [`AccessPolicy`] and map a denial to
+//! `403 Forbidden`. Messages posted to a thread are attributed with [`message_author`].
+
+use crate::tls::ClientCertificate;
+use api_shared::{
+    auth::{self, Principal, UserIdentity},
+    policy::{AccessPolicy, Operation, ThreadAccess},
+    tls::CertificateIdentity,
+};
+use axum::{
+    extract::Request,
+    http::{header::AUTHORIZATION, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use fhir::{LedgerData, MessageAuthor};
+use serde::Serialize;
+use vpr_core::{Author, AuthorRegistration, EmailAddress, NonEmptyText};
+
+/// Header carrying the API key, shared with the gRPC interceptor.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Path prefixes that are served without credentials.
+const EXEMPT_PREFIXES: &[&str] = &["/swagger-ui", "/api-docs/"];
+
+/// JSON body returned when a request is rejected.
+#[derive(Debug, Serialize)]
+pub struct AuthError {
+    pub error: &'static str,
+    pub message: String,
+}
+
+/// Returns whether a request path may be served without credentials.
+fn is_exempt(path: &str) -> bool {
+    path == "/health"
+        || EXEMPT_PREFIXES
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+}
+
+/// Middleware that rejects requests without a valid bearer token, client certificate or
+/// `x-api-key` header.
+///
+/// The authenticated [`Principal`] is added to the request extensions for handlers to use.
+///
+/// # Returns
+/// The inner service's response when the credentials are valid or the path is exempt.
+///
+/// # Errors
+/// Responds with:
+/// - `401 Unauthorized` if no credentials are supplied, the bearer token fails verification,
+///   the client certificate carries no registration, or the API key does not match `API_KEY`,
+/// - `500 Internal Server Error` if the server's authentication configuration is unusable.
+pub async fn require_authentication(mut req: Request, next: Next) -> Response {
+    if is_exempt(req.uri().path()) {
+        return next.run(req).await;
+    }
+
+    let result = {
+        let header = |name| req.headers().get(name).and_then(|v| v.to_str().ok());
+        let client_certificate = req
+            .extensions()
+            .get::<ClientCertificate>()
+            .map(|cert| cert.0.as_slice());
+        api_shared::auth::authenticate(
+            header(AUTHORIZATION.as_str()),
+            client_certificate,
+            header(API_KEY_HEADER),
+        )
+    };
+
+    match result {
+        Ok(principal) => {
+            req.extensions_mut().insert(principal);
+            next.run(req).await
+        }
+        Err(status) if status.code() == tonic::Code::Unauthenticated => {
+            unauthorised(status.message().to_string())
+        }
+        Err(status) => {
+            tracing::error!("Authentication failed: {}", status.message());
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(AuthError {
+                    error: "internal",
+                    message: "Authentication is not configured".into(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Builds the commit author for a user identified by a bearer token.
+///
+/// # Arguments
+/// * `identity` - Identity claims from the verified token
+/// * `signature` - Signing key supplied with the request, if any
+///
+/// # Errors
+/// Returns `401 Unauthorized` if a claim is not a valid author field.
+pub fn author_from_identity(
+    identity: &UserIdentity,
+    signature: Option<Vec<u8>>,
+) -> Result<Author, (StatusCode, &'static str)> {
+    const INVALID_CLAIMS: (StatusCode, &str) = (
+        StatusCode::UNAUTHORIZED,
+        "Bearer token identity claims are invalid",
+    );
+
+    Ok(Author {
+        name: NonEmptyText::new(&identity.name).map_err(|_| INVALID_CLAIMS)?,
+        role: NonEmptyText::new(&identity.role).map_err(|_| INVALID_CLAIMS)?,
+        email: EmailAddress::parse(&identity.email).map_err(|_| INVALID_CLAIMS)?,
+        registrations: identity
+            .registrations
+            .iter()
+            .map(|r| AuthorRegistration::new(&r.authority, &r.number))
+            .collect::<Result<_, _>>()
+            .map_err(|_| INVALID_CLAIMS)?,
+        signature,
+        certificate: None,
+    })
+}
+
+/// Builds the commit author for a caller identified by a client certificate.
+///
+/// The name and registration come from the certificate; the role and email, which the
+/// certificate does not carry, come from the request.
+///
+/// # Errors
+/// Returns:
+/// - `400 Bad Request` if the role or email is invalid,
+/// - `401 Unauthorized` if the certificate identity is not a valid author.
+pub fn author_from_certificate(
+    identity: &CertificateIdentity,
+    role: &str,
+    email: &str,
+    signature: Option<Vec<u8>>,
+) -> Result<Author, (StatusCode, &'static str)> {
+    const INVALID_IDENTITY: (StatusCode, &str) = (
+        StatusCode::UNAUTHORIZED,
+        "Client certificate identity is invalid",
+    );
+
+    Ok(Author {
+        name: NonEmptyText::new(&identity.common_name).map_err(|_| INVALID_IDENTITY)?,
+        role: NonEmptyText::new(role)
+            .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid author role"))?,
+        email: EmailAddress::parse(email)
+            .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid author email"))?,
+        registrations: vec![AuthorRegistration::new(
+            &identity.registration.authority,
+            &identity.registration.number,
+        )
+        .map_err(|_| INVALID_IDENTITY)?],
+        signature,
+        certificate: None,
+    })
+}
+
+/// Checks that the caller may perform `operation` under the access policy.
+///
+/// # Errors
+/// Returns `403 Forbidden` if none of the caller's roles is allowed.
+pub fn authorize(
+    policy: &AccessPolicy,
+    principal: &Principal,
+    operation: Operation,
+) -> Result<(), (StatusCode, &'static str)> {
+    policy.authorize(principal, operation).map_err(forbidden)
+}
+
+/// Checks that the caller may read or write the thread described by `ledger`.
+///
+/// # Errors
+/// Returns `403 Forbidden` if the thread's ledger denies access.
+pub fn authorize_thread(
+    policy: &AccessPolicy,
+    principal: &Principal,
+    ledger: &LedgerData,
+    access: ThreadAccess,
+) -> Result<(), (StatusCode, &'static str)> {
+    policy
+        .authorize_thread(principal, ledger, access)
+        .map_err(forbidden)
+}
+
+/// Returns the author a caller's message is recorded under.
+///
+/// The author always comes from the caller's credentials; an author named in the request is
+/// only checked against them (see [`api_shared::auth::message_author`]).
+///
+/// # Errors
+/// Returns `403 Forbidden` if the caller cannot post messages under their own identity, or
+/// `requested` names someone else.
+pub fn message_author(
+    principal: &Principal,
+    requested: Option<&MessageAuthor>,
+) -> Result<MessageAuthor, (StatusCode, &'static str)> {
+    auth::message_author(principal, requested).map_err(forbidden)
+}
+
+fn forbidden(status: tonic::Status) -> (StatusCode, &'static str) {
+    tracing::debug!("Access denied: {}", status.message());
+    (StatusCode::FORBIDDEN, "Forbidden")
+}
+
+fn unauthorised(message: String) -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(AuthError {
+            error: "unauthenticated",
+            message,
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use api_shared::auth::{api_key_identity, RegistrationClaim};
+    use axum::{body::Body, middleware, routing::get, Extension, Router};
+    use fhir::AuthorRole;
+    use tower::ServiceExt;
+
+    const TEST_API_KEY: &str = "test-rest-api-key";
+
+    fn app() -> Router {
+        std::env::set_var("API_KEY", TEST_API_KEY);
+        Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/patients", get(|| async { "patients" }))
+            .route(
+                "/whoami",
+                get(|Extension(principal): Extension<Principal>| async move {
+                    format!("{principal:?}")
+                }),
+            )
+            .route("/swagger-ui/index.html", get(|| async { "swagger" }))
+            .route("/api-docs/openapi.json", get(|| async { "{}" }))
+            .layer(middleware::from_fn(require_authentication))
+    }
+
+    async fn status_for(path: &str, api_key: Option<&str>) -> StatusCode {
+        let mut req = Request::builder().uri(path);
+        if let Some(key) = api_key {
+            req = req.header(API_KEY_HEADER, key);
+        }
+        app()
+            .oneshot(req.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_and_invalid_keys_with_json() {
+        assert_eq!(
+            status_for("/patients", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status_for("/patients", Some("wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+
+        let res = app()
+            .oneshot(
+                Request::builder()
+                    .uri("/patients")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "unauthenticated");
+        assert_eq!(json["message"], "Missing x-api-key header or bearer token");
+    }
+
+    #[tokio::test]
+    async fn accepts_valid_key() {
+        assert_eq!(
+            status_for("/patients", Some(TEST_API_KEY)).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn passes_principal_to_handlers() {
+        let res = app()
+            .oneshot(
+                Request::builder()
+                    .uri("/whoami")
+                    .header(API_KEY_HEADER, TEST_API_KEY)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"ApiKey");
+
+        // A malformed bearer token is rejected even alongside a valid API key.
+        let res = app()
+            .oneshot(
+                Request::builder()
+                    .uri("/whoami")
+                    .header(AUTHORIZATION, "Bearer not-a-jwt")
+                    .header(API_KEY_HEADER, TEST_API_KEY)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn author_comes_from_identity_claims() {
+        let identity = UserIdentity {
+            subject: "user-123".into(),
+            name: "Dr Jane Smith".into(),
+            role: "Consultant".into(),
+            email: "jane.smith@example.org".into(),
+            registrations: vec![RegistrationClaim {
+                authority: "GMC".into(),
+                number: "1234567".into(),
+            }],
+            roles: vec!["clinician".into()],
+        };
+
+        let author = author_from_identity(&identity, Some(b"key".to_vec())).unwrap();
+        assert_eq!(author.name.as_str(), "Dr Jane Smith");
+        assert_eq!(author.role.as_str(), "Consultant");
+        assert_eq!(author.email.as_str(), "jane.smith@example.org");
+        assert_eq!(author.registrations.len(), 1);
+        assert_eq!(author.signature.as_deref(), Some(&b"key"[..]));
+
+        let nameless = UserIdentity {
+            name: " ".into(),
+            ..identity
+        };
+        assert_eq!(
+            author_from_identity(&nameless, None).unwrap_err().0,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[test]
+    fn api_key_author_is_the_service_identity() {
+        let author = author_from_identity(&api_key_identity(), None).unwrap();
+        assert_eq!(author.name.as_str(), "VPR API key client");
+        assert_eq!(author.role.as_str(), "System");
+        assert!(author.registrations.is_empty());
+    }
+
+    #[test]
+    fn certificate_author_takes_name_and_registration_from_certificate() {
+        let identity = CertificateIdentity {
+            common_name: "Dr Jane Smith".into(),
+            registration: RegistrationClaim {
+                authority: "GMC".into(),
+                number: "1234567".into(),
+            },
+        };
+
+        let author =
+            author_from_certificate(&identity, "Consultant", "jane.smith@example.org", None)
+                .unwrap();
+        assert_eq!(author.name.as_str(), "Dr Jane Smith");
+        assert_eq!(author.role.as_str(), "Consultant");
+        assert_eq!(author.registrations.len(), 1);
+        assert_eq!(author.registrations[0].authority.as_str(), "GMC");
+
+        assert_eq!(
+            author_from_certificate(&identity, "Consultant", "not-an-email", None)
+                .unwrap_err()
+                .0,
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    #[test]
+    fn denials_map_to_forbidden() {
+        let policy = AccessPolicy::default();
+        let patient = Principal::User(UserIdentity {
+            subject: "patient-1".into(),
+            name: "Pat Example".into(),
+            role: "Patient".into(),
+            email: "pat@example.org".into(),
+            registrations: vec![],
+            roles: vec!["patient".into()],
+        });
+
+        assert_eq!(
+            authorize(&policy, &patient, Operation::WriteLetter),
+            Err((StatusCode::FORBIDDEN, "Forbidden"))
+        );
+        assert!(authorize(&policy, &Principal::ApiKey, Operation::WriteLetter).is_ok());
+    }
+
+    #[test]
+    fn message_author_cannot_be_spoofed_from_the_body() {
+        let patient_id = uuid::Uuid::new_v4();
+        let patient = Principal::User(UserIdentity {
+            subject: patient_id.to_string(),
+            name: "Pat Example".into(),
+            role: "Patient".into(),
+            email: "pat@example.org".into(),
+            registrations: vec![],
+            roles: vec!["patient".into()],
+        });
+
+        let author = message_author(&patient, None).unwrap();
+        assert_eq!(author.id, patient_id);
+        assert_eq!(author.name.as_str(), "Pat Example");
+        assert_eq!(author.role, AuthorRole::Patient);
+
+        let as_clinician = MessageAuthor {
+            role: AuthorRole::Clinician,
+            ..author.clone()
+        };
+        assert_eq!(
+            message_author(&patient, Some(&as_clinician)),
+            Err((StatusCode::FORBIDDEN, "Forbidden"))
+        );
+        let as_other = MessageAuthor {
+            id: uuid::Uuid::new_v4(),
+            ..author.clone()
+        };
+        assert!(message_author(&patient, Some(&as_other)).is_err());
+        assert_eq!(message_author(&patient, Some(&author)), Ok(author));
+    }
+
+    #[tokio::test]
+    async fn exempts_health_and_swagger_only() {
+        assert_eq!(status_for("/health", None).await, StatusCode::OK);
+        assert_eq!(
+            status_for("/swagger-ui/index.html", None).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status_for("/api-docs/openapi.json", None).await,
+            StatusCode::OK
+        );
+        assert!(!is_exempt("/healthz"));
+        assert!(!is_exempt("/health/../patients"));
+    }
+}
@@ -5,10 +5,16 @@
 //! Handles:
 //! - HTTP endpoints with axum
 //! - OpenAPI/Swagger documentation
-//! - REST-specific concerns (JSON serialisation, CORS)
+//! - REST-specific concerns (JSON serialisation, CORS, authentication)
+//! - TLS termination, including mutual TLS
+//! - running blocking core writes and mapping their errors
 //!
 //! Uses `api-shared` for common types and utilities.
 
 #![warn(rust_2018_idioms)]
 
+pub mod auth;
+pub mod tls;
+pub mod write;
+
 pub use vpr_core::PatientService;
@@ -8,28 +8,55 @@
 //! OpenAPI/Swagger UI). The workspace's main `vpr-run` binary runs both gRPC and REST concurrently.
 
 use axum::{
-    extract::{Path as AxumPath, State},
+    extract::{DefaultBodyLimit, Extension, Multipart, Path as AxumPath, Query, State},
     http::StatusCode,
+    middleware,
     response::Json,
     routing::{get, post, put},
     Router,
 };
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
+use fhir::{
+    coordination_status::LifecycleState, AuthorRole, LedgerData,
+    MessageAuthor as FhirMessageAuthor, SensitivityLevel, ThreadStatus as FhirThreadStatus,
+};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tower_http::cors::CorsLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
+use api_rest::auth::{
+    author_from_certificate, author_from_identity, authorize, authorize_thread, message_author,
+    require_authentication,
+};
+use api_rest::tls::serve_tls;
+use api_rest::write::{blocking_write, write_error, ApiError};
 use api_shared::pb;
+use api_shared::{
+    auth::{api_key_identity, Principal},
+    policy::{AccessPolicy, Operation, ThreadAccess},
+    tls::TlsSettings,
+};
 use std::path::Path;
 use vpr_core::{
-    config::rm_system_version_from_env_value,
-    repositories::clinical::ClinicalService,
-    repositories::coordination::CoordinationService,
-    repositories::demographics::{DemographicsService, Uninitialised as DemographicsUninitialised},
-    Author, AuthorRegistration, CoreConfig, EmailAddress, NonEmptyText, PatientService,
-    ShardableUuid,
+    config::{
+        mirror_root_from_env_value, revocation_list_from_env_value,
+        rm_system_version_from_env_value, timestamp_authority_from_env_value,
+        trust_anchors_from_env_value,
+    },
+    error::PatientError,
+    repositories::clinical::{ClinicalService, ListLettersQuery},
+    repositories::coordination::{
+        CoordinationService, CoordinationStatusUpdate, Initialised as CoordinationInitialised,
+        LedgerUpdate, ListCommunicationsQuery, MessageContent,
+    },
+    repositories::demographics::{
+        patient_details_from_pb, patient_to_pb, DemographicsService,
+        Uninitialised as DemographicsUninitialised,
+    },
+    Author, CoreConfig, NonEmptyText, PatientService, ShardableUuid, TimestampId,
 };
 
 /// Application state for the REST API server
@@ -39,9 +66,13 @@ use vpr_core::{
 #[derive(Clone)]
 struct AppState {
     cfg: Arc<CoreConfig>,
+    policy: Arc<AccessPolicy>,
     demographics_service: Arc<DemographicsService<DemographicsUninitialised>>,
 }
 
+/// Largest request body accepted by the multipart attachment upload route.
+const MAX_ATTACHMENT_UPLOAD_BYTES: usize = 25 * 1024 * 1024;
+
 #[derive(OpenApi)]
 #[openapi(
     paths(
@@ -50,13 +81,24 @@ struct AppState {
         create_patient,
         initialise_full_record,
         initialise_demographics,
+        read_demographics,
         update_demographics,
+        update_patient_details,
         initialise_clinical,
         link_to_demographics,
         new_letter,
         new_letter_complete,
+        new_letter_with_attachments,
         read_letter,
+        list_letters,
+        get_letter_attachments,
         initialise_coordination,
+        create_thread,
+        add_message,
+        read_communication,
+        list_communications,
+        update_communication_ledger,
+        update_coordination_status,
     ),
     components(schemas(
         pb::HealthRes,
@@ -67,8 +109,11 @@ struct AppState {
         pb::InitialiseFullRecordRes,
         pb::InitialiseDemographicsReq,
         pb::InitialiseDemographicsRes,
+        pb::ReadDemographicsRes,
         pb::UpdateDemographicsReq,
         pb::UpdateDemographicsRes,
+        pb::UpdatePatientDetailsReq,
+        pb::UpdatePatientDetailsRes,
         pb::InitialiseClinicalReq,
         pb::InitialiseClinicalRes,
         pb::LinkToDemographicsReq,
@@ -77,10 +122,32 @@ struct AppState {
         pb::NewLetterRes,
         pb::NewLetterCompleteReq,
         pb::NewLetterCompleteRes,
+        LetterAttachmentsForm,
+        pb::NewLetterWithAttachmentsRes,
+        pb::AttachmentMetadata,
+        pb::LetterAttachment,
+        pb::GetLetterAttachmentsRes,
         pb::ReadLetterReq,
         pb::ReadLetterRes,
+        pb::LetterSummary,
+        pb::ListLettersRes,
         pb::InitialiseCoordinationReq,
         pb::InitialiseCoordinationRes,
+        pb::MessageAuthor,
+        pb::CreateThreadReq,
+        pb::CreateThreadRes,
+        pb::AddMessageReq,
+        pb::AddMessageRes,
+        pb::MessageMetadata,
+        pb::Message,
+        pb::ReadCommunicationRes,
+        pb::Ledger,
+        pb::LedgerParticipant,
+        pb::ListCommunicationsRes,
+        pb::UpdateCommunicationLedgerReq,
+        pb::UpdateCommunicationLedgerRes,
+        pb::UpdateCoordinationStatusReq,
+        pb::UpdateCoordinationStatusRes,
     ))
 )]
 struct ApiDoc;
@@ -90,8 +157,25 @@ struct ApiDoc;
 /// Starts the REST API server on the configured address (default: 0.0.0.0:3000).
 /// Provides HTTP endpoints for patient operations with OpenAPI/Swagger documentation.
 ///
+/// Every route except `/health` and the Swagger UI requires a bearer token, a verified client
+/// certificate or a valid `x-api-key` header.
+///
 /// # Environment Variables
 /// - `VPR_REST_ADDR`: Server address (default: "0.0.0.0:3000")
+/// - `API_KEY`: API key accepted in the `x-api-key` header
+/// - `ACCESS_POLICY_PATH`: JSON file overriding the default role rules (optional)
+/// - `JWT_JWKS_PATH` / `JWT_PUBLIC_KEY_PATH`: Keys that verify bearer tokens (optional)
+/// - `JWT_ISSUER` / `JWT_AUDIENCE`: Required bearer token `iss` / `aud` claims (optional)
+/// - `VPR_TLS_CERT_PATH` / `VPR_TLS_KEY_PATH`: Server certificate and key; enables TLS (optional)
+/// - `VPR_TLS_CLIENT_CA_PATH`: CA bundle for client certificates; enables mutual TLS (optional)
+/// - `VPR_TLS_CLIENT_AUTH_REQUIRED`: Refuse clients without a certificate (default: "false")
+/// - `VPR_TRUST_ANCHORS_PATH`: CA bundle that commit signing certificates must chain to (optional)
+/// - `VPR_REVOCATION_CRL_PATH`: CRL of revoked commit signing certificates (optional)
+/// - `VPR_TSA_URL`: RFC 3161 timestamp authority that timestamps every commit (optional)
+/// - `VPR_TSA_TRUST_ANCHORS_PATH`: CA bundle that timestamp authority certificates must chain to
+///   (optional)
+/// - `VPR_MIRROR_URL`: Directory or `file://` URL of the bare repositories every commit is
+///   pushed to (optional)
 ///
 /// # Returns
 /// * `Ok(())` - If server starts and runs successfully
@@ -99,6 +183,7 @@ struct ApiDoc;
 /// # Errors
 /// Returns an error if:
 /// - the logging/tracing configuration cannot be initialised,
+/// - the TLS configuration cannot be read or is invalid,
 /// - the server address cannot be bound, or
 /// - the HTTP server fails while running.
 #[tokio::main]
@@ -135,14 +220,56 @@ async fn main() -> anyhow::Result<()> {
         .and_then(|s| vpr_core::NonEmptyText::new(s).ok())
         .unwrap_or_else(|| vpr_core::NonEmptyText::new("vpr.dev.1").unwrap());
 
-    let cfg = Arc::new(CoreConfig::new(
+    let mut cfg = CoreConfig::new(
         patient_data_path.to_path_buf(),
         rm_system_version,
         vpr_namespace,
-    )?);
+    )?;
+    if let Some(anchors) =
+        trust_anchors_from_env_value(std::env::var("VPR_TRUST_ANCHORS_PATH").ok().as_deref())?
+    {
+        cfg = cfg.with_trust_anchors(anchors);
+    }
+    if let Some(revocations) =
+        revocation_list_from_env_value(std::env::var("VPR_REVOCATION_CRL_PATH").ok().as_deref())?
+    {
+        cfg = cfg.with_revocation_list(revocations);
+    }
+    if let Some(tsa) =
+        timestamp_authority_from_env_value(std::env::var("VPR_TSA_URL").ok().as_deref())?
+    {
+        cfg = cfg.with_timestamp_authority(tsa);
+    }
+    if let Some(anchors) =
+        trust_anchors_from_env_value(std::env::var("VPR_TSA_TRUST_ANCHORS_PATH").ok().as_deref())?
+    {
+        cfg = cfg.with_timestamp_trust_anchors(anchors);
+    }
+    if let Some(mirror_root) =
+        mirror_root_from_env_value(std::env::var("VPR_MIRROR_URL").ok().as_deref())?
+    {
+        cfg = cfg.with_mirror(mirror_root);
+    }
+    let cfg = Arc::new(cfg);
+
+    // Finish or discard full records whose creation was interrupted by a crash.
+    match PatientService::new(cfg.clone()).recover_interrupted_records() {
+        Ok(report) if !report.completed.is_empty() || !report.rolled_back.is_empty() => {
+            tracing::info!(
+                "Recovered interrupted records: {} completed, {} rolled back",
+                report.completed.len(),
+                report.rolled_back.len()
+            );
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to recover interrupted records: {}", e),
+    }
+    let policy = Arc::new(AccessPolicy::from_env().map_err(anyhow::Error::msg)?);
+    let tls = TlsSettings::from_env().map_err(anyhow::Error::msg)?;
 
     let state = AppState {
         cfg: cfg.clone(),
+        policy,
         demographics_service: Arc::new(DemographicsService::new(cfg)),
     };
 
@@ -152,21 +279,53 @@ async fn main() -> anyhow::Result<()> {
         .route("/patients", post(create_patient))
         .route("/patients/full", post(initialise_full_record))
         .route("/demographics", post(initialise_demographics))
+        .route("/demographics/:id", get(read_demographics))
         .route("/demographics/:id", put(update_demographics))
+        .route("/demographics/:id/details", put(update_patient_details))
         .route("/clinical", post(initialise_clinical))
         .route("/clinical/:id/link", post(link_to_demographics))
+        .route("/clinical/:id/letters", get(list_letters))
         .route("/clinical/:id/letters", post(new_letter))
         .route("/clinical/:id/letters/complete", post(new_letter_complete))
+        .route(
+            "/clinical/:id/letters/attachments",
+            post(new_letter_with_attachments)
+                .layer(DefaultBodyLimit::max(MAX_ATTACHMENT_UPLOAD_BYTES)),
+        )
         .route("/clinical/:id/letters/:letter_id", get(read_letter))
+        .route(
+            "/clinical/:id/letters/:letter_id/attachments",
+            get(get_letter_attachments),
+        )
         .route("/coordination", post(initialise_coordination))
-        .merge(
-            SwaggerUi::new("/swagger-ui/{_:.*}").url("/api-docs/openapi.json", ApiDoc::openapi()),
+        .route("/coordination/:id/status", put(update_coordination_status))
+        .route("/coordination/:id/communications", get(list_communications))
+        .route("/coordination/:id/communications", post(create_thread))
+        .route(
+            "/coordination/:id/communications/:thread_id",
+            get(read_communication),
         )
+        .route(
+            "/coordination/:id/communications/:thread_id/messages",
+            post(add_message),
+        )
+        .route(
+            "/coordination/:id/communications/:thread_id/ledger",
+            put(update_communication_ledger),
+        )
+        .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
+        .layer(middleware::from_fn(require_authentication))
         .layer(CorsLayer::permissive())
         .with_state(state);
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    match tls {
+        Some(tls) => {
+            tracing::info!("REST TLS enabled");
+            serve_tls(listener, app, &tls).await?;
+        }
+        None => axum::serve(listener, app).await?,
+    }
 
     Ok(())
 }
@@ -216,7 +375,10 @@ async fn health(State(_state): State<AppState>) -> Json<pb::HealthRes> {
 #[axum::debug_handler]
 async fn list_patients(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
 ) -> Result<Json<pb::ListPatientsRes>, (StatusCode, &'static str)> {
+    authorize(&state.policy, &principal, Operation::ListPatients)?;
+
     let patients = state.demographics_service.list_patients();
     Ok(Json(pb::ListPatientsRes { patients }))
 }
@@ -228,7 +390,8 @@ async fn list_patients(
     responses(
         (status = 201, description = "Patient created", body = pb::CreatePatientRes),
         (status = 400, description = "Bad request"),
-        (status = 500, description = "Internal server error")
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Record busy, retry later")
     )
 )]
 /// Create a new patient record
@@ -251,38 +414,22 @@ async fn list_patients(
 #[axum::debug_handler]
 async fn create_patient(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Json(req): Json<pb::CreatePatientReq>,
-) -> Result<Json<pb::CreatePatientRes>, (StatusCode, &'static str)> {
-    let registrations: Vec<AuthorRegistration> = req
-        .author_registrations
-        .into_iter()
-        .map(|r| AuthorRegistration::new(r.authority, r.number).expect("valid registration"))
-        .collect();
-
-    let name = NonEmptyText::new(&req.author_name)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid author name"))?;
-    let role = NonEmptyText::new(&req.author_role)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid author role"))?;
-    let email = EmailAddress::parse(&req.author_email)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid author email"))?;
-
-    let author = Author {
-        name,
-        role,
-        email,
-        registrations,
-        signature: if req.author_signature.is_empty() {
-            None
-        } else {
-            Some(req.author_signature.into_bytes())
-        },
-        certificate: None,
-    };
+) -> Result<Json<pb::CreatePatientRes>, ApiError> {
+    authorize(&state.policy, &principal, Operation::CreateRecord)?;
+
+    let author = build_author(
+        &principal,
+        req.author_email,
+        req.author_role,
+        req.author_signature,
+    )?;
     let care_location = NonEmptyText::new(&req.care_location)
         .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
 
     let clinical_service = ClinicalService::new(state.cfg.clone());
-    match clinical_service.initialise(author, care_location) {
+    match blocking_write(move || clinical_service.initialise(author, care_location)).await? {
         Ok(service) => {
             let resp = pb::CreatePatientRes {
                 filename: "".to_string(),
@@ -292,14 +439,12 @@ async fn create_patient(
                     last_name: "".to_string(),
                     created_at: "".to_string(),
                     national_id: "".to_string(),
+                    ..Default::default()
                 }),
             };
             Ok(Json(resp))
         }
-        Err(e) => {
-            tracing::error!("Initialise clinical error: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"))
-        }
+        Err(e) => Err(write_error("Initialise clinical", e)),
     }
 }
 #[utoipa::path(
@@ -308,19 +453,22 @@ async fn create_patient(
     request_body = pb::InitialiseFullRecordReq,
     responses(
         (status = 201, description = "Full patient record created", body = pb::InitialiseFullRecordRes),
-        (status = 500, description = "Internal server error")
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Record busy, retry later")
     )
 )]
 #[axum::debug_handler]
 async fn initialise_full_record(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Json(req): Json<pb::InitialiseFullRecordReq>,
-) -> Result<Json<pb::InitialiseFullRecordRes>, (StatusCode, &'static str)> {
+) -> Result<Json<pb::InitialiseFullRecordRes>, ApiError> {
+    authorize(&state.policy, &principal, Operation::CreateRecord)?;
+
     let author = build_author(
-        req.author_name,
+        &principal,
         req.author_email,
         req.author_role,
-        req.author_registrations,
         req.author_signature,
     )?;
     let care_location = NonEmptyText::new(&req.care_location)
@@ -351,23 +499,24 @@ async fn initialise_full_record(
         )
     };
 
-    match patient_service.initialise_full_record(
-        author,
-        care_location,
-        given_names,
-        last_name,
-        birth_date,
-        namespace,
-    ) {
+    match blocking_write(move || {
+        patient_service.initialise_full_record(
+            author,
+            care_location,
+            given_names,
+            last_name,
+            birth_date,
+            namespace,
+        )
+    })
+    .await?
+    {
         Ok(record) => Ok(Json(pb::InitialiseFullRecordRes {
             demographics_uuid: record.demographics_uuid.to_string(),
             clinical_uuid: record.clinical_uuid.to_string(),
             coordination_uuid: record.coordination_uuid.to_string(),
         })),
-        Err(e) => {
-            tracing::error!("Initialise full record error: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"))
-        }
+        Err(e) => Err(write_error("Initialise full record", e)),
     }
 }
 
@@ -377,31 +526,68 @@ async fn initialise_full_record(
     request_body = pb::InitialiseDemographicsReq,
     responses(
         (status = 201, description = "Demographics created", body = pb::InitialiseDemographicsRes),
-        (status = 500, description = "Internal server error")
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Record busy, retry later")
     )
 )]
 #[axum::debug_handler]
 async fn initialise_demographics(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Json(req): Json<pb::InitialiseDemographicsReq>,
-) -> Result<Json<pb::InitialiseDemographicsRes>, (StatusCode, &'static str)> {
+) -> Result<Json<pb::InitialiseDemographicsRes>, ApiError> {
+    authorize(&state.policy, &principal, Operation::CreateRecord)?;
+
     let author = build_author(
-        req.author_name,
+        &principal,
         req.author_email,
         req.author_role,
-        req.author_registrations,
         req.author_signature,
     )?;
     let care_location = NonEmptyText::new(&req.care_location)
         .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
 
     let demographics_service = DemographicsService::new(state.cfg.clone());
-    match demographics_service.initialise(author, care_location) {
+    match blocking_write(move || demographics_service.initialise(author, care_location)).await? {
         Ok(service) => Ok(Json(pb::InitialiseDemographicsRes {
             demographics_uuid: service.demographics_id().to_string(),
         })),
+        Err(e) => Err(write_error("Initialise demographics", e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/demographics/{id}",
+    responses(
+        (status = 200, description = "Demographics retrieved", body = pb::ReadDemographicsRes),
+        (status = 400, description = "Bad request"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+#[axum::debug_handler]
+async fn read_demographics(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    AxumPath(id): AxumPath<String>,
+) -> Result<Json<pb::ReadDemographicsRes>, (StatusCode, &'static str)> {
+    authorize(&state.policy, &principal, Operation::ListPatients)?;
+
+    let demographics_service = match DemographicsService::with_id(state.cfg.clone(), &id) {
+        Ok(svc) => svc,
+        Err(e) => {
+            tracing::error!("Invalid demographics UUID: {:?}", e);
+            return Err((StatusCode::BAD_REQUEST, "Invalid demographics UUID"));
+        }
+    };
+
+    match demographics_service.read() {
+        Ok(result) => Ok(Json(pb::ReadDemographicsRes {
+            patient: Some(patient_to_pb(&result.patient)),
+            commit_id: result.commit_id,
+        })),
         Err(e) => {
-            tracing::error!("Initialise demographics error: {:?}", e);
+            tracing::error!("Read demographics error: {:?}", e);
             Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"))
         }
     }
@@ -414,25 +600,41 @@ async fn initialise_demographics(
     responses(
         (status = 200, description = "Demographics updated", body = pb::UpdateDemographicsRes),
         (status = 400, description = "Bad request"),
-        (status = 500, description = "Internal server error")
+        (status = 409, description = "Record changed concurrently"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Record busy, retry later")
     )
 )]
 #[axum::debug_handler]
 async fn update_demographics(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     AxumPath(id): AxumPath<String>,
     Json(mut req): Json<pb::UpdateDemographicsReq>,
-) -> Result<Json<pb::UpdateDemographicsRes>, (StatusCode, &'static str)> {
+) -> Result<Json<pb::UpdateDemographicsRes>, ApiError> {
+    authorize(&state.policy, &principal, Operation::UpdateDemographics)?;
+
     req.demographics_uuid = id;
 
+    let author = build_author(
+        &principal,
+        req.author_email,
+        req.author_role,
+        req.author_signature,
+    )?;
+    let care_location = NonEmptyText::new(&req.care_location)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
+
     let demographics_service =
         match DemographicsService::with_id(state.cfg.clone(), &req.demographics_uuid) {
             Ok(svc) => svc,
             Err(e) => {
                 tracing::error!("Invalid demographics UUID: {:?}", e);
-                return Err((StatusCode::BAD_REQUEST, "Invalid demographics UUID"));
+                return Err((StatusCode::BAD_REQUEST, "Invalid demographics UUID").into());
             }
-        };
+        }
+        .with_expected_commit(expected_commit(&req.expected_commit_id))
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid expected_commit_id"))?;
 
     let given_names: Vec<NonEmptyText> = req
         .given_names
@@ -450,12 +652,78 @@ async fn update_demographics(
 
     let birth_date_str = birth_date.format("%Y-%m-%d").to_string();
 
-    match demographics_service.update(given_names, last_name.as_str(), &birth_date_str) {
+    match blocking_write(move || {
+        demographics_service.update(
+            &author,
+            care_location,
+            given_names,
+            last_name.as_str(),
+            &birth_date_str,
+        )
+    })
+    .await?
+    {
         Ok(()) => Ok(Json(pb::UpdateDemographicsRes { success: true })),
-        Err(e) => {
-            tracing::error!("Update demographics error: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"))
+        Err(e) => Err(write_error("Update demographics", e)),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/demographics/{id}/details",
+    request_body = pb::UpdatePatientDetailsReq,
+    responses(
+        (status = 200, description = "Patient details replaced", body = pb::UpdatePatientDetailsRes),
+        (status = 400, description = "Bad request"),
+        (status = 409, description = "Record changed concurrently"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Record busy, retry later")
+    )
+)]
+#[axum::debug_handler]
+async fn update_patient_details(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    AxumPath(id): AxumPath<String>,
+    Json(mut req): Json<pb::UpdatePatientDetailsReq>,
+) -> Result<Json<pb::UpdatePatientDetailsRes>, ApiError> {
+    authorize(&state.policy, &principal, Operation::UpdateDemographics)?;
+
+    req.demographics_uuid = id;
+
+    let author = build_author(
+        &principal,
+        req.author_email,
+        req.author_role,
+        req.author_signature,
+    )?;
+    let care_location = NonEmptyText::new(&req.care_location)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
+
+    let demographics_service =
+        match DemographicsService::with_id(state.cfg.clone(), &req.demographics_uuid) {
+            Ok(svc) => svc,
+            Err(e) => {
+                tracing::error!("Invalid demographics UUID: {:?}", e);
+                return Err((StatusCode::BAD_REQUEST, "Invalid demographics UUID").into());
+            }
         }
+        .with_expected_commit(expected_commit(&req.expected_commit_id))
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid expected_commit_id"))?;
+
+    let details = patient_details_from_pb(req.identifiers, &req.gender, req.telecom, req.addresses)
+        .map_err(|e| {
+            tracing::warn!("Invalid patient details: {}", e);
+            (StatusCode::BAD_REQUEST, "Invalid patient details")
+        })?;
+
+    match blocking_write(move || {
+        demographics_service.update_details(&author, care_location, details)
+    })
+    .await?
+    {
+        Ok(()) => Ok(Json(pb::UpdatePatientDetailsRes { success: true })),
+        Err(e) => Err(write_error("Update patient details", e)),
     }
 }
 
@@ -465,33 +733,33 @@ async fn update_demographics(
     request_body = pb::InitialiseClinicalReq,
     responses(
         (status = 201, description = "Clinical created", body = pb::InitialiseClinicalRes),
-        (status = 500, description = "Internal server error")
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Record busy, retry later")
     )
 )]
 #[axum::debug_handler]
 async fn initialise_clinical(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Json(req): Json<pb::InitialiseClinicalReq>,
-) -> Result<Json<pb::InitialiseClinicalRes>, (StatusCode, &'static str)> {
+) -> Result<Json<pb::InitialiseClinicalRes>, ApiError> {
+    authorize(&state.policy, &principal, Operation::CreateRecord)?;
+
     let author = build_author(
-        req.author_name,
+        &principal,
         req.author_email,
         req.author_role,
-        req.author_registrations,
         req.author_signature,
     )?;
     let care_location = NonEmptyText::new(&req.care_location)
         .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
 
     let clinical_service = ClinicalService::new(state.cfg.clone());
-    match clinical_service.initialise(author, care_location) {
+    match blocking_write(move || clinical_service.initialise(author, care_location)).await? {
         Ok(service) => Ok(Json(pb::InitialiseClinicalRes {
             clinical_uuid: service.clinical_id().simple().to_string(),
         })),
-        Err(e) => {
-            tracing::error!("Initialise clinical error: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"))
-        }
+        Err(e) => Err(write_error("Initialise clinical", e)),
     }
 }
 
@@ -502,22 +770,26 @@ async fn initialise_clinical(
     responses(
         (status = 200, description = "Linked to demographics", body = pb::LinkToDemographicsRes),
         (status = 400, description = "Bad request"),
-        (status = 500, description = "Internal server error")
+        (status = 409, description = "Record changed concurrently"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Record busy, retry later")
     )
 )]
 #[axum::debug_handler]
 async fn link_to_demographics(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     AxumPath(id): AxumPath<String>,
     Json(mut req): Json<pb::LinkToDemographicsReq>,
-) -> Result<Json<pb::LinkToDemographicsRes>, (StatusCode, &'static str)> {
+) -> Result<Json<pb::LinkToDemographicsRes>, ApiError> {
+    authorize(&state.policy, &principal, Operation::LinkToDemographics)?;
+
     req.clinical_uuid = id;
 
     let author = build_author(
-        req.author_name,
+        &principal,
         req.author_email,
         req.author_role,
-        req.author_registrations,
         req.author_signature,
     )?;
 
@@ -525,13 +797,15 @@ async fn link_to_demographics(
         Ok(uuid) => uuid.uuid(),
         Err(e) => {
             tracing::error!("Invalid clinical UUID: {:?}", e);
-            return Err((StatusCode::BAD_REQUEST, "Invalid clinical UUID"));
+            return Err((StatusCode::BAD_REQUEST, "Invalid clinical UUID").into());
         }
     };
     let care_location = NonEmptyText::new(&req.care_location)
         .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
 
-    let clinical_service = ClinicalService::with_id(state.cfg.clone(), clinical_uuid);
+    let clinical_service = ClinicalService::with_id(state.cfg.clone(), clinical_uuid)
+        .with_expected_commit(expected_commit(&req.expected_commit_id))
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid expected_commit_id"))?;
 
     let namespace = if req.namespace.is_empty() {
         None
@@ -542,17 +816,18 @@ async fn link_to_demographics(
         )
     };
 
-    match clinical_service.link_to_demographics(
-        &author,
-        care_location,
-        &req.demographics_uuid,
-        namespace,
-    ) {
+    match blocking_write(move || {
+        clinical_service.link_to_demographics(
+            &author,
+            care_location,
+            &req.demographics_uuid,
+            namespace,
+        )
+    })
+    .await?
+    {
         Ok(()) => Ok(Json(pb::LinkToDemographicsRes { success: true })),
-        Err(e) => {
-            tracing::error!("Link to demographics error: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"))
-        }
+        Err(e) => Err(write_error("Link to demographics", e)),
     }
 }
 
@@ -563,22 +838,26 @@ async fn link_to_demographics(
     responses(
         (status = 201, description = "Letter created", body = pb::NewLetterRes),
         (status = 400, description = "Bad request"),
-        (status = 500, description = "Internal server error")
+        (status = 409, description = "Record changed concurrently"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Record busy, retry later")
     )
 )]
 #[axum::debug_handler]
 async fn new_letter(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     AxumPath(id): AxumPath<String>,
     Json(mut req): Json<pb::NewLetterReq>,
-) -> Result<Json<pb::NewLetterRes>, (StatusCode, &'static str)> {
+) -> Result<Json<pb::NewLetterRes>, ApiError> {
+    authorize(&state.policy, &principal, Operation::WriteLetter)?;
+
     req.clinical_uuid = id;
 
     let author = build_author(
-        req.author_name,
+        &principal,
         req.author_email,
         req.author_role,
-        req.author_registrations,
         req.author_signature,
     )?;
 
@@ -586,25 +865,26 @@ async fn new_letter(
         Ok(uuid) => uuid.uuid(),
         Err(e) => {
             tracing::error!("Invalid clinical UUID: {:?}", e);
-            return Err((StatusCode::BAD_REQUEST, "Invalid clinical UUID"));
+            return Err((StatusCode::BAD_REQUEST, "Invalid clinical UUID").into());
         }
     };
     let care_location = NonEmptyText::new(&req.care_location)
         .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
 
-    let clinical_service = ClinicalService::with_id(state.cfg.clone(), clinical_uuid);
+    let clinical_service = ClinicalService::with_id(state.cfg.clone(), clinical_uuid)
+        .with_expected_commit(expected_commit(&req.expected_commit_id))
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid expected_commit_id"))?;
 
     let content =
         NonEmptyText::new(req.content).map_err(|_| (StatusCode::BAD_REQUEST, "Invalid content"))?;
 
-    match clinical_service.new_letter(&author, care_location, content, None) {
+    match blocking_write(move || clinical_service.new_letter(&author, care_location, content, None))
+        .await?
+    {
         Ok(timestamp_id) => Ok(Json(pb::NewLetterRes {
             timestamp_id: timestamp_id.to_string(),
         })),
-        Err(e) => {
-            tracing::error!("New letter error: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"))
-        }
+        Err(e) => Err(write_error("New letter", e)),
     }
 }
 
@@ -615,22 +895,26 @@ async fn new_letter(
     responses(
         (status = 201, description = "Complete letter created", body = pb::NewLetterCompleteRes),
         (status = 400, description = "Bad request"),
-        (status = 500, description = "Internal server error")
+        (status = 409, description = "Record changed concurrently"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Record busy, retry later")
     )
 )]
 #[axum::debug_handler]
 async fn new_letter_complete(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     AxumPath(id): AxumPath<String>,
     Json(mut req): Json<pb::NewLetterCompleteReq>,
-) -> Result<Json<pb::NewLetterCompleteRes>, (StatusCode, &'static str)> {
+) -> Result<Json<pb::NewLetterCompleteRes>, ApiError> {
+    authorize(&state.policy, &principal, Operation::WriteLetter)?;
+
     req.clinical_uuid = id;
 
     let author = build_author(
-        req.author_name,
+        &principal,
         req.author_email,
         req.author_role,
-        req.author_registrations,
         req.author_signature,
     )?;
 
@@ -638,58 +922,45 @@ async fn new_letter_complete(
         Ok(uuid) => uuid.uuid(),
         Err(e) => {
             tracing::error!("Invalid clinical UUID: {:?}", e);
-            return Err((StatusCode::BAD_REQUEST, "Invalid clinical UUID"));
+            return Err((StatusCode::BAD_REQUEST, "Invalid clinical UUID").into());
         }
     };
 
     // Write attachment files to temporary directory
-    let temp_dir = std::env::temp_dir().join(format!("vpr_attachments_{}", uuid::Uuid::new_v4()));
-    if let Err(e) = std::fs::create_dir_all(&temp_dir) {
-        tracing::error!("Failed to create temp dir: {:?}", e);
-        return Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"));
-    }
-
-    let mut attachment_paths = Vec::new();
-    for (i, (content, name)) in req
-        .attachment_files
-        .iter()
-        .zip(&req.attachment_names)
-        .enumerate()
-    {
-        let file_path = temp_dir.join(format!("{}_{}", i, name));
-        if let Err(e) = std::fs::write(&file_path, content) {
-            tracing::error!("Failed to write attachment: {:?}", e);
-            let _ = std::fs::remove_dir_all(&temp_dir);
-            return Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"));
-        }
-        attachment_paths.push(file_path);
-    }
+    let (temp_dir, attachment_paths) = write_attachments_to_temp_dir(
+        req.attachment_names
+            .iter()
+            .map(String::as_str)
+            .zip(req.attachment_files.iter().map(Vec::as_slice)),
+    )?;
     let care_location = NonEmptyText::new(&req.care_location)
         .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
 
     let content =
         NonEmptyText::new(req.content).map_err(|_| (StatusCode::BAD_REQUEST, "Invalid content"))?;
 
-    let clinical_service = ClinicalService::with_id(state.cfg.clone(), clinical_uuid);
-    let result = clinical_service.create_letter(
-        &author,
-        care_location,
-        Some(content),
-        &attachment_paths,
-        None,
-    );
+    let clinical_service = ClinicalService::with_id(state.cfg.clone(), clinical_uuid)
+        .with_expected_commit(expected_commit(&req.expected_commit_id))
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid expected_commit_id"))?;
+    let result = blocking_write(move || {
+        clinical_service.create_letter(
+            &author,
+            care_location,
+            Some(content),
+            &attachment_paths,
+            None,
+        )
+    })
+    .await;
 
     // Clean up temp files
     let _ = std::fs::remove_dir_all(&temp_dir);
 
-    match result {
+    match result? {
         Ok(timestamp_id) => Ok(Json(pb::NewLetterCompleteRes {
             timestamp_id: timestamp_id.to_string(),
         })),
-        Err(e) => {
-            tracing::error!("New complete letter error: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"))
-        }
+        Err(e) => Err(write_error("New complete letter", e)),
     }
 }
 
@@ -705,8 +976,11 @@ async fn new_letter_complete(
 #[axum::debug_handler]
 async fn read_letter(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     AxumPath((clinical_uuid, letter_id)): AxumPath<(String, String)>,
 ) -> Result<Json<pb::ReadLetterRes>, (StatusCode, &'static str)> {
+    authorize(&state.policy, &principal, Operation::ReadLetter)?;
+
     let clinical_uuid_parsed = match ShardableUuid::parse(&clinical_uuid) {
         Ok(uuid) => uuid.uuid(),
         Err(e) => {
@@ -732,6 +1006,9 @@ async fn read_letter(
                     kind: list.kind,
                 })
                 .collect(),
+            letter_timestamp_id: result.letter_data.uid.to_string(),
+            versions: result.versions.iter().map(|v| v.to_string()).collect(),
+            commit_id: result.commit_id,
         })),
         Err(e) => {
             tracing::error!("Read letter error: {:?}", e);
@@ -740,81 +1017,1007 @@ async fn read_letter(
     }
 }
 
+/// Query parameters for listing letters.
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+struct ListLettersParams {
+    /// `next_cursor` from the previous page
+    cursor: Option<String>,
+    /// Maximum number of letters to return
+    limit: Option<usize>,
+    /// Only letters created at or after this time (RFC3339)
+    from: Option<String>,
+    /// Only letters created before this time (RFC3339)
+    to: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/clinical/{id}/letters",
+    params(ListLettersParams),
+    responses(
+        (status = 200, description = "Page of letters", body = pb::ListLettersRes),
+        (status = 400, description = "Bad request"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+#[axum::debug_handler]
+async fn list_letters(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    AxumPath(clinical_uuid): AxumPath<String>,
+    Query(params): Query<ListLettersParams>,
+) -> Result<Json<pb::ListLettersRes>, (StatusCode, &'static str)> {
+    authorize(&state.policy, &principal, Operation::ReadLetter)?;
+
+    let clinical_uuid_parsed = match ShardableUuid::parse(&clinical_uuid) {
+        Ok(uuid) => uuid.uuid(),
+        Err(e) => {
+            tracing::error!("Invalid clinical UUID: {:?}", e);
+            return Err((StatusCode::BAD_REQUEST, "Invalid clinical UUID"));
+        }
+    };
+
+    let cursor = match params.cursor.as_deref().map(str::parse::<TimestampId>) {
+        None => None,
+        Some(Ok(cursor)) => Some(cursor),
+        Some(Err(e)) => {
+            tracing::error!("Invalid cursor: {:?}", e);
+            return Err((StatusCode::BAD_REQUEST, "Invalid cursor"));
+        }
+    };
+    let parse_time =
+        |value: Option<&str>| -> Result<Option<DateTime<Utc>>, (StatusCode, &'static str)> {
+            value
+                .map(|v| {
+                    DateTime::parse_from_rfc3339(v)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid date range"))
+                })
+                .transpose()
+        };
+    let query = ListLettersQuery {
+        cursor,
+        limit: params.limit,
+        from: parse_time(params.from.as_deref())?,
+        to: parse_time(params.to.as_deref())?,
+    };
+
+    let clinical_service = ClinicalService::with_id(state.cfg.clone(), clinical_uuid_parsed);
+    match clinical_service.list_letters(&query) {
+        Ok(page) => Ok(Json(pb::ListLettersRes {
+            letters: page
+                .letters
+                .into_iter()
+                .map(|letter| pb::LetterSummary {
+                    letter_timestamp_id: letter.uid.to_string(),
+                    composer_name: letter.composer_name,
+                    composer_role: letter.composer_role,
+                    start_time: letter.start_time.to_rfc3339(),
+                    has_body: letter.has_body,
+                    attachment_count: letter.attachment_count as u32,
+                    superseded_by: letter
+                        .superseded_by
+                        .map(|id| id.to_string())
+                        .unwrap_or_default(),
+                })
+                .collect(),
+            next_cursor: page
+                .next_cursor
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+        })),
+        Err(PatientError::InvalidInput(msg)) => {
+            tracing::error!("List letters rejected: {}", msg);
+            Err((StatusCode::BAD_REQUEST, "Invalid letter listing request"))
+        }
+        Err(e) => {
+            tracing::error!("List letters error: {:?}", e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"))
+        }
+    }
+}
+
+/// Multipart form for creating a letter with file attachments.
+///
+/// Text fields carry the commit author and care location. `author_name` and
+/// `author_registrations` are accepted but ignored, as in JSON bodies. Each file goes in its
+/// own `attachment` part, and the part's filename is kept as the original filename.
+#[derive(Debug, Default, utoipa::ToSchema)]
+struct LetterAttachmentsForm {
+    author_email: String,
+    author_role: String,
+    care_location: String,
+    author_signature: String,
+    /// Optional Markdown body for the letter
+    content: String,
+    /// Optional: reject the write if the record has changed since this commit
+    expected_commit_id: String,
+    /// Files to attach; repeat the part once per file
+    #[schema(value_type = Vec<String>, format = Binary)]
+    attachment: Vec<(String, Vec<u8>)>,
+}
+
+impl LetterAttachmentsForm {
+    /// Reads the form from a multipart request body.
+    async fn from_multipart(mut multipart: Multipart) -> Result<Self, (StatusCode, &'static str)> {
+        let mut form = Self::default();
+        while let Some(field) = multipart.next_field().await.map_err(|e| {
+            tracing::error!("Invalid multipart body: {:?}", e);
+            (StatusCode::BAD_REQUEST, "Invalid multipart body")
+        })? {
+            let name = field.name().unwrap_or_default().to_string();
+            if name == "attachment" {
+                let filename = field
+                    .file_name()
+                    .map(str::to_string)
+                    .ok_or((StatusCode::BAD_REQUEST, "Attachment is missing a filename"))?;
+                let bytes = field.bytes().await.map_err(|e| {
+                    tracing::error!("Failed to read attachment: {:?}", e);
+                    (StatusCode::BAD_REQUEST, "Invalid attachment")
+                })?;
+                form.attachment.push((filename, bytes.to_vec()));
+                continue;
+            }
+
+            let value = field.text().await.map_err(|e| {
+                tracing::error!("Failed to read form field {}: {:?}", name, e);
+                (StatusCode::BAD_REQUEST, "Invalid form field")
+            })?;
+            match name.as_str() {
+                // Accepted for compatibility; authorship comes from the caller's credential.
+                "author_name" | "author_registrations" => {}
+                "author_email" => form.author_email = value,
+                "author_role" => form.author_role = value,
+                "care_location" => form.care_location = value,
+                "author_signature" => form.author_signature = value,
+                "content" => form.content = value,
+                "expected_commit_id" => form.expected_commit_id = value,
+                _ => return Err((StatusCode::BAD_REQUEST, "Unknown form field")),
+            }
+        }
+        Ok(form)
+    }
+}
+
 #[utoipa::path(
     post,
-    path = "/coordination",
-    request_body = pb::InitialiseCoordinationReq,
+    path = "/clinical/{id}/letters/attachments",
+    request_body(content = LetterAttachmentsForm, content_type = "multipart/form-data"),
     responses(
-        (status = 201, description = "Coordination created", body = pb::InitialiseCoordinationRes),
+        (status = 201, description = "Letter with attachments created", body = pb::NewLetterWithAttachmentsRes),
         (status = 400, description = "Bad request"),
+        (status = 413, description = "Upload too large"),
+        (status = 409, description = "Record changed concurrently"),
         (status = 500, description = "Internal server error")
     )
 )]
 #[axum::debug_handler]
-async fn initialise_coordination(
+async fn new_letter_with_attachments(
     State(state): State<AppState>,
-    Json(req): Json<pb::InitialiseCoordinationReq>,
-) -> Result<Json<pb::InitialiseCoordinationRes>, (StatusCode, &'static str)> {
+    Extension(principal): Extension<Principal>,
+    AxumPath(id): AxumPath<String>,
+    multipart: Multipart,
+) -> Result<Json<pb::NewLetterWithAttachmentsRes>, (StatusCode, &'static str)> {
+    authorize(&state.policy, &principal, Operation::WriteLetter)?;
+
+    let form = LetterAttachmentsForm::from_multipart(multipart).await?;
+
     let author = build_author(
-        req.author_name,
-        req.author_email,
-        req.author_role,
-        req.author_registrations,
-        req.author_signature,
+        &principal,
+        form.author_email,
+        form.author_role,
+        form.author_signature,
     )?;
 
-    let clinical_uuid = match uuid::Uuid::parse_str(&req.clinical_uuid) {
-        Ok(uuid) => uuid,
+    let clinical_uuid = match ShardableUuid::parse(&id) {
+        Ok(uuid) => uuid.uuid(),
         Err(e) => {
             tracing::error!("Invalid clinical UUID: {:?}", e);
             return Err((StatusCode::BAD_REQUEST, "Invalid clinical UUID"));
         }
     };
+    let care_location = NonEmptyText::new(&form.care_location)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
+    let content = if form.content.is_empty() {
+        None
+    } else {
+        Some(
+            NonEmptyText::new(form.content)
+                .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid content"))?,
+        )
+    };
+    if form.attachment.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "At least one attachment is required",
+        ));
+    }
 
-    let coordination_service = CoordinationService::new(state.cfg.clone());
+    let (temp_dir, attachment_paths) = write_attachments_to_temp_dir(
+        form.attachment
+            .iter()
+            .map(|(name, bytes)| (name.as_str(), bytes.as_slice())),
+    )?;
 
-    let care_location = NonEmptyText::new(req.care_location)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
+    let clinical_service = ClinicalService::with_id(state.cfg.clone(), clinical_uuid)
+        .with_expected_commit(expected_commit(&form.expected_commit_id))
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid expected_commit_id"))?;
+    let result =
+        clinical_service.create_letter(&author, care_location, content, &attachment_paths, None);
 
-    match coordination_service.initialise(author, care_location, clinical_uuid) {
-        Ok(service) => Ok(Json(pb::InitialiseCoordinationRes {
-            coordination_uuid: service.coordination_id().to_string(),
+    // Clean up temp files
+    let _ = std::fs::remove_dir_all(&temp_dir);
+
+    match result {
+        Ok(timestamp_id) => Ok(Json(pb::NewLetterWithAttachmentsRes {
+            timestamp_id: timestamp_id.to_string(),
         })),
         Err(e) => {
-            tracing::error!("Initialise coordination error: {:?}", e);
+            tracing::error!("New letter with attachments error: {:?}", e);
             Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"))
         }
     }
 }
 
-// Helper function
-fn build_author(
-    name: String,
-    email: String,
-    role: String,
-    registrations: Vec<pb::AuthorRegistration>,
-    signature: String,
-) -> Result<Author, (StatusCode, &'static str)> {
-    let name =
-        NonEmptyText::new(&name).map_err(|_| (StatusCode::BAD_REQUEST, "Invalid author name"))?;
-    let role =
-        NonEmptyText::new(&role).map_err(|_| (StatusCode::BAD_REQUEST, "Invalid author role"))?;
-    let email = EmailAddress::parse(&email)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid author email"))?;
-
-    Ok(Author {
-        name,
-        email,
-        role,
-        registrations: registrations
-            .into_iter()
-            .map(|r| AuthorRegistration::new(r.authority, r.number).expect("valid registration"))
-            .collect(),
-        signature: if signature.is_empty() {
-            None
-        } else {
-            Some(signature.into_bytes())
-        },
-        certificate: None,
-    })
+#[utoipa::path(
+    get,
+    path = "/clinical/{id}/letters/{letter_id}/attachments",
+    responses(
+        (status = 200, description = "Letter attachments retrieved", body = pb::GetLetterAttachmentsRes),
+        (status = 400, description = "Bad request"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+#[axum::debug_handler]
+async fn get_letter_attachments(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    AxumPath((clinical_uuid, letter_id)): AxumPath<(String, String)>,
+) -> Result<Json<pb::GetLetterAttachmentsRes>, (StatusCode, &'static str)> {
+    authorize(&state.policy, &principal, Operation::ReadLetter)?;
+
+    let clinical_uuid_parsed = match ShardableUuid::parse(&clinical_uuid) {
+        Ok(uuid) => uuid.uuid(),
+        Err(e) => {
+            tracing::error!("Invalid clinical UUID: {:?}", e);
+            return Err((StatusCode::BAD_REQUEST, "Invalid clinical UUID"));
+        }
+    };
+
+    let clinical_service = ClinicalService::with_id(state.cfg.clone(), clinical_uuid_parsed);
+    match clinical_service.get_letter_attachments(&letter_id) {
+        Ok(attachments) => Ok(Json(pb::GetLetterAttachmentsRes {
+            attachments: attachments
+                .into_iter()
+                .map(|att| pb::LetterAttachment {
+                    metadata: Some(pb::AttachmentMetadata {
+                        filename: att.metadata.metadata_filename.to_string(),
+                        original_filename: att.metadata.original_filename.to_string(),
+                        hash: att.metadata.hash.to_string(),
+                        file_storage_path: att.metadata.file_storage_path.to_string(),
+                        size_bytes: att.metadata.size_bytes as i64,
+                        media_type: att
+                            .metadata
+                            .media_type
+                            .map(|mt| mt.to_string())
+                            .unwrap_or_default(),
+                    }),
+                    content: att.content,
+                })
+                .collect(),
+        })),
+        Err(e) => {
+            tracing::error!("Get letter attachments error: {:?}", e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/coordination",
+    request_body = pb::InitialiseCoordinationReq,
+    responses(
+        (status = 201, description = "Coordination created", body = pb::InitialiseCoordinationRes),
+        (status = 400, description = "Bad request"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Record busy, retry later")
+    )
+)]
+#[axum::debug_handler]
+async fn initialise_coordination(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<pb::InitialiseCoordinationReq>,
+) -> Result<Json<pb::InitialiseCoordinationRes>, ApiError> {
+    authorize(&state.policy, &principal, Operation::CreateRecord)?;
+
+    let author = build_author(
+        &principal,
+        req.author_email,
+        req.author_role,
+        req.author_signature,
+    )?;
+
+    let clinical_uuid = match uuid::Uuid::parse_str(&req.clinical_uuid) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            tracing::error!("Invalid clinical UUID: {:?}", e);
+            return Err((StatusCode::BAD_REQUEST, "Invalid clinical UUID").into());
+        }
+    };
+
+    let coordination_service = CoordinationService::new(state.cfg.clone());
+
+    let care_location = NonEmptyText::new(req.care_location)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
+
+    match blocking_write(move || {
+        coordination_service.initialise(author, care_location, clinical_uuid)
+    })
+    .await?
+    {
+        Ok(service) => Ok(Json(pb::InitialiseCoordinationRes {
+            coordination_uuid: service.coordination_id().to_string(),
+        })),
+        Err(e) => Err(write_error("Initialise coordination", e)),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/coordination/{id}/status",
+    request_body = pb::UpdateCoordinationStatusReq,
+    responses(
+        (status = 200, description = "Coordination status updated", body = pb::UpdateCoordinationStatusRes),
+        (status = 400, description = "Bad request"),
+        (status = 409, description = "Record changed concurrently"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Record busy, retry later")
+    )
+)]
+#[axum::debug_handler]
+async fn update_coordination_status(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    AxumPath(id): AxumPath<String>,
+    Json(mut req): Json<pb::UpdateCoordinationStatusReq>,
+) -> Result<Json<pb::UpdateCoordinationStatusRes>, ApiError> {
+    authorize(
+        &state.policy,
+        &principal,
+        Operation::UpdateCoordinationStatus,
+    )?;
+
+    req.coordination_uuid = id;
+
+    let author = build_author(
+        &principal,
+        req.author_email,
+        req.author_role,
+        req.author_signature,
+    )?;
+    let coordination_uuid = parse_coordination_uuid(&req.coordination_uuid)?;
+
+    let set_lifecycle_state = if req.set_lifecycle_state.is_empty() {
+        None
+    } else {
+        Some(parse_lifecycle_state(&req.set_lifecycle_state)?)
+    };
+    let status_update = CoordinationStatusUpdate {
+        set_lifecycle_state,
+        set_record_open: req.set_record_open,
+        set_record_queryable: req.set_record_queryable,
+        set_record_modifiable: req.set_record_modifiable,
+    };
+
+    let care_location = NonEmptyText::new(req.care_location)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
+
+    let coordination_service = CoordinationService::with_id(state.cfg.clone(), coordination_uuid)
+        .with_expected_commit(expected_commit(&req.expected_commit_id))
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid expected_commit_id"))?;
+    match blocking_write(move || {
+        coordination_service.update_coordination_status(&author, care_location, status_update)
+    })
+    .await?
+    {
+        Ok(()) => Ok(Json(pb::UpdateCoordinationStatusRes { success: true })),
+        Err(e) => Err(write_error("Update coordination status", e)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/coordination/{id}/communications",
+    request_body = pb::CreateThreadReq,
+    responses(
+        (status = 201, description = "Communication created", body = pb::CreateThreadRes),
+        (status = 400, description = "Bad request"),
+        (status = 409, description = "Record changed concurrently"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Record busy, retry later")
+    )
+)]
+#[axum::debug_handler]
+async fn create_thread(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    AxumPath(id): AxumPath<String>,
+    Json(mut req): Json<pb::CreateThreadReq>,
+) -> Result<Json<pb::CreateThreadRes>, ApiError> {
+    authorize(&state.policy, &principal, Operation::CreateThread)?;
+
+    req.coordination_uuid = id;
+
+    let author = build_author(
+        &principal,
+        req.author_email,
+        req.author_role,
+        req.author_signature,
+    )?;
+    let coordination_uuid = parse_coordination_uuid(&req.coordination_uuid)?;
+
+    let participants = req
+        .participants
+        .into_iter()
+        .map(parse_message_author)
+        .collect::<Result<Vec<_>, _>>()?;
+    let initial_message_author = req
+        .initial_message_author
+        .map(parse_message_author)
+        .transpose()?;
+    let initial_message_body = NonEmptyText::new(req.initial_message_body)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid initial message body"))?;
+    let message = MessageContent::new(
+        message_author(&principal, initial_message_author.as_ref())?,
+        initial_message_body,
+        None,
+    )
+    .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid message"))?;
+
+    let care_location = NonEmptyText::new(req.care_location)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
+
+    let coordination_service = CoordinationService::with_id(state.cfg.clone(), coordination_uuid)
+        .with_expected_commit(expected_commit(&req.expected_commit_id))
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid expected_commit_id"))?;
+    match blocking_write(move || {
+        coordination_service.communication_create(&author, care_location, participants, message)
+    })
+    .await?
+    {
+        Ok(thread_id) => Ok(Json(pb::CreateThreadRes {
+            thread_id: thread_id.to_string(),
+        })),
+        Err(e) => Err(write_error("Create thread", e)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/coordination/{id}/communications/{thread_id}/messages",
+    request_body = pb::AddMessageReq,
+    responses(
+        (status = 201, description = "Message added", body = pb::AddMessageRes),
+        (status = 400, description = "Bad request"),
+        (status = 409, description = "Record changed concurrently"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Record busy, retry later")
+    )
+)]
+#[axum::debug_handler]
+async fn add_message(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    AxumPath((id, thread_id)): AxumPath<(String, String)>,
+    Json(mut req): Json<pb::AddMessageReq>,
+) -> Result<Json<pb::AddMessageRes>, ApiError> {
+    authorize(&state.policy, &principal, Operation::PostMessage)?;
+
+    req.coordination_uuid = id;
+    req.thread_id = thread_id;
+
+    let author = build_author(
+        &principal,
+        req.author_email,
+        req.author_role,
+        req.author_signature,
+    )?;
+    let coordination_uuid = parse_coordination_uuid(&req.coordination_uuid)?;
+    let thread_id = parse_thread_id(&req.thread_id)?;
+
+    let requested_author = req.message_author.map(parse_message_author).transpose()?;
+    let corrects = if req.corrects.is_empty() {
+        None
+    } else {
+        Some(
+            uuid::Uuid::parse_str(&req.corrects)
+                .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid corrects UUID"))?,
+        )
+    };
+    let message_body = NonEmptyText::new(req.message_body)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid message body"))?;
+    let message = MessageContent::new(
+        message_author(&principal, requested_author.as_ref())?,
+        message_body,
+        corrects,
+    )
+    .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid message"))?;
+
+    let care_location = NonEmptyText::new(req.care_location)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
+
+    let coordination_service = CoordinationService::with_id(state.cfg.clone(), coordination_uuid);
+    let ledger_commit = check_thread_access(
+        &state,
+        &principal,
+        &coordination_service,
+        &thread_id,
+        ThreadAccess::Write,
+    )?;
+    let coordination_service = coordination_service
+        .with_expected_commit(Some(
+            expected_commit(&req.expected_commit_id).unwrap_or(&ledger_commit),
+        ))
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid expected_commit_id"))?;
+    match blocking_write(move || {
+        coordination_service.message_add(&author, care_location, &thread_id, message)
+    })
+    .await?
+    {
+        Ok(message_id) => Ok(Json(pb::AddMessageRes {
+            message_id: message_id.to_string(),
+        })),
+        Err(e) => Err(write_error("Add message", e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/coordination/{id}/communications/{thread_id}",
+    responses(
+        (status = 200, description = "Communication retrieved", body = pb::ReadCommunicationRes),
+        (status = 400, description = "Bad request"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+#[axum::debug_handler]
+async fn read_communication(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    AxumPath((id, thread_id)): AxumPath<(String, String)>,
+) -> Result<Json<pb::ReadCommunicationRes>, (StatusCode, &'static str)> {
+    authorize(&state.policy, &principal, Operation::ReadCommunication)?;
+
+    let coordination_uuid = parse_coordination_uuid(&id)?;
+    let thread_id = parse_thread_id(&thread_id)?;
+
+    let coordination_service = CoordinationService::with_id(state.cfg.clone(), coordination_uuid);
+    match coordination_service.read_communication(&thread_id) {
+        Ok(comm) => {
+            authorize_thread(&state.policy, &principal, &comm.ledger, ThreadAccess::Read)?;
+            Ok(Json(pb::ReadCommunicationRes {
+                communication_id: comm.communication_id.to_string(),
+                ledger: Some(ledger_to_pb(comm.ledger)),
+                messages: comm
+                    .messages
+                    .into_iter()
+                    .map(|msg| pb::Message {
+                        metadata: Some(pb::MessageMetadata {
+                            message_id: msg.metadata.message_id.to_string(),
+                            author: Some(pb::MessageAuthor {
+                                id: msg.metadata.author.id.to_string(),
+                                name: msg.metadata.author.name.to_string(),
+                                role: format!("{:?}", msg.metadata.author.role).to_lowercase(),
+                            }),
+                            timestamp: msg.metadata.timestamp.to_rfc3339(),
+                            corrects: msg.corrects.map(|id| id.to_string()).unwrap_or_default(),
+                        }),
+                        body: msg.body.to_string(),
+                    })
+                    .collect(),
+                commit_id: comm.commit_id,
+            }))
+        }
+        Err(e) => {
+            tracing::error!("Read communication error: {:?}", e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"))
+        }
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/coordination/{id}/communications/{thread_id}/ledger",
+    request_body = pb::UpdateCommunicationLedgerReq,
+    responses(
+        (status = 200, description = "Communication ledger updated", body = pb::UpdateCommunicationLedgerRes),
+        (status = 400, description = "Bad request"),
+        (status = 409, description = "Record changed concurrently"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Record busy, retry later")
+    )
+)]
+#[axum::debug_handler]
+async fn update_communication_ledger(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    AxumPath((id, thread_id)): AxumPath<(String, String)>,
+    Json(mut req): Json<pb::UpdateCommunicationLedgerReq>,
+) -> Result<Json<pb::UpdateCommunicationLedgerRes>, ApiError> {
+    authorize(
+        &state.policy,
+        &principal,
+        Operation::UpdateCommunicationLedger,
+    )?;
+
+    req.coordination_uuid = id;
+    req.thread_id = thread_id;
+
+    let author = build_author(
+        &principal,
+        req.author_email,
+        req.author_role,
+        req.author_signature,
+    )?;
+    let coordination_uuid = parse_coordination_uuid(&req.coordination_uuid)?;
+    let thread_id = parse_thread_id(&req.thread_id)?;
+
+    let add_participants = if req.add_participants.is_empty() {
+        None
+    } else {
+        Some(
+            req.add_participants
+                .into_iter()
+                .map(parse_message_author)
+                .collect::<Result<Vec<_>, _>>()?,
+        )
+    };
+    let remove_participants = if req.remove_participant_ids.is_empty() {
+        None
+    } else {
+        Some(
+            req.remove_participant_ids
+                .iter()
+                .map(|id| uuid::Uuid::parse_str(id))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid participant UUID"))?,
+        )
+    };
+    let set_status = if req.set_status.is_empty() {
+        None
+    } else {
+        Some(parse_thread_status(&req.set_status)?)
+    };
+    let set_visibility = if req.set_sensitivity.is_empty() {
+        None
+    } else {
+        Some((
+            parse_sensitivity_level(&req.set_sensitivity)?,
+            req.set_restricted.unwrap_or(false),
+        ))
+    };
+    let set_policies = match (req.set_allow_patient, req.set_allow_external) {
+        (Some(ap), Some(ae)) => Some((ap, ae)),
+        (Some(ap), None) => Some((ap, true)),
+        (None, Some(ae)) => Some((true, ae)),
+        (None, None) => None,
+    };
+    let ledger_update = LedgerUpdate {
+        add_participants,
+        remove_participants,
+        set_status,
+        set_visibility,
+        set_policies,
+    };
+
+    let care_location = NonEmptyText::new(req.care_location)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
+
+    let coordination_service = CoordinationService::with_id(state.cfg.clone(), coordination_uuid);
+    let ledger_commit = check_thread_access(
+        &state,
+        &principal,
+        &coordination_service,
+        &thread_id,
+        ThreadAccess::Write,
+    )?;
+    let coordination_service = coordination_service
+        .with_expected_commit(Some(
+            expected_commit(&req.expected_commit_id).unwrap_or(&ledger_commit),
+        ))
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid expected_commit_id"))?;
+    match blocking_write(move || {
+        coordination_service.update_communication_ledger(
+            &author,
+            care_location,
+            &thread_id,
+            ledger_update,
+        )
+    })
+    .await?
+    {
+        Ok(()) => Ok(Json(pb::UpdateCommunicationLedgerRes { success: true })),
+        Err(e) => Err(write_error("Update communication ledger", e)),
+    }
+}
+
+/// Query parameters for listing communications.
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+struct ListCommunicationsParams {
+    /// `next_cursor` from the previous page
+    cursor: Option<String>,
+    /// Maximum number of communications to return
+    limit: Option<usize>,
+    /// Only communications with this status (open, closed, archived)
+    status: Option<String>,
+    /// Only communications that include this participant (UUID)
+    participant_id: Option<String>,
+    /// Only communications with this sensitivity (standard, confidential, restricted)
+    sensitivity: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/coordination/{id}/communications",
+    params(ListCommunicationsParams),
+    responses(
+        (status = 200, description = "Page of communications", body = pb::ListCommunicationsRes),
+        (status = 400, description = "Bad request"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+#[axum::debug_handler]
+async fn list_communications(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    AxumPath(coordination_uuid): AxumPath<String>,
+    Query(params): Query<ListCommunicationsParams>,
+) -> Result<Json<pb::ListCommunicationsRes>, (StatusCode, &'static str)> {
+    authorize(&state.policy, &principal, Operation::ReadCommunication)?;
+
+    let coordination_uuid_parsed = parse_coordination_uuid(&coordination_uuid)?;
+
+    let cursor = params
+        .cursor
+        .as_deref()
+        .map(str::parse::<TimestampId>)
+        .transpose()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid cursor"))?;
+    let status = params
+        .status
+        .as_deref()
+        .map(parse_thread_status)
+        .transpose()?;
+    let participant_id = params
+        .participant_id
+        .as_deref()
+        .map(uuid::Uuid::parse_str)
+        .transpose()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid participant ID"))?;
+    let sensitivity = params
+        .sensitivity
+        .as_deref()
+        .map(parse_sensitivity_level)
+        .transpose()?;
+    let query = ListCommunicationsQuery {
+        cursor,
+        limit: params.limit,
+        status,
+        participant_id,
+        sensitivity,
+    };
+
+    let coordination_service =
+        CoordinationService::with_id(state.cfg.clone(), coordination_uuid_parsed);
+    match coordination_service.list_communications(&query) {
+        Ok(page) => Ok(Json(pb::ListCommunicationsRes {
+            // Threads the caller may not read are left out of the page.
+            communications: page
+                .communications
+                .into_iter()
+                .filter(|ledger| state.policy.can_read_thread(&principal, ledger))
+                .map(ledger_to_pb)
+                .collect(),
+            next_cursor: page
+                .next_cursor
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+        })),
+        Err(PatientError::InvalidInput(msg)) => {
+            tracing::error!("List communications rejected: {}", msg);
+            Err((
+                StatusCode::BAD_REQUEST,
+                "Invalid communication listing request",
+            ))
+        }
+        Err(e) => {
+            tracing::error!("List communications error: {:?}", e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"))
+        }
+    }
+}
+
+// Helper functions
+
+/// Builds the commit author for a request.
+///
+/// A bearer-token caller is always recorded as the user named in the token; the author fields
+/// in the request body are ignored. A client-certificate caller is recorded under the
+/// certificate's name and registration, with role and email from the body. API-key callers
+/// are recorded as the fixed service identity from [`api_key_identity`], whatever the body
+/// says.
+fn build_author(
+    principal: &Principal,
+    email: String,
+    role: String,
+    signature: String,
+) -> Result<Author, (StatusCode, &'static str)> {
+    let signature = if signature.is_empty() {
+        None
+    } else {
+        Some(signature.into_bytes())
+    };
+
+    match principal {
+        Principal::User(identity) => author_from_identity(identity, signature),
+        Principal::ApiKey => author_from_identity(&api_key_identity(), signature),
+        Principal::Certificate(identity) => {
+            author_from_certificate(identity, &role, &email, signature)
+        }
+    }
+}
+
+/// Checks the caller may access a thread, using the thread's current ledger.
+///
+/// Returns the commit the ledger was read at. Writes pin themselves to it as their expected
+/// commit, so a ledger change that lands between this check and the write (removing the
+/// caller, say) makes the write fail instead of being bypassed.
+fn check_thread_access(
+    state: &AppState,
+    principal: &Principal,
+    coordination_service: &CoordinationService<CoordinationInitialised>,
+    thread_id: &TimestampId,
+    access: ThreadAccess,
+) -> Result<String, (StatusCode, &'static str)> {
+    let communication = coordination_service
+        .read_communication(thread_id)
+        .map_err(|e| {
+            tracing::error!("Read communication error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal error")
+        })?;
+    authorize_thread(&state.policy, principal, &communication.ledger, access)?;
+    Ok(communication.commit_id)
+}
+
+/// Writes uploaded attachments to a fresh temporary directory.
+///
+/// Only the final component of each supplied filename is used, so a filename cannot
+/// place a file outside the temporary directory. The caller removes the directory
+/// once the letter has been written.
+fn write_attachments_to_temp_dir<'a>(
+    attachments: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+) -> Result<(PathBuf, Vec<PathBuf>), (StatusCode, &'static str)> {
+    let temp_dir = std::env::temp_dir().join(format!("vpr_attachments_{}", uuid::Uuid::new_v4()));
+    if let Err(e) = std::fs::create_dir_all(&temp_dir) {
+        tracing::error!("Failed to create temp dir: {:?}", e);
+        return Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"));
+    }
+
+    let mut attachment_paths = Vec::new();
+    for (i, (name, content)) in attachments.into_iter().enumerate() {
+        let Some(file_name) = Path::new(name).file_name() else {
+            let _ = std::fs::remove_dir_all(&temp_dir);
+            return Err((StatusCode::BAD_REQUEST, "Invalid attachment filename"));
+        };
+        let file_path = temp_dir.join(format!("{}_{}", i, file_name.to_string_lossy()));
+        if let Err(e) = std::fs::write(&file_path, content) {
+            tracing::error!("Failed to write attachment: {:?}", e);
+            let _ = std::fs::remove_dir_all(&temp_dir);
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"));
+        }
+        attachment_paths.push(file_path);
+    }
+
+    Ok((temp_dir, attachment_paths))
+}
+
+/// Returns the commit a conditional write expects, or `None` if the request names none.
+fn expected_commit(commit_id: &str) -> Option<&str> {
+    (!commit_id.is_empty()).then_some(commit_id)
+}
+
+fn parse_coordination_uuid(id: &str) -> Result<uuid::Uuid, (StatusCode, &'static str)> {
+    match ShardableUuid::parse(id) {
+        Ok(uuid) => Ok(uuid.uuid()),
+        Err(e) => {
+            tracing::error!("Invalid coordination UUID: {:?}", e);
+            Err((StatusCode::BAD_REQUEST, "Invalid coordination UUID"))
+        }
+    }
+}
+
+fn parse_thread_id(thread_id: &str) -> Result<TimestampId, (StatusCode, &'static str)> {
+    thread_id
+        .parse()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid thread ID"))
+}
+
+fn parse_message_author(
+    author: pb::MessageAuthor,
+) -> Result<FhirMessageAuthor, (StatusCode, &'static str)> {
+    Ok(FhirMessageAuthor {
+        id: uuid::Uuid::parse_str(&author.id)
+            .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid message author UUID"))?,
+        name: NonEmptyText::new(author.name)
+            .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid message author name"))?,
+        role: parse_author_role(&author.role)?,
+    })
+}
+
+fn parse_author_role(role: &str) -> Result<AuthorRole, (StatusCode, &'static str)> {
+    match role.to_lowercase().as_str() {
+        "clinician" => Ok(AuthorRole::Clinician),
+        "careadministrator" | "care_administrator" | "care-administrator" => {
+            Ok(AuthorRole::CareAdministrator)
+        }
+        "patient" => Ok(AuthorRole::Patient),
+        "patientassociate" | "patient_associate" | "patient-associate" => {
+            Ok(AuthorRole::PatientAssociate)
+        }
+        "system" => Ok(AuthorRole::System),
+        _ => Err((StatusCode::BAD_REQUEST, "Invalid role")),
+    }
+}
+
+fn parse_thread_status(status: &str) -> Result<FhirThreadStatus, (StatusCode, &'static str)> {
+    match status.to_lowercase().as_str() {
+        "open" => Ok(FhirThreadStatus::Open),
+        "closed" => Ok(FhirThreadStatus::Closed),
+        "archived" => Ok(FhirThreadStatus::Archived),
+        _ => Err((StatusCode::BAD_REQUEST, "Invalid status")),
+    }
+}
+
+fn parse_sensitivity_level(
+    sensitivity: &str,
+) -> Result<SensitivityLevel, (StatusCode, &'static str)> {
+    match sensitivity.to_lowercase().as_str() {
+        "standard" => Ok(SensitivityLevel::Standard),
+        "confidential" => Ok(SensitivityLevel::Confidential),
+        "restricted" => Ok(SensitivityLevel::Restricted),
+        _ => Err((StatusCode::BAD_REQUEST, "Invalid sensitivity")),
+    }
+}
+
+fn parse_lifecycle_state(state: &str) -> Result<LifecycleState, (StatusCode, &'static str)> {
+    match state.to_lowercase().as_str() {
+        "active" => Ok(LifecycleState::Active),
+        "suspended" => Ok(LifecycleState::Suspended),
+        "closed" => Ok(LifecycleState::Closed),
+        _ => Err((StatusCode::BAD_REQUEST, "Invalid lifecycle state")),
+    }
+}
+
+fn ledger_to_pb(ledger: LedgerData) -> pb::Ledger {
+    pb::Ledger {
+        communication_id: ledger.communication_id.to_string(),
+        status: format!("{:?}", ledger.status).to_lowercase(),
+        participants: ledger
+            .participants
+            .into_iter()
+            .map(|p| pb::LedgerParticipant {
+                id: p.id.to_string(),
+                name: p.name.to_string(),
+                role: format!("{:?}", p.role).to_lowercase(),
+            })
+            .collect(),
+        sensitivity: format!("{:?}", ledger.sensitivity).to_lowercase(),
+        restricted: ledger.restricted,
+        allow_patient_participation: ledger.allow_patient_participation,
+        allow_external_organisations: ledger.allow_external_organisations,
+        created_at: ledger.created_at.to_rfc3339(),
+        last_updated_at: ledger.last_updated_at.to_rfc3339(),
+    }
 }
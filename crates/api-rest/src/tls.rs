@@ -0,0 +1,285 @@
+//! TLS termination for the REST API.
+//!
+//! ## Purpose
+//! `axum::serve` only accepts plain TCP connections. This module terminates TLS with
+//! `rustls`, optionally verifying client certificates against the configured CA bundle, and
+//! then serves the axum router over each connection.
+//!
+//! ## Intended use
+//! Call [`serve_tls`] in place of `axum::serve` when [`TlsSettings::from_env`] returns
+//! settings. A verified client certificate is added to each request's extensions as a
+//! [`ClientCertificate`], where [`crate::auth::require_authentication`] picks it up.
+//!
+//! [`TlsSettings::from_env`]: api_shared::tls::TlsSettings::from_env
+
+use api_shared::tls::TlsSettings;
+use axum::{extract::Request, Router};
+use hyper::body::Incoming;
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto,
+    service::TowerToHyperService,
+};
+use rustls::{
+    crypto::ring,
+    pki_types::{CertificateDer, PrivateKeyDer},
+    server::WebPkiClientVerifier,
+    RootCertStore, ServerConfig,
+};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+use tower::ServiceExt;
+
+/// DER-encoded client certificate verified during the TLS handshake.
+#[derive(Clone, Debug)]
+pub struct ClientCertificate(pub Vec<u8>);
+
+/// Builds the `rustls` server configuration from TLS settings.
+///
+/// # Errors
+/// Returns a description of the problem if the certificate, key or client CA bundle cannot be
+/// parsed, or if `rustls` rejects them.
+pub fn server_config(settings: &TlsSettings) -> Result<ServerConfig, String> {
+    let provider = Arc::new(ring::default_provider());
+
+    let cert_chain = parse_certificates(&settings.cert_pem)?;
+    let key: PrivateKeyDer<'static> = rustls_pemfile::private_key(&mut settings.key_pem.as_slice())
+        .map_err(|e| format!("Failed to read TLS private key: {e}"))?
+        .ok_or("TLS key file contains no private key")?;
+
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("Failed to configure TLS: {e}"))?;
+
+    let builder = match &settings.client_ca_pem {
+        Some(ca_pem) => {
+            let mut roots = RootCertStore::empty();
+            for cert in parse_certificates(ca_pem)? {
+                roots
+                    .add(cert)
+                    .map_err(|e| format!("Invalid client CA certificate: {e}"))?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+            let verifier = if settings.client_auth_required {
+                verifier
+            } else {
+                verifier.allow_unauthenticated()
+            };
+            builder.with_client_cert_verifier(
+                verifier
+                    .build()
+                    .map_err(|e| format!("Failed to configure client verification: {e}"))?,
+            )
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut config = builder
+        .with_single_cert(cert_chain, key)
+        .map_err(|e| format!("Invalid TLS certificate or key: {e}"))?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+/// Serves `app` over TLS on `listener` until the listener fails.
+///
+/// Each connection is handshaken and served on its own task, so a slow or failed handshake
+/// does not hold up other clients.
+///
+/// # Errors
+/// Returns an error if the TLS configuration is invalid or the listener stops accepting
+/// connections.
+pub async fn serve_tls(
+    listener: TcpListener,
+    app: Router,
+    settings: &TlsSettings,
+) -> anyhow::Result<()> {
+    let acceptor = TlsAcceptor::from(Arc::new(
+        server_config(settings).map_err(anyhow::Error::msg)?,
+    ));
+
+    loop {
+        let (stream, remote) = listener.accept().await?;
+        let acceptor = acceptor.clone();
+        let app = app.clone();
+
+        tokio::spawn(async move {
+            let stream = match acceptor.accept(stream).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    tracing::debug!("TLS handshake with {} failed: {}", remote, e);
+                    return;
+                }
+            };
+            let client_certificate = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .and_then(|certs| certs.first())
+                .map(|cert| ClientCertificate(cert.to_vec()));
+
+            let service = tower::service_fn(move |mut req: Request<Incoming>| {
+                if let Some(cert) = &client_certificate {
+                    req.extensions_mut().insert(cert.clone());
+                }
+                app.clone().oneshot(req)
+            });
+
+            if let Err(e) = auto::Builder::new(TokioExecutor::new())
+                .serve_connection(TokioIo::new(stream), TowerToHyperService::new(service))
+                .await
+            {
+                tracing::debug!("Connection from {} closed with error: {}", remote, e);
+            }
+        });
+    }
+}
+
+fn parse_certificates(pem: &[u8]) -> Result<Vec<CertificateDer<'static>>, String> {
+    let certs = rustls_pemfile::certs(&mut &pem[..])
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read TLS certificates: {e}"))?;
+    if certs.is_empty() {
+        return Err("TLS certificate file contains no certificates".into());
+    }
+    Ok(certs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::require_authentication;
+    use api_shared::auth::Principal;
+    use axum::{middleware, routing::get, Extension};
+    use rcgen::{
+        BasicConstraints, CertificateParams, DnType, IsCa, KeyPair, KeyUsagePurpose, SanType,
+    };
+    use rustls::{pki_types::ServerName, ClientConfig};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_rustls::TlsConnector;
+
+    struct Pki {
+        ca_pem: String,
+        server: (String, String),
+        client: (String, String),
+    }
+
+    fn pki() -> Pki {
+        let ca_key = KeyPair::generate().unwrap();
+        let mut ca_params = CertificateParams::default();
+        ca_params
+            .distinguished_name
+            .push(DnType::CommonName, "Test CA");
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        ca_params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+        let ca = ca_params.self_signed(&ca_key).unwrap();
+
+        let server_key = KeyPair::generate().unwrap();
+        let server = CertificateParams::new(vec!["localhost".to_string()])
+            .unwrap()
+            .signed_by(&server_key, &ca, &ca_key)
+            .unwrap();
+
+        let client_key = KeyPair::generate().unwrap();
+        let mut client_params = CertificateParams::default();
+        client_params
+            .distinguished_name
+            .push(DnType::CommonName, "Dr Jane Smith");
+        client_params.subject_alt_names.push(SanType::URI(
+            "vpr://GMC/1234567".to_string().try_into().unwrap(),
+        ));
+        let client = client_params.signed_by(&client_key, &ca, &ca_key).unwrap();
+
+        Pki {
+            ca_pem: ca.pem(),
+            server: (server.pem(), server_key.serialize_pem()),
+            client: (client.pem(), client_key.serialize_pem()),
+        }
+    }
+
+    async fn start(pki: &Pki, client_auth_required: bool) -> std::net::SocketAddr {
+        let settings = TlsSettings {
+            cert_pem: pki.server.0.clone().into_bytes(),
+            key_pem: pki.server.1.clone().into_bytes(),
+            client_ca_pem: Some(pki.ca_pem.clone().into_bytes()),
+            client_auth_required,
+        };
+        let app = Router::new()
+            .route(
+                "/whoami",
+                get(|Extension(principal): Extension<Principal>| async move {
+                    format!("{principal:?}")
+                }),
+            )
+            .layer(middleware::from_fn(require_authentication));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { serve_tls(listener, app, &settings).await });
+        addr
+    }
+
+    async fn get_whoami(
+        addr: std::net::SocketAddr,
+        pki: &Pki,
+        with_client_cert: bool,
+    ) -> std::io::Result<String> {
+        let mut roots = RootCertStore::empty();
+        roots
+            .add(parse_certificates(pki.ca_pem.as_bytes()).unwrap().remove(0))
+            .unwrap();
+        let builder = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots);
+        let config = if with_client_cert {
+            let key = rustls_pemfile::private_key(&mut pki.client.1.as_bytes())
+                .unwrap()
+                .unwrap();
+            builder
+                .with_client_auth_cert(parse_certificates(pki.client.0.as_bytes()).unwrap(), key)
+                .unwrap()
+        } else {
+            builder.with_no_client_auth()
+        };
+
+        let tcp = tokio::net::TcpStream::connect(addr).await?;
+        let mut tls = TlsConnector::from(Arc::new(config))
+            .connect(ServerName::try_from("localhost").unwrap(), tcp)
+            .await?;
+        tls.write_all(b"GET /whoami HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await?;
+        let mut response = String::new();
+        tls.read_to_string(&mut response).await?;
+        Ok(response)
+    }
+
+    #[tokio::test]
+    async fn client_certificate_authenticates_request() {
+        let pki = pki();
+        let addr = start(&pki, false).await;
+
+        let response = get_whoami(addr, &pki, true).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.contains("Dr Jane Smith"), "{response}");
+        assert!(response.contains("1234567"), "{response}");
+
+        // Without a certificate the connection is accepted but the request needs credentials.
+        let response = get_whoami(addr, &pki, false).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 401"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn required_client_auth_rejects_anonymous_clients() {
+        let pki = pki();
+        let addr = start(&pki, true).await;
+
+        assert!(get_whoami(addr, &pki, true).await.is_ok());
+        let anonymous = get_whoami(addr, &pki, false).await;
+        assert!(
+            anonymous.is_err() || !anonymous.unwrap().starts_with("HTTP/1.1 200"),
+            "anonymous client was served"
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! Running core writes from REST handlers.
+//!
+//! ## Purpose
+//! `vpr-core` writes are blocking: each takes the repository write lock, waiting for it by
+//! sleeping, and then commits with `git2`. This module moves that work off the async workers
+//! and maps the write's failure to an HTTP response.
+//!
+//! ## Intended use
+//! Handlers run each core write with [`blocking_write`] and map a failed write with
+//! [`write_error`]. Handlers that do so return [`ApiError`], which accepts the
+//! `(StatusCode, &'static str)` pairs used elsewhere through `?`.
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use vpr_core::error::{PatientError, PatientResult};
+
+/// Error response from a write handler: a status code and a plain-text message.
+///
+/// Unlike the `(StatusCode, &'static str)` pairs used elsewhere, the message can be built at
+/// run time, so conflict responses can say what actually conflicted.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ApiError(pub StatusCode, pub String);
+
+impl From<(StatusCode, &'static str)> for ApiError {
+    fn from((status, message): (StatusCode, &'static str)) -> Self {
+        ApiError(status, message.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, self.1).into_response()
+    }
+}
+
+/// Runs a core write on Tokio's blocking pool.
+///
+/// Writes wait for the repository write lock by sleeping, which must not stall the async
+/// workers. Failures from the write itself are returned for [`write_error`] to map.
+///
+/// # Errors
+/// Returns `500 Internal Server Error` if the write task panics or is cancelled.
+pub async fn blocking_write<T, F>(write: F) -> Result<PatientResult<T>, (StatusCode, &'static str)>
+where
+    T: Send + 'static,
+    F: FnOnce() -> PatientResult<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(write).await.map_err(|e| {
+        tracing::error!("Write task error: {:?}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, "Internal error")
+    })
+}
+
+/// Maps a failed core write to an error response.
+///
+/// A write against a record that moved on is a 409 and a write that timed out waiting for the
+/// repository lock is a 503, both carrying the error's own message; the client may retry
+/// either. Anything else is logged under `context` and returned as a bare 500.
+pub fn write_error(context: &str, e: PatientError) -> ApiError {
+    match e {
+        PatientError::ConcurrentModification { .. } => {
+            tracing::warn!("{} rejected: {}", context, e);
+            ApiError(StatusCode::CONFLICT, e.to_string())
+        }
+        PatientError::RepositoryLockTimeout(_) => {
+            tracing::warn!("{} rejected: {}", context, e);
+            ApiError(StatusCode::SERVICE_UNAVAILABLE, e.to_string())
+        }
+        e => {
+            tracing::error!("{} error: {:?}", context, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal error").into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_errors_map_to_retryable_statuses() {
+        let conflict = PatientError::ConcurrentModification {
+            expected: "a".repeat(40),
+            actual: "b".repeat(40),
+        };
+        let message = conflict.to_string();
+        assert_eq!(
+            write_error("Add message", conflict),
+            ApiError(StatusCode::CONFLICT, message)
+        );
+
+        let timeout = PatientError::RepositoryLockTimeout("/records/abc".into());
+        assert_eq!(
+            write_error("Add message", timeout).0,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        let other = PatientError::InvalidInput("secret detail".into());
+        assert_eq!(
+            write_error("Add message", other),
+            ApiError(StatusCode::INTERNAL_SERVER_ERROR, "Internal error".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn blocking_write_returns_the_write_result() {
+        let result = blocking_write(|| Ok(42)).await.unwrap();
+        assert_eq!(result.unwrap(), 42);
+    }
+}
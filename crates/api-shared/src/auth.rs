@@ -1,12 +1,225 @@
 //! Authentication helpers shared by the gRPC and REST APIs.
 //!
 //! ## Purpose
-//! Provides simple API key validation used by API front-ends.
+//! Provides the credential checks used by API front-ends:
+//! - a shared API key, compared against the `API_KEY` environment variable,
+//! - bearer JWTs, verified against a locally configured JWKS file or issuer public key, and
+//! - client certificates presented over mutual TLS (see [`crate::tls`]).
+//!
+//! A verified bearer token or client certificate identifies an individual professional.
+//! Front-ends take commit authorship from the token's claims or the certificate rather than
+//! from the request body, so callers cannot record commits under someone else's name.
 //!
 //! ## Intended use
 //! This module contains API-level authentication utilities only. It is not used by `vpr-core`.
+//! Front-ends call [`authenticate`] with the raw `authorization` and `x-api-key` header values
+//! and the client certificate, if any, and convert a [`Principal::User`] or
+//! [`Principal::Certificate`] into their own author type. API-key callers are recorded as
+//! the service identity from [`api_key_identity`]. Messages posted to communication threads
+//! are likewise attributed with [`message_author`].
 
+use crate::policy::{roles, Role};
+use crate::tls::{certificate_identity, CertificateIdentity};
+use fhir::{AuthorRole, MessageAuthor};
+use jsonwebtoken::{jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
 use std::env;
+use uuid::Uuid;
+use vpr_types::NonEmptyText;
+
+/// Environment variable naming a JWKS file whose keys verify bearer tokens.
+pub const JWT_JWKS_PATH_ENV: &str = "JWT_JWKS_PATH";
+
+/// Environment variable naming a PEM public key that verifies bearer tokens.
+///
+/// Only consulted when [`JWT_JWKS_PATH_ENV`] is not set.
+pub const JWT_PUBLIC_KEY_PATH_ENV: &str = "JWT_PUBLIC_KEY_PATH";
+
+/// Environment variable holding the required `iss` claim, if any.
+pub const JWT_ISSUER_ENV: &str = "JWT_ISSUER";
+
+/// Environment variable holding the required `aud` claim, if any.
+pub const JWT_AUDIENCE_ENV: &str = "JWT_AUDIENCE";
+
+/// The caller behind an authenticated request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Principal {
+    /// A client holding the shared API key.
+    ///
+    /// The key does not identify a person, so its commits are recorded under the fixed
+    /// service identity from [`api_key_identity`] rather than any author named in the request.
+    ApiKey,
+    /// A user identified by a verified bearer token.
+    User(UserIdentity),
+    /// A professional identified by a client certificate verified during the TLS handshake.
+    ///
+    /// Commit authorship takes the name and registration from the certificate; role and email,
+    /// which certificates do not carry, come from the request.
+    Certificate(CertificateIdentity),
+}
+
+/// User identity carried in the claims of a verified bearer token.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct UserIdentity {
+    /// Stable subject identifier from the `sub` claim.
+    #[serde(rename = "sub")]
+    pub subject: String,
+    /// Display name from the `name` claim.
+    pub name: String,
+    /// Professional role from the `role` claim.
+    pub role: String,
+    /// Email address from the `email` claim.
+    pub email: String,
+    /// Professional registrations from the `registrations` claim.
+    #[serde(default)]
+    pub registrations: Vec<RegistrationClaim>,
+    /// Access roles from the `roles` claim, interpreted by [`crate::policy`].
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+/// Returns the identity recorded as the commit author for API-key requests.
+///
+/// The shared key cannot say who is acting, so every API-key write is attributed to this
+/// service identity. Author fields in the request body are never used in its place.
+pub fn api_key_identity() -> UserIdentity {
+    UserIdentity {
+        subject: "api-key".into(),
+        name: "VPR API key client".into(),
+        role: "System".into(),
+        email: "api-key@vpr.invalid".into(),
+        registrations: vec![],
+        roles: vec!["system".into()],
+    }
+}
+
+impl Principal {
+    /// Returns the id under which the caller takes part in communication threads.
+    ///
+    /// A bearer-token user is identified by their `sub` claim, if it is a UUID, and a certificate
+    /// holder by [`CertificateIdentity::participant_id`]. API-key callers do not identify a
+    /// person and have no participant id.
+    pub fn participant_id(&self) -> Option<Uuid> {
+        match self {
+            Principal::User(identity) => Uuid::parse_str(&identity.subject).ok(),
+            Principal::Certificate(identity) => Some(identity.participant_id()),
+            Principal::ApiKey => None,
+        }
+    }
+}
+
+/// Returns the thread participant a caller posts messages as.
+///
+/// Messages are attributed from the caller's credentials, never from the request body:
+/// - a bearer-token user posts under [`Principal::participant_id`] and their `name` claim, with
+///   the participant role matching their access roles (clinician, then administrator, then
+///   patient, then system),
+/// - a certificate holder posts under their certificate's participant id and common name as a
+///   clinician,
+/// - API-key callers post as the service identity from [`api_key_identity`], under the nil
+///   UUID.
+///
+/// A request may still name the message author, but only as a check: its id and role must
+/// match the caller.
+///
+/// # Errors
+/// Returns `PERMISSION_DENIED` if the caller has no participant id, name or role to post
+/// under, or if `requested` names someone else.
+#[allow(clippy::result_large_err)]
+pub fn message_author(
+    principal: &Principal,
+    requested: Option<&MessageAuthor>,
+) -> Result<MessageAuthor, tonic::Status> {
+    let (id, name) = match principal {
+        Principal::ApiKey => (Uuid::nil(), api_key_identity().name),
+        Principal::User(identity) => (
+            principal.participant_id().ok_or_else(|| {
+                tonic::Status::permission_denied("Token subject is not a participant UUID")
+            })?,
+            identity.name.clone(),
+        ),
+        Principal::Certificate(identity) => {
+            (identity.participant_id(), identity.common_name.clone())
+        }
+    };
+    let held = roles(principal);
+    let role = [
+        (Role::Clinician, AuthorRole::Clinician),
+        (Role::Administrator, AuthorRole::CareAdministrator),
+        (Role::Patient, AuthorRole::Patient),
+        (Role::System, AuthorRole::System),
+    ]
+    .into_iter()
+    .find_map(|(role, author_role)| held.contains(&role).then_some(author_role))
+    .ok_or_else(|| tonic::Status::permission_denied("No role to post messages under"))?;
+    let name = NonEmptyText::new(name)
+        .map_err(|_| tonic::Status::permission_denied("No name to post messages under"))?;
+
+    if let Some(requested) = requested {
+        if requested.id != id || requested.role != role {
+            return Err(tonic::Status::permission_denied(
+                "Message author does not match the authenticated caller",
+            ));
+        }
+    }
+
+    Ok(MessageAuthor { id, name, role })
+}
+
+/// A professional registration listed in a bearer token.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RegistrationClaim {
+    pub authority: String,
+    pub number: String,
+}
+
+/// Authenticates a request from its credentials.
+///
+/// Credentials are considered in order: a bearer token, then a client certificate, then an
+/// API key. When an `authorization` header is present it must carry a valid bearer token,
+/// and the other credentials are ignored; likewise a client certificate must identify a
+/// professional, and any API key is then ignored.
+///
+/// # Arguments
+/// * `authorization` - Value of the `authorization` header, if present
+/// * `client_certificate` - DER of the client certificate verified by the TLS layer, if any
+/// * `api_key` - Value of the `x-api-key` header, if present
+///
+/// # Returns
+/// The authenticated [`Principal`].
+///
+/// # Errors
+/// Returns `tonic::Status` if:
+/// - no credential is present (`UNAUTHENTICATED`),
+/// - the bearer token is rejected by [`validate_bearer_token`],
+/// - the client certificate carries no usable identity (`UNAUTHENTICATED`),
+/// - the API key is rejected by [`validate_api_key`].
+#[allow(clippy::result_large_err)]
+pub fn authenticate(
+    authorization: Option<&str>,
+    client_certificate: Option<&[u8]>,
+    api_key: Option<&str>,
+) -> Result<Principal, tonic::Status> {
+    if let Some(authorization) = authorization {
+        let token = authorization
+            .strip_prefix("Bearer ")
+            .ok_or_else(|| tonic::Status::unauthenticated("Expected a Bearer token"))?;
+        return validate_bearer_token(token.trim()).map(Principal::User);
+    }
+
+    if let Some(der) = client_certificate {
+        return certificate_identity(der)
+            .map(Principal::Certificate)
+            .map_err(tonic::Status::unauthenticated);
+    }
+
+    match api_key {
+        Some(api_key) => validate_api_key(api_key).map(|()| Principal::ApiKey),
+        None => Err(tonic::Status::unauthenticated(
+            "Missing x-api-key header or bearer token",
+        )),
+    }
+}
 
 /// Validates the provided API key against the expected API key from environment.
 ///
@@ -37,3 +250,321 @@ pub fn validate_api_key(provided_key: &str) -> Result<(), tonic::Status> {
         Err(tonic::Status::unauthenticated("Invalid API key"))
     }
 }
+
+/// Verifies a bearer JWT and returns the identity in its claims.
+///
+/// The verification key is read from the JWKS file named by `JWT_JWKS_PATH` or, failing
+/// that, the PEM public key named by `JWT_PUBLIC_KEY_PATH`. With a JWKS file the token's
+/// `kid` header selects the key; a file holding a single key may be used without one.
+/// Only asymmetric algorithms are accepted. Expiry is always checked, and `iss`/`aud` are
+/// checked when `JWT_ISSUER`/`JWT_AUDIENCE` are set.
+///
+/// # Arguments
+/// * `token` - The encoded JWT, without the `Bearer ` prefix
+///
+/// # Returns
+/// The [`UserIdentity`] from the token's claims.
+///
+/// # Errors
+/// Returns `tonic::Status` if:
+/// - no verification key is configured (`UNAUTHENTICATED`),
+/// - the configured key file cannot be read or parsed (`INTERNAL`),
+/// - the token is malformed, uses an unsupported algorithm, names an unknown key, fails
+///   signature or claim validation, or lacks an identity claim (`UNAUTHENTICATED`).
+#[allow(clippy::result_large_err)]
+pub fn validate_bearer_token(token: &str) -> Result<UserIdentity, tonic::Status> {
+    let header = jsonwebtoken::decode_header(token)
+        .map_err(|_| tonic::Status::unauthenticated("Malformed bearer token"))?;
+    if matches!(
+        header.alg,
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+    ) {
+        return Err(tonic::Status::unauthenticated(
+            "Bearer token algorithm is not supported",
+        ));
+    }
+
+    let key = decoding_key(header.alg, header.kid.as_deref())?;
+
+    let mut validation = Validation::new(header.alg);
+    if let Ok(issuer) = env::var(JWT_ISSUER_ENV) {
+        validation.set_issuer(&[issuer]);
+    }
+    match env::var(JWT_AUDIENCE_ENV) {
+        Ok(audience) => validation.set_audience(&[audience]),
+        Err(_) => validation.validate_aud = false,
+    }
+
+    jsonwebtoken::decode::<UserIdentity>(token, &key, &validation)
+        .map(|data| data.claims)
+        .map_err(|_| tonic::Status::unauthenticated("Invalid bearer token"))
+}
+
+/// Loads the configured key that verifies tokens signed with `alg`.
+#[allow(clippy::result_large_err)]
+fn decoding_key(alg: Algorithm, kid: Option<&str>) -> Result<DecodingKey, tonic::Status> {
+    if let Ok(path) = env::var(JWT_JWKS_PATH_ENV) {
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| tonic::Status::internal(format!("Failed to read JWKS file: {e}")))?;
+        let jwks: JwkSet = serde_json::from_str(&contents)
+            .map_err(|e| tonic::Status::internal(format!("Failed to parse JWKS file: {e}")))?;
+
+        let jwk = match (kid, jwks.keys.as_slice()) {
+            (Some(kid), _) => jwks.find(kid),
+            (None, [only]) => Some(only),
+            (None, _) => None,
+        }
+        .ok_or_else(|| tonic::Status::unauthenticated("Unknown bearer token key"))?;
+
+        if jwk
+            .common
+            .key_algorithm
+            .is_some_and(|key_alg| key_alg.to_string() != format!("{alg:?}"))
+        {
+            return Err(tonic::Status::unauthenticated(
+                "Bearer token algorithm does not match its key",
+            ));
+        }
+
+        return DecodingKey::from_jwk(jwk)
+            .map_err(|_| tonic::Status::unauthenticated("Unusable bearer token key"));
+    }
+
+    let path = env::var(JWT_PUBLIC_KEY_PATH_ENV)
+        .map_err(|_| tonic::Status::unauthenticated("Bearer tokens are not accepted"))?;
+    let pem = std::fs::read(&path)
+        .map_err(|e| tonic::Status::internal(format!("Failed to read JWT public key: {e}")))?;
+
+    match alg {
+        Algorithm::ES256 | Algorithm::ES384 => DecodingKey::from_ec_pem(&pem),
+        Algorithm::EdDSA => DecodingKey::from_ed_pem(&pem),
+        _ => DecodingKey::from_rsa_pem(&pem),
+    }
+    .map_err(|_| tonic::Status::unauthenticated("Bearer token algorithm does not match its key"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+    use jsonwebtoken::{EncodingKey, Header};
+    use std::sync::Mutex;
+
+    /// Serialises tests that configure authentication through environment variables.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    struct Issuer {
+        key_pair: rcgen::KeyPair,
+        _dir: tempfile::TempDir,
+    }
+
+    impl Issuer {
+        /// Creates a P-256 issuer key and points the JWT environment at it.
+        fn configure(use_jwks: bool) -> Self {
+            let key_pair = rcgen::KeyPair::generate().unwrap();
+            let dir = tempfile::tempdir().unwrap();
+
+            env::remove_var(JWT_ISSUER_ENV);
+            env::remove_var(JWT_AUDIENCE_ENV);
+            if use_jwks {
+                // Uncompressed SEC1 point: 0x04 || x || y.
+                let point = key_pair.public_key_raw();
+                let jwks = serde_json::json!({
+                    "keys": [{
+                        "kty": "EC",
+                        "crv": "P-256",
+                        "kid": "issuer-key-1",
+                        "alg": "ES256",
+                        "x": URL_SAFE_NO_PAD.encode(&point[1..33]),
+                        "y": URL_SAFE_NO_PAD.encode(&point[33..65]),
+                    }]
+                });
+                let path = dir.path().join("jwks.json");
+                std::fs::write(&path, jwks.to_string()).unwrap();
+                env::remove_var(JWT_PUBLIC_KEY_PATH_ENV);
+                env::set_var(JWT_JWKS_PATH_ENV, path);
+            } else {
+                let path = dir.path().join("issuer.pem");
+                std::fs::write(&path, key_pair.public_key_pem()).unwrap();
+                env::remove_var(JWT_JWKS_PATH_ENV);
+                env::set_var(JWT_PUBLIC_KEY_PATH_ENV, path);
+            }
+
+            Self {
+                key_pair,
+                _dir: dir,
+            }
+        }
+
+        fn token(&self, kid: Option<&str>, claims: serde_json::Value) -> String {
+            let mut header = Header::new(Algorithm::ES256);
+            header.kid = kid.map(str::to_string);
+            let key = EncodingKey::from_ec_pem(self.key_pair.serialize_pem().as_bytes()).unwrap();
+            jsonwebtoken::encode(&header, &claims, &key).unwrap()
+        }
+    }
+
+    fn claims(expires_in_secs: i64) -> serde_json::Value {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        serde_json::json!({
+            "sub": "user-123",
+            "name": "Dr Jane Smith",
+            "role": "Consultant",
+            "email": "jane.smith@example.org",
+            "registrations": [{ "authority": "GMC", "number": "1234567" }],
+            "roles": ["clinician"],
+            "iss": "https://idp.example.org",
+            "exp": now + expires_in_secs,
+        })
+    }
+
+    #[test]
+    fn validates_token_signed_by_configured_public_key() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let issuer = Issuer::configure(false);
+
+        let identity = validate_bearer_token(&issuer.token(None, claims(300))).unwrap();
+        assert_eq!(identity.subject, "user-123");
+        assert_eq!(identity.name, "Dr Jane Smith");
+        assert_eq!(identity.role, "Consultant");
+        assert_eq!(identity.email, "jane.smith@example.org");
+        assert_eq!(
+            identity.registrations,
+            vec![RegistrationClaim {
+                authority: "GMC".into(),
+                number: "1234567".into(),
+            }]
+        );
+        assert_eq!(identity.roles, vec!["clinician".to_string()]);
+
+        env::set_var(JWT_ISSUER_ENV, "https://other-idp.example.org");
+        let err = validate_bearer_token(&issuer.token(None, claims(300))).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+        env::remove_var(JWT_ISSUER_ENV);
+    }
+
+    #[test]
+    fn rejects_expired_foreign_and_incomplete_tokens() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let foreign = Issuer::configure(false);
+        let issuer = Issuer::configure(false);
+
+        let expired = issuer.token(None, claims(-3600));
+        let foreign_token = foreign.token(None, claims(300));
+        let mut incomplete = claims(300);
+        incomplete.as_object_mut().unwrap().remove("email");
+        let incomplete = issuer.token(None, incomplete);
+
+        for token in [expired, foreign_token, incomplete, "not-a-jwt".to_string()] {
+            let err = validate_bearer_token(&token).unwrap_err();
+            assert_eq!(err.code(), tonic::Code::Unauthenticated);
+        }
+    }
+
+    #[test]
+    fn selects_jwks_key_by_kid() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let issuer = Issuer::configure(true);
+
+        let identity =
+            validate_bearer_token(&issuer.token(Some("issuer-key-1"), claims(300))).unwrap();
+        assert_eq!(identity.subject, "user-123");
+
+        // A single-key set may be used without a kid.
+        assert!(validate_bearer_token(&issuer.token(None, claims(300))).is_ok());
+
+        let err =
+            validate_bearer_token(&issuer.token(Some("rotated-out"), claims(300))).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+    }
+
+    #[test]
+    fn rejects_symmetric_tokens() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let _issuer = Issuer::configure(false);
+
+        let token = jsonwebtoken::encode(
+            &Header::new(Algorithm::HS256),
+            &claims(300),
+            &EncodingKey::from_secret(b"shared-secret"),
+        )
+        .unwrap();
+        let err = validate_bearer_token(&token).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+    }
+
+    #[test]
+    fn message_author_comes_from_the_principal() {
+        let subject = "7b3f4c2e-9a1d-4e8b-a6c5-0d2f1e3b4a5c";
+        let patient = Principal::User(UserIdentity {
+            subject: subject.into(),
+            name: "Pat Patient".into(),
+            role: "Patient".into(),
+            email: "pat@example.org".into(),
+            registrations: vec![],
+            roles: vec!["patient".into()],
+        });
+
+        let author = message_author(&patient, None).unwrap();
+        assert_eq!(author.id, Uuid::parse_str(subject).unwrap());
+        assert_eq!(author.name.as_str(), "Pat Patient");
+        assert_eq!(author.role, AuthorRole::Patient);
+        assert_eq!(message_author(&patient, Some(&author)).unwrap(), author);
+
+        // A patient cannot post as a clinician, or under someone else's id.
+        let as_clinician = MessageAuthor {
+            role: AuthorRole::Clinician,
+            ..author.clone()
+        };
+        let err = message_author(&patient, Some(&as_clinician)).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+        let as_other = MessageAuthor {
+            id: Uuid::nil(),
+            ..author
+        };
+        assert!(message_author(&patient, Some(&as_other)).is_err());
+
+        let service = message_author(&Principal::ApiKey, None).unwrap();
+        assert_eq!(service.id, Uuid::nil());
+        assert_eq!(service.role, AuthorRole::System);
+
+        let certificate = Principal::Certificate(CertificateIdentity {
+            common_name: "Dr Jane Smith".into(),
+            registration: RegistrationClaim {
+                authority: "GMC".into(),
+                number: "1234567".into(),
+            },
+        });
+        let author = message_author(&certificate, None).unwrap();
+        assert_eq!(author.id, certificate.participant_id().unwrap());
+        assert_eq!(author.role, AuthorRole::Clinician);
+    }
+
+    #[test]
+    fn authenticate_prefers_bearer_token_over_api_key() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let issuer = Issuer::configure(false);
+        env::set_var("API_KEY", "shared-key");
+
+        let bearer = format!("Bearer {}", issuer.token(None, claims(300)));
+        match authenticate(Some(&bearer), None, Some("shared-key")).unwrap() {
+            Principal::User(identity) => assert_eq!(identity.subject, "user-123"),
+            other => panic!("expected a user principal, got {other:?}"),
+        }
+        assert_eq!(
+            authenticate(None, None, Some("shared-key")).unwrap(),
+            Principal::ApiKey
+        );
+
+        // An invalid token is not rescued by a valid API key.
+        let err = authenticate(Some("Bearer not-a-jwt"), None, Some("shared-key")).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+        let err = authenticate(Some("Basic dXNlcjpwYXNz"), None, None).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+        let err = authenticate(None, None, None).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+    }
+}
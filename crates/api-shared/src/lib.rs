@@ -6,6 +6,8 @@
 //! - Protobuf-generated types (`pb` module)
 //! - Shared services like `HealthService`
 //! - Authentication utilities (usable by both gRPC and REST)
+//! - Role-based access policy (usable by both gRPC and REST)
+//! - TLS settings and client certificate identities (usable by both gRPC and REST)
 //!
 //! Used by `api-grpc` and `api-rest` for common functionality.
 
@@ -17,6 +19,8 @@ pub mod pb {
 
 pub mod auth;
 pub mod health;
+pub mod policy;
+pub mod tls;
 
 pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("proto_descriptor");
 
@@ -0,0 +1,472 @@
+//! Role-based access control shared by the gRPC and REST APIs.
+//!
+//! ## Purpose
+//! Decides whether an authenticated [`Principal`] may perform an API operation. The policy
+//! sits between the transports and the `vpr-core` services:
+//! - each [`Operation`] is allowed for a configurable set of [`Role`]s, and
+//! - access to an individual communication thread additionally honours the thread ledger's
+//!   `restricted`, `sensitivity` and `allow_patient_participation` settings.
+//!
+//! ## Intended use
+//! Front-ends load an [`AccessPolicy`] once at startup with [`AccessPolicy::from_env`] and call
+//! [`AccessPolicy::authorize`] after authenticating each request. Handlers that read or write
+//! a thread also call [`AccessPolicy::authorize_thread`] with the thread's ledger.
+//!
+//! A caller counts as a thread participant when [`Principal::participant_id`] is listed in the
+//! ledger. For bearer tokens that is the `sub` claim; client certificates carry no UUID, so
+//! their holders are listed under the id [`crate::tls::CertificateIdentity::participant_id`]
+//! derives from their registration.
+//!
+//! Like [`crate::auth`], this module is API-level only and is not used by `vpr-core`.
+
+use crate::auth::Principal;
+use fhir::{LedgerData, SensitivityLevel};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::env;
+
+/// Environment variable naming a JSON file that overrides the default operation rules.
+pub const ACCESS_POLICY_PATH_ENV: &str = "ACCESS_POLICY_PATH";
+
+/// Access role held by a caller.
+///
+/// Bearer-token users take their roles from the token's `roles` claim; unrecognised values are
+/// ignored. Client certificates identify registered professionals, who hold
+/// [`Role::Clinician`]. API-key callers are trusted integrations and always hold
+/// [`Role::System`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Clinical staff member.
+    Clinician,
+    /// Records or care administrator.
+    Administrator,
+    /// The patient, or someone acting for them.
+    Patient,
+    /// Automated integration.
+    System,
+}
+
+impl Role {
+    /// Parses a role from its claim or configuration string.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "clinician" => Some(Self::Clinician),
+            "administrator" => Some(Self::Administrator),
+            "patient" => Some(Self::Patient),
+            "system" => Some(Self::System),
+            _ => None,
+        }
+    }
+}
+
+/// An API operation subject to access control.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    /// List all patients, or read one patient's demographics.
+    ListPatients,
+    /// Create a patient, full record, or any individual repository.
+    CreateRecord,
+    /// Update patient demographics, including identifiers and contact details.
+    UpdateDemographics,
+    /// Link a clinical record to its demographics.
+    LinkToDemographics,
+    /// Write a letter, with or without attachments.
+    WriteLetter,
+    /// Read or list letters and their attachments.
+    ReadLetter,
+    /// Change the lifecycle status of a coordination record.
+    UpdateCoordinationStatus,
+    /// Start a communication thread.
+    CreateThread,
+    /// Post a message to a thread.
+    PostMessage,
+    /// Read or list communication threads.
+    ReadCommunication,
+    /// Change a thread's ledger (participants, status, visibility).
+    UpdateCommunicationLedger,
+}
+
+/// How a caller wants to use a communication thread.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThreadAccess {
+    /// Read the thread's ledger and messages.
+    Read,
+    /// Post to the thread or change its ledger.
+    Write,
+}
+
+/// Operation rules as written in an access policy file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyFile {
+    #[serde(default)]
+    operations: HashMap<Operation, HashSet<Role>>,
+}
+
+/// Per-operation role rules plus the fixed thread visibility rules.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccessPolicy {
+    operations: HashMap<Operation, HashSet<Role>>,
+}
+
+impl Default for AccessPolicy {
+    /// The default rules.
+    ///
+    /// - Clinicians write and read clinical letters and take part in coordination.
+    /// - Administrators create records, maintain demographics and manage coordination.
+    /// - Patients may only read and post to threads that allow patient participation and list
+    ///   them as participants.
+    /// - System callers may perform every operation.
+    fn default() -> Self {
+        use Operation::*;
+        use Role::*;
+
+        let rules: [(Operation, &[Role]); 11] = [
+            (ListPatients, &[Clinician, Administrator, System]),
+            (CreateRecord, &[Administrator, System]),
+            (UpdateDemographics, &[Administrator, System]),
+            (LinkToDemographics, &[Administrator, System]),
+            (WriteLetter, &[Clinician, System]),
+            (ReadLetter, &[Clinician, System]),
+            (UpdateCoordinationStatus, &[Administrator, System]),
+            (CreateThread, &[Clinician, Administrator, System]),
+            (PostMessage, &[Clinician, Administrator, Patient, System]),
+            (
+                ReadCommunication,
+                &[Clinician, Administrator, Patient, System],
+            ),
+            (
+                UpdateCommunicationLedger,
+                &[Clinician, Administrator, System],
+            ),
+        ];
+
+        Self {
+            operations: rules
+                .into_iter()
+                .map(|(operation, roles)| (operation, roles.iter().copied().collect()))
+                .collect(),
+        }
+    }
+}
+
+impl AccessPolicy {
+    /// Loads the policy, applying any overrides from the file named by `ACCESS_POLICY_PATH`.
+    ///
+    /// The file is JSON of the form `{"operations": {"write_letter": ["clinician"]}}`. Each
+    /// listed operation replaces its default roles; unlisted operations keep their defaults.
+    ///
+    /// # Errors
+    /// Returns a description of the problem if the file cannot be read or parsed.
+    pub fn from_env() -> Result<Self, String> {
+        match env::var(ACCESS_POLICY_PATH_ENV) {
+            Ok(path) => {
+                let contents = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read access policy {path}: {e}"))?;
+                Self::from_json(&contents)
+                    .map_err(|e| format!("Failed to parse access policy {path}: {e}"))
+            }
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Builds a policy from the default rules and the overrides in a JSON policy document.
+    ///
+    /// # Errors
+    /// Returns the parse error if the document is not a valid policy.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let file: PolicyFile = serde_json::from_str(json)?;
+        let mut policy = Self::default();
+        policy.operations.extend(file.operations);
+        Ok(policy)
+    }
+
+    /// Checks that the principal holds a role allowed to perform `operation`.
+    ///
+    /// # Errors
+    /// Returns `PERMISSION_DENIED` if none of the principal's roles is allowed.
+    #[allow(clippy::result_large_err)]
+    pub fn authorize(
+        &self,
+        principal: &Principal,
+        operation: Operation,
+    ) -> Result<(), tonic::Status> {
+        let allowed = self.operations.get(&operation);
+        if roles(principal)
+            .iter()
+            .any(|role| allowed.is_some_and(|allowed| allowed.contains(role)))
+        {
+            Ok(())
+        } else {
+            Err(tonic::Status::permission_denied(format!(
+                "Not permitted to perform {operation:?}"
+            )))
+        }
+    }
+
+    /// Checks that the principal may read or write a particular communication thread.
+    ///
+    /// This is applied on top of [`AccessPolicy::authorize`]. System callers may access any
+    /// thread. For everyone else:
+    /// - `restricted` threads, and threads with `Restricted` sensitivity, are limited to the
+    ///   thread's participants (matched on [`Principal::participant_id`]: the token `sub`, or
+    ///   the id derived from a client certificate's registration),
+    /// - `Confidential` threads may be read by participants and clinicians, but only
+    ///   participants may post to them or change their ledger,
+    /// - callers whose only role is patient must be participants, and the thread must have
+    ///   `allow_patient_participation`.
+    ///
+    /// # Errors
+    /// Returns `PERMISSION_DENIED` if any of these rules denies access.
+    #[allow(clippy::result_large_err)]
+    pub fn authorize_thread(
+        &self,
+        principal: &Principal,
+        ledger: &LedgerData,
+        access: ThreadAccess,
+    ) -> Result<(), tonic::Status> {
+        if can_access_thread(principal, ledger, access) {
+            Ok(())
+        } else {
+            let action = match access {
+                ThreadAccess::Read => "read",
+                ThreadAccess::Write => "write to",
+            };
+            Err(tonic::Status::permission_denied(format!(
+                "Not permitted to {action} this communication"
+            )))
+        }
+    }
+
+    /// Returns whether the principal may read the thread described by `ledger`.
+    ///
+    /// Used to filter listings without failing the whole request.
+    pub fn can_read_thread(&self, principal: &Principal, ledger: &LedgerData) -> bool {
+        can_access_thread(principal, ledger, ThreadAccess::Read)
+    }
+}
+
+/// Returns the roles held by a principal.
+pub fn roles(principal: &Principal) -> Vec<Role> {
+    match principal {
+        Principal::ApiKey => vec![Role::System],
+        Principal::Certificate(_) => vec![Role::Clinician],
+        Principal::User(identity) => identity
+            .roles
+            .iter()
+            .filter_map(|role| Role::parse(role))
+            .collect(),
+    }
+}
+
+fn can_access_thread(principal: &Principal, ledger: &LedgerData, access: ThreadAccess) -> bool {
+    let roles = roles(principal);
+    if roles.contains(&Role::System) {
+        return true;
+    }
+
+    let is_participant = principal
+        .participant_id()
+        .is_some_and(|id| ledger.participants.iter().any(|p| p.id == id));
+
+    if ledger.restricted && !is_participant {
+        return false;
+    }
+
+    let sensitivity_allows = match ledger.sensitivity {
+        SensitivityLevel::Standard => true,
+        SensitivityLevel::Confidential => match access {
+            ThreadAccess::Read => is_participant || roles.contains(&Role::Clinician),
+            ThreadAccess::Write => is_participant,
+        },
+        SensitivityLevel::Restricted => is_participant,
+    };
+    if !sensitivity_allows {
+        return false;
+    }
+
+    let patient_only = roles.iter().all(|role| *role == Role::Patient);
+    !patient_only || (ledger.allow_patient_participation && is_participant)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{RegistrationClaim, UserIdentity};
+    use crate::tls::CertificateIdentity;
+    use chrono::Utc;
+    use fhir::{messaging::MessageParticipant, AuthorRole, ThreadStatus};
+    use uuid::Uuid;
+    use vpr_types::NonEmptyText;
+
+    const PARTICIPANT_ID: &str = "7b3f4c2e-9a1d-4e8b-a6c5-0d2f1e3b4a5c";
+
+    fn user(subject: &str, roles: &[&str]) -> Principal {
+        Principal::User(UserIdentity {
+            subject: subject.into(),
+            name: "Test User".into(),
+            role: "Tester".into(),
+            email: "test@example.org".into(),
+            registrations: vec![],
+            roles: roles.iter().map(|r| r.to_string()).collect(),
+        })
+    }
+
+    fn ledger(sensitivity: SensitivityLevel, restricted: bool, patients: bool) -> LedgerData {
+        LedgerData {
+            communication_id: "20260101T000000.000Z-550e8400-e29b-41d4-a716-446655440000"
+                .parse()
+                .unwrap(),
+            status: ThreadStatus::Open,
+            created_at: Utc::now(),
+            last_updated_at: Utc::now(),
+            participants: vec![MessageParticipant {
+                id: Uuid::parse_str(PARTICIPANT_ID).unwrap(),
+                name: NonEmptyText::new("Participant").unwrap(),
+                role: AuthorRole::Clinician,
+            }],
+            sensitivity,
+            restricted,
+            allow_patient_participation: patients,
+            allow_external_organisations: false,
+        }
+    }
+
+    #[test]
+    fn default_rules_follow_roles() {
+        let policy = AccessPolicy::default();
+        let clinician = user("c", &["clinician"]);
+        let administrator = user("a", &["Administrator"]);
+        let patient = user("p", &["patient"]);
+        let nobody = user("n", &["auditor"]);
+
+        assert!(policy.authorize(&clinician, Operation::WriteLetter).is_ok());
+        assert!(policy
+            .authorize(&administrator, Operation::WriteLetter)
+            .is_err());
+        assert!(policy
+            .authorize(&administrator, Operation::UpdateCoordinationStatus)
+            .is_ok());
+        assert!(policy
+            .authorize(&clinician, Operation::UpdateCoordinationStatus)
+            .is_err());
+        assert!(policy.authorize(&patient, Operation::PostMessage).is_ok());
+        assert!(policy.authorize(&patient, Operation::ReadLetter).is_err());
+        assert!(policy
+            .authorize(&Principal::ApiKey, Operation::CreateRecord)
+            .is_ok());
+
+        let err = policy
+            .authorize(&nobody, Operation::ListPatients)
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+    }
+
+    #[test]
+    fn policy_file_overrides_listed_operations_only() {
+        let policy = AccessPolicy::from_json(
+            r#"{"operations": {"write_letter": ["clinician", "administrator"], "create_record": []}}"#,
+        )
+        .unwrap();
+
+        let administrator = user("a", &["administrator"]);
+        assert!(policy
+            .authorize(&administrator, Operation::WriteLetter)
+            .is_ok());
+        assert!(policy
+            .authorize(&Principal::ApiKey, Operation::CreateRecord)
+            .is_err());
+        assert!(policy
+            .authorize(&administrator, Operation::UpdateDemographics)
+            .is_ok());
+
+        assert!(
+            AccessPolicy::from_json(r#"{"operations": {"write_letter": ["janitor"]}}"#).is_err()
+        );
+        assert!(AccessPolicy::from_json(r#"{"rules": {}}"#).is_err());
+    }
+
+    #[test]
+    fn thread_rules_honour_ledger() {
+        let policy = AccessPolicy::default();
+        let participant = user(&PARTICIPANT_ID.to_uppercase(), &["administrator"]);
+        let clinician = user("other-clinician", &["clinician"]);
+        let administrator = user("other-admin", &["administrator"]);
+        let patient = user(PARTICIPANT_ID, &["patient"]);
+        let other_patient = user("0e9a3c1d-2b4f-4a6e-8c7d-5f1b3a2e4d6c", &["patient"]);
+
+        let standard = ledger(SensitivityLevel::Standard, false, false);
+        assert!(policy.can_read_thread(&clinician, &standard));
+        assert!(policy.can_read_thread(&administrator, &standard));
+        assert!(!policy.can_read_thread(&patient, &standard));
+
+        let with_patients = ledger(SensitivityLevel::Standard, false, true);
+        assert!(policy.can_read_thread(&patient, &with_patients));
+        assert!(policy
+            .authorize_thread(&patient, &with_patients, ThreadAccess::Write)
+            .is_ok());
+        assert!(!policy.can_read_thread(&other_patient, &with_patients));
+        let err = policy
+            .authorize_thread(&other_patient, &with_patients, ThreadAccess::Write)
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+
+        let restricted = ledger(SensitivityLevel::Standard, true, true);
+        assert!(policy.can_read_thread(&participant, &restricted));
+        assert!(!policy.can_read_thread(&clinician, &restricted));
+        assert!(policy.can_read_thread(&Principal::ApiKey, &restricted));
+
+        let confidential = ledger(SensitivityLevel::Confidential, false, false);
+        assert!(policy.can_read_thread(&clinician, &confidential));
+        assert!(policy.can_read_thread(&participant, &confidential));
+        assert!(!policy.can_read_thread(&administrator, &confidential));
+
+        assert!(policy
+            .authorize_thread(&clinician, &confidential, ThreadAccess::Read)
+            .is_ok());
+        let err = policy
+            .authorize_thread(&clinician, &confidential, ThreadAccess::Write)
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+        assert!(policy
+            .authorize_thread(&participant, &confidential, ThreadAccess::Write)
+            .is_ok());
+
+        let certificate = |number: &str| {
+            Principal::Certificate(CertificateIdentity {
+                common_name: "Dr Jane Smith".into(),
+                registration: RegistrationClaim {
+                    authority: "GMC".into(),
+                    number: number.into(),
+                },
+            })
+        };
+        let listed = certificate("1234567");
+        let unlisted = certificate("7654321");
+        let mut with_certificate = ledger(SensitivityLevel::Confidential, true, false);
+        with_certificate.participants.push(MessageParticipant {
+            id: listed.participant_id().unwrap(),
+            name: NonEmptyText::new("Dr Jane Smith").unwrap(),
+            role: AuthorRole::Clinician,
+        });
+        assert!(policy
+            .authorize_thread(&listed, &with_certificate, ThreadAccess::Write)
+            .is_ok());
+        assert!(!policy.can_read_thread(&unlisted, &with_certificate));
+
+        let sensitive = ledger(SensitivityLevel::Restricted, false, false);
+        assert!(policy.can_read_thread(&participant, &sensitive));
+        assert!(!policy.can_read_thread(&clinician, &sensitive));
+
+        let err = policy
+            .authorize_thread(&clinician, &sensitive, ThreadAccess::Write)
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+        assert_eq!(
+            err.message(),
+            "Not permitted to write to this communication"
+        );
+    }
+}
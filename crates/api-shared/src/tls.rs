@@ -0,0 +1,293 @@
+//! TLS settings and client certificate identities shared by the gRPC and REST servers.
+//!
+//! ## Purpose
+//! Provides:
+//! - [`TlsSettings`], the server certificate and optional client CA bundle, read from the
+//!   environment, and
+//! - [`certificate_identity`], which maps a verified client certificate issued by
+//!   `vpr-certificates` to the professional registration it carries.
+//!
+//! ## Intended use
+//! Servers load [`TlsSettings::from_env`] at startup and terminate TLS with it. Chain
+//! verification against the client CA bundle is done by the TLS stack; by the time
+//! [`certificate_identity`] sees a certificate it is already trusted, so the identity it
+//! returns may be used for authorisation and commit metadata.
+
+use crate::auth::RegistrationClaim;
+use std::env;
+use uuid::Uuid;
+use x509_parser::prelude::*;
+
+/// Environment variable naming the server certificate chain (PEM). Enables TLS when set.
+pub const TLS_CERT_PATH_ENV: &str = "VPR_TLS_CERT_PATH";
+
+/// Environment variable naming the server private key (PEM).
+pub const TLS_KEY_PATH_ENV: &str = "VPR_TLS_KEY_PATH";
+
+/// Environment variable naming the CA bundle (PEM) that client certificates must chain to.
+/// Enables mutual TLS when set.
+pub const TLS_CLIENT_CA_PATH_ENV: &str = "VPR_TLS_CLIENT_CA_PATH";
+
+/// Environment variable that, when `true`, rejects TLS connections without a client certificate.
+pub const TLS_CLIENT_AUTH_REQUIRED_ENV: &str = "VPR_TLS_CLIENT_AUTH_REQUIRED";
+
+/// Scheme of the subjectAltName URI carrying a professional registration.
+const REGISTRATION_URI_PREFIX: &str = "vpr://";
+
+/// X.520 serialNumber attribute (2.5.4.5), used for the registration number.
+const OID_SERIAL_NUMBER: &str = "2.5.4.5";
+
+/// PEM material for terminating TLS.
+#[derive(Clone, Debug)]
+pub struct TlsSettings {
+    /// Server certificate chain.
+    pub cert_pem: Vec<u8>,
+    /// Server private key.
+    pub key_pem: Vec<u8>,
+    /// CA bundle for verifying client certificates; `None` disables mutual TLS.
+    pub client_ca_pem: Option<Vec<u8>>,
+    /// Whether connections without a client certificate are refused.
+    ///
+    /// When `false`, clients without a certificate can still authenticate with a bearer
+    /// token or API key.
+    pub client_auth_required: bool,
+}
+
+impl TlsSettings {
+    /// Reads TLS settings from the environment.
+    ///
+    /// # Returns
+    /// `None` if `VPR_TLS_CERT_PATH` is not set, in which case servers run without TLS.
+    ///
+    /// # Errors
+    /// Returns a description of the problem if:
+    /// - `VPR_TLS_CERT_PATH` is set without `VPR_TLS_KEY_PATH`,
+    /// - `VPR_TLS_CLIENT_AUTH_REQUIRED` is set without `VPR_TLS_CLIENT_CA_PATH`,
+    /// - any configured file cannot be read.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let Ok(cert_path) = env::var(TLS_CERT_PATH_ENV) else {
+            return Ok(None);
+        };
+        let key_path = env::var(TLS_KEY_PATH_ENV)
+            .map_err(|_| format!("{TLS_KEY_PATH_ENV} must be set with {TLS_CERT_PATH_ENV}"))?;
+
+        let read = |path: &str| {
+            std::fs::read(path).map_err(|e| format!("Failed to read TLS file {path}: {e}"))
+        };
+
+        let client_ca_pem = env::var(TLS_CLIENT_CA_PATH_ENV)
+            .ok()
+            .map(|path| read(&path))
+            .transpose()?;
+        let client_auth_required = env::var(TLS_CLIENT_AUTH_REQUIRED_ENV)
+            .map(|v| v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if client_auth_required && client_ca_pem.is_none() {
+            return Err(format!(
+                "{TLS_CLIENT_AUTH_REQUIRED_ENV} requires {TLS_CLIENT_CA_PATH_ENV}"
+            ));
+        }
+
+        Ok(Some(Self {
+            cert_pem: read(&cert_path)?,
+            key_pem: read(&key_path)?,
+            client_ca_pem,
+            client_auth_required,
+        }))
+    }
+}
+
+/// Identity carried by a verified client certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateIdentity {
+    /// Subject common name (the professional's name).
+    pub common_name: String,
+    /// Professional registration from the `vpr://<authority>/<number>` subjectAltName, or
+    /// from the subject O and serialNumber attributes.
+    pub registration: RegistrationClaim,
+}
+
+impl CertificateIdentity {
+    /// Returns the id under which the certificate holder takes part in communication threads.
+    ///
+    /// Certificates carry no UUID, so the id is the name-based (v5) UUID of the holder's
+    /// `vpr://<authority>/<number>` registration URI in the URL namespace. The same
+    /// registration always maps to the same id, so it can be listed as a thread participant
+    /// before the holder first connects.
+    pub fn participant_id(&self) -> Uuid {
+        let uri = format!(
+            "{REGISTRATION_URI_PREFIX}{}/{}",
+            self.registration.authority, self.registration.number
+        );
+        Uuid::new_v5(&Uuid::NAMESPACE_URL, uri.as_bytes())
+    }
+}
+
+/// Extracts the identity from a DER-encoded client certificate.
+///
+/// The registration is read from a `vpr://<authority>/<number>` subjectAltName URI. If the
+/// subject also has O and serialNumber attributes they must name the same registration. A
+/// certificate without the URI may still carry its registration in O and serialNumber alone.
+///
+/// # Errors
+/// Returns a description of the problem if the certificate cannot be parsed, has no common
+/// name, carries no registration, or carries conflicting registrations.
+pub fn certificate_identity(der: &[u8]) -> Result<CertificateIdentity, String> {
+    let (_, cert) = X509Certificate::from_der(der)
+        .map_err(|e| format!("Failed to parse client certificate: {e}"))?;
+    let subject = cert.subject();
+
+    let attribute = |found: Option<&AttributeTypeAndValue<'_>>| {
+        found
+            .and_then(|attr| attr.as_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    let common_name = attribute(subject.iter_common_name().next())
+        .ok_or("Client certificate has no common name")?;
+    let organisation = attribute(subject.iter_organization().next());
+    let serial_number = attribute(
+        subject
+            .iter_attributes()
+            .find(|attr| attr.attr_type().to_id_string() == OID_SERIAL_NUMBER),
+    );
+
+    let san_registration = cert
+        .subject_alternative_name()
+        .ok()
+        .flatten()
+        .into_iter()
+        .flat_map(|san| san.value.general_names.iter())
+        .find_map(|name| match name {
+            GeneralName::URI(uri) => uri.strip_prefix(REGISTRATION_URI_PREFIX),
+            _ => None,
+        })
+        .map(|rest| {
+            rest.split_once('/')
+                .filter(|(authority, number)| !authority.is_empty() && !number.is_empty())
+                .map(|(authority, number)| RegistrationClaim {
+                    authority: authority.to_string(),
+                    number: number.to_string(),
+                })
+                .ok_or_else(|| {
+                    format!("Malformed registration URI: {REGISTRATION_URI_PREFIX}{rest}")
+                })
+        })
+        .transpose()?;
+
+    let subject_registration = match (organisation, serial_number) {
+        (Some(authority), Some(number)) => Some(RegistrationClaim { authority, number }),
+        _ => None,
+    };
+
+    let registration = match (san_registration, subject_registration) {
+        (Some(san), Some(subject)) if san != subject => {
+            return Err(
+                "Client certificate subject and subjectAltName name different registrations".into(),
+            )
+        }
+        (Some(registration), _) | (None, Some(registration)) => registration,
+        (None, None) => {
+            return Err("Client certificate carries no professional registration".into())
+        }
+    };
+
+    Ok(CertificateIdentity {
+        common_name,
+        registration,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{CertificateParams, DistinguishedName, DnType, SanType};
+
+    fn certificate_der(configure: impl FnOnce(&mut CertificateParams)) -> Vec<u8> {
+        let mut params = CertificateParams::default();
+        params.distinguished_name = DistinguishedName::new();
+        configure(&mut params);
+        let key_pair = rcgen::KeyPair::generate().unwrap();
+        params.self_signed(&key_pair).unwrap().der().to_vec()
+    }
+
+    #[test]
+    fn reads_identity_from_vpr_certificate() {
+        let (cert_pem, _) =
+            vpr_certificates::Certificate::create("Dr Jane Smith", "GMC", "1234567").unwrap();
+        let (_, pem) = parse_x509_pem(cert_pem.as_bytes()).unwrap();
+
+        let identity = certificate_identity(&pem.contents).unwrap();
+        assert_eq!(identity.common_name, "Dr Jane Smith");
+        assert_eq!(
+            identity.registration,
+            RegistrationClaim {
+                authority: "GMC".into(),
+                number: "1234567".into(),
+            }
+        );
+        assert_eq!(
+            identity.participant_id(),
+            Uuid::new_v5(&Uuid::NAMESPACE_URL, b"vpr://GMC/1234567")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_subject_registration() {
+        let der = certificate_der(|params| {
+            params
+                .distinguished_name
+                .push(DnType::CommonName, "Nurse Lee");
+            params
+                .distinguished_name
+                .push(DnType::OrganizationName, "NMC");
+            params
+                .distinguished_name
+                .push(DnType::CustomDnType(vec![2, 5, 4, 5]), "99A1234B");
+        });
+
+        let identity = certificate_identity(&der).unwrap();
+        assert_eq!(identity.registration.authority, "NMC");
+        assert_eq!(identity.registration.number, "99A1234B");
+    }
+
+    #[test]
+    fn rejects_missing_or_conflicting_registrations() {
+        let no_registration = certificate_der(|params| {
+            params
+                .distinguished_name
+                .push(DnType::CommonName, "Someone");
+        });
+        assert!(certificate_identity(&no_registration)
+            .unwrap_err()
+            .contains("no professional registration"));
+
+        let conflicting = certificate_der(|params| {
+            params
+                .distinguished_name
+                .push(DnType::CommonName, "Someone");
+            params
+                .distinguished_name
+                .push(DnType::OrganizationName, "GMC");
+            params
+                .distinguished_name
+                .push(DnType::CustomDnType(vec![2, 5, 4, 5]), "1111111");
+            params.subject_alt_names.push(SanType::URI(
+                "vpr://GMC/2222222".to_string().try_into().unwrap(),
+            ));
+        });
+        assert!(certificate_identity(&conflicting)
+            .unwrap_err()
+            .contains("different registrations"));
+
+        let nameless = certificate_der(|params| {
+            params.subject_alt_names.push(SanType::URI(
+                "vpr://GMC/1234567".to_string().try_into().unwrap(),
+            ));
+        });
+        assert!(certificate_identity(&nameless)
+            .unwrap_err()
+            .contains("no common name"));
+    }
+}
@@ -0,0 +1,378 @@
+//! VPR certificate authority.
+//!
+//! ## Purpose
+//! Creates root and intermediate CA certificates and issues professional registration leaf
+//! certificates from them, either with a freshly generated key or by signing a PKCS#10 CSR.
+//!
+//! ## Intended use
+//! Issued leaf certificates have the same subject and `vpr://` subjectAltName as
+//! [`Certificate::create`](crate::Certificate::create), but chain to the CA so that commit
+//! signatures and client certificates can be checked against a trust anchor.
+
+use crate::{leaf_params, CertificateError, OID_SERIAL_NUMBER};
+use rand::Rng;
+use rcgen::{
+    BasicConstraints, CertificateParams, CertificateSigningRequestParams, DistinguishedName,
+    DnType, IsCa, KeyPair, KeyUsagePurpose, SanType, SerialNumber,
+};
+use x509_parser::prelude::*;
+
+/// Validity of a root CA certificate.
+const ROOT_VALIDITY_DAYS: i64 = 3650;
+
+/// Validity of an intermediate CA certificate.
+const INTERMEDIATE_VALIDITY_DAYS: i64 = 1825;
+
+/// A CA certificate and its private key, able to issue certificates.
+pub struct CertificateAuthority {
+    cert: rcgen::Certificate,
+    cert_pem: String,
+    key_pair: KeyPair,
+}
+
+impl CertificateAuthority {
+    /// Creates a self-signed root CA.
+    ///
+    /// The root may issue intermediates as well as leaf certificates, and is valid for ten
+    /// years.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CertificateError::InvalidInput` if `name` is empty, or
+    /// `CertificateError::GenerationError` if the certificate cannot be generated.
+    pub fn create_root(name: &str) -> Result<Self, CertificateError> {
+        let params = ca_params(name, BasicConstraints::Unconstrained, ROOT_VALIDITY_DAYS)?;
+        let key_pair = generate_key_pair()?;
+        let cert = params.self_signed(&key_pair).map_err(generation_error)?;
+
+        Ok(Self {
+            cert_pem: cert.pem(),
+            cert,
+            key_pair,
+        })
+    }
+
+    /// Creates an intermediate CA signed by this CA.
+    ///
+    /// The intermediate may issue leaf certificates but not further CAs, and is valid for five
+    /// years.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CertificateError::InvalidInput` if `name` is empty, or
+    /// `CertificateError::GenerationError` if the certificate cannot be generated.
+    pub fn create_intermediate(&self, name: &str) -> Result<Self, CertificateError> {
+        let params = ca_params(
+            name,
+            BasicConstraints::Constrained(0),
+            INTERMEDIATE_VALIDITY_DAYS,
+        )?;
+        let key_pair = generate_key_pair()?;
+        let cert = params
+            .signed_by(&key_pair, &self.cert, &self.key_pair)
+            .map_err(generation_error)?;
+
+        Ok(Self {
+            cert_pem: cert.pem(),
+            cert,
+            key_pair,
+        })
+    }
+
+    /// Loads an existing CA from its certificate and private key.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CertificateError::InvalidInput` if either PEM cannot be parsed, the certificate
+    /// is not a CA, or the key does not belong to the certificate.
+    pub fn from_pem(cert_pem: &str, key_pem: &str) -> Result<Self, CertificateError> {
+        let key_pair = KeyPair::from_pem(key_pem)
+            .map_err(|e| CertificateError::InvalidInput(format!("invalid CA key: {e}")))?;
+
+        let (_, pem) = parse_x509_pem(cert_pem.as_bytes())
+            .map_err(|e| CertificateError::InvalidInput(format!("invalid CA certificate: {e}")))?;
+        let (_, x509) = parse_x509_certificate(&pem.contents)
+            .map_err(|e| CertificateError::InvalidInput(format!("invalid CA certificate: {e}")))?;
+        if !x509.is_ca() {
+            return Err(CertificateError::InvalidInput(
+                "certificate is not a CA".to_string(),
+            ));
+        }
+        if x509.public_key().raw != key_pair.public_key_der().as_slice() {
+            return Err(CertificateError::InvalidInput(
+                "CA key does not match the CA certificate".to_string(),
+            ));
+        }
+
+        // rcgen only needs the issuer's name, key identifier and key usages to sign with it,
+        // so re-signing the parsed parameters yields a usable issuer.
+        let params = CertificateParams::from_ca_cert_pem(cert_pem)
+            .map_err(|e| CertificateError::InvalidInput(format!("invalid CA certificate: {e}")))?;
+        let cert = params.self_signed(&key_pair).map_err(generation_error)?;
+
+        Ok(Self {
+            cert,
+            cert_pem: cert_pem.to_string(),
+            key_pair,
+        })
+    }
+
+    /// Returns the CA certificate PEM.
+    pub fn cert_pem(&self) -> &str {
+        &self.cert_pem
+    }
+
+    /// Returns the CA private key PEM.
+    pub fn key_pem(&self) -> String {
+        self.key_pair.serialize_pem()
+    }
+
+    /// Returns the issuer certificate used by rcgen when signing.
+    pub(crate) fn certificate(&self) -> &rcgen::Certificate {
+        &self.cert
+    }
+
+    /// Returns the CA key pair.
+    pub(crate) fn key_pair(&self) -> &KeyPair {
+        &self.key_pair
+    }
+
+    /// Issues a professional registration certificate with a newly generated key.
+    ///
+    /// Prefer [`sign_csr`](Self::sign_csr) where the clinician can generate their own key.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of (X.509 certificate PEM, private key PEM).
+    ///
+    /// # Errors
+    ///
+    /// Returns `CertificateError::InvalidInput` if a field is empty or contains newlines, or
+    /// `CertificateError::GenerationError` if the certificate cannot be generated.
+    pub fn issue(
+        &self,
+        name: &str,
+        registration_authority: &str,
+        registration_number: &str,
+    ) -> Result<(String, String), CertificateError> {
+        let params = self.issued_params(name, registration_authority, registration_number)?;
+        let key_pair = generate_key_pair()?;
+        let cert = params
+            .signed_by(&key_pair, &self.cert, &self.key_pair)
+            .map_err(generation_error)?;
+
+        Ok((cert.pem(), key_pair.serialize_pem()))
+    }
+
+    /// Signs a PKCS#10 certificate signing request for a professional registration.
+    ///
+    /// The CSR subject must carry `CN`, `O` (registration authority) and `serialNumber`
+    /// (registration number), as produced by
+    /// [`Certificate::create_csr`](crate::Certificate::create_csr). Only those fields and the
+    /// public key are taken from the CSR; validity, key usage and the `vpr://` subjectAltName
+    /// are set by the CA.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CertificateError::InvalidInput` if the CSR cannot be parsed, its signature does
+    /// not verify, a required subject field is missing, or a `vpr://` subjectAltName in the CSR
+    /// names a different registration. Returns `CertificateError::GenerationError` if the
+    /// certificate cannot be generated.
+    pub fn sign_csr(&self, csr_pem: &str) -> Result<String, CertificateError> {
+        let invalid_csr = |e: String| CertificateError::InvalidInput(format!("invalid CSR: {e}"));
+
+        let (_, pem) =
+            parse_x509_pem(csr_pem.as_bytes()).map_err(|e| invalid_csr(e.to_string()))?;
+        let (_, csr) = X509CertificationRequest::from_der(&pem.contents)
+            .map_err(|e| invalid_csr(e.to_string()))?;
+
+        let subject = &csr.certification_request_info.subject;
+        let attribute = |found: Option<&AttributeTypeAndValue<'_>>, field: &str| {
+            found
+                .and_then(|attr| attr.as_str().ok())
+                .map(str::to_string)
+                .ok_or_else(|| invalid_csr(format!("subject has no {field}")))
+        };
+        let name = attribute(subject.iter_common_name().next(), "CN")?;
+        let registration_authority = attribute(subject.iter_organization().next(), "O")?;
+        let registration_number = attribute(
+            subject.iter_attributes().find(|attr| {
+                attr.attr_type()
+                    .iter()
+                    .is_some_and(|arcs| arcs.eq(OID_SERIAL_NUMBER.iter().copied()))
+            }),
+            "serialNumber",
+        )?;
+
+        // rcgen verifies the CSR's self-signature, proving possession of the private key.
+        let requested = CertificateSigningRequestParams::from_pem(csr_pem)
+            .map_err(|e| invalid_csr(e.to_string()))?;
+
+        let params = self.issued_params(&name, &registration_authority, &registration_number)?;
+        let expected_uri = params.subject_alt_names.first().cloned();
+        for san in &requested.params.subject_alt_names {
+            if let SanType::URI(uri) = san {
+                if uri.as_str().starts_with("vpr://") && Some(san) != expected_uri.as_ref() {
+                    return Err(invalid_csr(format!(
+                        "subjectAltName {} does not match the subject",
+                        uri.as_str()
+                    )));
+                }
+            }
+        }
+
+        let cert = params
+            .signed_by(&requested.public_key, &self.cert, &self.key_pair)
+            .map_err(generation_error)?;
+        Ok(cert.pem())
+    }
+
+    /// Builds leaf parameters with a random serial number and a link back to this CA.
+    fn issued_params(
+        &self,
+        name: &str,
+        registration_authority: &str,
+        registration_number: &str,
+    ) -> Result<CertificateParams, CertificateError> {
+        let mut params = leaf_params(name, registration_authority, registration_number)?;
+        params.serial_number = Some(random_serial());
+        params.use_authority_key_identifier_extension = true;
+        Ok(params)
+    }
+}
+
+/// Builds CA certificate parameters.
+fn ca_params(
+    name: &str,
+    constraints: BasicConstraints,
+    validity_days: i64,
+) -> Result<CertificateParams, CertificateError> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(['\n', '\r']) {
+        return Err(CertificateError::InvalidInput(
+            "CA name must be non-empty and on a single line".to_string(),
+        ));
+    }
+
+    let mut params = CertificateParams::default();
+    let mut subject = DistinguishedName::new();
+    subject.push(DnType::CommonName, name);
+    params.distinguished_name = subject;
+    params.is_ca = IsCa::Ca(constraints);
+    params.key_usages = vec![
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+        KeyUsagePurpose::DigitalSignature,
+    ];
+    params.serial_number = Some(random_serial());
+    params.use_authority_key_identifier_extension = true;
+
+    let now = ::time::OffsetDateTime::now_utc();
+    params.not_before = now;
+    params.not_after = now + ::time::Duration::days(validity_days);
+
+    Ok(params)
+}
+
+/// Returns a random, positive 128-bit serial number.
+fn random_serial() -> SerialNumber {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill(&mut bytes);
+    bytes[0] &= 0x7f;
+    SerialNumber::from(bytes.to_vec())
+}
+
+fn generate_key_pair() -> Result<KeyPair, CertificateError> {
+    KeyPair::generate().map_err(generation_error)
+}
+
+fn generation_error(e: rcgen::Error) -> CertificateError {
+    CertificateError::GenerationError(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Certificate;
+
+    fn parse(pem: &str) -> Vec<u8> {
+        parse_x509_pem(pem.as_bytes()).unwrap().1.contents
+    }
+
+    fn verify_issued_by(cert_pem: &str, issuer_pem: &str) {
+        let cert_der = parse(cert_pem);
+        let issuer_der = parse(issuer_pem);
+        let (_, cert) = parse_x509_certificate(&cert_der).unwrap();
+        let (_, issuer) = parse_x509_certificate(&issuer_der).unwrap();
+        assert_eq!(cert.issuer(), issuer.subject());
+        cert.verify_signature(Some(issuer.public_key())).unwrap();
+    }
+
+    #[test]
+    fn root_and_intermediate_issue_registration_certificates() {
+        let root = CertificateAuthority::create_root("VPR Root CA").unwrap();
+        let intermediate = root.create_intermediate("VPR Issuing CA").unwrap();
+        verify_issued_by(intermediate.cert_pem(), root.cert_pem());
+
+        let (cert_pem, key_pem) = intermediate.issue("Jane Smith", "NMC", "789012").unwrap();
+        verify_issued_by(&cert_pem, intermediate.cert_pem());
+        assert!(key_pem.contains("BEGIN PRIVATE KEY"));
+
+        let der = parse(&cert_pem);
+        let (_, cert) = parse_x509_certificate(&der).unwrap();
+        assert!(!cert.is_ca());
+        assert!(cert.subject().to_string().contains("CN=Jane Smith"));
+
+        let der = parse(intermediate.cert_pem());
+        let (_, cert) = parse_x509_certificate(&der).unwrap();
+        let constraints = cert.basic_constraints().unwrap().unwrap().value;
+        assert!(constraints.ca);
+        assert_eq!(constraints.path_len_constraint, Some(0));
+    }
+
+    #[test]
+    fn signs_csr_without_seeing_private_key() {
+        let root = CertificateAuthority::create_root("VPR Root CA").unwrap();
+        let (csr_pem, key_pem) = Certificate::create_csr("John Doe", "GMC", "123456").unwrap();
+        assert!(csr_pem.contains("BEGIN CERTIFICATE REQUEST"));
+
+        let cert_pem = root.sign_csr(&csr_pem).unwrap();
+        verify_issued_by(&cert_pem, root.cert_pem());
+
+        // The issued certificate carries the CSR's public key.
+        let der = parse(&cert_pem);
+        let (_, cert) = parse_x509_certificate(&der).unwrap();
+        let key_pair = KeyPair::from_pem(&key_pem).unwrap();
+        assert_eq!(cert.public_key().raw, key_pair.public_key_der().as_slice());
+        assert!(cert.subject().to_string().contains("O=GMC"));
+    }
+
+    #[test]
+    fn rejects_csr_without_registration() {
+        let root = CertificateAuthority::create_root("VPR Root CA").unwrap();
+        let mut params = CertificateParams::default();
+        params.distinguished_name = DistinguishedName::new();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "No Registration");
+        let key_pair = KeyPair::generate().unwrap();
+        let csr_pem = params.serialize_request(&key_pair).unwrap().pem().unwrap();
+
+        let err = root.sign_csr(&csr_pem).unwrap_err();
+        assert!(err.to_string().contains("subject has no O"), "{err}");
+    }
+
+    #[test]
+    fn reloads_ca_from_pem() {
+        let root = CertificateAuthority::create_root("VPR Root CA").unwrap();
+        let reloaded = CertificateAuthority::from_pem(root.cert_pem(), &root.key_pem()).unwrap();
+        let (cert_pem, _) = reloaded.issue("Jane Smith", "NMC", "789012").unwrap();
+        verify_issued_by(&cert_pem, root.cert_pem());
+
+        let other = CertificateAuthority::create_root("Other CA").unwrap();
+        assert!(CertificateAuthority::from_pem(root.cert_pem(), &other.key_pem()).is_err());
+
+        let (leaf_pem, leaf_key) = Certificate::create("John Doe", "GMC", "123456").unwrap();
+        assert!(CertificateAuthority::from_pem(&leaf_pem, &leaf_key).is_err());
+    }
+}
@@ -1,17 +1,34 @@
 //! X.509 certificate generation utilities.
 //!
 //! ## Purpose
-//! Provides helper functionality to generate self-signed X.509 certificates.
+//! Provides helper functionality to generate X.509 certificates for professional
+//! registrations, either self-signed or issued by a VPR certificate authority.
 //!
 //! ## Intended use
 //! Certificates are used for user authentication and commit signing within the wider VPR system.
+//!
+//! A deployment creates a root CA (and optionally an intermediate) with
+//! [`CertificateAuthority`]. Clinicians generate a key and CSR locally with
+//! [`Certificate::create_csr`]; the CA signs the CSR with [`CertificateAuthority::sign_csr`], so
+//! the private key never leaves the clinician's machine. Certificates that must no longer be
+//! trusted are revoked with [`CertificateAuthority::revoke`], which produces a signed CRL.
+
+mod authority;
+mod revocation;
+
+pub use authority::CertificateAuthority;
+pub use revocation::RevokedCertificate;
 
 use rcgen::{
     CertificateParams, DistinguishedName, DnType, Ia5String, IsCa, KeyPair, KeyUsagePurpose,
-    SanType, SerialNumber,
+    SanType, SerialNumber, SignatureAlgorithm,
 };
+use std::str::FromStr;
 use thiserror::Error;
 
+/// X.520 serialNumber attribute (2.5.4.5), carrying the registration number.
+const OID_SERIAL_NUMBER: &[u64] = &[2, 5, 4, 5];
+
 /// Errors that can occur during certificate creation.
 #[derive(Error, Debug)]
 pub enum CertificateError {
@@ -21,6 +38,51 @@ pub enum CertificateError {
     GenerationError(String),
 }
 
+/// Key type of a generated registration certificate.
+///
+/// These are the algorithms VPR can sign commits with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyAlgorithm {
+    /// ECDSA over P-256 with SHA-256.
+    #[default]
+    EcdsaP256,
+    /// ECDSA over P-384 with SHA-384.
+    EcdsaP384,
+    /// Ed25519.
+    Ed25519,
+}
+
+impl KeyAlgorithm {
+    fn signature_algorithm(self) -> &'static SignatureAlgorithm {
+        match self {
+            Self::EcdsaP256 => &rcgen::PKCS_ECDSA_P256_SHA256,
+            Self::EcdsaP384 => &rcgen::PKCS_ECDSA_P384_SHA384,
+            Self::Ed25519 => &rcgen::PKCS_ED25519,
+        }
+    }
+
+    fn generate_key_pair(self) -> Result<KeyPair, CertificateError> {
+        KeyPair::generate_for(self.signature_algorithm())
+            .map_err(|e| CertificateError::GenerationError(e.to_string()))
+    }
+}
+
+impl FromStr for KeyAlgorithm {
+    type Err = CertificateError;
+
+    /// Parses `p256`, `p384` or `ed25519`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "p256" | "ecdsa-p256" => Ok(Self::EcdsaP256),
+            "p384" | "ecdsa-p384" => Ok(Self::EcdsaP384),
+            "ed25519" => Ok(Self::Ed25519),
+            other => Err(CertificateError::InvalidInput(format!(
+                "unknown key algorithm '{other}' (expected p256, p384 or ed25519)"
+            ))),
+        }
+    }
+}
+
 /// A struct representing a digital certificate for professional registration.
 /// This generates X.509 certificates compliant with the specified requirements.
 pub struct Certificate;
@@ -46,80 +108,36 @@ impl Certificate {
         registration_authority: &str,
         registration_number: &str,
     ) -> Result<(String, String), CertificateError> {
-        let name = name.trim();
-        if name.is_empty() {
-            return Err(CertificateError::InvalidInput(
-                "name must not be empty".to_string(),
-            ));
-        }
-        if name.contains(['\n', '\r']) {
-            return Err(CertificateError::InvalidInput(
-                "name must not contain newlines".to_string(),
-            ));
-        }
-
-        let registration_authority = registration_authority.trim();
-        if registration_authority.is_empty() {
-            return Err(CertificateError::InvalidInput(
-                "registration_authority must not be empty".to_string(),
-            ));
-        }
-        if registration_authority.contains(['\n', '\r']) {
-            return Err(CertificateError::InvalidInput(
-                "registration_authority must not contain newlines".to_string(),
-            ));
-        }
-
-        let registration_number = registration_number.trim();
-        if registration_number.is_empty() {
-            return Err(CertificateError::InvalidInput(
-                "registration_number must not be empty".to_string(),
-            ));
-        }
-        if registration_number.contains(['\n', '\r']) {
-            return Err(CertificateError::InvalidInput(
-                "registration_number must not contain newlines".to_string(),
-            ));
-        }
+        Self::create_with_algorithm(
+            name,
+            registration_authority,
+            registration_number,
+            KeyAlgorithm::default(),
+        )
+    }
 
-        let mut params = CertificateParams::default();
+    /// Creates a new X.509 certificate with a key of the given algorithm.
+    ///
+    /// Like [`Certificate::create`], which always uses ECDSA P-256. The private key is
+    /// returned as PKCS#8 PEM, which VPR accepts as a commit signing key for every
+    /// [`KeyAlgorithm`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `CertificateError::GenerationError` if certificate generation fails.
+    pub fn create_with_algorithm(
+        name: &str,
+        registration_authority: &str,
+        registration_number: &str,
+        algorithm: KeyAlgorithm,
+    ) -> Result<(String, String), CertificateError> {
+        let mut params = leaf_params(name, registration_authority, registration_number)?;
 
-        // Subject DN fields are designed to be human-readable in tools like:
-        // `openssl x509 -noout -subject`
-        let mut subject = DistinguishedName::new();
-        subject.push(DnType::CommonName, name);
-        subject.push(DnType::OrganizationName, registration_authority);
-        // X.520 serialNumber (OID 2.5.4.5) – commonly displayed by OpenSSL as `serialNumber=...`.
-        subject.push(DnType::CustomDnType(vec![2, 5, 4, 5]), registration_number);
-        params.distinguished_name = subject;
-
-        // Set issuer (assuming self-signed for simplicity, but in practice this would be a CA)
-        params.is_ca = IsCa::NoCa;
-
-        // Add registration number as a URI in subjectAltName
-        let uri = format!("vpr://{}/{}", registration_authority, registration_number);
-        let uri = Ia5String::try_from(uri).map_err(|e| {
-            CertificateError::InvalidInput(format!("invalid registration URI: {e}"))
-        })?;
-        params.subject_alt_names.push(SanType::URI(uri));
-
-        // Set key usage for signing
-        params.key_usages = vec![
-            KeyUsagePurpose::DigitalSignature,
-            KeyUsagePurpose::ContentCommitment,
-        ];
-
-        // Set validity period (1 year from now)
-        let now = time::OffsetDateTime::now_utc();
-        params.not_before = now;
-        params.not_after = now + time::Duration::days(365);
-
-        // Generate serial number
+        // Self-signed; use `CertificateAuthority::issue` for a certificate that chains to a CA.
         params.serial_number = Some(SerialNumber::from(vec![0, 1, 2, 3, 4, 5, 6, 7]));
 
         // Generate key pair
-        let key_pair =
-            KeyPair::generate().map_err(|e| CertificateError::GenerationError(e.to_string()))?;
+        let key_pair = algorithm.generate_key_pair()?;
 
         // Generate the certificate
         let cert = params
@@ -128,6 +146,140 @@ impl Certificate {
 
         Ok((cert.pem(), key_pair.serialize_pem()))
     }
+
+    /// Creates a PKCS#10 certificate signing request for a professional registration.
+    ///
+    /// The request carries the same subject and `vpr://` subjectAltName as [`Certificate::create`].
+    /// The private key stays with the caller; only the CSR is sent to the CA for signing with
+    /// [`CertificateAuthority::sign_csr`].
+    ///
+    /// # Returns
+    ///
+    /// A tuple of (CSR PEM, private key PEM).
+    ///
+    /// # Errors
+    ///
+    /// Returns `CertificateError::InvalidInput` if a field is empty or contains newlines, or
+    /// `CertificateError::GenerationError` if the request cannot be generated.
+    pub fn create_csr(
+        name: &str,
+        registration_authority: &str,
+        registration_number: &str,
+    ) -> Result<(String, String), CertificateError> {
+        let (name, registration_authority, registration_number) =
+            registration_fields(name, registration_authority, registration_number)?;
+
+        let mut params = CertificateParams::default();
+        params.distinguished_name =
+            registration_subject(name, registration_authority, registration_number);
+        params.subject_alt_names.push(registration_uri(
+            registration_authority,
+            registration_number,
+        )?);
+
+        let key_pair =
+            KeyPair::generate().map_err(|e| CertificateError::GenerationError(e.to_string()))?;
+        let csr = params
+            .serialize_request(&key_pair)
+            .and_then(|csr| csr.pem())
+            .map_err(|e| CertificateError::GenerationError(e.to_string()))?;
+
+        Ok((csr, key_pair.serialize_pem()))
+    }
+}
+
+/// Trims and validates the three fields identifying a professional registration.
+pub(crate) fn registration_fields<'a>(
+    name: &'a str,
+    registration_authority: &'a str,
+    registration_number: &'a str,
+) -> Result<(&'a str, &'a str, &'a str), CertificateError> {
+    Ok((
+        required_field("name", name)?,
+        required_field("registration_authority", registration_authority)?,
+        required_field("registration_number", registration_number)?,
+    ))
+}
+
+fn required_field<'a>(field: &str, value: &'a str) -> Result<&'a str, CertificateError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(CertificateError::InvalidInput(format!(
+            "{field} must not be empty"
+        )));
+    }
+    if value.contains(['\n', '\r']) {
+        return Err(CertificateError::InvalidInput(format!(
+            "{field} must not contain newlines"
+        )));
+    }
+    Ok(value)
+}
+
+/// Builds the subject DN for a professional registration.
+fn registration_subject(
+    name: &str,
+    registration_authority: &str,
+    registration_number: &str,
+) -> DistinguishedName {
+    // Subject DN fields are designed to be human-readable in tools like:
+    // `openssl x509 -noout -subject`
+    let mut subject = DistinguishedName::new();
+    subject.push(DnType::CommonName, name);
+    subject.push(DnType::OrganizationName, registration_authority);
+    // X.520 serialNumber (OID 2.5.4.5) – commonly displayed by OpenSSL as `serialNumber=...`.
+    subject.push(
+        DnType::CustomDnType(OID_SERIAL_NUMBER.to_vec()),
+        registration_number,
+    );
+    subject
+}
+
+/// Builds the `vpr://<authority>/<number>` subjectAltName URI.
+fn registration_uri(
+    registration_authority: &str,
+    registration_number: &str,
+) -> Result<SanType, CertificateError> {
+    let uri = format!("vpr://{}/{}", registration_authority, registration_number);
+    let uri = Ia5String::try_from(uri)
+        .map_err(|e| CertificateError::InvalidInput(format!("invalid registration URI: {e}")))?;
+    Ok(SanType::URI(uri))
+}
+
+/// Builds the parameters of a professional registration leaf certificate, valid for one year.
+///
+/// The serial number is left unset for the caller to choose.
+pub(crate) fn leaf_params(
+    name: &str,
+    registration_authority: &str,
+    registration_number: &str,
+) -> Result<CertificateParams, CertificateError> {
+    let (name, registration_authority, registration_number) =
+        registration_fields(name, registration_authority, registration_number)?;
+
+    let mut params = CertificateParams::default();
+    params.distinguished_name =
+        registration_subject(name, registration_authority, registration_number);
+    params.is_ca = IsCa::NoCa;
+
+    // Add registration number as a URI in subjectAltName
+    params.subject_alt_names.push(registration_uri(
+        registration_authority,
+        registration_number,
+    )?);
+
+    // Set key usage for signing
+    params.key_usages = vec![
+        KeyUsagePurpose::DigitalSignature,
+        KeyUsagePurpose::ContentCommitment,
+    ];
+
+    // Set validity period (1 year from now)
+    let now = time::OffsetDateTime::now_utc();
+    params.not_before = now;
+    params.not_after = now + time::Duration::days(365);
+
+    Ok(params)
 }
 
 #[cfg(test)]
@@ -190,4 +342,29 @@ mod tests {
         assert!(!cert.is_empty());
         assert!(!key.is_empty());
     }
+
+    #[test]
+    fn test_create_certificate_with_each_key_algorithm() {
+        for (algorithm, oid) in [
+            (KeyAlgorithm::EcdsaP256, "1.2.840.10045.2.1"),
+            (KeyAlgorithm::EcdsaP384, "1.2.840.10045.2.1"),
+            (KeyAlgorithm::Ed25519, "1.3.101.112"),
+        ] {
+            let (cert_pem, key_pem) =
+                Certificate::create_with_algorithm("Jane Smith", "NMC", "789012", algorithm)
+                    .unwrap();
+            assert!(key_pem.contains("BEGIN PRIVATE KEY"));
+
+            let (_, pem) = x509_parser::pem::parse_x509_pem(cert_pem.as_bytes()).unwrap();
+            let (_, cert) = x509_parser::parse_x509_certificate(&pem.contents).unwrap();
+            assert_eq!(cert.public_key().algorithm.algorithm.to_id_string(), oid);
+            cert.verify_signature(None).unwrap();
+        }
+
+        assert_eq!(
+            "P384".parse::<KeyAlgorithm>().unwrap(),
+            KeyAlgorithm::EcdsaP384
+        );
+        assert!("rsa".parse::<KeyAlgorithm>().is_err());
+    }
 }
@@ -0,0 +1,226 @@
+//! Certificate revocation lists issued by a VPR certificate authority.
+//!
+//! ## Purpose
+//! Lets a [`CertificateAuthority`] revoke certificates it issued, for example when a
+//! clinician's private key is compromised, and publish the result as a signed X.509 CRL.
+//!
+//! ## Intended use
+//! A CA keeps one CRL. Each revocation reads the current CRL, adds the certificate and
+//! re-signs it with the next CRL number, so the CRL is both the revocation list and its own
+//! record of what has been revoked. `vpr-core` reads these CRLs when verifying commit
+//! signatures.
+
+use crate::{CertificateAuthority, CertificateError};
+use rcgen::{CertificateRevocationListParams, RevokedCertParams, SerialNumber};
+use std::time::SystemTime;
+use x509_parser::prelude::*;
+
+/// Days until a CRL's nextUpdate.
+///
+/// Revocations never expire, but relying parties use nextUpdate to decide when to fetch a
+/// fresh list.
+const CRL_VALIDITY_DAYS: i64 = 30;
+
+/// A certificate listed in a CRL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RevokedCertificate {
+    /// Serial number of the revoked certificate, big-endian without leading zero bytes.
+    pub serial_number: Vec<u8>,
+    /// When the certificate was revoked; signatures made from this time are not trusted.
+    pub revoked_at: SystemTime,
+}
+
+impl CertificateAuthority {
+    /// Revokes a certificate issued by this CA, returning the updated CRL.
+    ///
+    /// # Arguments
+    ///
+    /// * `crl_pem` - This CA's current CRL, or `None` if it has not revoked anything yet.
+    /// * `cert_pem` - The certificate to revoke.
+    /// * `revoked_at` - When the certificate stopped being trustworthy. This may be earlier
+    ///   than now, for example the time a key is known to have been compromised.
+    ///
+    /// # Returns
+    ///
+    /// The new CRL PEM, listing every earlier revocation as well as this one, with a CRL
+    /// number one higher than `crl_pem`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CertificateError::InvalidInput` if the certificate was not issued by this CA or
+    /// is already revoked, or if `crl_pem` cannot be parsed or was not signed by this CA.
+    /// Returns `CertificateError::GenerationError` if the CRL cannot be signed.
+    pub fn revoke(
+        &self,
+        crl_pem: Option<&str>,
+        cert_pem: &str,
+        revoked_at: SystemTime,
+    ) -> Result<String, CertificateError> {
+        let invalid =
+            |what: &str, e: String| CertificateError::InvalidInput(format!("invalid {what}: {e}"));
+
+        let (_, ca_pem) =
+            parse_x509_pem(self.cert_pem().as_bytes()).map_err(|e| invalid("CA", e.to_string()))?;
+        let (_, ca) =
+            parse_x509_certificate(&ca_pem.contents).map_err(|e| invalid("CA", e.to_string()))?;
+
+        let (_, pem) = parse_x509_pem(cert_pem.as_bytes())
+            .map_err(|e| invalid("certificate", e.to_string()))?;
+        let (_, cert) = parse_x509_certificate(&pem.contents)
+            .map_err(|e| invalid("certificate", e.to_string()))?;
+        if cert.issuer() != ca.subject() || cert.verify_signature(Some(ca.public_key())).is_err() {
+            return Err(CertificateError::InvalidInput(
+                "certificate was not issued by this CA".to_string(),
+            ));
+        }
+        let serial_number = normalise_serial(cert.raw_serial());
+
+        let (crl_number, mut revoked) = match crl_pem {
+            Some(crl_pem) => {
+                let (_, pem) = parse_x509_pem(crl_pem.as_bytes())
+                    .map_err(|e| invalid("CRL", e.to_string()))?;
+                let (_, crl) =
+                    parse_x509_crl(&pem.contents).map_err(|e| invalid("CRL", e.to_string()))?;
+                if crl.issuer() != ca.subject() || crl.verify_signature(ca.public_key()).is_err() {
+                    return Err(CertificateError::InvalidInput(
+                        "CRL was not issued by this CA".to_string(),
+                    ));
+                }
+                let number = crl
+                    .crl_number()
+                    .and_then(|n| u64::try_from(n).ok())
+                    .unwrap_or(0);
+                let entries = crl
+                    .iter_revoked_certificates()
+                    .map(|entry| RevokedCertificate {
+                        serial_number: normalise_serial(entry.raw_serial()),
+                        revoked_at: entry.revocation_date.to_datetime().into(),
+                    })
+                    .collect::<Vec<_>>();
+                (number, entries)
+            }
+            None => (0, Vec::new()),
+        };
+
+        if revoked.iter().any(|r| r.serial_number == serial_number) {
+            return Err(CertificateError::InvalidInput(
+                "certificate is already revoked".to_string(),
+            ));
+        }
+        revoked.push(RevokedCertificate {
+            serial_number,
+            revoked_at,
+        });
+
+        self.sign_crl(crl_number + 1, &revoked)
+    }
+
+    /// Signs a CRL listing `revoked`.
+    ///
+    /// Prefer [`revoke`](Self::revoke), which carries earlier revocations forward; this builds
+    /// a CRL from scratch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CertificateError::GenerationError` if the CRL cannot be signed.
+    pub fn sign_crl(
+        &self,
+        crl_number: u64,
+        revoked: &[RevokedCertificate],
+    ) -> Result<String, CertificateError> {
+        let now = ::time::OffsetDateTime::now_utc();
+        let params = CertificateRevocationListParams {
+            this_update: now,
+            next_update: now + ::time::Duration::days(CRL_VALIDITY_DAYS),
+            crl_number: SerialNumber::from(crl_number.to_be_bytes().to_vec()),
+            issuing_distribution_point: None,
+            revoked_certs: revoked
+                .iter()
+                .map(|r| RevokedCertParams {
+                    serial_number: SerialNumber::from(r.serial_number.clone()),
+                    revocation_time: r.revoked_at.into(),
+                    reason_code: None,
+                    invalidity_date: None,
+                })
+                .collect(),
+            key_identifier_method: self.certificate().params().key_identifier_method.clone(),
+        };
+
+        params
+            .signed_by(self.certificate(), self.key_pair())
+            .and_then(|crl| crl.pem())
+            .map_err(|e| CertificateError::GenerationError(e.to_string()))
+    }
+}
+
+/// Strips the sign padding from a DER serial so serials compare by value.
+fn normalise_serial(raw: &[u8]) -> Vec<u8> {
+    let start = raw.iter().position(|&b| b != 0).unwrap_or(raw.len());
+    raw[start..].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn parse_crl(pem: &str) -> Vec<u8> {
+        parse_x509_pem(pem.as_bytes()).unwrap().1.contents
+    }
+
+    #[test]
+    fn revocations_accumulate_in_signed_crl() {
+        let ca = CertificateAuthority::create_root("VPR Root CA").unwrap();
+        let (first, _) = ca.issue("Jane Smith", "NMC", "789012").unwrap();
+        let (second, _) = ca.issue("John Doe", "GMC", "123456").unwrap();
+        let compromised_at = SystemTime::now() - Duration::from_secs(3600);
+
+        let crl_pem = ca.revoke(None, &first, compromised_at).unwrap();
+        let crl_pem = ca
+            .revoke(Some(&crl_pem), &second, SystemTime::now())
+            .unwrap();
+        assert!(crl_pem.contains("BEGIN X509 CRL"));
+
+        let der = parse_crl(&crl_pem);
+        let (_, crl) = parse_x509_crl(&der).unwrap();
+        let ca_der = parse_x509_pem(ca.cert_pem().as_bytes()).unwrap().1.contents;
+        let (_, ca_cert) = parse_x509_certificate(&ca_der).unwrap();
+        crl.verify_signature(ca_cert.public_key()).unwrap();
+        assert_eq!(crl.crl_number().map(|n| n.to_string()), Some("2".into()));
+
+        let entries: Vec<_> = crl.iter_revoked_certificates().collect();
+        assert_eq!(entries.len(), 2);
+        let first_der = parse_x509_pem(first.as_bytes()).unwrap().1.contents;
+        let (_, first_cert) = parse_x509_certificate(&first_der).unwrap();
+        assert_eq!(entries[0].serial(), &first_cert.serial);
+        let revoked_at: SystemTime = entries[0].revocation_date.to_datetime().into();
+        let drift = compromised_at
+            .duration_since(revoked_at)
+            .unwrap_or_else(|e| e.duration());
+        assert!(drift < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn rejects_foreign_and_repeated_revocations() {
+        let ca = CertificateAuthority::create_root("VPR Root CA").unwrap();
+        let other = CertificateAuthority::create_root("Other CA").unwrap();
+        let (cert, _) = ca.issue("Jane Smith", "NMC", "789012").unwrap();
+        let (foreign, _) = other.issue("John Doe", "GMC", "123456").unwrap();
+
+        let err = ca.revoke(None, &foreign, SystemTime::now()).unwrap_err();
+        assert!(err.to_string().contains("not issued by this CA"), "{err}");
+
+        let crl_pem = ca.revoke(None, &cert, SystemTime::now()).unwrap();
+        let err = ca
+            .revoke(Some(&crl_pem), &cert, SystemTime::now())
+            .unwrap_err();
+        assert!(err.to_string().contains("already revoked"), "{err}");
+
+        let other_crl = other.revoke(None, &foreign, SystemTime::now()).unwrap();
+        let (another, _) = ca.issue("Sam Jones", "HCPC", "PA12345").unwrap();
+        let err = ca
+            .revoke(Some(&other_crl), &another, SystemTime::now())
+            .unwrap_err();
+        assert!(err.to_string().contains("CRL was not issued"), "{err}");
+    }
+}
@@ -6,22 +6,30 @@
 
 #![allow(rustdoc::invalid_html_tags)]
 
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use clap::{Parser, Subcommand};
 use fhir::{
     coordination_status::LifecycleState, messaging::SensitivityLevel,
     messaging::ThreadStatus as FhirThreadStatus, AuthorRole, MessageAuthor,
 };
-use vpr_certificates::Certificate;
+use vpr_certificates::{Certificate, CertificateAuthority, KeyAlgorithm};
 use vpr_core::{
-    config::rm_system_version_from_env_value,
+    backup::BackupService,
+    config::{
+        mirror_root_from_env_value, revocation_list_from_env_value,
+        rm_system_version_from_env_value, timestamp_authority_from_env_value,
+        trust_anchors_from_env_value,
+    },
     constants,
-    repositories::clinical::ClinicalService,
+    integrity::{IntegrityService, IssueSeverity},
+    replication::ReplicationService,
+    repositories::clinical::{ClinicalService, ListLettersQuery},
     repositories::coordination::{
         CoordinationService, CoordinationStatusUpdate, LedgerUpdate, MessageContent,
     },
     repositories::demographics::DemographicsService,
-    versioned_files::VersionedFileService,
+    repositories::revocation::RevocationService,
+    versioned_files::{CommitSignatureStatus, CommitTimestampStatus, VersionedFileService},
     Author, AuthorRegistration, CoreConfig, EmailAddress, NonEmptyText, PatientService,
     ShardableUuid, TimestampId,
 };
@@ -63,7 +71,7 @@ enum Commands {
         /// Mandatory organisational location for the commit (e.g. hospital name, GP surgery)
         #[arg(long)]
         care_location: String,
-        /// ECDSA private key PEM for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
+        /// Private key PEM (PKCS#8 ECDSA P-256, P-384 or Ed25519, or OpenSSH for SSH signatures) for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
         #[arg(long)]
         signature: Option<String>,
     },
@@ -82,7 +90,7 @@ enum Commands {
         /// Mandatory organisational location for the commit (e.g. hospital name, GP surgery)
         #[arg(long)]
         care_location: String,
-        /// ECDSA private key PEM for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
+        /// Private key PEM (PKCS#8 ECDSA P-256, P-384 or Ed25519, or OpenSSH for SSH signatures) for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
         #[arg(long)]
         signature: Option<String>,
     },
@@ -107,14 +115,16 @@ enum Commands {
         /// Mandatory organisational location for the commit (e.g. hospital name, GP surgery)
         #[arg(long)]
         care_location: String,
-        /// ECDSA private key PEM for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
+        /// Private key PEM (PKCS#8 ECDSA P-256, P-384 or Ed25519, or OpenSSH for SSH signatures) for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
         #[arg(long)]
         signature: Option<String>,
         /// Organisation domain (optional)
         #[arg(long)]
         namespace: Option<String>,
     },
-    /// Update demographics: <demographics_uuid> <given_names> <last_name> <birth_date>
+    /// Update demographics (and commit):
+    /// <demographics_uuid> <given_names> <last_name> <birth_date> <name> <email>
+    /// --role <role> --care-location <care_location> [--signature <ecdsa_private_key_pem>]
     UpdateDemographics {
         /// Demographics UUID
         demographics_uuid: String,
@@ -124,6 +134,22 @@ enum Commands {
         last_name: String,
         /// Date of birth (YYYY-MM-DD)
         birth_date: String,
+        /// Author name for Git commit
+        name: String,
+        /// Author email for Git commit
+        email: String,
+        /// Mandatory author role for commit metadata
+        #[arg(long)]
+        role: String,
+        /// Declared professional registrations (repeatable): --registration <AUTHORITY> <NUMBER>
+        #[arg(long, value_names = ["AUTHORITY", "NUMBER"], num_args = 2, action = clap::ArgAction::Append)]
+        registration: Vec<String>,
+        /// Mandatory organisational location for the commit (e.g. hospital name, GP surgery)
+        #[arg(long)]
+        care_location: String,
+        /// Private key PEM (PKCS#8 ECDSA P-256, P-384 or Ed25519, or OpenSSH for SSH signatures) for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
+        #[arg(long)]
+        signature: Option<String>,
     },
     /// Initialise full record:
     ///
@@ -156,7 +182,7 @@ enum Commands {
             value_name = "AUTHORITY NUMBER",
         )]
         author_registration: Vec<String>,
-        /// ECDSA private key PEM for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
+        /// Private key PEM (PKCS#8 ECDSA P-256, P-384 or Ed25519, or OpenSSH for SSH signatures) for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
         #[arg(long)]
         signature: Option<String>,
         /// Organisation domain (optional)
@@ -167,15 +193,91 @@ enum Commands {
     ///
     /// The public key can be:
     /// - PEM text (contains "-----BEGIN")
-    /// - a file path to a PEM file
+    /// - an OpenSSH public key line (`ssh-ed25519 AAAA...`), for SSH-signed commits
+    /// - a file path to a PEM or OpenSSH public key file
     /// - base64-encoded PEM
     VerifyClinicalCommitSignature {
         /// Clinical repository UUID
         clinical_uuid: String,
-        /// ECDSA P-256 public key (PEM string, base64-encoded PEM, or file path)
+        /// Public key (ECDSA P-256, P-384 or Ed25519 PEM, or OpenSSH) or X.509 certificate (PEM string, base64-encoded PEM, or file path)
         public_key: String,
     },
-    /// Create a professional registration certificate: <name> <registration_authority> <registration_number> [--cert-out <cert_file>] [--key-out <key_file>]
+    /// Verify the signature on every commit of a repository: <repository> <uuid>
+    ///
+    /// `repository` is one of `clinical`, `demographics` or `coordination`. Each commit is
+    /// reported as VALID, UNSIGNED, INVALID or CERTIFICATE_MISMATCH. With trust anchors
+    /// configured a commit may also be UNTRUSTED or REGISTRATION_MISMATCH, and a commit whose
+    /// signing certificate has been revoked is REVOKED_AFTER_SIGNING or REVOKED_BEFORE_SIGNING.
+    VerifyCommitSignatures {
+        /// Repository kind: clinical, demographics or coordination
+        repository: String,
+        /// Repository UUID
+        uuid: String,
+    },
+    /// Check the integrity of every patient repository
+    ///
+    /// Checks Git objects and their connectivity, that HEAD points to `refs/heads/main`, that
+    /// every YAML file parses, and that stored attachment files match their hashes. Prints a
+    /// JSON report and exits with an error if any repository is damaged; missing attachment
+    /// files are reported as warnings only.
+    Fsck,
+    /// Back up every patient repository: <backup_dir>
+    ///
+    /// Writes a verified `git bundle` of each clinical, demographics, coordination and
+    /// redaction repository, its stored attachment files and a `manifest.json` into
+    /// `backup_dir`, which must not exist or be empty.
+    Backup {
+        /// Directory to write the backup to
+        backup_dir: String,
+    },
+    /// Restore patient repositories from a backup:
+    ///
+    /// <backup_dir> <author_name> <author_email>
+    /// --role <author_role>
+    /// --care-location <care_location>
+    /// --signature <private_key_pem>
+    /// [--registration <AUTHORITY> <NUMBER> ...]
+    ///
+    /// Refuses to overwrite existing repositories. Each restored repository is checked
+    /// against the manifest and its commit signatures are verified, then a signed recovery
+    /// marker commit is added on top of the restored history.
+    Restore {
+        /// Directory written by `backup`
+        backup_dir: String,
+        /// Author name for the recovery marker commits
+        author_name: String,
+        /// Author email for the recovery marker commits
+        author_email: String,
+        /// Mandatory author role for commit metadata
+        #[arg(long)]
+        role: String,
+        /// Declared professional registrations (repeatable): --registration <AUTHORITY> <NUMBER>
+        #[arg(long, value_names = ["AUTHORITY", "NUMBER"], num_args = 2, action = clap::ArgAction::Append)]
+        registration: Vec<String>,
+        /// Mandatory organisational location for the commit (e.g. hospital name, GP surgery)
+        #[arg(long)]
+        care_location: String,
+        /// Private key PEM (PKCS#8 ECDSA P-256, P-384 or Ed25519, or OpenSSH for SSH signatures) for signing the recovery markers (PEM string, base64-encoded PEM, or file path)
+        #[arg(long)]
+        signature: String,
+    },
+    /// Compare every patient repository with the mirror in `VPR_MIRROR_URL`: [--dry-run]
+    ///
+    /// Pushes clinical, demographics and coordination repositories whose mirror is missing or
+    /// behind. Mirrors that are ahead of the primary or have diverged from it are never
+    /// updated; they are reported, and the command exits with an error while any remain.
+    Reconcile {
+        /// Only report drift; push nothing
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Finish or discard full records whose creation was interrupted
+    ///
+    /// Records that had been fully created are moved into place and pushed to the mirror;
+    /// partial ones are deleted. Records still being created by a running process are left
+    /// alone.
+    Recover,
+    /// Create a professional registration certificate: <name> <registration_authority> <registration_number> [--algorithm <p256|p384|ed25519>] [--cert-out <cert_file>] [--key-out <key_file>]
     ///
     /// The generated X.509 Subject includes:
     /// - `CN` = name
@@ -183,6 +285,9 @@ enum Commands {
     /// - `serialNumber` = registration number
     ///
     /// A `subjectAltName` URI is also added: `vpr://<authority>/<number>`.
+    ///
+    /// The private key is ECDSA P-256 unless `--algorithm` selects P-384 or Ed25519; any of
+    /// them can be passed as `--signature` to sign commits.
     CreateCertificate {
         /// Full name of the person
         name: String,
@@ -190,6 +295,9 @@ enum Commands {
         registration_authority: String,
         /// Registration number. Populates X.509 Subject `serialNumber`.
         registration_number: String,
+        /// Key algorithm: p256 (default), p384 or ed25519
+        #[arg(long, default_value = "p256")]
+        algorithm: String,
         /// Output file for the certificate (optional, prints to stdout if not specified)
         #[arg(long)]
         cert_out: Option<String>,
@@ -197,6 +305,106 @@ enum Commands {
         #[arg(long)]
         key_out: Option<String>,
     },
+    /// Create a certificate authority: <name> [--issuer-cert <ca_cert> --issuer-key <ca_key>] [--cert-out <cert_file>] [--key-out <key_file>]
+    ///
+    /// Without an issuer this creates a self-signed root CA. With `--issuer-cert` and
+    /// `--issuer-key` it creates an intermediate CA signed by that issuer, which can sign
+    /// registration certificates but not further CAs.
+    CreateCa {
+        /// CA name. Populates X.509 Subject `CN`.
+        name: String,
+        /// Issuing CA certificate (creates an intermediate CA)
+        #[arg(long, requires = "issuer_key")]
+        issuer_cert: Option<String>,
+        /// Issuing CA private key
+        #[arg(long, requires = "issuer_cert")]
+        issuer_key: Option<String>,
+        /// Output file for the CA certificate (optional, prints to stdout if not specified)
+        #[arg(long)]
+        cert_out: Option<String>,
+        /// Output file for the CA private key (optional, prints to stdout if not specified)
+        #[arg(long)]
+        key_out: Option<String>,
+    },
+    /// Create a certificate signing request: <name> <registration_authority> <registration_number> [--csr-out <csr_file>] [--key-out <key_file>]
+    ///
+    /// The private key is generated locally and should stay on this machine; only the CSR is
+    /// sent to the CA for `sign-csr`.
+    CreateCsr {
+        /// Full name of the person
+        name: String,
+        /// Registration authority (e.g., GMC, NMC). Populates X.509 Subject `O`.
+        registration_authority: String,
+        /// Registration number. Populates X.509 Subject `serialNumber`.
+        registration_number: String,
+        /// Output file for the CSR (optional, prints to stdout if not specified)
+        #[arg(long)]
+        csr_out: Option<String>,
+        /// Output file for the private key (optional, prints to stdout if not specified)
+        #[arg(long)]
+        key_out: Option<String>,
+    },
+    /// Sign a certificate signing request: <csr_file> --ca-cert <ca_cert> --ca-key <ca_key> [--cert-out <cert_file>]
+    ///
+    /// The CSR subject must carry the registration (`CN`, `O`, `serialNumber`), as produced by
+    /// `create-csr`. The issued certificate also carries the `vpr://<authority>/<number>` URI.
+    SignCsr {
+        /// CSR file (PEM)
+        csr_file: String,
+        /// CA certificate (PEM)
+        #[arg(long)]
+        ca_cert: String,
+        /// CA private key (PEM)
+        #[arg(long)]
+        ca_key: String,
+        /// Output file for the certificate (optional, prints to stdout if not specified)
+        #[arg(long)]
+        cert_out: Option<String>,
+    },
+    /// Revoke a certificate and publish the updated CRL to the revocation repository:
+    ///
+    /// <cert_file> <author_name> <author_email>
+    /// --role <author_role>
+    /// --care-location <care_location>
+    /// --ca-cert <ca_cert> --ca-key <ca_key>
+    /// [--revoked-at <time>] [--crl-out <crl_file>]
+    /// [--registration <AUTHORITY> <NUMBER> ...]
+    /// [--signature <ecdsa_private_key_pem>]
+    ///
+    /// Commits signed with the certificate at or after `--revoked-at` (default: now) are then
+    /// reported as REVOKED_BEFORE_SIGNING.
+    RevokeCertificate {
+        /// Certificate to revoke (PEM)
+        cert_file: String,
+        /// Author name for Git commit
+        author_name: String,
+        /// Author email for Git commit
+        author_email: String,
+        /// Mandatory author role for commit metadata
+        #[arg(long)]
+        role: String,
+        /// Declared professional registrations (repeatable): --registration <AUTHORITY> <NUMBER>
+        #[arg(long, value_names = ["AUTHORITY", "NUMBER"], num_args = 2, action = clap::ArgAction::Append)]
+        registration: Vec<String>,
+        /// Mandatory organisational location for the commit (e.g. hospital name, GP surgery)
+        #[arg(long)]
+        care_location: String,
+        /// CA certificate that issued the certificate (PEM)
+        #[arg(long)]
+        ca_cert: String,
+        /// CA private key (PEM)
+        #[arg(long)]
+        ca_key: String,
+        /// When the certificate stopped being trustworthy, as RFC3339 or YYYY-MM-DD (default: now)
+        #[arg(long)]
+        revoked_at: Option<String>,
+        /// Also write the updated CRL to this file (optional)
+        #[arg(long)]
+        crl_out: Option<String>,
+        /// Private key PEM (PKCS#8 ECDSA P-256, P-384 or Ed25519, or OpenSSH for SSH signatures) for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
+        #[arg(long)]
+        signature: Option<String>,
+    },
 
     /// Create a new letter:
     ///
@@ -225,7 +433,7 @@ enum Commands {
         /// Letter content (markdown text)
         #[arg(long)]
         content: String,
-        /// ECDSA private key PEM for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
+        /// Private key PEM (PKCS#8 ECDSA P-256, P-384 or Ed25519, or OpenSSH for SSH signatures) for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
         #[arg(long)]
         signature: Option<String>,
     },
@@ -262,7 +470,7 @@ enum Commands {
         /// Mandatory organisational location for the commit (e.g. hospital name, GP surgery)
         #[arg(long)]
         care_location: String,
-        /// ECDSA private key PEM for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
+        /// Private key PEM (PKCS#8 ECDSA P-256, P-384 or Ed25519, or OpenSSH for SSH signatures) for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
         #[arg(long)]
         signature: Option<String>,
     },
@@ -298,7 +506,7 @@ enum Commands {
         /// Initial message content (markdown)
         #[arg(long)]
         initial_message: Option<String>,
-        /// ECDSA private key PEM for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
+        /// Private key PEM (PKCS#8 ECDSA P-256, P-384 or Ed25519, or OpenSSH for SSH signatures) for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
         #[arg(long)]
         signature: Option<String>,
     },
@@ -348,7 +556,7 @@ enum Commands {
         /// Message ID being corrected (for correction messages)
         #[arg(long)]
         corrects: Option<String>,
-        /// ECDSA private key PEM for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
+        /// Private key PEM (PKCS#8 ECDSA P-256, P-384 or Ed25519, or OpenSSH for SSH signatures) for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
         #[arg(long)]
         signature: Option<String>,
     },
@@ -373,6 +581,28 @@ enum Commands {
         letter_timestamp_id: String,
     },
 
+    /// List letters in a clinical record, oldest first:
+    ///
+    /// <clinical_uuid>
+    /// [--cursor <timestamp_id>] [--limit <n>]
+    /// [--from <date>] [--to <date>]
+    ListLetters {
+        /// Clinical repository UUID
+        clinical_uuid: String,
+        /// Next-page cursor printed by a previous listing
+        #[arg(long)]
+        cursor: Option<String>,
+        /// Maximum number of letters to list
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Only letters created at or after this time (RFC3339 or YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Only letters created before this time (RFC3339 or YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+    },
+
     /// Create a new letter with file attachments:
     ///
     /// <clinical_uuid> <author_name> <author_email>
@@ -400,7 +630,7 @@ enum Commands {
         /// File paths for attachments (repeatable): --attachment-file <path>
         #[arg(long = "attachment-file", action = clap::ArgAction::Append)]
         attachment_file: Vec<PathBuf>,
-        /// ECDSA private key PEM for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
+        /// Private key PEM (PKCS#8 ECDSA P-256, P-384 or Ed25519, or OpenSSH for SSH signatures) for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
         #[arg(long)]
         signature: Option<String>,
     },
@@ -436,7 +666,7 @@ enum Commands {
         /// File paths for attachments (repeatable): --attachment-file <path>
         #[arg(long = "attachment-file", action = clap::ArgAction::Append)]
         attachment_file: Vec<PathBuf>,
-        /// ECDSA private key PEM for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
+        /// Private key PEM (PKCS#8 ECDSA P-256, P-384 or Ed25519, or OpenSSH for SSH signatures) for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
         #[arg(long)]
         signature: Option<String>,
     },
@@ -504,7 +734,7 @@ enum Commands {
         /// Set allow external organisations (true/false)
         #[arg(long)]
         allow_external: Option<bool>,
-        /// ECDSA private key PEM for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
+        /// Private key PEM (PKCS#8 ECDSA P-256, P-384 or Ed25519, or OpenSSH for SSH signatures) for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
         #[arg(long)]
         signature: Option<String>,
     },
@@ -548,7 +778,7 @@ enum Commands {
         /// Set record modifiable flag (true/false)
         #[arg(long)]
         record_modifiable: Option<bool>,
-        /// ECDSA private key PEM for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
+        /// Private key PEM (PKCS#8 ECDSA P-256, P-384 or Ed25519, or OpenSSH for SSH signatures) for X.509 signing (optional, can be PEM string, base64-encoded PEM, or file path)
         #[arg(long)]
         signature: Option<String>,
     },
@@ -856,7 +1086,60 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             given_names,
             last_name,
             birth_date,
+            name,
+            email,
+            role,
+            registration,
+            care_location,
+            signature,
         }) => {
+            let registrations: Vec<AuthorRegistration> = registration
+                .chunks(2)
+                .map(|chunk| {
+                    AuthorRegistration::new(
+                        chunk.first().cloned().unwrap_or_default(),
+                        chunk.get(1).cloned().unwrap_or_default(),
+                    )
+                    .expect("valid registration")
+                })
+                .collect();
+            let name = match NonEmptyText::new(&name) {
+                Ok(n) => n,
+                Err(e) => {
+                    eprintln!("Invalid author name: {}", e);
+                    return Ok(());
+                }
+            };
+            let role = match NonEmptyText::new(&role) {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("Invalid author role: {}", e);
+                    return Ok(());
+                }
+            };
+            let email = match EmailAddress::parse(&email) {
+                Ok(e) => e,
+                Err(e) => {
+                    eprintln!("Invalid author email: {:?}", e);
+                    return Ok(());
+                }
+            };
+            let author = Author {
+                name,
+                role,
+                email,
+                registrations,
+                signature: signature.map(|s| s.into_bytes()),
+                certificate: None,
+            };
+            let care_location = match NonEmptyText::new(&care_location) {
+                Ok(cl) => cl,
+                Err(e) => {
+                    eprintln!("Invalid care_location: {}", e);
+                    return Ok(());
+                }
+            };
+
             let given_names_vec: Vec<String> = given_names
                 .split(',')
                 .map(|s| s.trim().to_string())
@@ -868,7 +1151,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
             match DemographicsService::with_id(cfg.clone(), &demographics_uuid) {
                 Ok(demographics_service) => {
-                    match demographics_service.update(given_names_vec, &last_name, &birth_date) {
+                    match demographics_service.update(
+                        &author,
+                        care_location,
+                        given_names_vec,
+                        &last_name,
+                        &birth_date,
+                    ) {
                         Ok(()) => println!("Updated demographics for UUID: {}", demographics_uuid),
                         Err(e) => eprintln!("Error updating demographics: {}", e),
                     }
@@ -976,7 +1265,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             clinical_uuid,
             public_key,
         }) => {
-            let public_key_pem = if public_key.contains("-----BEGIN") {
+            let public_key_pem = if public_key.contains("-----BEGIN")
+                || public_key.starts_with("ssh-")
+                || public_key.starts_with("ecdsa-sha2-")
+            {
                 public_key
             } else if std::path::Path::new(&public_key).exists() {
                 match std::fs::read_to_string(&public_key) {
@@ -1016,13 +1308,247 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 Err(e) => eprintln!("Error verifying signature: {}", e),
             }
         }
+        Some(Commands::VerifyCommitSignatures { repository, uuid }) => {
+            let parsed_uuid = match ShardableUuid::parse(&uuid) {
+                Ok(u) => u,
+                Err(e) => {
+                    eprintln!("Error parsing UUID: {}", e);
+                    return Ok(());
+                }
+            };
+
+            let report = match repository.to_lowercase().as_str() {
+                "clinical" => ClinicalService::with_id(cfg.clone(), parsed_uuid.uuid())
+                    .verify_signature_chain(),
+                "demographics" => match DemographicsService::with_id(cfg.clone(), &uuid) {
+                    Ok(service) => service.verify_signature_chain(),
+                    Err(e) => Err(e),
+                },
+                "coordination" => CoordinationService::with_id(cfg.clone(), parsed_uuid.uuid())
+                    .verify_signature_chain(),
+                _ => {
+                    eprintln!(
+                        "Invalid repository: must be clinical, demographics, or coordination"
+                    );
+                    return Ok(());
+                }
+            };
+
+            match report {
+                Ok(report) => {
+                    for commit in &report.commits {
+                        let status = match commit.status {
+                            CommitSignatureStatus::Valid => "VALID",
+                            CommitSignatureStatus::Unsigned => "UNSIGNED",
+                            CommitSignatureStatus::Invalid => "INVALID",
+                            CommitSignatureStatus::CertificateMismatch => "CERTIFICATE_MISMATCH",
+                            CommitSignatureStatus::Untrusted => "UNTRUSTED",
+                            CommitSignatureStatus::RegistrationMismatch => "REGISTRATION_MISMATCH",
+                            CommitSignatureStatus::RevokedAfterSigning => "REVOKED_AFTER_SIGNING",
+                            CommitSignatureStatus::RevokedBeforeSigning => "REVOKED_BEFORE_SIGNING",
+                        };
+                        let timestamp = match commit.timestamp {
+                            CommitTimestampStatus::Absent => String::new(),
+                            CommitTimestampStatus::Valid => commit
+                                .timestamped_at
+                                .map(|at| format!(" TIMESTAMPED {}", at.to_rfc3339()))
+                                .unwrap_or_default(),
+                            CommitTimestampStatus::Untrusted => " TIMESTAMP_UNTRUSTED".into(),
+                            CommitTimestampStatus::Invalid => " TIMESTAMP_INVALID".into(),
+                        };
+                        println!("{} {}{}", commit.commit_id, status, timestamp);
+                    }
+                    println!(
+                        "{} commits: {} valid, {} unsigned, {} invalid, {} certificate mismatch, {} untrusted, {} registration mismatch, {} revoked after signing, {} revoked before signing",
+                        report.commits.len(),
+                        report.count(CommitSignatureStatus::Valid),
+                        report.count(CommitSignatureStatus::Unsigned),
+                        report.count(CommitSignatureStatus::Invalid),
+                        report.count(CommitSignatureStatus::CertificateMismatch),
+                        report.count(CommitSignatureStatus::Untrusted),
+                        report.count(CommitSignatureStatus::RegistrationMismatch),
+                        report.count(CommitSignatureStatus::RevokedAfterSigning),
+                        report.count(CommitSignatureStatus::RevokedBeforeSigning),
+                    );
+                    println!(
+                        "timestamps: {} valid, {} untrusted, {} invalid, {} absent",
+                        report.count_timestamps(CommitTimestampStatus::Valid),
+                        report.count_timestamps(CommitTimestampStatus::Untrusted),
+                        report.count_timestamps(CommitTimestampStatus::Invalid),
+                        report.count_timestamps(CommitTimestampStatus::Absent),
+                    );
+                }
+                Err(e) => eprintln!("Error verifying commit signatures: {}", e),
+            }
+        }
+        Some(Commands::Backup { backup_dir }) => {
+            match BackupService::new(cfg.clone()).backup(Path::new(&backup_dir)) {
+                Ok(manifest) => {
+                    for repository in &manifest.repositories {
+                        println!(
+                            "{} {} {} ({} files)",
+                            repository.kind.as_str(),
+                            repository.id,
+                            repository.head,
+                            repository.files.len()
+                        );
+                    }
+                    println!(
+                        "Backed up {} repositories to {} (backup {})",
+                        manifest.repositories.len(),
+                        backup_dir,
+                        manifest.backup_id
+                    );
+                }
+                Err(e) => eprintln!("Error backing up repositories: {}", e),
+            }
+        }
+        Some(Commands::Restore {
+            backup_dir,
+            author_name,
+            author_email,
+            role,
+            registration,
+            care_location,
+            signature,
+        }) => {
+            let registrations: Vec<AuthorRegistration> = registration
+                .chunks(2)
+                .map(|chunk| {
+                    AuthorRegistration::new(
+                        chunk.first().cloned().unwrap_or_default(),
+                        chunk.get(1).cloned().unwrap_or_default(),
+                    )
+                    .expect("valid registration")
+                })
+                .collect();
+            let name = match NonEmptyText::new(&author_name) {
+                Ok(n) => n,
+                Err(e) => {
+                    eprintln!("Invalid author name: {}", e);
+                    return Ok(());
+                }
+            };
+            let role = match NonEmptyText::new(&role) {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("Invalid author role: {}", e);
+                    return Ok(());
+                }
+            };
+            let email = match EmailAddress::parse(&author_email) {
+                Ok(e) => e,
+                Err(e) => {
+                    eprintln!("Invalid author email: {:?}", e);
+                    return Ok(());
+                }
+            };
+            let care_location = match NonEmptyText::new(&care_location) {
+                Ok(cl) => cl,
+                Err(e) => {
+                    eprintln!("Invalid care_location: {}", e);
+                    return Ok(());
+                }
+            };
+            let author = Author {
+                name,
+                role,
+                email,
+                registrations,
+                signature: Some(signature.into_bytes()),
+                certificate: None,
+            };
+
+            match BackupService::new(cfg.clone()).restore(
+                Path::new(&backup_dir),
+                &author,
+                care_location,
+            ) {
+                Ok(restored) => {
+                    for repository in &restored {
+                        println!(
+                            "{} {} restored at {}, recovery marker {}",
+                            repository.kind.as_str(),
+                            repository.id,
+                            repository.restored_head,
+                            repository.marker_commit
+                        );
+                    }
+                    println!("Restored {} repositories", restored.len());
+                }
+                Err(e) => eprintln!("Error restoring repositories: {}", e),
+            }
+        }
+        Some(Commands::Reconcile { dry_run }) => {
+            let service = ReplicationService::new(cfg.clone());
+            let report = if dry_run {
+                service.drift()?
+            } else {
+                service.reconcile()?
+            };
+            for repository in &report.repositories {
+                println!(
+                    "{} {} {}{} (primary +{}, mirror +{})",
+                    repository.kind.as_str(),
+                    repository.id,
+                    repository.status.as_str(),
+                    if repository.updated { " UPDATED" } else { "" },
+                    repository.primary_ahead,
+                    repository.replica_ahead
+                );
+            }
+            let drifted = report.drift().count();
+            if drifted > 0 {
+                return Err(format!(
+                    "{} of {} repositories differ from the mirror",
+                    drifted,
+                    report.repositories.len()
+                )
+                .into());
+            }
+            println!("{} repositories in sync", report.repositories.len());
+        }
+        Some(Commands::Recover) => {
+            let report = PatientService::new(cfg.clone()).recover_interrupted_records()?;
+            for id in &report.completed {
+                println!("completed {}", id.simple());
+            }
+            for id in &report.rolled_back {
+                println!("rolled back {}", id.simple());
+            }
+            println!(
+                "{} interrupted records completed, {} rolled back",
+                report.completed.len(),
+                report.rolled_back.len()
+            );
+        }
+        Some(Commands::Fsck) => {
+            let report = IntegrityService::new(cfg.clone()).check()?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            if !report.is_ok() {
+                return Err(format!(
+                    "integrity check failed: {} errors, {} warnings",
+                    report.count(IssueSeverity::Error),
+                    report.count(IssueSeverity::Warning)
+                )
+                .into());
+            }
+        }
         Some(Commands::CreateCertificate {
             name,
             registration_authority,
             registration_number,
+            algorithm,
             cert_out,
             key_out,
-        }) => match Certificate::create(&name, &registration_authority, &registration_number) {
+        }) => match algorithm.parse::<KeyAlgorithm>().and_then(|algorithm| {
+            Certificate::create_with_algorithm(
+                &name,
+                &registration_authority,
+                &registration_number,
+                algorithm,
+            )
+        }) {
             Ok((cert_pem, key_pem)) => {
                 if let Some(cert_file) = cert_out {
                     if let Err(e) = std::fs::write(&cert_file, &cert_pem) {
@@ -1048,6 +1574,169 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
             Err(e) => eprintln!("Error creating certificate: {}", e),
         },
+        Some(Commands::CreateCa {
+            name,
+            issuer_cert,
+            issuer_key,
+            cert_out,
+            key_out,
+        }) => {
+            let ca = match (issuer_cert, issuer_key) {
+                (Some(issuer_cert), Some(issuer_key)) => {
+                    let issuer = CertificateAuthority::from_pem(
+                        &std::fs::read_to_string(&issuer_cert)?,
+                        &std::fs::read_to_string(&issuer_key)?,
+                    );
+                    issuer.and_then(|issuer| issuer.create_intermediate(&name))
+                }
+                _ => CertificateAuthority::create_root(&name),
+            };
+            match ca {
+                Ok(ca) => {
+                    if write_pem_output("CA Certificate", cert_out, ca.cert_pem()) {
+                        write_pem_output("CA Private Key", key_out, &ca.key_pem());
+                    }
+                }
+                Err(e) => eprintln!("Error creating CA: {}", e),
+            }
+        }
+        Some(Commands::CreateCsr {
+            name,
+            registration_authority,
+            registration_number,
+            csr_out,
+            key_out,
+        }) => match Certificate::create_csr(&name, &registration_authority, &registration_number) {
+            Ok((csr_pem, key_pem)) => {
+                if write_pem_output("Certificate Signing Request", csr_out, &csr_pem) {
+                    write_pem_output("Private Key", key_out, &key_pem);
+                }
+            }
+            Err(e) => eprintln!("Error creating CSR: {}", e),
+        },
+        Some(Commands::SignCsr {
+            csr_file,
+            ca_cert,
+            ca_key,
+            cert_out,
+        }) => {
+            let ca = CertificateAuthority::from_pem(
+                &std::fs::read_to_string(&ca_cert)?,
+                &std::fs::read_to_string(&ca_key)?,
+            );
+            let csr_pem = std::fs::read_to_string(&csr_file)?;
+            match ca.and_then(|ca| ca.sign_csr(&csr_pem)) {
+                Ok(cert_pem) => {
+                    write_pem_output("Certificate", cert_out, &cert_pem);
+                }
+                Err(e) => eprintln!("Error signing CSR: {}", e),
+            }
+        }
+        Some(Commands::RevokeCertificate {
+            cert_file,
+            author_name,
+            author_email,
+            role,
+            registration,
+            care_location,
+            ca_cert,
+            ca_key,
+            revoked_at,
+            crl_out,
+            signature,
+        }) => {
+            let registrations: Vec<AuthorRegistration> = registration
+                .chunks(2)
+                .map(|chunk| {
+                    AuthorRegistration::new(
+                        chunk.first().cloned().unwrap_or_default(),
+                        chunk.get(1).cloned().unwrap_or_default(),
+                    )
+                    .expect("valid registration")
+                })
+                .collect();
+            let name = match NonEmptyText::new(&author_name) {
+                Ok(n) => n,
+                Err(e) => {
+                    eprintln!("Invalid author name: {}", e);
+                    return Ok(());
+                }
+            };
+            let role = match NonEmptyText::new(&role) {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("Invalid author role: {}", e);
+                    return Ok(());
+                }
+            };
+            let email = match EmailAddress::parse(&author_email) {
+                Ok(e) => e,
+                Err(e) => {
+                    eprintln!("Invalid author email: {:?}", e);
+                    return Ok(());
+                }
+            };
+            let care_location = match NonEmptyText::new(&care_location) {
+                Ok(cl) => cl,
+                Err(e) => {
+                    eprintln!("Invalid care_location: {}", e);
+                    return Ok(());
+                }
+            };
+            let revoked_at = match revoked_at.as_deref().map(parse_cli_datetime).transpose() {
+                Ok(t) => t.unwrap_or_else(Utc::now),
+                Err(e) => {
+                    eprintln!("Invalid --revoked-at: {}", e);
+                    return Ok(());
+                }
+            };
+            let author = Author {
+                name,
+                role,
+                email,
+                registrations,
+                signature: signature.map(|s| s.into_bytes()),
+                certificate: None,
+            };
+
+            let ca_cert_pem = std::fs::read_to_string(&ca_cert)?;
+            let ca = match CertificateAuthority::from_pem(
+                &ca_cert_pem,
+                &std::fs::read_to_string(&ca_key)?,
+            ) {
+                Ok(ca) => ca,
+                Err(e) => {
+                    eprintln!("Error loading CA: {}", e);
+                    return Ok(());
+                }
+            };
+            let cert_pem = std::fs::read_to_string(&cert_file)?;
+
+            let service = RevocationService::new(cfg.clone());
+            let current = match service.crl_for_issuer(&ca_cert_pem) {
+                Ok(crl) => crl,
+                Err(e) => {
+                    eprintln!("Error reading revocation list: {}", e);
+                    return Ok(());
+                }
+            };
+            let crl_pem = match ca.revoke(current.as_deref(), &cert_pem, revoked_at.into()) {
+                Ok(crl) => crl,
+                Err(e) => {
+                    eprintln!("Error revoking certificate: {}", e);
+                    return Ok(());
+                }
+            };
+            match service.publish_crl(&author, care_location, &crl_pem) {
+                Ok(()) => {
+                    println!("Certificate revoked from {}", revoked_at.to_rfc3339());
+                    if crl_out.is_some() {
+                        write_pem_output("CRL", crl_out, &crl_pem);
+                    }
+                }
+                Err(e) => eprintln!("Error publishing revocation list: {}", e),
+            }
+        }
         Some(Commands::NewLetter {
             clinical_uuid,
             author_name,
@@ -1508,6 +2197,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     println!("Status: {:?}", thread.ledger.status);
                     println!("Created: {}", thread.ledger.created_at);
                     println!("Last Updated: {}", thread.ledger.last_updated_at);
+                    println!("Commit: {}", thread.commit_id);
                     println!("\nParticipants:");
                     for p in &thread.ledger.participants {
                         println!("  - {} ({:?}): {}", p.id, p.role, p.name);
@@ -1546,7 +2236,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             let clinical_service = ClinicalService::with_id(cfg.clone(), clinical_uuid_parsed);
             match clinical_service.read_letter(&letter_timestamp_id) {
                 Ok(result) => {
-                    println!("Letter Timestamp ID: {}", letter_timestamp_id);
+                    println!("Letter Timestamp ID: {}", result.letter_data.uid);
+                    println!("Commit: {}", result.commit_id);
+                    if result.versions.len() > 1 {
+                        println!("\nVersions (oldest first):");
+                        for version in &result.versions {
+                            println!("  - {}", version);
+                        }
+                    }
                     println!("\n--- Composition Data ---");
                     println!("RM Version: {:?}", result.letter_data.rm_version);
                     println!("Composer: {}", result.letter_data.composer_name);
@@ -1564,6 +2261,74 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 Err(e) => eprintln!("Error reading letter: {}", e),
             }
         }
+        Some(Commands::ListLetters {
+            clinical_uuid,
+            cursor,
+            limit,
+            from,
+            to,
+        }) => {
+            let clinical_uuid_parsed = match ShardableUuid::parse(&clinical_uuid) {
+                Ok(uuid) => uuid.uuid(),
+                Err(e) => {
+                    eprintln!("Error parsing clinical UUID: {}", e);
+                    return Ok(());
+                }
+            };
+
+            let cursor = match cursor.as_deref().map(str::parse::<TimestampId>).transpose() {
+                Ok(cursor) => cursor,
+                Err(e) => {
+                    eprintln!("Error parsing cursor: {}", e);
+                    return Ok(());
+                }
+            };
+            let (from, to) = match (
+                from.as_deref().map(parse_cli_datetime).transpose(),
+                to.as_deref().map(parse_cli_datetime).transpose(),
+            ) {
+                (Ok(from), Ok(to)) => (from, to),
+                (Err(e), _) | (_, Err(e)) => {
+                    eprintln!("Error parsing date: {}", e);
+                    return Ok(());
+                }
+            };
+
+            let clinical_service = ClinicalService::with_id(cfg.clone(), clinical_uuid_parsed);
+            let query = ListLettersQuery {
+                cursor,
+                limit,
+                from,
+                to,
+            };
+            match clinical_service.list_letters(&query) {
+                Ok(page) => {
+                    if page.letters.is_empty() {
+                        println!("No letters found");
+                    }
+                    for letter in &page.letters {
+                        println!("{}", letter.uid);
+                        println!(
+                            "  Composer: {} ({})",
+                            letter.composer_name, letter.composer_role
+                        );
+                        println!("  Start Time: {}", letter.start_time.to_rfc3339());
+                        println!(
+                            "  Body: {}, Attachments: {}",
+                            if letter.has_body { "yes" } else { "no" },
+                            letter.attachment_count
+                        );
+                        if let Some(superseded_by) = &letter.superseded_by {
+                            println!("  Superseded by: {}", superseded_by);
+                        }
+                    }
+                    if let Some(next_cursor) = page.next_cursor {
+                        println!("\nMore letters available: --cursor {}", next_cursor);
+                    }
+                }
+                Err(e) => eprintln!("Error listing letters: {}", e),
+            }
+        }
         Some(Commands::NewLetterWithAttachments {
             clinical_uuid,
             author_name,
@@ -2106,6 +2871,39 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Writes PEM output to `path`, or prints it under a `label` heading if no path is given.
+///
+/// Returns `false` if the file could not be written.
+fn write_pem_output(label: &str, path: Option<String>, pem: &str) -> bool {
+    match path {
+        Some(path) => match std::fs::write(&path, pem) {
+            Ok(()) => {
+                println!("{} written to {}", label, path);
+                true
+            }
+            Err(e) => {
+                eprintln!("Error writing {} to {}: {}", label.to_lowercase(), path, e);
+                false
+            }
+        },
+        None => {
+            println!("--- {} ---", label);
+            println!("{}", pem);
+            true
+        }
+    }
+}
+
+/// Parses a CLI date argument given as RFC3339 or as a `YYYY-MM-DD` date (midnight UTC).
+fn parse_cli_datetime(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc())
+        .map_err(|_| format!("'{}' is not an RFC3339 time or YYYY-MM-DD date", value))
+}
+
 fn build_core_config_from_env() -> Result<Arc<CoreConfig>, Box<dyn std::error::Error>> {
     let patient_data_dir = std::env::var("PATIENT_DATA_DIR")
         .unwrap_or_else(|_| vpr_core::DEFAULT_PATIENT_DATA_DIR.into());
@@ -2128,9 +2926,36 @@ fn build_core_config_from_env() -> Result<Arc<CoreConfig>, Box<dyn std::error::E
         .and_then(|s| vpr_core::NonEmptyText::new(s).ok())
         .unwrap_or_else(|| vpr_core::NonEmptyText::new("vpr.dev.1").unwrap());
 
-    Ok(Arc::new(CoreConfig::new(
+    let mut cfg = CoreConfig::new(
         patient_data_path.to_path_buf(),
         rm_system_version,
         vpr_namespace,
-    )?))
+    )?;
+    if let Some(anchors) =
+        trust_anchors_from_env_value(std::env::var("VPR_TRUST_ANCHORS_PATH").ok().as_deref())?
+    {
+        cfg = cfg.with_trust_anchors(anchors);
+    }
+    if let Some(revocations) =
+        revocation_list_from_env_value(std::env::var("VPR_REVOCATION_CRL_PATH").ok().as_deref())?
+    {
+        cfg = cfg.with_revocation_list(revocations);
+    }
+    if let Some(tsa) =
+        timestamp_authority_from_env_value(std::env::var("VPR_TSA_URL").ok().as_deref())?
+    {
+        cfg = cfg.with_timestamp_authority(tsa);
+    }
+    if let Some(anchors) =
+        trust_anchors_from_env_value(std::env::var("VPR_TSA_TRUST_ANCHORS_PATH").ok().as_deref())?
+    {
+        cfg = cfg.with_timestamp_trust_anchors(anchors);
+    }
+    if let Some(mirror_root) =
+        mirror_root_from_env_value(std::env::var("VPR_MIRROR_URL").ok().as_deref())?
+    {
+        cfg = cfg.with_mirror(mirror_root);
+    }
+
+    Ok(Arc::new(cfg))
 }
@@ -4,9 +4,11 @@
 //! signatures, and commit validation in the VPR system.
 
 use crate::error::{PatientError, PatientResult};
+use crate::signing::SignatureAlgorithm;
+use crate::versioned_files::{VprCommitSignaturePayloadV2, SIGNATURE_PAYLOAD_VERSION_2};
 use crate::{EmailAddress, NonEmptyText};
 use base64::{engine::general_purpose, Engine as _};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use x509_parser::prelude::*;
 
 /// Represents an author of a commit or record operation.
@@ -34,9 +36,11 @@ pub struct Author {
 /// Material embedded in the Git commit object to enable offline verification.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EmbeddedCommitSignature {
-    /// Raw 64-byte ECDSA P-256 signature (`r || s`).
+    /// Algorithm of `signature` and `public_key`; always ECDSA P-256 for version 1 payloads.
+    pub algorithm: SignatureAlgorithm,
+    /// Raw signature bytes (`r || s` for ECDSA).
     pub signature: Vec<u8>,
-    /// SEC1-encoded public key bytes.
+    /// Public key bytes: SEC1-encoded for ECDSA, raw 32 bytes for Ed25519.
     pub public_key: Vec<u8>,
     /// Optional X.509 certificate bytes (PEM or DER).
     pub certificate: Option<Vec<u8>>,
@@ -50,6 +54,13 @@ struct VprCommitSignaturePayloadV1 {
     certificate: Option<String>,
 }
 
+/// The `version` field shared by every payload version; absent in version 1.
+#[derive(Deserialize)]
+struct VprCommitSignaturePayloadVersion {
+    #[serde(default)]
+    version: Option<u32>,
+}
+
 fn extract_cert_public_key_sec1(cert_bytes: &[u8]) -> PatientResult<Vec<u8>> {
     let cert_der: Vec<u8> = if cert_bytes
         .windows("-----BEGIN CERTIFICATE-----".len())
@@ -71,11 +82,13 @@ fn extract_cert_public_key_sec1(cert_bytes: &[u8]) -> PatientResult<Vec<u8>> {
 /// Extract the embedded signature material from a commit.
 ///
 /// VPR stores a base64-encoded JSON container in the commit's `gpgsig` header that includes:
-/// - `signature` (base64 raw 64-byte `r||s`)
-/// - `public_key` (base64 SEC1 public key bytes)
+/// - `version` and `algorithm` (version 2 only; version 1 is always ECDSA P-256)
+/// - `signature` (base64 raw signature bytes)
+/// - `public_key` (base64 public key bytes)
 /// - optional `certificate` (base64 of PEM or DER bytes)
 ///
 /// If a certificate is present, this validates that it corresponds to the embedded public key.
+/// Payloads with an unknown `version` are rejected as invalid.
 pub fn extract_embedded_commit_signature(
     commit: &git2::Commit<'_>,
 ) -> PatientResult<EmbeddedCommitSignature> {
@@ -93,8 +106,24 @@ pub fn extract_embedded_commit_signature(
     let payload_bytes = general_purpose::STANDARD
         .decode(sig_b64)
         .map_err(|_| PatientError::InvalidCommitSignaturePayload)?;
-    let payload: VprCommitSignaturePayloadV1 = serde_json::from_slice(&payload_bytes)
+    let version: VprCommitSignaturePayloadVersion = serde_json::from_slice(&payload_bytes)
         .map_err(|_| PatientError::InvalidCommitSignaturePayload)?;
+    let payload = match version.version {
+        None => {
+            let v1: VprCommitSignaturePayloadV1 = serde_json::from_slice(&payload_bytes)
+                .map_err(|_| PatientError::InvalidCommitSignaturePayload)?;
+            VprCommitSignaturePayloadV2 {
+                version: SIGNATURE_PAYLOAD_VERSION_2,
+                algorithm: SignatureAlgorithm::EcdsaP256,
+                signature: v1.signature,
+                public_key: v1.public_key,
+                certificate: v1.certificate,
+            }
+        }
+        Some(SIGNATURE_PAYLOAD_VERSION_2) => serde_json::from_slice(&payload_bytes)
+            .map_err(|_| PatientError::InvalidCommitSignaturePayload)?,
+        Some(_) => return Err(PatientError::InvalidCommitSignaturePayload),
+    };
 
     let signature = general_purpose::STANDARD
         .decode(payload.signature)
@@ -119,6 +148,7 @@ pub fn extract_embedded_commit_signature(
     }
 
     Ok(EmbeddedCommitSignature {
+        algorithm: payload.algorithm,
         signature,
         public_key,
         certificate,
@@ -130,7 +160,7 @@ pub fn extract_embedded_commit_signature(
 /// This is rendered in commit trailers as:
 ///
 /// `Author-Registration: <authority> <number>`
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct AuthorRegistration {
     pub authority: NonEmptyText,
     pub number: NonEmptyText,
@@ -0,0 +1,742 @@
+//! Backup and restore of patient repositories.
+//!
+//! ## Purpose
+//! A VPR instance holds one Git repository per record, plus the content-addressed binary
+//! files that Git does not track. This module copies both into a self-describing backup
+//! directory, and recreates the repositories from it without rewriting any history.
+//!
+//! ## Backup layout
+//!
+//! ```text
+//! <backup_dir>/
+//!   manifest.json                       # What was backed up, and when
+//!   <kind>/
+//!     <id>.bundle                       # `git bundle` of every ref
+//!     <id>/files/sha256/ab/cd/<hash>    # Stored binary files
+//! ```
+//!
+//! where `<kind>` is `clinical`, `demographics`, `coordination` or `redaction`. Bundles are
+//! standard v2 Git bundles, so `git clone <id>.bundle` also works. Each bundle is read back
+//! into a scratch repository and checked before the backup is reported as complete.
+//!
+//! ## Restore
+//!
+//! [`BackupService::restore`] only recreates repositories that do not exist; it never
+//! overwrites one. Each restored repository has its objects, its bundle and file hashes, and
+//! its commit signatures checked (against the configured trust anchors and revocations),
+//! then receives a signed `recover` commit recording the backup it came from. The marker
+//! changes no files, so the restored history stays exactly as it was backed up.
+
+use crate::author::Author;
+use crate::config::CoreConfig;
+use crate::error::{PatientError, PatientResult};
+use crate::integrity::{check_objects, collect_files, RepositoryIntegrity};
+use crate::repositories::revocation::RevocationService;
+use crate::repositories::shared::sharded_repository_dirs;
+use crate::versioned_files::{
+    ClinicalDomain, CoordinationDomain, DemographicsDomain, RedactionDomain, RevocationDomain,
+    VersionedFileService, VprCommitAction, VprCommitDomain, VprCommitMessage, VprRepositoryKind,
+    MAIN_REF,
+};
+use crate::{NonEmptyText, ShardableUuid};
+use chrono::{DateTime, Utc};
+use git2::{Oid, Repository};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+use vpr_files::FILES_FOLDER_NAME;
+use vpr_uuid::Sha256Hash;
+
+/// Name of the manifest file at the root of a backup directory.
+pub const MANIFEST_FILENAME: &str = "manifest.json";
+
+/// Version of the backup layout written into [`BackupManifest::format`].
+const BACKUP_FORMAT: u32 = 1;
+
+/// First line of a v2 Git bundle.
+const BUNDLE_SIGNATURE: &[u8] = b"# v2 git bundle\n";
+
+/// Directory (under `files/`) holding content-addressed binary files.
+const SHA256_DIR: &str = "sha256";
+
+/// The repository kinds that are backed up, in backup order.
+const BACKUP_KINDS: [VprRepositoryKind; 4] = [
+    VprRepositoryKind::Clinical,
+    VprRepositoryKind::Demographics,
+    VprRepositoryKind::Coordination,
+    VprRepositoryKind::Redaction,
+];
+
+/// Description of a backup, stored as `manifest.json` in the backup directory.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BackupManifest {
+    /// Version of the backup layout.
+    pub format: u32,
+    /// Identifier recorded in the recovery marker of every repository restored from it.
+    pub backup_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub repositories: Vec<BackedUpRepository>,
+}
+
+/// One repository in a backup.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BackedUpRepository {
+    pub kind: VprRepositoryKind,
+    /// Repository UUID, as named on disk.
+    pub id: String,
+    /// Commit `refs/heads/main` pointed to when the backup was made.
+    pub head: String,
+    /// SHA-256 of the bundle file.
+    pub bundle_sha256: Sha256Hash,
+    /// Hashes of the stored binary files copied with the repository.
+    pub files: Vec<Sha256Hash>,
+}
+
+/// One repository recreated by [`BackupService::restore`].
+#[derive(Clone, Debug, Serialize)]
+pub struct RestoredRepository {
+    pub kind: VprRepositoryKind,
+    pub id: String,
+    /// Commit the backup held, now the parent of the recovery marker.
+    pub restored_head: String,
+    /// The recovery marker commit.
+    pub marker_commit: String,
+}
+
+/// Service for backing up and restoring every patient repository.
+#[derive(Clone, Debug)]
+pub struct BackupService {
+    cfg: Arc<CoreConfig>,
+}
+
+impl BackupService {
+    /// Creates a service for the repositories under the configured patient data directory.
+    pub fn new(cfg: Arc<CoreConfig>) -> Self {
+        Self { cfg }
+    }
+
+    /// Backs up every clinical, demographics, coordination and redaction repository.
+    ///
+    /// # Arguments
+    ///
+    /// * `backup_dir` - Directory to write the backup to. It must not exist, or be empty.
+    ///
+    /// # Returns
+    ///
+    /// The manifest written to `backup_dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::InvalidInput` if `backup_dir` is not empty or a stored binary
+    /// file does not match its hash, `PatientError::GitBundle` if a bundle cannot be written
+    /// or does not read back intact, and I/O or Git errors otherwise. A failed backup may
+    /// leave a partial backup directory without a manifest.
+    pub fn backup(&self, backup_dir: &Path) -> PatientResult<BackupManifest> {
+        if fs::read_dir(backup_dir).is_ok_and(|mut entries| entries.next().is_some()) {
+            return Err(PatientError::InvalidInput(format!(
+                "backup directory is not empty: {}",
+                backup_dir.display()
+            )));
+        }
+
+        fs::create_dir_all(backup_dir).map_err(PatientError::FileWrite)?;
+
+        let mut manifest = BackupManifest {
+            format: BACKUP_FORMAT,
+            backup_id: Uuid::new_v4(),
+            created_at: Utc::now(),
+            repositories: Vec::new(),
+        };
+
+        for kind in BACKUP_KINDS {
+            for repo_dir in sharded_repository_dirs(&self.kind_dir(kind))? {
+                let id = repo_dir
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                manifest
+                    .repositories
+                    .push(backup_repository(kind, &id, &repo_dir, backup_dir)?);
+            }
+        }
+
+        // The manifest is written last, so a backup without one is known to be incomplete.
+        let json = serde_json::to_vec_pretty(&manifest).map_err(PatientError::Serialization)?;
+        fs::write(backup_dir.join(MANIFEST_FILENAME), json).map_err(PatientError::FileWrite)?;
+
+        Ok(manifest)
+    }
+
+    /// Restores every repository in a backup, adding a recovery marker commit to each.
+    ///
+    /// Nothing is restored unless every repository in the backup is absent from the patient
+    /// data directory. Repositories are then restored one at a time; if one fails
+    /// verification it is removed again and the restore stops, leaving the repositories
+    /// already restored in place.
+    ///
+    /// # Arguments
+    ///
+    /// * `backup_dir` - Directory written by [`backup`](Self::backup).
+    /// * `author` - Author of the recovery marker commits. Must have a signing key.
+    /// * `care_location` - Organisational location recorded in the marker commits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::InvalidInput` if the author cannot sign, the manifest has an
+    /// unsupported format, or a repository in the backup already exists;
+    /// `PatientError::RestoreVerification` if a bundle or file does not match the manifest,
+    /// or a restored commit signature fails verification; and I/O, JSON or Git errors
+    /// otherwise.
+    pub fn restore(
+        &self,
+        backup_dir: &Path,
+        author: &Author,
+        care_location: NonEmptyText,
+    ) -> PatientResult<Vec<RestoredRepository>> {
+        if author.signature.is_none() {
+            return Err(PatientError::InvalidInput(
+                "recovery marker commits must be signed".into(),
+            ));
+        }
+
+        let manifest = read_manifest(backup_dir)?;
+        let mut targets = Vec::new();
+        for entry in &manifest.repositories {
+            if !BACKUP_KINDS.contains(&entry.kind) {
+                return Err(PatientError::InvalidInput(format!(
+                    "backup holds an unsupported repository kind: {}",
+                    entry.kind.as_str()
+                )));
+            }
+            let target = ShardableUuid::parse(&entry.id)?.sharded_dir(&self.kind_dir(entry.kind));
+            if target.exists() {
+                return Err(PatientError::InvalidInput(format!(
+                    "{} repository {} already exists",
+                    entry.kind.as_str(),
+                    entry.id
+                )));
+            }
+            targets.push(target);
+        }
+
+        let revocations = RevocationService::new(self.cfg.clone()).revocation_list()?;
+        let mut restored = Vec::new();
+        for (entry, target) in manifest.repositories.iter().zip(targets) {
+            let result = (|| {
+                restore_repository(entry, backup_dir, &target)?;
+
+                let report = VersionedFileService::verify_signature_chain(
+                    &self.kind_dir(entry.kind),
+                    &entry.id,
+                    self.cfg.trust_anchors(),
+                    Some(&revocations),
+                    self.cfg.timestamp_trust_anchors(),
+                )?;
+                if let Some(failure) = report.failures().next() {
+                    return Err(PatientError::RestoreVerification(format!(
+                        "commit {} in {} repository {} failed signature verification",
+                        failure.commit_id,
+                        entry.kind.as_str(),
+                        entry.id
+                    )));
+                }
+
+                let msg = recovery_message(entry, &manifest, care_location.clone())?;
+                VersionedFileService::open(&target)?.commit_paths(
+                    author,
+                    &msg,
+                    &[],
+                    self.cfg.timestamp_authority(),
+                    self.cfg.mirror(),
+                )
+            })();
+
+            match result {
+                Ok(marker) => restored.push(RestoredRepository {
+                    kind: entry.kind,
+                    id: entry.id.clone(),
+                    restored_head: entry.head.clone(),
+                    marker_commit: marker.to_string(),
+                }),
+                Err(e) => {
+                    if let Err(cleanup) = fs::remove_dir_all(&target) {
+                        tracing::warn!(
+                            "Failed to remove partially restored repository {}: {}",
+                            target.display(),
+                            cleanup
+                        );
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(restored)
+    }
+
+    fn kind_dir(&self, kind: VprRepositoryKind) -> PathBuf {
+        self.cfg.patient_data_dir().join(kind.as_str())
+    }
+}
+
+/// Writes and verifies the bundle for one repository, and copies its stored files.
+fn backup_repository(
+    kind: VprRepositoryKind,
+    id: &str,
+    repo_dir: &Path,
+    backup_dir: &Path,
+) -> PatientResult<BackedUpRepository> {
+    let repo = Repository::open(repo_dir).map_err(PatientError::GitOpen)?;
+    let head = repo
+        .find_reference(MAIN_REF)
+        .and_then(|reference| reference.peel_to_commit())
+        .map_err(PatientError::GitHead)?
+        .id();
+
+    let bundle = write_bundle(&repo)?;
+    verify_bundle(&bundle, head)?;
+
+    let kind_dir = backup_dir.join(kind.as_str());
+    fs::create_dir_all(&kind_dir).map_err(PatientError::FileWrite)?;
+    fs::write(kind_dir.join(format!("{id}.bundle")), &bundle).map_err(PatientError::FileWrite)?;
+
+    let store = Path::new(FILES_FOLDER_NAME).join(SHA256_DIR);
+    let mut stored = Vec::new();
+    collect_files(repo_dir, &store, &mut stored).map_err(PatientError::FileRead)?;
+    let mut files = Vec::new();
+    for relative in stored {
+        let content = fs::read(repo_dir.join(&relative)).map_err(PatientError::FileRead)?;
+        let hash = content_hash(&content);
+        if relative != stored_path(&hash) {
+            return Err(PatientError::InvalidInput(format!(
+                "stored file {} in {} repository {} does not match its hash",
+                relative.display(),
+                kind.as_str(),
+                id
+            )));
+        }
+
+        let target = kind_dir.join(id).join(&relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(PatientError::FileWrite)?;
+        }
+        fs::write(&target, &content).map_err(PatientError::FileWrite)?;
+        files.push(hash);
+    }
+
+    Ok(BackedUpRepository {
+        kind,
+        id: id.to_string(),
+        head: head.to_string(),
+        bundle_sha256: content_hash(&bundle),
+        files,
+    })
+}
+
+/// Recreates one repository from its bundle and files, checking both against the manifest.
+fn restore_repository(
+    entry: &BackedUpRepository,
+    backup_dir: &Path,
+    target: &Path,
+) -> PatientResult<()> {
+    let kind_dir = backup_dir.join(entry.kind.as_str());
+    let mismatch = |what: String| {
+        PatientError::RestoreVerification(format!(
+            "{what} for {} repository {} does not match the backup manifest",
+            entry.kind.as_str(),
+            entry.id
+        ))
+    };
+
+    let bundle =
+        fs::read(kind_dir.join(format!("{}.bundle", entry.id))).map_err(PatientError::FileRead)?;
+    if content_hash(&bundle) != entry.bundle_sha256 {
+        return Err(mismatch("bundle".into()));
+    }
+
+    fs::create_dir_all(target).map_err(PatientError::PatientDirCreation)?;
+    let repo = Repository::init(target).map_err(PatientError::GitInit)?;
+    unpack_bundle(&repo, &bundle)?;
+    let head = repo
+        .find_reference(MAIN_REF)
+        .and_then(|reference| reference.peel_to_commit())
+        .map_err(PatientError::GitHead)?
+        .id();
+    if head.to_string() != entry.head {
+        return Err(mismatch(MAIN_REF.into()));
+    }
+    repo.set_head(MAIN_REF).map_err(PatientError::GitSetHead)?;
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+        .map_err(PatientError::GitBundle)?;
+
+    let mut integrity = RepositoryIntegrity {
+        kind: entry.kind,
+        id: entry.id.clone(),
+        issues: Vec::new(),
+    };
+    check_objects(&repo, &mut integrity);
+    if let Some(issue) = integrity.issues.first() {
+        return Err(PatientError::RestoreVerification(issue.message.clone()));
+    }
+
+    for hash in &entry.files {
+        let relative = stored_path(hash);
+        let content =
+            fs::read(kind_dir.join(&entry.id).join(&relative)).map_err(PatientError::FileRead)?;
+        if content_hash(&content) != *hash {
+            return Err(mismatch(format!("stored file {hash}")));
+        }
+        let path = target.join(&relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(PatientError::FileWrite)?;
+        }
+        fs::write(path, content).map_err(PatientError::FileWrite)?;
+    }
+
+    Ok(())
+}
+
+/// Builds the recovery marker commit message for a restored repository.
+fn recovery_message(
+    entry: &BackedUpRepository,
+    manifest: &BackupManifest,
+    care_location: NonEmptyText,
+) -> PatientResult<VprCommitMessage> {
+    let domain = match entry.kind {
+        VprRepositoryKind::Clinical => VprCommitDomain::Clinical(ClinicalDomain::Record),
+        VprRepositoryKind::Demographics => {
+            VprCommitDomain::Demographics(DemographicsDomain::Record)
+        }
+        VprRepositoryKind::Coordination => {
+            VprCommitDomain::Coordination(CoordinationDomain::Record)
+        }
+        VprRepositoryKind::Redaction => VprCommitDomain::Redaction(RedactionDomain::Record),
+        VprRepositoryKind::Revocation => VprCommitDomain::Revocation(RevocationDomain::Record),
+    };
+
+    VprCommitMessage::new(
+        domain,
+        VprCommitAction::Recover,
+        "Restored from backup",
+        care_location,
+    )?
+    .with_trailer("Recovery-Backup-Id", manifest.backup_id.to_string())?
+    .with_trailer("Recovery-Backup-Created", manifest.created_at.to_rfc3339())?
+    .with_trailer("Recovery-Restored-Head", entry.head.clone())
+}
+
+fn read_manifest(backup_dir: &Path) -> PatientResult<BackupManifest> {
+    let json = fs::read(backup_dir.join(MANIFEST_FILENAME)).map_err(PatientError::FileRead)?;
+    let manifest: BackupManifest =
+        serde_json::from_slice(&json).map_err(PatientError::Deserialization)?;
+    if manifest.format != BACKUP_FORMAT {
+        return Err(PatientError::InvalidInput(format!(
+            "unsupported backup format: {}",
+            manifest.format
+        )));
+    }
+    Ok(manifest)
+}
+
+/// Writes a v2 bundle holding `HEAD` and every reference in `repo`, with no prerequisites.
+fn write_bundle(repo: &Repository) -> PatientResult<Vec<u8>> {
+    let mut refs = Vec::new();
+    if let Ok(head) = repo.head() {
+        refs.extend(head.target().map(|oid| (oid, "HEAD".to_string())));
+    }
+    for reference in repo.references().map_err(PatientError::GitBundle)? {
+        let reference = reference.map_err(PatientError::GitBundle)?;
+        if let (Some(oid), Some(name)) = (reference.target(), reference.name()) {
+            refs.push((oid, name.to_string()));
+        }
+    }
+
+    let mut walk = repo.revwalk().map_err(PatientError::GitBundle)?;
+    for (oid, _) in &refs {
+        walk.push(*oid).map_err(PatientError::GitBundle)?;
+    }
+    let mut builder = repo.packbuilder().map_err(PatientError::GitBundle)?;
+    builder
+        .insert_walk(&mut walk)
+        .map_err(PatientError::GitBundle)?;
+    let mut pack = git2::Buf::new();
+    builder
+        .write_buf(&mut pack)
+        .map_err(PatientError::GitBundle)?;
+
+    let mut bundle = BUNDLE_SIGNATURE.to_vec();
+    for (oid, name) in &refs {
+        bundle.extend_from_slice(format!("{oid} {name}\n").as_bytes());
+    }
+    bundle.push(b'\n');
+    bundle.extend_from_slice(&pack);
+    Ok(bundle)
+}
+
+/// Reads a bundle back into a scratch repository and checks that it holds `head` intact.
+fn verify_bundle(bundle: &[u8], head: Oid) -> PatientResult<()> {
+    let scratch = ScratchDir::new()?;
+    let repo = Repository::init_bare(&scratch.0).map_err(PatientError::GitInit)?;
+    unpack_bundle(&repo, bundle)?;
+
+    let bundled_head = repo
+        .find_reference(MAIN_REF)
+        .and_then(|reference| reference.peel_to_commit())
+        .map_err(PatientError::GitBundle)?
+        .id();
+    let mut integrity = RepositoryIntegrity {
+        kind: VprRepositoryKind::Clinical,
+        id: String::new(),
+        issues: Vec::new(),
+    };
+    check_objects(&repo, &mut integrity);
+    if bundled_head != head || !integrity.issues.is_empty() {
+        return Err(PatientError::GitBundle(git2::Error::from_str(
+            "bundle does not read back intact",
+        )));
+    }
+    Ok(())
+}
+
+/// Indexes the pack in `bundle` into `repo` and creates the references it lists.
+fn unpack_bundle(repo: &Repository, bundle: &[u8]) -> PatientResult<()> {
+    let invalid = |reason: &str| PatientError::GitBundle(git2::Error::from_str(reason));
+
+    let mut rest = bundle
+        .strip_prefix(BUNDLE_SIGNATURE)
+        .ok_or_else(|| invalid("not a v2 git bundle"))?;
+    let mut refs = Vec::new();
+    loop {
+        let end = rest
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or_else(|| invalid("truncated bundle header"))?;
+        let line = std::str::from_utf8(&rest[..end]).map_err(|_| invalid("invalid header"))?;
+        rest = &rest[end + 1..];
+        if line.is_empty() {
+            break;
+        }
+        if line.starts_with('-') {
+            return Err(invalid("bundle has prerequisites"));
+        }
+        let (oid, name) = line
+            .split_once(' ')
+            .ok_or_else(|| invalid("invalid bundle reference"))?;
+        let oid = Oid::from_str(oid).map_err(PatientError::GitBundle)?;
+        refs.push((oid, name.to_string()));
+    }
+
+    let odb = repo.odb().map_err(PatientError::GitBundle)?;
+    let mut writer = odb.packwriter().map_err(PatientError::GitBundle)?;
+    writer
+        .write_all(rest)
+        .map_err(|e| invalid(&e.to_string()))?;
+    writer.commit().map_err(PatientError::GitBundle)?;
+
+    for (oid, name) in refs {
+        if name.starts_with("refs/") {
+            repo.reference(&name, oid, true, "restore from backup")
+                .map_err(PatientError::GitReference)?;
+        }
+    }
+    Ok(())
+}
+
+fn content_hash(content: &[u8]) -> Sha256Hash {
+    Sha256Hash::from_bytes(&Sha256::digest(content).into())
+}
+
+/// Returns the repository-relative path a stored file with `hash` lives at.
+fn stored_path(hash: &Sha256Hash) -> PathBuf {
+    let hex = hash.as_str();
+    Path::new(FILES_FOLDER_NAME)
+        .join(SHA256_DIR)
+        .join(&hex[0..2])
+        .join(&hex[2..4])
+        .join(hex)
+}
+
+/// A uniquely named directory under the system temporary directory, removed on drop.
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn new() -> PatientResult<Self> {
+        let path = std::env::temp_dir().join(format!("vpr-bundle-{}", Uuid::new_v4().simple()));
+        fs::create_dir(&path).map_err(PatientError::FileWrite)?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::rm_system_version_from_env_value;
+    use crate::integrity::IntegrityService;
+    use crate::repositories::clinical::ClinicalService;
+    use crate::versioned_files::CommitSignatureStatus;
+    use crate::{EmailAddress, PatientService};
+    use chrono::NaiveDate;
+    use p256::ecdsa::SigningKey;
+    use p256::pkcs8::EncodePrivateKey;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Arc<CoreConfig>, Author) {
+        let temp_dir = TempDir::new().unwrap();
+        let cfg = Arc::new(
+            CoreConfig::new(
+                temp_dir.path().join("patient_data"),
+                rm_system_version_from_env_value(None).unwrap(),
+                NonEmptyText::new("vpr.dev.1").unwrap(),
+            )
+            .unwrap(),
+        );
+        let key = SigningKey::random(&mut rand::thread_rng())
+            .to_pkcs8_pem(p256::pkcs8::LineEnding::LF)
+            .unwrap();
+        let author = Author {
+            name: NonEmptyText::new("Dr. Test").unwrap(),
+            role: NonEmptyText::new("Consultant").unwrap(),
+            email: EmailAddress::parse("test@example.com").unwrap(),
+            registrations: vec![],
+            signature: Some(key.as_bytes().to_vec()),
+            certificate: None,
+        };
+        (temp_dir, cfg, author)
+    }
+
+    fn location() -> NonEmptyText {
+        NonEmptyText::new("Test Hospital").unwrap()
+    }
+
+    /// Creates a full record whose clinical repository holds a letter with one attachment.
+    fn full_record(temp_dir: &TempDir, cfg: &Arc<CoreConfig>, author: &Author) {
+        let record = PatientService::new(cfg.clone())
+            .initialise_full_record(
+                author.clone(),
+                location(),
+                vec![NonEmptyText::new("Ada").unwrap()],
+                NonEmptyText::new("Lovelace").unwrap(),
+                NaiveDate::from_ymd_opt(1815, 12, 10).unwrap(),
+                None,
+            )
+            .unwrap();
+
+        let attachment = temp_dir.path().join("scan.pdf");
+        fs::write(&attachment, b"scanned referral").unwrap();
+        ClinicalService::with_id(cfg.clone(), record.clinical_uuid.uuid())
+            .new_letter_with_attachments(author, location(), &[attachment], None)
+            .unwrap();
+    }
+
+    #[test]
+    fn restores_backed_up_repositories_with_recovery_markers() {
+        let (temp_dir, cfg, author) = setup();
+        full_record(&temp_dir, &cfg, &author);
+        let backup_dir = temp_dir.path().join("backup");
+        let service = BackupService::new(cfg.clone());
+
+        let manifest = service.backup(&backup_dir).unwrap();
+        assert_eq!(manifest.repositories.len(), 3);
+        let clinical = &manifest.repositories[0];
+        assert_eq!(clinical.kind, VprRepositoryKind::Clinical);
+        assert_eq!(clinical.files.len(), 1);
+        assert!(backup_dir.join(MANIFEST_FILENAME).is_file());
+        assert!(
+            fs::read(backup_dir.join(format!("clinical/{}.bundle", clinical.id)))
+                .unwrap()
+                .starts_with(BUNDLE_SIGNATURE)
+        );
+
+        // A second backup into the same directory is refused.
+        assert!(matches!(
+            service.backup(&backup_dir),
+            Err(PatientError::InvalidInput(_))
+        ));
+        // Restoring over the live repositories is refused.
+        assert!(matches!(
+            service.restore(&backup_dir, &author, location()),
+            Err(PatientError::InvalidInput(_))
+        ));
+
+        fs::remove_dir_all(cfg.patient_data_dir()).unwrap();
+        let restored = service.restore(&backup_dir, &author, location()).unwrap();
+        assert_eq!(restored.len(), 3);
+
+        for (entry, restored) in manifest.repositories.iter().zip(&restored) {
+            let dir = ShardableUuid::parse(&entry.id)
+                .unwrap()
+                .sharded_dir(&cfg.patient_data_dir().join(entry.kind.as_str()));
+            let repo = Repository::open(&dir).unwrap();
+            let marker = repo.head().unwrap().peel_to_commit().unwrap();
+            assert_eq!(marker.id().to_string(), restored.marker_commit);
+            assert_eq!(marker.parent_id(0).unwrap().to_string(), entry.head);
+            assert_eq!(marker.tree_id(), marker.parent(0).unwrap().tree_id());
+
+            let message = marker.message().unwrap();
+            assert!(message.starts_with("record:recover: Restored from backup"));
+            assert!(message.contains(&format!("Recovery-Backup-Id: {}", manifest.backup_id)));
+            assert!(message.contains(&format!("Recovery-Restored-Head: {}", entry.head)));
+
+            let report = VersionedFileService::verify_signature_chain(
+                &cfg.patient_data_dir().join(entry.kind.as_str()),
+                &entry.id,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+            assert!(report
+                .commits
+                .iter()
+                .all(|c| c.status == CommitSignatureStatus::Valid));
+        }
+
+        let integrity = IntegrityService::new(cfg).check().unwrap();
+        assert!(integrity.repositories.iter().all(|r| r.issues.is_empty()));
+    }
+
+    #[test]
+    fn restore_rejects_unsigned_authors_and_tampered_backups() {
+        let (temp_dir, cfg, author) = setup();
+        full_record(&temp_dir, &cfg, &author);
+        let backup_dir = temp_dir.path().join("backup");
+        let service = BackupService::new(cfg.clone());
+        let manifest = service.backup(&backup_dir).unwrap();
+        fs::remove_dir_all(cfg.patient_data_dir()).unwrap();
+
+        let unsigned = Author {
+            signature: None,
+            ..author.clone()
+        };
+        assert!(matches!(
+            service.restore(&backup_dir, &unsigned, location()),
+            Err(PatientError::InvalidInput(_))
+        ));
+
+        let clinical = &manifest.repositories[0];
+        let stored = backup_dir
+            .join("clinical")
+            .join(&clinical.id)
+            .join(stored_path(&clinical.files[0]));
+        fs::write(&stored, b"altered referral").unwrap();
+
+        let err = service
+            .restore(&backup_dir, &author, location())
+            .unwrap_err();
+        assert!(matches!(err, PatientError::RestoreVerification(_)), "{err}");
+        let target = ShardableUuid::parse(&clinical.id)
+            .unwrap()
+            .sharded_dir(&cfg.clinical_dir());
+        assert!(!target.exists());
+    }
+}
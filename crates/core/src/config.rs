@@ -12,6 +12,14 @@
 //! - `PATIENT_DATA_DIR`: Base directory for patient data storage
 //! - `RM_SYSTEM_VERSION`: OpenEHR Reference Model version (optional)
 //! - `VPR_NAMESPACE`: Namespace identifier for this VPR instance
+//! - `VPR_TRUST_ANCHORS_PATH`: PEM bundle of CAs that commit signing certificates must chain
+//!   to (optional)
+//! - `VPR_REVOCATION_CRL_PATH`: Local CRL file of revoked signing certificates (optional)
+//! - `VPR_TSA_URL`: RFC 3161 timestamp authority that timestamps every commit (optional)
+//! - `VPR_TSA_TRUST_ANCHORS_PATH`: PEM bundle of CAs that timestamp authority certificates must
+//!   chain to (optional)
+//! - `VPR_MIRROR_URL`: Directory or `file://` URL of the bare repositories every commit is
+//!   pushed to (optional)
 //!
 //! # Directory Structure
 //!
@@ -20,7 +28,8 @@
 //! ```text
 //! patient_data_dir/
 //! ├── clinical/          # Clinical records (Git repos per patient)
-//! └── demographics/      # Demographic data (JSON files per patient)
+//! ├── demographics/      # Demographic data (JSON files per patient)
+//! └── .staging/          # Records being created, until all their repositories exist
 //! ```
 //!
 //! # Safety and Validation
@@ -46,10 +55,18 @@
 //! let demographics_service = DemographicsService::new(Arc::new(config));
 //! ```
 
-use crate::constants::{CLINICAL_DIR_NAME, DEMOGRAPHICS_DIR_NAME, LATEST_RM};
-use crate::error::PatientResult;
+use crate::constants::{
+    CLINICAL_DIR_NAME, COORDINATION_DIR_NAME, DEMOGRAPHICS_DIR_NAME, LATEST_RM, REDACTION_DIR_NAME,
+    REVOCATION_DIR_NAME, STAGING_DIR_NAME,
+};
+use crate::error::{PatientError, PatientResult};
+use crate::replication::Mirror;
+use crate::revocation::RevocationList;
+use crate::timestamp::{HttpTimestampAuthority, TimestampAuthority};
+use crate::trust::TrustAnchors;
 use crate::NonEmptyText;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Core configuration resolved at startup.
 ///
@@ -59,6 +76,10 @@ use std::path::{Path, PathBuf};
 /// - Patient data storage directories
 /// - OpenEHR Reference Model version
 /// - VPR instance namespace
+/// - Trust anchors for commit signing certificates, if configured
+/// - A local certificate revocation list, if configured
+/// - An RFC 3161 timestamp authority and its trust anchors, if configured
+/// - A mirror that commits are replicated to, if configured
 ///
 /// All paths are validated and canonicalized during construction.
 #[derive(Clone, Debug)]
@@ -66,6 +87,11 @@ pub struct CoreConfig {
     patient_data_dir: PathBuf,
     rm_system_version: openehr::RmVersion,
     vpr_namespace: NonEmptyText,
+    trust_anchors: Option<Arc<TrustAnchors>>,
+    revocation_list: Option<Arc<RevocationList>>,
+    timestamp_authority: Option<Arc<dyn TimestampAuthority>>,
+    timestamp_trust_anchors: Option<Arc<TrustAnchors>>,
+    mirror: Option<Mirror>,
 }
 
 impl CoreConfig {
@@ -89,9 +115,62 @@ impl CoreConfig {
             patient_data_dir,
             rm_system_version,
             vpr_namespace,
+            trust_anchors: None,
+            revocation_list: None,
+            timestamp_authority: None,
+            timestamp_trust_anchors: None,
+            mirror: None,
         })
     }
 
+    /// Returns this configuration with trust anchors for commit signing certificates.
+    ///
+    /// When set, signature verification also requires each signing certificate to chain to
+    /// one of these anchors. See [`TrustAnchors::verify`].
+    pub fn with_trust_anchors(mut self, trust_anchors: TrustAnchors) -> Self {
+        self.trust_anchors = Some(Arc::new(trust_anchors));
+        self
+    }
+
+    /// Returns this configuration with a local certificate revocation list.
+    ///
+    /// These revocations are checked alongside those kept in the revocation repository. See
+    /// [`RevocationList::status`].
+    pub fn with_revocation_list(mut self, revocation_list: RevocationList) -> Self {
+        self.revocation_list = Some(Arc::new(revocation_list));
+        self
+    }
+
+    /// Returns this configuration with an RFC 3161 timestamp authority.
+    ///
+    /// When set, every commit carries a timestamp token from this authority, and a commit
+    /// fails if no token can be obtained. See [`crate::timestamp`].
+    pub fn with_timestamp_authority(
+        mut self,
+        timestamp_authority: impl TimestampAuthority + 'static,
+    ) -> Self {
+        self.timestamp_authority = Some(Arc::new(timestamp_authority));
+        self
+    }
+
+    /// Returns this configuration with trust anchors for timestamp authority certificates.
+    ///
+    /// When set, timestamp verification also requires each token's TSA certificate to chain to
+    /// one of these anchors. See [`TrustAnchors::verify_timestamping`].
+    pub fn with_timestamp_trust_anchors(mut self, trust_anchors: TrustAnchors) -> Self {
+        self.timestamp_trust_anchors = Some(Arc::new(trust_anchors));
+        self
+    }
+
+    /// Returns this configuration with a mirror rooted at `mirror_root`.
+    ///
+    /// When set, every commit is pushed to the matching bare repository under `mirror_root`.
+    /// See [`crate::replication`].
+    pub fn with_mirror(mut self, mirror_root: PathBuf) -> Self {
+        self.mirror = Some(Mirror::new(self.patient_data_dir.clone(), mirror_root));
+        self
+    }
+
     /// Get the base patient data directory.
     ///
     /// This is the root directory containing `clinical/` and `demographics/` subdirectories.
@@ -113,6 +192,47 @@ impl CoreConfig {
         self.patient_data_dir.join(DEMOGRAPHICS_DIR_NAME)
     }
 
+    /// Get the coordination records directory.
+    ///
+    /// Returns `patient_data_dir/coordination/`.
+    pub fn coordination_dir(&self) -> PathBuf {
+        self.patient_data_dir.join(COORDINATION_DIR_NAME)
+    }
+
+    /// Get the Redaction Retention Repository directory.
+    ///
+    /// Returns `patient_data_dir/redaction/`.
+    pub fn redaction_dir(&self) -> PathBuf {
+        self.patient_data_dir.join(REDACTION_DIR_NAME)
+    }
+
+    /// Get the certificate revocation repository directory.
+    ///
+    /// Returns `patient_data_dir/revocation/`.
+    pub fn revocation_dir(&self) -> PathBuf {
+        self.patient_data_dir.join(REVOCATION_DIR_NAME)
+    }
+
+    /// Get the directory where multi-repository records are staged before they are moved
+    /// into place.
+    ///
+    /// Returns `patient_data_dir/.staging/`.
+    pub fn staging_dir(&self) -> PathBuf {
+        self.patient_data_dir.join(STAGING_DIR_NAME)
+    }
+
+    /// Returns this configuration with repositories created under `staged_data_dir` instead of
+    /// the patient data directory, and without a mirror.
+    ///
+    /// Staged repositories are pushed to the mirror once they have been moved into place.
+    pub(crate) fn staged(&self, staged_data_dir: PathBuf) -> Self {
+        Self {
+            patient_data_dir: staged_data_dir,
+            mirror: None,
+            ..self.clone()
+        }
+    }
+
     /// Get the OpenEHR Reference Model version.
     ///
     /// This determines which RM features and constraints are enforced.
@@ -126,6 +246,135 @@ impl CoreConfig {
     pub fn vpr_namespace(&self) -> &str {
         self.vpr_namespace.as_str()
     }
+
+    /// Get the trust anchors for commit signing certificates, if configured.
+    pub fn trust_anchors(&self) -> Option<&TrustAnchors> {
+        self.trust_anchors.as_deref()
+    }
+
+    /// Get the local certificate revocation list, if configured.
+    pub fn revocation_list(&self) -> Option<&RevocationList> {
+        self.revocation_list.as_deref()
+    }
+
+    /// Get the timestamp authority for new commits, if configured.
+    pub fn timestamp_authority(&self) -> Option<&dyn TimestampAuthority> {
+        self.timestamp_authority.as_deref()
+    }
+
+    /// Get the trust anchors for timestamp authority certificates, if configured.
+    pub fn timestamp_trust_anchors(&self) -> Option<&TrustAnchors> {
+        self.timestamp_trust_anchors.as_deref()
+    }
+
+    /// Get the mirror that commits are pushed to, if configured.
+    pub fn mirror(&self) -> Option<&Mirror> {
+        self.mirror.as_ref()
+    }
+}
+
+/// Load trust anchors from an optional `VPR_TRUST_ANCHORS_PATH` or `VPR_TSA_TRUST_ANCHORS_PATH`
+/// value.
+///
+/// # Arguments
+///
+/// * `value` - Optional path to a PEM bundle of CA certificates
+///
+/// # Returns
+///
+/// `None` if `value` is `None` or empty/whitespace, so trust checks stay disabled.
+///
+/// # Errors
+///
+/// Returns `PatientError::FileRead` if the file cannot be read, or
+/// `PatientError::InvalidInput` if it holds no parseable certificates.
+pub fn trust_anchors_from_env_value(value: Option<&str>) -> PatientResult<Option<TrustAnchors>> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(path) => TrustAnchors::from_file(Path::new(path)).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Load a local certificate revocation list from an optional `VPR_REVOCATION_CRL_PATH` value.
+///
+/// # Arguments
+///
+/// * `value` - Optional path to a CRL file (PEM or DER)
+///
+/// # Returns
+///
+/// `None` if `value` is `None` or empty/whitespace.
+///
+/// # Errors
+///
+/// Returns `PatientError::FileRead` if the file cannot be read, or
+/// `PatientError::InvalidInput` if it holds no parseable CRLs.
+pub fn revocation_list_from_env_value(
+    value: Option<&str>,
+) -> PatientResult<Option<RevocationList>> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(path) => RevocationList::from_file(Path::new(path)).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Create a timestamp authority client from an optional `VPR_TSA_URL` value.
+///
+/// # Arguments
+///
+/// * `value` - Optional URL of an RFC 3161 timestamp authority
+///
+/// # Returns
+///
+/// `None` if `value` is `None` or empty/whitespace, so commits are not timestamped.
+///
+/// # Errors
+///
+/// Returns `PatientError::InvalidInput` if the URL is not an `http` or `https` URL.
+pub fn timestamp_authority_from_env_value(
+    value: Option<&str>,
+) -> PatientResult<Option<HttpTimestampAuthority>> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(url) => HttpTimestampAuthority::new(url).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Parse a mirror location from an optional `VPR_MIRROR_URL` value.
+///
+/// # Arguments
+///
+/// * `value` - Optional local directory path or `file://` URL of the mirror
+///
+/// # Returns
+///
+/// `None` if `value` is `None` or empty/whitespace, so commits are not mirrored.
+///
+/// # Errors
+///
+/// Returns `PatientError::InvalidInput` if the value is a URL with a scheme other than
+/// `file`, or a relative path.
+pub fn mirror_root_from_env_value(value: Option<&str>) -> PatientResult<Option<PathBuf>> {
+    let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+
+    let path = match value.strip_prefix("file://") {
+        Some(path) => path,
+        None if value.contains("://") => {
+            return Err(PatientError::InvalidInput(format!(
+                "mirror must be a local path or file:// URL: {value}"
+            )))
+        }
+        None => value,
+    };
+    let path = PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err(PatientError::InvalidInput(format!(
+            "mirror path must be absolute: {value}"
+        )));
+    }
+    Ok(Some(path))
 }
 
 /// Parse the RM system version from an optional string value.
@@ -24,6 +24,12 @@ pub const DEMOGRAPHICS_DIR_NAME: &str = "demographics";
 /// Directory name for coordination records storage.
 pub const COORDINATION_DIR_NAME: &str = "coordination";
 
+/// Directory name for Redaction Retention Repository storage.
+pub const REDACTION_DIR_NAME: &str = "redaction";
+
+/// Directory name for the certificate revocation repository.
+pub const REVOCATION_DIR_NAME: &str = "revocation";
+
 /// Latest supported openEHR RM module version.
 pub const LATEST_RM: openehr::RmVersion = openehr::RmVersion::rm_1_1_0;
 
@@ -32,3 +38,21 @@ pub const THREAD_FILENAME: &str = "thread.md";
 
 /// Filename for coordination thread ledger.
 pub const THREAD_LEDGER_FILENAME: &str = "ledger.yaml";
+
+/// Default number of items returned per page by listing operations.
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Maximum number of items a caller may request per page from listing operations.
+pub const MAX_PAGE_SIZE: usize = 500;
+
+/// Name of the write lock file in each repository's `.git` directory.
+pub const WRITE_LOCK_FILENAME: &str = "vpr-write-lock";
+
+/// How long a write waits for another writer to release a repository's lock.
+pub const WRITE_LOCK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Directory (under the patient data directory) where multi-repository records are staged.
+pub const STAGING_DIR_NAME: &str = ".staging";
+
+/// Name of the journal file in each staged transaction directory.
+pub const JOURNAL_FILENAME: &str = "journal.json";
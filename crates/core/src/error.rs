@@ -126,6 +126,20 @@ pub enum PatientError {
     GitCommitBuffer(git2::Error),
     #[error("failed to create signed commit: {0}")]
     GitCommitSigned(git2::Error),
+    #[error("failed to obtain timestamp token: {0}")]
+    TimestampAuthority(String),
+    #[error("failed to write or read git bundle: {0}")]
+    GitBundle(git2::Error),
+    #[error("restored repository failed verification: {0}")]
+    RestoreVerification(String),
+    #[error("failed to push to mirror repository: {0}")]
+    GitPush(git2::Error),
+    #[error("timed out waiting for the write lock on {0}")]
+    RepositoryLockTimeout(std::path::PathBuf),
+    #[error("repository changed concurrently: expected main at {expected}, found {actual}")]
+    ConcurrentModification { expected: String, actual: String },
+    #[error("failed to move staged repository into place: {0}")]
+    StagedRepositoryMove(std::io::Error),
     #[error("failed to convert commit buffer to string: {0}")]
     CommitBufferToString(std::string::FromUtf8Error),
     #[error("failed to open git repository: {0}")]
@@ -138,8 +152,12 @@ pub enum PatientError {
     GitSetHead(git2::Error),
     #[error("failed to peel git commit: {0}")]
     GitPeel(git2::Error),
+    #[error("failed to walk git history: {0}")]
+    GitRevwalk(git2::Error),
     #[error("invalid timestamp")]
     InvalidTimestamp,
+    #[error("invalid VPR commit message: {0}")]
+    InvalidCommitMessage(String),
 
     #[error("missing Author-Name")]
     MissingAuthorName,
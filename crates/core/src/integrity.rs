@@ -0,0 +1,691 @@
+//! Integrity checks across every patient repository.
+//!
+//! ## Purpose
+//! Git detects some corruption on its own, but nothing in VPR looks for it until a record is
+//! read. This module walks every clinical, demographics and coordination repository and
+//! reports anything that would stop a record from being read back exactly as it was written:
+//!
+//! - **objects**: every stored Git object reads back with a matching hash, and every object
+//!   reachable from a reference is present,
+//! - **head**: `HEAD` points to `refs/heads/main`, which resolves to a commit,
+//! - **yaml**: every YAML file parses with the openEHR or FHIR parser for its path, and
+//! - **attachments**: every file under `files/sha256` hashes to its name, and every
+//!   attachment reference points to a stored file.
+//!
+//! ## Intended use
+//! Run [`IntegrityService::check`] from an operator tool (the CLI `fsck` command) and act on
+//! the returned [`IntegrityReport`], which serialises to JSON. Missing binary files are
+//! reported as warnings, because a repository stays valid without its `files/` directory;
+//! everything else is an error.
+
+use crate::config::CoreConfig;
+use crate::constants::{
+    CLINICAL_DIR_NAME, COORDINATION_DIR_NAME, DEMOGRAPHICS_DIR_NAME, THREAD_LEDGER_FILENAME,
+};
+use crate::error::PatientResult;
+use crate::paths::clinical::ehr_status::EhrStatusFile;
+use crate::paths::clinical::letter::{AttachmentsDir, CompositionYaml};
+use crate::paths::coordination::coordination_status::CoordinationStatusFile;
+use crate::paths::demographics::patient::PatientFile;
+use crate::repositories::clinical::AttachmentMetadata;
+use crate::repositories::shared::sharded_repository_dirs;
+use crate::versioned_files::{VprRepositoryKind, MAIN_REF};
+use fhir::{CoordinationStatus, Messaging, Patient};
+use git2::{ObjectType, Oid, Repository};
+use openehr::{extract_rm_version, EhrStatus, Letter};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use vpr_files::FILES_FOLDER_NAME;
+
+/// Directory (under `files/`) holding content-addressed binary files.
+const SHA256_DIR: &str = "sha256";
+
+/// The check that found an integrity issue.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityCheck {
+    /// Git object storage and connectivity.
+    Objects,
+    /// The `HEAD` reference.
+    Head,
+    /// Parsing of YAML files.
+    Yaml,
+    /// Content-addressed binary files and the references to them.
+    Attachments,
+}
+
+/// How serious an integrity issue is.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSeverity {
+    /// The repository is damaged.
+    Error,
+    /// Something is missing that the repository can do without.
+    Warning,
+}
+
+/// One problem found in a repository.
+#[derive(Clone, Debug, Serialize)]
+pub struct IntegrityIssue {
+    pub check: IntegrityCheck,
+    pub severity: IssueSeverity,
+    /// Path of the affected file, relative to the repository root, if the issue concerns one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub message: String,
+}
+
+/// The issues found in one repository.
+#[derive(Clone, Debug, Serialize)]
+pub struct RepositoryIntegrity {
+    pub kind: VprRepositoryKind,
+    /// Repository UUID, as named on disk.
+    pub id: String,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl RepositoryIntegrity {
+    /// Returns `true` if no errors were found; warnings are allowed.
+    pub fn is_ok(&self) -> bool {
+        self.issues
+            .iter()
+            .all(|issue| issue.severity == IssueSeverity::Warning)
+    }
+
+    fn error(&mut self, check: IntegrityCheck, path: Option<&Path>, message: String) {
+        self.push(check, IssueSeverity::Error, path, message);
+    }
+
+    fn warning(&mut self, check: IntegrityCheck, path: Option<&Path>, message: String) {
+        self.push(check, IssueSeverity::Warning, path, message);
+    }
+
+    fn push(
+        &mut self,
+        check: IntegrityCheck,
+        severity: IssueSeverity,
+        path: Option<&Path>,
+        message: String,
+    ) {
+        self.issues.push(IntegrityIssue {
+            check,
+            severity,
+            path: path.map(|p| p.to_string_lossy().into_owned()),
+            message,
+        });
+    }
+}
+
+/// Result of checking every patient repository.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct IntegrityReport {
+    /// One entry per repository checked, in clinical, demographics, coordination order.
+    pub repositories: Vec<RepositoryIntegrity>,
+}
+
+impl IntegrityReport {
+    /// Returns `true` if no repository has errors; warnings are allowed.
+    pub fn is_ok(&self) -> bool {
+        self.repositories.iter().all(RepositoryIntegrity::is_ok)
+    }
+
+    /// Returns the number of issues with the given severity, across all repositories.
+    pub fn count(&self, severity: IssueSeverity) -> usize {
+        self.repositories
+            .iter()
+            .flat_map(|r| &r.issues)
+            .filter(|issue| issue.severity == severity)
+            .count()
+    }
+}
+
+/// Service that checks the integrity of every patient repository.
+#[derive(Clone, Debug)]
+pub struct IntegrityService {
+    cfg: Arc<CoreConfig>,
+}
+
+impl IntegrityService {
+    /// Creates a service for the repositories under the configured patient data directory.
+    pub fn new(cfg: Arc<CoreConfig>) -> Self {
+        Self { cfg }
+    }
+
+    /// Checks every clinical, demographics and coordination repository.
+    ///
+    /// Problems inside a repository are recorded in the report rather than returned, so one
+    /// damaged repository does not stop the others from being checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::FileRead` if a repository type directory exists but cannot be
+    /// listed.
+    pub fn check(&self) -> PatientResult<IntegrityReport> {
+        let data_dir = self.cfg.patient_data_dir();
+        let mut report = IntegrityReport::default();
+
+        for (kind, dir_name) in [
+            (VprRepositoryKind::Clinical, CLINICAL_DIR_NAME),
+            (VprRepositoryKind::Demographics, DEMOGRAPHICS_DIR_NAME),
+            (VprRepositoryKind::Coordination, COORDINATION_DIR_NAME),
+        ] {
+            for repo_dir in sharded_repository_dirs(&data_dir.join(dir_name))? {
+                report.repositories.push(check_repository(kind, &repo_dir));
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+fn check_repository(kind: VprRepositoryKind, repo_dir: &Path) -> RepositoryIntegrity {
+    let mut result = RepositoryIntegrity {
+        kind,
+        id: repo_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        issues: Vec::new(),
+    };
+
+    match Repository::open(repo_dir) {
+        Ok(repo) => {
+            check_objects(&repo, &mut result);
+            check_head(&repo, &mut result);
+        }
+        Err(e) => result.error(
+            IntegrityCheck::Objects,
+            None,
+            format!("not a readable Git repository: {}", e.message()),
+        ),
+    }
+    check_yaml(kind, repo_dir, &mut result);
+    check_files(repo_dir, &mut result);
+
+    result
+}
+
+/// Reads back every stored object and follows every reference down to its blobs.
+pub(crate) fn check_objects(repo: &Repository, result: &mut RepositoryIntegrity) {
+    let odb = match repo.odb() {
+        Ok(odb) => odb,
+        Err(e) => {
+            let message = format!("object database cannot be opened: {}", e.message());
+            return result.error(IntegrityCheck::Objects, None, message);
+        }
+    };
+
+    // libgit2 verifies each object's hash as it is read.
+    let mut stored = Vec::new();
+    if let Err(e) = odb.foreach(|oid| {
+        stored.push(*oid);
+        true
+    }) {
+        let message = format!("object database cannot be listed: {}", e.message());
+        result.error(IntegrityCheck::Objects, None, message);
+    }
+    for oid in stored {
+        if let Err(e) = odb.read(oid) {
+            let message = format!("object {oid} is corrupt: {}", e.message());
+            result.error(IntegrityCheck::Objects, None, message);
+        }
+    }
+
+    let mut pending = Vec::new();
+    match repo.references() {
+        Ok(references) => {
+            for reference in references {
+                match reference {
+                    Ok(reference) => {
+                        pending.extend(reference.resolve().ok().and_then(|r| r.target()))
+                    }
+                    Err(e) => {
+                        let message = format!("reference cannot be read: {}", e.message());
+                        result.error(IntegrityCheck::Objects, None, message);
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            let message = format!("references cannot be listed: {}", e.message());
+            result.error(IntegrityCheck::Objects, None, message);
+        }
+    }
+
+    let mut seen = HashSet::new();
+    while let Some(oid) = pending.pop() {
+        if !seen.insert(oid) {
+            continue;
+        }
+        let commit = match repo.find_commit(oid) {
+            Ok(commit) => commit,
+            Err(e) => {
+                let message = format!("commit {oid} is missing or unreadable: {}", e.message());
+                result.error(IntegrityCheck::Objects, None, message);
+                continue;
+            }
+        };
+        check_tree(repo, commit.tree_id(), Path::new(""), &mut seen, result);
+        pending.extend(commit.parent_ids());
+    }
+}
+
+fn check_tree(
+    repo: &Repository,
+    oid: Oid,
+    path: &Path,
+    seen: &mut HashSet<Oid>,
+    result: &mut RepositoryIntegrity,
+) {
+    if !seen.insert(oid) {
+        return;
+    }
+    let tree = match repo.find_tree(oid) {
+        Ok(tree) => tree,
+        Err(e) => {
+            let message = format!("tree {oid} is missing or unreadable: {}", e.message());
+            return result.error(IntegrityCheck::Objects, Some(path), message);
+        }
+    };
+
+    for entry in tree.iter() {
+        let entry_path = path.join(String::from_utf8_lossy(entry.name_bytes()).as_ref());
+        match entry.kind() {
+            Some(ObjectType::Tree) => check_tree(repo, entry.id(), &entry_path, seen, result),
+            Some(ObjectType::Blob) => {
+                if seen.insert(entry.id()) && repo.find_blob(entry.id()).is_err() {
+                    let message = format!("blob {} is missing or unreadable", entry.id());
+                    result.error(IntegrityCheck::Objects, Some(&entry_path), message);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Checks that `HEAD` is the symbolic reference VPR commits through, and that it resolves.
+fn check_head(repo: &Repository, result: &mut RepositoryIntegrity) {
+    let head = match repo.find_reference("HEAD") {
+        Ok(head) => head,
+        Err(e) => {
+            let message = format!("HEAD cannot be read: {}", e.message());
+            return result.error(IntegrityCheck::Head, None, message);
+        }
+    };
+
+    match head.symbolic_target() {
+        Some(MAIN_REF) => {}
+        Some(target) => {
+            let message = format!("HEAD points to {target}, not {MAIN_REF}");
+            return result.error(IntegrityCheck::Head, None, message);
+        }
+        None => {
+            let message = format!("HEAD is detached, not pointing to {MAIN_REF}");
+            return result.error(IntegrityCheck::Head, None, message);
+        }
+    }
+
+    if let Err(e) = head.peel_to_commit() {
+        let message = format!("{MAIN_REF} does not resolve to a commit: {}", e.message());
+        result.error(IntegrityCheck::Head, None, message);
+    }
+}
+
+/// Parses every YAML file in the working tree with the parser for its path.
+fn check_yaml(kind: VprRepositoryKind, repo_dir: &Path, result: &mut RepositoryIntegrity) {
+    let mut files = Vec::new();
+    if let Err(e) = collect_yaml_files(repo_dir, Path::new(""), &mut files) {
+        let message = format!("working tree cannot be listed: {e}");
+        return result.error(IntegrityCheck::Yaml, None, message);
+    }
+
+    for relative in files {
+        let outcome = fs::read_to_string(repo_dir.join(&relative))
+            .map_err(|e| e.to_string())
+            .and_then(|yaml| parse_yaml(kind, &relative, &yaml));
+        if let Err(message) = outcome {
+            result.error(IntegrityCheck::Yaml, Some(&relative), message);
+        }
+    }
+}
+
+/// Collects YAML files under `dir`, skipping Git metadata and binary file storage.
+fn collect_yaml_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    let mut entries = fs::read_dir(root.join(dir))?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name();
+        let relative = dir.join(&name);
+        if entry.file_type()?.is_dir() {
+            if dir.as_os_str().is_empty() && (name == ".git" || name == FILES_FOLDER_NAME) {
+                continue;
+            }
+            collect_yaml_files(root, &relative, files)?;
+        } else if relative.extension().is_some_and(|ext| ext == "yaml") {
+            files.push(relative);
+        }
+    }
+    Ok(())
+}
+
+/// Parses one YAML file, choosing the parser from the repository kind and the file's path.
+///
+/// Files without a dedicated parser only have to be well-formed YAML.
+fn parse_yaml(kind: VprRepositoryKind, relative: &Path, yaml: &str) -> Result<(), String> {
+    let file_name = relative.file_name().and_then(|name| name.to_str());
+    let parent_name = relative
+        .parent()
+        .and_then(Path::file_name)
+        .and_then(|name| name.to_str());
+
+    let parsed = match (kind, file_name) {
+        (VprRepositoryKind::Clinical, Some(EhrStatusFile::NAME)) if is_top_level(relative) => {
+            extract_rm_version(yaml)
+                .and_then(|rm| EhrStatus::parse(rm, yaml))
+                .map(drop)
+                .map_err(|e| e.to_string())
+        }
+        (VprRepositoryKind::Clinical, Some(CompositionYaml::NAME)) => extract_rm_version(yaml)
+            .and_then(|rm| Letter::composition_parse(rm, yaml))
+            .map(drop)
+            .map_err(|e| e.to_string()),
+        (VprRepositoryKind::Clinical, _) if parent_name == Some(AttachmentsDir::NAME) => {
+            serde_yaml::from_str::<AttachmentMetadata>(yaml)
+                .map(drop)
+                .map_err(|e| e.to_string())
+        }
+        (VprRepositoryKind::Demographics, Some(PatientFile::NAME)) if is_top_level(relative) => {
+            Patient::parse(yaml).map(drop).map_err(|e| e.to_string())
+        }
+        (VprRepositoryKind::Coordination, Some(CoordinationStatusFile::NAME))
+            if is_top_level(relative) =>
+        {
+            CoordinationStatus::parse(yaml)
+                .map(drop)
+                .map_err(|e| e.to_string())
+        }
+        (VprRepositoryKind::Coordination, Some(THREAD_LEDGER_FILENAME)) => {
+            Messaging::ledger_parse(yaml)
+                .map(drop)
+                .map_err(|e| e.to_string())
+        }
+        _ => serde_yaml::from_str::<serde_yaml::Value>(yaml)
+            .map(drop)
+            .map_err(|e| e.to_string()),
+    };
+    parsed.map_err(|e| format!("does not parse: {e}"))
+}
+
+fn is_top_level(relative: &Path) -> bool {
+    relative.parent() == Some(Path::new(""))
+}
+
+/// Checks stored binary files against their names, and attachment references against the
+/// stored files.
+fn check_files(repo_dir: &Path, result: &mut RepositoryIntegrity) {
+    let store = Path::new(FILES_FOLDER_NAME).join(SHA256_DIR);
+    let mut stored = HashSet::new();
+
+    let mut files = Vec::new();
+    if let Err(e) = collect_files(repo_dir, &store, &mut files) {
+        let message = format!("file storage cannot be listed: {e}");
+        return result.error(IntegrityCheck::Attachments, Some(&store), message);
+    }
+    for relative in files {
+        let name = relative
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let expected = (name.len() == 64 && name.bytes().all(|b| b.is_ascii_hexdigit()))
+            .then(|| store.join(&name[0..2]).join(&name[2..4]).join(&name));
+        if expected.as_deref() != Some(relative.as_path()) {
+            let message = "file is not stored under its SHA-256 hash".to_string();
+            result.error(IntegrityCheck::Attachments, Some(&relative), message);
+            continue;
+        }
+
+        match fs::read(repo_dir.join(&relative)) {
+            Ok(content) => {
+                let digest = format!("{:x}", Sha256::digest(&content));
+                if digest != name.to_ascii_lowercase() {
+                    let message = format!("content hashes to {digest}");
+                    result.error(IntegrityCheck::Attachments, Some(&relative), message);
+                } else {
+                    stored.insert(digest);
+                }
+            }
+            Err(e) => {
+                let message = format!("file cannot be read: {e}");
+                result.error(IntegrityCheck::Attachments, Some(&relative), message);
+            }
+        }
+    }
+
+    let mut references = Vec::new();
+    if collect_yaml_files(repo_dir, Path::new(""), &mut references).is_err() {
+        // Already reported by the YAML check.
+        return;
+    }
+    for relative in references {
+        let is_attachment = relative
+            .parent()
+            .and_then(Path::file_name)
+            .is_some_and(|name| name == AttachmentsDir::NAME);
+        if !is_attachment {
+            continue;
+        }
+        let Some(metadata) = fs::read_to_string(repo_dir.join(&relative))
+            .ok()
+            .and_then(|yaml| serde_yaml::from_str::<AttachmentMetadata>(&yaml).ok())
+        else {
+            // Unparseable metadata is reported by the YAML check.
+            continue;
+        };
+        if !stored.contains(metadata.hash.as_str()) {
+            let message = format!("referenced file {} is not stored", metadata.hash);
+            result.warning(IntegrityCheck::Attachments, Some(&relative), message);
+        }
+    }
+}
+
+/// Collects every file under `dir`, or nothing if it does not exist.
+pub(crate) fn collect_files(
+    root: &Path,
+    dir: &Path,
+    files: &mut Vec<PathBuf>,
+) -> std::io::Result<()> {
+    let entries = match fs::read_dir(root.join(dir)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    let mut entries = entries.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let relative = dir.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            collect_files(root, &relative, files)?;
+        } else {
+            files.push(relative);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::rm_system_version_from_env_value;
+    use crate::repositories::clinical::ClinicalService;
+    use crate::{Author, EmailAddress, NonEmptyText, PatientService};
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Arc<CoreConfig>, Author) {
+        let temp_dir = TempDir::new().unwrap();
+        let cfg = Arc::new(
+            CoreConfig::new(
+                temp_dir.path().to_path_buf(),
+                rm_system_version_from_env_value(None).unwrap(),
+                NonEmptyText::new("vpr.dev.1").unwrap(),
+            )
+            .unwrap(),
+        );
+        let author = Author {
+            name: NonEmptyText::new("Dr. Test").unwrap(),
+            role: NonEmptyText::new("Consultant").unwrap(),
+            email: EmailAddress::parse("test@example.com").unwrap(),
+            registrations: vec![],
+            signature: None,
+            certificate: None,
+        };
+        (temp_dir, cfg, author)
+    }
+
+    fn location() -> NonEmptyText {
+        NonEmptyText::new("Test Hospital").unwrap()
+    }
+
+    /// Creates a full record whose clinical repository holds a letter with one attachment.
+    fn full_record(temp_dir: &TempDir, cfg: &Arc<CoreConfig>, author: &Author) -> PathBuf {
+        let record = PatientService::new(cfg.clone())
+            .initialise_full_record(
+                author.clone(),
+                location(),
+                vec![NonEmptyText::new("Ada").unwrap()],
+                NonEmptyText::new("Lovelace").unwrap(),
+                NaiveDate::from_ymd_opt(1815, 12, 10).unwrap(),
+                None,
+            )
+            .unwrap();
+
+        let attachment = temp_dir.path().join("scan.pdf");
+        fs::write(&attachment, b"scanned referral").unwrap();
+        ClinicalService::with_id(cfg.clone(), record.clinical_uuid.uuid())
+            .new_letter_with_attachments(author, location(), &[attachment], None)
+            .unwrap();
+
+        record
+            .clinical_uuid
+            .sharded_dir(&cfg.patient_data_dir().join(CLINICAL_DIR_NAME))
+    }
+
+    fn issues(report: &IntegrityReport, kind: VprRepositoryKind) -> Vec<&IntegrityIssue> {
+        report
+            .repositories
+            .iter()
+            .filter(|r| r.kind == kind)
+            .flat_map(|r| &r.issues)
+            .collect()
+    }
+
+    #[test]
+    fn fresh_records_have_no_issues() {
+        let (temp_dir, cfg, author) = setup();
+        full_record(&temp_dir, &cfg, &author);
+
+        let report = IntegrityService::new(cfg).check().unwrap();
+
+        let kinds: Vec<_> = report.repositories.iter().map(|r| r.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                VprRepositoryKind::Clinical,
+                VprRepositoryKind::Demographics,
+                VprRepositoryKind::Coordination
+            ]
+        );
+        for repository in &report.repositories {
+            assert!(repository.issues.is_empty(), "{:?}", repository.issues);
+        }
+        assert!(report.is_ok());
+    }
+
+    #[test]
+    fn reports_damage_in_each_check() {
+        let (temp_dir, cfg, author) = setup();
+        let clinical_dir = full_record(&temp_dir, &cfg, &author);
+
+        // Corrupt the stored attachment, break ehr_status.yaml and move HEAD off main.
+        let mut files = Vec::new();
+        let store = Path::new(FILES_FOLDER_NAME).join(SHA256_DIR);
+        collect_files(&clinical_dir, &store, &mut files).unwrap();
+        let stored = clinical_dir.join(&files[0]);
+        fs::write(&stored, b"altered referral").unwrap();
+        fs::write(clinical_dir.join(EhrStatusFile::NAME), "rm_version: [").unwrap();
+        let repo = Repository::open(&clinical_dir).unwrap();
+        repo.reference_symbolic("HEAD", "refs/heads/other", true, "test")
+            .unwrap();
+
+        // Delete the object holding the committed patient.yaml.
+        let demographics_dir =
+            sharded_repository_dirs(&cfg.patient_data_dir().join(DEMOGRAPHICS_DIR_NAME))
+                .unwrap()
+                .remove(0);
+        let repo = Repository::open(&demographics_dir).unwrap();
+        let blob = repo
+            .head()
+            .unwrap()
+            .peel_to_tree()
+            .unwrap()
+            .get_name(PatientFile::NAME)
+            .unwrap()
+            .id()
+            .to_string();
+        fs::remove_file(
+            demographics_dir
+                .join(".git/objects")
+                .join(&blob[..2])
+                .join(&blob[2..]),
+        )
+        .unwrap();
+
+        let report = IntegrityService::new(cfg).check().unwrap();
+        assert!(!report.is_ok());
+
+        let clinical = issues(&report, VprRepositoryKind::Clinical);
+        let found = |check, severity, path: &str| {
+            clinical.iter().any(|issue| {
+                issue.check == check
+                    && issue.severity == severity
+                    && issue.path.as_deref().is_some_and(|p| p.ends_with(path))
+            })
+        };
+        assert!(found(
+            IntegrityCheck::Yaml,
+            IssueSeverity::Error,
+            "ehr_status.yaml"
+        ));
+        assert!(found(
+            IntegrityCheck::Attachments,
+            IssueSeverity::Error,
+            stored.file_name().unwrap().to_str().unwrap()
+        ));
+        assert!(found(
+            IntegrityCheck::Attachments,
+            IssueSeverity::Warning,
+            "attachment_1.yaml"
+        ));
+        assert!(clinical
+            .iter()
+            .any(|issue| issue.check == IntegrityCheck::Head
+                && issue.message.contains("refs/heads/other")));
+
+        let demographics = issues(&report, VprRepositoryKind::Demographics);
+        assert!(demographics.iter().any(|issue| {
+            issue.check == IntegrityCheck::Objects
+                && issue.path.as_deref() == Some(PatientFile::NAME)
+        }));
+        assert!(issues(&report, VprRepositoryKind::Coordination).is_empty());
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["repositories"][0]["kind"], "clinical");
+        assert_eq!(report.count(IssueSeverity::Warning), 1);
+    }
+}
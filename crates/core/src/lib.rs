@@ -10,11 +10,20 @@
 //! **No API concerns**: Authentication, HTTP/gRPC servers, or service interfaces belong in `api-grpc`, `api-rest`, or `api-shared`.
 
 pub mod author;
+pub mod backup;
 pub mod config;
 pub mod constants;
+pub mod integrity;
+pub mod lock;
 pub mod markdown;
 pub mod paths;
+pub mod replication;
 pub mod repositories;
+pub mod revocation;
+pub mod signing;
+pub mod staging;
+pub mod timestamp;
+pub mod trust;
 pub mod versioned_files;
 
 pub mod error;
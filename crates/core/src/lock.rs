@@ -0,0 +1,240 @@
+//! Per-repository write locking.
+//!
+//! Every VPR write reads files from a repository, computes new content, and commits it. Two
+//! writers doing this at once on the same repository would both build on the same files and
+//! race on the index and `refs/heads/main`, so one of the changes would be lost.
+//!
+//! A [`RepositoryLock`] serialises writers on one repository (one `ShardableUuid`), across
+//! threads and processes. It is an OS advisory lock on `.git/vpr-write-lock`, so it is released
+//! when the guard is dropped or the process exits, and a crashed writer never leaves a stale
+//! lock behind. Services take the lock before reading what they are about to change, and the
+//! commit functions in [`crate::versioned_files`] require it.
+//!
+//! The lock also records where `main` pointed when it was taken. A commit made under the lock
+//! fails with [`PatientError::ConcurrentModification`] if `main` has moved since, which can
+//! only happen if something other than VPR wrote to the repository.
+//!
+//! Clients can also make a write conditional on `main` still being at a commit they read earlier
+//! (optimistic concurrency): [`RepositoryLock::expect_head`] rejects the write with the same
+//! error if another write landed in between.
+
+use crate::constants::{WRITE_LOCK_FILENAME, WRITE_LOCK_TIMEOUT};
+use crate::error::{PatientError, PatientResult};
+use crate::versioned_files::MAIN_REF;
+use git2::Oid;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How often a waiting writer retries the lock.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Exclusive write access to one repository, held until dropped.
+#[derive(Debug)]
+pub struct RepositoryLock {
+    repo_dir: PathBuf,
+    head: Option<Oid>,
+    // Holding the open file holds the OS lock; closing it on drop releases the lock.
+    _file: File,
+}
+
+impl RepositoryLock {
+    /// Takes the write lock on the repository at `repo_dir`, waiting up to
+    /// [`WRITE_LOCK_TIMEOUT`] for another writer to release it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::GitOpen` if `repo_dir` is not a Git repository,
+    /// `PatientError::FileWrite` if the lock file cannot be opened or locked, and
+    /// `PatientError::RepositoryLockTimeout` if the lock is not released in time.
+    pub fn acquire(repo_dir: &Path) -> PatientResult<Self> {
+        Self::acquire_with_timeout(repo_dir, WRITE_LOCK_TIMEOUT)
+    }
+
+    /// Takes the write lock on the repository at `repo_dir`, waiting up to `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// As [`acquire`](Self::acquire).
+    pub fn acquire_with_timeout(repo_dir: &Path, timeout: Duration) -> PatientResult<Self> {
+        let repo = git2::Repository::open_ext(
+            repo_dir,
+            git2::RepositoryOpenFlags::NO_SEARCH,
+            std::iter::empty::<&std::ffi::OsStr>(),
+        )
+        .map_err(PatientError::GitOpen)?;
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(repo.path().join(WRITE_LOCK_FILENAME))
+            .map_err(PatientError::FileWrite)?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            match file.try_lock() {
+                Ok(()) => break,
+                Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                    std::thread::sleep(POLL_INTERVAL);
+                }
+                Err(TryLockError::WouldBlock) => {
+                    return Err(PatientError::RepositoryLockTimeout(repo_dir.to_path_buf()));
+                }
+                Err(TryLockError::Error(e)) => return Err(PatientError::FileWrite(e)),
+            }
+        }
+
+        // Read `main` only once the lock is held, so no other VPR writer can move it after.
+        Ok(Self {
+            repo_dir: repo_dir.to_path_buf(),
+            head: repo.refname_to_id(MAIN_REF).ok(),
+            _file: file,
+        })
+    }
+
+    /// Returns the working directory of the locked repository.
+    pub fn repo_dir(&self) -> &Path {
+        &self.repo_dir
+    }
+
+    /// Returns the commit `refs/heads/main` pointed to when the lock was taken, if any.
+    pub fn head(&self) -> Option<Oid> {
+        self.head
+    }
+
+    /// Checks that `refs/heads/main` was at `expected` when the lock was taken.
+    ///
+    /// `expected` is the commit a client read before asking for this write; `None` accepts
+    /// any commit.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::ConcurrentModification` if `main` was elsewhere.
+    pub fn expect_head(&self, expected: Option<Oid>) -> PatientResult<()> {
+        match expected {
+            Some(expected) if self.head != Some(expected) => {
+                Err(concurrent_modification(Some(expected), self.head))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that `refs/heads/main` of `repo` has not moved since the lock was taken.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::ConcurrentModification` if it has.
+    pub(crate) fn check_head(&self, repo: &git2::Repository) -> PatientResult<()> {
+        let actual = repo.refname_to_id(MAIN_REF).ok();
+        if actual == self.head {
+            Ok(())
+        } else {
+            Err(concurrent_modification(self.head, actual))
+        }
+    }
+}
+
+/// Parses a commit id supplied by a client, such as the expected commit of a conditional write.
+///
+/// # Errors
+///
+/// Returns `PatientError::InvalidInput` unless `commit_id` is a full 40-character hex commit id.
+pub fn parse_commit_id(commit_id: &str) -> PatientResult<Oid> {
+    // `Oid::from_str` accepts abbreviated ids and pads them with zeros, which would never match.
+    if commit_id.len() != 40 || !commit_id.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(PatientError::InvalidInput(format!(
+            "Invalid commit id: {}",
+            commit_id
+        )));
+    }
+    Oid::from_str(commit_id).map_err(|e| PatientError::InvalidInput(e.to_string()))
+}
+
+/// Builds the error for `main` found at `actual` when `expected` was required.
+pub(crate) fn concurrent_modification(expected: Option<Oid>, actual: Option<Oid>) -> PatientError {
+    let describe =
+        |oid: Option<Oid>| oid.map_or_else(|| "no commit".to_string(), |o| o.to_string());
+    PatientError::ConcurrentModification {
+        expected: describe(expected),
+        actual: describe(actual),
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn lock_is_exclusive_until_dropped() {
+        let temp_dir = TempDir::new().unwrap();
+        git2::Repository::init(temp_dir.path()).unwrap();
+
+        let lock = RepositoryLock::acquire(temp_dir.path()).unwrap();
+        assert_eq!(lock.head(), None);
+        assert!(matches!(
+            RepositoryLock::acquire_with_timeout(temp_dir.path(), Duration::from_millis(50)),
+            Err(PatientError::RepositoryLockTimeout(_))
+        ));
+
+        // A waiting writer gets the lock as soon as it is released.
+        let dir = temp_dir.path().to_path_buf();
+        let waiter = std::thread::spawn(move || {
+            RepositoryLock::acquire_with_timeout(&dir, Duration::from_secs(5)).map(|_| ())
+        });
+        std::thread::sleep(Duration::from_millis(50));
+        drop(lock);
+        waiter.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn expect_head_rejects_a_stale_commit() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let first = repo
+            .commit(Some(MAIN_REF), &sig, &sig, "first", &tree, &[])
+            .unwrap();
+        let parent = repo.find_commit(first).unwrap();
+        let second = repo
+            .commit(Some(MAIN_REF), &sig, &sig, "second", &tree, &[&parent])
+            .unwrap();
+
+        let lock = RepositoryLock::acquire(temp_dir.path()).unwrap();
+        lock.expect_head(None).unwrap();
+        lock.expect_head(Some(second)).unwrap();
+        assert!(matches!(
+            lock.expect_head(Some(first)),
+            Err(PatientError::ConcurrentModification { .. })
+        ));
+    }
+
+    #[test]
+    fn parse_commit_id_requires_a_full_hex_id() {
+        let id = "0123456789abcdef0123456789abcdef01234567";
+        assert_eq!(parse_commit_id(id).unwrap().to_string(), id);
+        for bad in ["", "0123456", &format!("{}0", id), &id.replace('a', "g")] {
+            assert!(matches!(
+                parse_commit_id(bad),
+                Err(PatientError::InvalidInput(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn lock_requires_a_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(matches!(
+            RepositoryLock::acquire(temp_dir.path()),
+            Err(PatientError::GitOpen(_))
+        ));
+    }
+}
@@ -7,3 +7,4 @@ pub mod clinical;
 pub mod common;
 pub mod coordination;
 pub mod demographics;
+pub mod redaction;
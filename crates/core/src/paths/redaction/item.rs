@@ -0,0 +1,128 @@
+//! Redaction Retention Repository item paths.
+//!
+//! This module defines the relative filesystem structure for redacted items
+//! retained in a Redaction Retention Repository (RRR).
+//!
+//! It contains **no I/O**, **no Git logic**, and **no redaction semantics**.
+//!
+//! # Path Structure
+//!
+//! Each retained item is stored under:
+//! ```text
+//! items/
+//!     <redaction-id>/
+//!         redaction.yaml
+//!         content/
+//!             <original path relative to the routine repository root>
+//! ```
+//!
+//! Where `<redaction-id>` is a [`TimestampId`] in the format:
+//! `YYYYMMDDTHHMMSS.mmmZ-<uuid>`
+
+use std::path::{Path, PathBuf};
+
+use crate::TimestampId;
+
+/// Top-level directory holding retained items.
+#[derive(Debug, Clone, Copy)]
+pub struct ItemsDir;
+
+impl ItemsDir {
+    pub const NAME: &'static str = "items";
+}
+
+/// Redaction record file.
+///
+/// Describes what was redacted, from where, why, and by whom.
+#[derive(Debug, Clone, Copy)]
+pub struct RedactionYaml;
+
+impl RedactionYaml {
+    pub const NAME: &'static str = "redaction.yaml";
+}
+
+/// Directory holding the retained copies of the redacted files.
+#[derive(Debug, Clone, Copy)]
+pub struct ContentDir;
+
+impl ContentDir {
+    pub const NAME: &'static str = "content";
+}
+
+/// Relative on-disk paths for a single retained item.
+///
+/// The paths are relative to the RRR root and must be resolved by
+/// repository-level code before filesystem access.
+#[derive(Debug, Clone)]
+pub struct RedactedItemPaths {
+    relative_root: PathBuf,
+}
+
+impl RedactedItemPaths {
+    /// Creates a new relative path set for the item with the given redaction ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `redaction_id` - The timestamp identifier for this redaction
+    pub fn new(redaction_id: &TimestampId) -> Self {
+        Self {
+            relative_root: PathBuf::from(ItemsDir::NAME).join(redaction_id.to_string()),
+        }
+    }
+
+    /// Returns the relative path to the item directory.
+    pub fn dir(&self) -> &Path {
+        &self.relative_root
+    }
+
+    /// Returns the relative path to `redaction.yaml`.
+    pub fn redaction_yaml(&self) -> PathBuf {
+        self.relative_root.join(RedactionYaml::NAME)
+    }
+
+    /// Returns the relative path to the retained content directory.
+    pub fn content_dir(&self) -> PathBuf {
+        self.relative_root.join(ContentDir::NAME)
+    }
+
+    /// Returns the relative path of the retained copy of an original file.
+    ///
+    /// This does not validate `original_path` and performs no I/O.
+    ///
+    /// # Arguments
+    ///
+    /// * `original_path` - Path of the file relative to the routine repository root
+    pub fn content(&self, original_path: &Path) -> PathBuf {
+        self.content_dir().join(original_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_redacted_item_paths() {
+        let redaction_id =
+            TimestampId::from_str("20260114T143522.045Z-550e8400-e29b-41d4-a716-446655440000")
+                .expect("valid timestamp id");
+
+        let paths = RedactedItemPaths::new(&redaction_id);
+
+        assert_eq!(
+            paths.dir(),
+            Path::new("items/20260114T143522.045Z-550e8400-e29b-41d4-a716-446655440000")
+        );
+        assert_eq!(
+            paths.redaction_yaml(),
+            PathBuf::from(
+                "items/20260114T143522.045Z-550e8400-e29b-41d4-a716-446655440000/redaction.yaml"
+            )
+        );
+        assert_eq!(
+            paths.content(Path::new("correspondence/letter/x/body.md")),
+            PathBuf::from("items/20260114T143522.045Z-550e8400-e29b-41d4-a716-446655440000/content/correspondence/letter/x/body.md")
+        );
+    }
+}
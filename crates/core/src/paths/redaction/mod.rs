@@ -0,0 +1,7 @@
+//! Redaction path definitions.
+//!
+//! Covers both sides of a redaction: the retained item inside the Redaction Retention
+//! Repository, and the tombstone left behind in the routine repository.
+
+pub mod item;
+pub mod tombstone;
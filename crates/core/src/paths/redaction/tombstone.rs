@@ -0,0 +1,29 @@
+//! Redaction tombstone paths.
+//!
+//! When an item is redacted, a tombstone file is written into the routine
+//! repository the item was removed from:
+//! ```text
+//! redactions/
+//!     <redaction-id>.yaml
+//! ```
+
+use std::path::PathBuf;
+
+use crate::TimestampId;
+
+/// Directory (in routine repositories) holding redaction tombstones.
+#[derive(Debug, Clone, Copy)]
+pub struct RedactionsDir;
+
+impl RedactionsDir {
+    pub const NAME: &'static str = "redactions";
+}
+
+/// Returns the relative path to the tombstone for a redaction.
+///
+/// # Arguments
+///
+/// * `redaction_id` - The timestamp identifier for the redaction
+pub fn tombstone_path(redaction_id: &TimestampId) -> PathBuf {
+    PathBuf::from(RedactionsDir::NAME).join(format!("{redaction_id}.yaml"))
+}
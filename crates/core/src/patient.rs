@@ -4,9 +4,14 @@
 //! including initialising full patient records.
 
 use crate::{
-    author::Author, error::PatientResult, repositories::clinical::ClinicalService,
+    author::Author,
+    error::{PatientError, PatientResult},
+    repositories::clinical::ClinicalService,
     repositories::coordination::CoordinationService,
-    repositories::demographics::DemographicsService, NonEmptyText, ShardableUuid,
+    repositories::demographics::DemographicsService,
+    staging::{self, RecoveryReport, StagedTransaction},
+    versioned_files::VprRepositoryKind,
+    NonEmptyText, ShardableUuid,
 };
 use chrono::NaiveDate;
 
@@ -44,6 +49,7 @@ impl PatientService {
     /// # Arguments
     ///
     /// * `author` - The author information for Git commits.
+    /// * `care_location` - High-level organisational location for the commits.
     /// * `given_names` - A vector of the patient's given names.
     /// * `last_name` - The patient's family/last name.
     /// * `birth_date` - The patient's date of birth.
@@ -53,12 +59,22 @@ impl PatientService {
     ///
     /// Returns a `FullRecord` containing both UUIDs on success.
     ///
+    /// The repositories are created under the staging directory (see [`crate::staging`]) and
+    /// only moved into their sharded locations once every step has succeeded, so a failure
+    /// leaves no partial record behind.
+    ///
     /// # Errors
     ///
     /// Returns a `PatientError` if:
     /// - demographics initialisation or update fails,
     /// - clinical initialisation fails,
-    /// - linking clinical to demographics fails.
+    /// - linking clinical to demographics fails,
+    /// - coordination initialisation fails,
+    /// - the staged repositories cannot be moved into place
+    ///   ([`PatientError::StagedRepositoryMove`]).
+    ///
+    /// If a step fails and the staged repositories cannot be removed, returns
+    /// [`PatientError::CleanupAfterInitialiseFailed`].
     pub fn initialise_full_record(
         &self,
         author: Author,
@@ -67,6 +83,61 @@ impl PatientService {
         last_name: NonEmptyText,
         birth_date: NaiveDate,
         namespace: Option<NonEmptyText>,
+    ) -> PatientResult<FullRecord> {
+        let transaction = StagedTransaction::begin(self.cfg.clone())?;
+
+        let record = match PatientService::new(transaction.config()).create_full_record(
+            author,
+            care_location,
+            given_names,
+            last_name,
+            birth_date,
+            namespace,
+        ) {
+            Ok(record) => record,
+            Err(init_error) => {
+                let path = transaction.dir().to_path_buf();
+                return Err(match transaction.abort() {
+                    Ok(()) => init_error,
+                    Err(cleanup_error) => PatientError::CleanupAfterInitialiseFailed {
+                        path,
+                        init_error: Box::new(init_error),
+                        cleanup_error,
+                    },
+                });
+            }
+        };
+
+        transaction.commit(&[
+            (VprRepositoryKind::Demographics, &record.demographics_uuid),
+            (VprRepositoryKind::Clinical, &record.clinical_uuid),
+            (VprRepositoryKind::Coordination, &record.coordination_uuid),
+        ])?;
+
+        Ok(record)
+    }
+
+    /// Finishes or discards full records whose creation was interrupted by a crash.
+    ///
+    /// Records that had been fully created are moved into place; partial ones are deleted.
+    /// Call this at startup.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PatientError` if a staged record cannot be read, moved into place or removed.
+    pub fn recover_interrupted_records(&self) -> PatientResult<RecoveryReport> {
+        staging::recover(&self.cfg)
+    }
+
+    /// Creates and links the three repositories of a full record in place.
+    pub(crate) fn create_full_record(
+        &self,
+        author: Author,
+        care_location: NonEmptyText,
+        given_names: Vec<NonEmptyText>,
+        last_name: NonEmptyText,
+        birth_date: NaiveDate,
+        namespace: Option<NonEmptyText>,
     ) -> PatientResult<FullRecord> {
         let demographics_service = DemographicsService::new(self.cfg.clone());
         // Initialise demographics
@@ -77,7 +148,13 @@ impl PatientService {
         let demographics_uuid = demographics_service.demographics_id().to_string();
 
         // Update demographics with patient information
-        demographics_service.update(given_names, last_name.as_str(), &birth_date.to_string())?;
+        demographics_service.update(
+            &author,
+            care_location.clone(),
+            given_names,
+            last_name.as_str(),
+            &birth_date.to_string(),
+        )?;
 
         // Initialise clinical
         let clinical_service = ClinicalService::new(self.cfg.clone());
@@ -0,0 +1,578 @@
+//! Replication of patient repositories to a mirror.
+//!
+//! ## Purpose
+//! A second VPR site keeps a copy of every repository so it can take over if the primary is
+//! lost. The copy is a tree of bare Git repositories (the *mirror*) laid out exactly like the
+//! patient data directory:
+//!
+//! ```text
+//! <mirror_root>/
+//!   clinical/<s1>/<s2>/<uuid>        # Bare repository
+//!   demographics/<s1>/<s2>/<uuid>
+//!   coordination/<s1>/<s2>/<uuid>
+//!   ...
+//! ```
+//!
+//! ## Pushing commits
+//! When [`CoreConfig::with_mirror`] is set, [`crate::versioned_files::VersionedFileService`]
+//! pushes `refs/heads/main` to the matching mirror repository after every commit, creating the
+//! mirror repository on first use. Pushes are fast-forward only. A push failure is logged and
+//! does not fail the commit, because the commit has already been made locally; the drift it
+//! leaves is found by [`ReplicationService`].
+//!
+//! ## Reconciliation
+//! [`ReplicationService::drift`] compares every clinical, demographics and coordination
+//! repository with its mirror, and [`ReplicationService::reconcile`] additionally brings
+//! replicas that are only behind up to date. A replica that has commits the primary does not
+//! (ahead or diverged) is never updated: that needs a person to decide which history is right.
+
+use crate::config::CoreConfig;
+use crate::error::{PatientError, PatientResult};
+use crate::repositories::shared::sharded_repository_dirs;
+use crate::versioned_files::{VprRepositoryKind, MAIN_REF};
+use git2::{Oid, Repository};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The repository kinds compared by [`ReplicationService`], in report order.
+const REPLICATED_KINDS: [VprRepositoryKind; 3] = [
+    VprRepositoryKind::Clinical,
+    VprRepositoryKind::Demographics,
+    VprRepositoryKind::Coordination,
+];
+
+/// Mirror location for the repositories under one patient data directory.
+#[derive(Clone, Debug)]
+pub struct Mirror {
+    primary_dir: PathBuf,
+    root: PathBuf,
+}
+
+impl Mirror {
+    /// Creates a mirror of the repositories under `primary_dir`, stored under `root`.
+    pub fn new(primary_dir: PathBuf, root: PathBuf) -> Self {
+        Self { primary_dir, root }
+    }
+
+    /// Returns the directory holding the mirror repositories.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Pushes `refs/heads/main` of `repo` to its mirror repository, creating that if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::InvalidInput` if `repo` is not under the primary data directory,
+    /// `PatientError::GitInit` if the mirror repository cannot be created, and
+    /// `PatientError::GitPush` if the push fails or is not a fast-forward.
+    pub(crate) fn push(&self, repo: &Repository) -> PatientResult<()> {
+        let workdir = repo.workdir().ok_or_else(|| {
+            PatientError::InvalidInput("cannot mirror a repository without a working tree".into())
+        })?;
+        let replica_dir = self.replica_dir(workdir)?;
+        if !replica_dir.exists() {
+            init_replica(&replica_dir)?;
+        }
+        push_main(repo, &replica_dir)
+    }
+
+    /// Returns the mirror repository for the repository with working tree `workdir`.
+    fn replica_dir(&self, workdir: &Path) -> PatientResult<PathBuf> {
+        // git2 reports canonical working tree paths, so compare canonical paths.
+        let primary_dir =
+            fs::canonicalize(&self.primary_dir).unwrap_or_else(|_| self.primary_dir.clone());
+        let workdir = fs::canonicalize(workdir).unwrap_or_else(|_| workdir.to_path_buf());
+        let relative = workdir.strip_prefix(&primary_dir).map_err(|_| {
+            PatientError::InvalidInput(format!(
+                "repository {} is not under the patient data directory",
+                workdir.display()
+            ))
+        })?;
+        Ok(self.root.join(relative))
+    }
+}
+
+/// Replication state of one repository, compared with its mirror.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplicaStatus {
+    /// The mirror holds the same `main` as the primary.
+    InSync,
+    /// The mirror has no copy of the repository (or an empty one).
+    Missing,
+    /// The mirror lacks commits the primary has; a fast-forward brings it up to date.
+    Behind,
+    /// The mirror has commits the primary does not.
+    Ahead,
+    /// Each side has commits the other does not.
+    Diverged,
+    /// The mirror has a repository the primary does not.
+    PrimaryMissing,
+}
+
+impl ReplicaStatus {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::InSync => "in_sync",
+            Self::Missing => "missing",
+            Self::Behind => "behind",
+            Self::Ahead => "ahead",
+            Self::Diverged => "diverged",
+            Self::PrimaryMissing => "primary_missing",
+        }
+    }
+}
+
+/// One repository in a [`ReplicationReport`].
+#[derive(Clone, Debug, Serialize)]
+pub struct ReplicatedRepository {
+    pub kind: VprRepositoryKind,
+    /// Repository UUID, as named on disk.
+    pub id: String,
+    pub status: ReplicaStatus,
+    /// Commit `refs/heads/main` points to on the primary, if the repository exists there.
+    pub primary_head: Option<String>,
+    /// Commit `refs/heads/main` points to on the mirror, if it has one.
+    pub replica_head: Option<String>,
+    /// Commits on the primary that the mirror lacks.
+    pub primary_ahead: usize,
+    /// Commits on the mirror that the primary lacks.
+    pub replica_ahead: usize,
+    /// Whether [`ReplicationService::reconcile`] pushed to the mirror. The status and heads
+    /// describe the repository before the push.
+    pub updated: bool,
+}
+
+impl ReplicatedRepository {
+    /// Returns `true` if the mirror matches the primary, now or after reconciliation.
+    pub fn is_in_sync(&self) -> bool {
+        self.status == ReplicaStatus::InSync || self.updated
+    }
+}
+
+/// Result of comparing every replicated repository with its mirror.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ReplicationReport {
+    pub repositories: Vec<ReplicatedRepository>,
+}
+
+impl ReplicationReport {
+    /// Returns `true` if every mirror matches its primary.
+    pub fn is_in_sync(&self) -> bool {
+        self.repositories
+            .iter()
+            .all(ReplicatedRepository::is_in_sync)
+    }
+
+    /// Returns the repositories whose mirror does not match the primary.
+    pub fn drift(&self) -> impl Iterator<Item = &ReplicatedRepository> {
+        self.repositories.iter().filter(|r| !r.is_in_sync())
+    }
+}
+
+/// Service for comparing the primary repositories with the configured mirror.
+#[derive(Clone, Debug)]
+pub struct ReplicationService {
+    cfg: Arc<CoreConfig>,
+}
+
+impl ReplicationService {
+    /// Creates a service for the repositories under the configured patient data directory.
+    pub fn new(cfg: Arc<CoreConfig>) -> Self {
+        Self { cfg }
+    }
+
+    /// Compares every clinical, demographics and coordination repository with its mirror.
+    ///
+    /// Nothing is changed on either side.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::InvalidInput` if no mirror is configured, and I/O or Git errors
+    /// if a repository cannot be read.
+    pub fn drift(&self) -> PatientResult<ReplicationReport> {
+        self.compare(false)
+    }
+
+    /// Compares every repository with its mirror, and pushes those that are behind or missing.
+    ///
+    /// Replicas that are ahead or have diverged are reported and left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::InvalidInput` if no mirror is configured, `PatientError::GitPush`
+    /// if a fast-forward push fails, and I/O or Git errors if a repository cannot be read.
+    pub fn reconcile(&self) -> PatientResult<ReplicationReport> {
+        self.compare(true)
+    }
+
+    fn compare(&self, update: bool) -> PatientResult<ReplicationReport> {
+        let mirror = self
+            .cfg
+            .mirror()
+            .ok_or_else(|| PatientError::InvalidInput("no mirror is configured".into()))?;
+
+        let mut report = ReplicationReport::default();
+        for kind in REPLICATED_KINDS {
+            let primary_kind_dir = self.cfg.patient_data_dir().join(kind.as_str());
+            let replica_kind_dir = mirror.root().join(kind.as_str());
+
+            let primary = sharded_paths(&primary_kind_dir)?;
+            let replica = sharded_paths(&replica_kind_dir)?;
+
+            for relative in primary.union(&replica) {
+                let id = relative
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let mut entry = compare_repository(
+                    kind,
+                    id,
+                    primary
+                        .contains(relative)
+                        .then(|| primary_kind_dir.join(relative)),
+                    &replica_kind_dir.join(relative),
+                )?;
+
+                if update && matches!(entry.status, ReplicaStatus::Missing | ReplicaStatus::Behind)
+                {
+                    let repo = Repository::open(primary_kind_dir.join(relative))
+                        .map_err(PatientError::GitOpen)?;
+                    mirror.push(&repo)?;
+                    entry.updated = true;
+                }
+                report.repositories.push(entry);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Returns the shard-relative paths (`<s1>/<s2>/<uuid>`) of the repositories under `kind_dir`.
+fn sharded_paths(kind_dir: &Path) -> PatientResult<BTreeSet<PathBuf>> {
+    Ok(sharded_repository_dirs(kind_dir)?
+        .into_iter()
+        .filter_map(|dir| dir.strip_prefix(kind_dir).ok().map(Path::to_path_buf))
+        .collect())
+}
+
+/// Compares one primary repository (if it exists) with its mirror repository.
+fn compare_repository(
+    kind: VprRepositoryKind,
+    id: String,
+    primary_dir: Option<PathBuf>,
+    replica_dir: &Path,
+) -> PatientResult<ReplicatedRepository> {
+    let replica = if replica_dir.exists() {
+        Some(Repository::open_bare(replica_dir).map_err(PatientError::GitOpen)?)
+    } else {
+        None
+    };
+    let replica_head = replica.as_ref().and_then(main_head);
+
+    let mut entry = ReplicatedRepository {
+        kind,
+        id,
+        status: ReplicaStatus::InSync,
+        primary_head: None,
+        replica_head: replica_head.map(|oid| oid.to_string()),
+        primary_ahead: 0,
+        replica_ahead: 0,
+        updated: false,
+    };
+
+    let Some(primary_dir) = primary_dir else {
+        entry.status = ReplicaStatus::PrimaryMissing;
+        return Ok(entry);
+    };
+    let primary = Repository::open(&primary_dir).map_err(PatientError::GitOpen)?;
+    let primary_head = main_head(&primary);
+    entry.primary_head = primary_head.map(|oid| oid.to_string());
+
+    let (Some(replica), Some(primary_head), Some(replica_head)) =
+        (replica, primary_head, replica_head)
+    else {
+        entry.status = ReplicaStatus::Missing;
+        return Ok(entry);
+    };
+
+    // Let the mirror handle see the primary's objects, so both histories can be walked from
+    // one repository. The alternate only lasts as long as the handle.
+    replica
+        .odb()
+        .and_then(|odb| odb.add_disk_alternate(&primary.path().join("objects").to_string_lossy()))
+        .map_err(PatientError::GitOpen)?;
+    let (primary_ahead, replica_ahead) = replica
+        .graph_ahead_behind(primary_head, replica_head)
+        .map_err(PatientError::GitOpen)?;
+
+    entry.primary_ahead = primary_ahead;
+    entry.replica_ahead = replica_ahead;
+    entry.status = match (primary_ahead, replica_ahead) {
+        (0, 0) => ReplicaStatus::InSync,
+        (_, 0) => ReplicaStatus::Behind,
+        (0, _) => ReplicaStatus::Ahead,
+        _ => ReplicaStatus::Diverged,
+    };
+    Ok(entry)
+}
+
+/// Returns the commit `refs/heads/main` points to, if any.
+fn main_head(repo: &Repository) -> Option<Oid> {
+    repo.refname_to_id(MAIN_REF).ok()
+}
+
+/// Creates an empty bare mirror repository whose `HEAD` is `refs/heads/main`.
+fn init_replica(replica_dir: &Path) -> PatientResult<()> {
+    let mut options = git2::RepositoryInitOptions::new();
+    options.bare(true).initial_head("main").mkpath(true);
+    Repository::init_opts(replica_dir, &options).map_err(PatientError::GitInit)?;
+    Ok(())
+}
+
+/// Pushes `refs/heads/main` to the repository at `replica_dir`, refusing non-fast-forwards.
+fn push_main(repo: &Repository, replica_dir: &Path) -> PatientResult<()> {
+    let mut remote = repo
+        .remote_anonymous(&replica_dir.to_string_lossy())
+        .map_err(PatientError::GitPush)?;
+
+    let mut rejection = None;
+    let mut callbacks = git2::RemoteCallbacks::new();
+    callbacks.push_update_reference(|refname, status| {
+        if let Some(status) = status {
+            rejection = Some(format!("{refname}: {status}"));
+        }
+        Ok(())
+    });
+    let mut options = git2::PushOptions::new();
+    options.remote_callbacks(callbacks);
+
+    // No leading `+`: libgit2 refuses the push unless it is a fast-forward.
+    let refspec = format!("{MAIN_REF}:{MAIN_REF}");
+    remote
+        .push(&[refspec.as_str()], Some(&mut options))
+        .map_err(PatientError::GitPush)?;
+    drop(options);
+
+    match rejection {
+        Some(message) => Err(PatientError::GitPush(git2::Error::from_str(&message))),
+        None => Ok(()),
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::author::Author;
+    use crate::config::mirror_root_from_env_value;
+    use crate::patient::{FullRecord, PatientService};
+    use crate::repositories::clinical::ClinicalService;
+    use crate::{EmailAddress, NonEmptyText};
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, CoreConfig, Author) {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join("data");
+        fs::create_dir_all(&data_dir).unwrap();
+
+        let cfg = CoreConfig::new(
+            data_dir,
+            openehr::RmVersion::rm_1_1_0,
+            NonEmptyText::new("test-namespace").unwrap(),
+        )
+        .unwrap();
+
+        let author = Author {
+            name: NonEmptyText::new("Test Author").unwrap(),
+            role: NonEmptyText::new("Clinician").unwrap(),
+            email: EmailAddress::parse("test@example.com").unwrap(),
+            registrations: vec![],
+            signature: None,
+            certificate: None,
+        };
+
+        (temp_dir, cfg, author)
+    }
+
+    fn location() -> NonEmptyText {
+        NonEmptyText::new("Test Hospital").unwrap()
+    }
+
+    fn full_record(cfg: &Arc<CoreConfig>, author: &Author) -> FullRecord {
+        PatientService::new(cfg.clone())
+            .initialise_full_record(
+                author.clone(),
+                location(),
+                vec![NonEmptyText::new("Ada").unwrap()],
+                NonEmptyText::new("Lovelace").unwrap(),
+                NaiveDate::from_ymd_opt(1815, 12, 10).unwrap(),
+                None,
+            )
+            .unwrap()
+    }
+
+    fn new_letter(cfg: &Arc<CoreConfig>, author: &Author, record: &FullRecord) {
+        ClinicalService::with_id(cfg.clone(), record.clinical_uuid.uuid())
+            .new_letter(
+                author,
+                location(),
+                NonEmptyText::new("Seen in clinic.").unwrap(),
+                None,
+            )
+            .unwrap();
+    }
+
+    fn statuses(report: &ReplicationReport) -> Vec<ReplicaStatus> {
+        report.repositories.iter().map(|r| r.status).collect()
+    }
+
+    #[test]
+    fn commits_are_pushed_to_the_mirror() {
+        let (temp_dir, cfg, author) = setup();
+        let mirror_dir = temp_dir.path().join("mirror");
+        let cfg = Arc::new(cfg.with_mirror(mirror_dir.clone()));
+
+        let record = full_record(&cfg, &author);
+        new_letter(&cfg, &author, &record);
+
+        let report = ReplicationService::new(cfg.clone()).drift().unwrap();
+        assert_eq!(statuses(&report), [ReplicaStatus::InSync; 3]);
+        assert!(report.is_in_sync());
+
+        let kinds: Vec<_> = report.repositories.iter().map(|r| r.kind).collect();
+        assert_eq!(kinds, REPLICATED_KINDS);
+        let clinical = &report.repositories[0];
+        assert_eq!(clinical.id, record.clinical_uuid.to_string());
+        assert!(clinical.primary_head.is_some());
+        assert_eq!(clinical.primary_head, clinical.replica_head);
+
+        let replica = Repository::open_bare(
+            record
+                .clinical_uuid
+                .sharded_dir(&mirror_dir.join("clinical")),
+        )
+        .unwrap();
+        assert_eq!(
+            replica.find_reference("HEAD").unwrap().symbolic_target(),
+            Some(MAIN_REF)
+        );
+    }
+
+    #[test]
+    fn reconcile_fast_forwards_replicas_and_refuses_diverged_ones() {
+        let (temp_dir, cfg, author) = setup();
+        let mirror_dir = temp_dir.path().join("mirror");
+        let unmirrored = Arc::new(cfg.clone());
+        let cfg = Arc::new(cfg.with_mirror(mirror_dir.clone()));
+        let service = ReplicationService::new(cfg.clone());
+
+        // Commits made while no mirror was configured leave the replicas missing.
+        let record = full_record(&unmirrored, &author);
+        let report = service.drift().unwrap();
+        assert_eq!(statuses(&report), [ReplicaStatus::Missing; 3]);
+        assert_eq!(report.drift().count(), 3);
+
+        let report = service.reconcile().unwrap();
+        assert!(report.repositories.iter().all(|r| r.updated));
+        assert!(service.drift().unwrap().is_in_sync());
+
+        // Later unmirrored commits leave the replica behind; reconciling fast-forwards it.
+        new_letter(&unmirrored, &author, &record);
+        let report = service.drift().unwrap();
+        let drifted: Vec<_> = report.drift().collect();
+        assert_eq!(drifted.len(), 1);
+        assert_eq!(drifted[0].status, ReplicaStatus::Behind);
+        assert_eq!((drifted[0].primary_ahead, drifted[0].replica_ahead), (1, 0));
+        service.reconcile().unwrap();
+        assert!(service.drift().unwrap().is_in_sync());
+
+        // A commit made on the replica puts it ahead.
+        let replica_dir = record
+            .clinical_uuid
+            .sharded_dir(&mirror_dir.join("clinical"));
+        commit_on_main(&Repository::open_bare(&replica_dir).unwrap(), "on replica");
+        let report = service.reconcile().unwrap();
+        let drifted: Vec<_> = report.drift().collect();
+        assert_eq!(drifted.len(), 1);
+        assert_eq!(drifted[0].status, ReplicaStatus::Ahead);
+        assert!(!drifted[0].updated);
+
+        // A primary commit is then not pushed, but the commit itself still succeeds.
+        let replica_head = main_head(&Repository::open_bare(&replica_dir).unwrap());
+        new_letter(&cfg, &author, &record);
+        assert_eq!(
+            main_head(&Repository::open_bare(&replica_dir).unwrap()),
+            replica_head
+        );
+        let report = service.reconcile().unwrap();
+        let drifted: Vec<_> = report.drift().collect();
+        assert_eq!(drifted.len(), 1);
+        assert_eq!(drifted[0].status, ReplicaStatus::Diverged);
+        assert_eq!((drifted[0].primary_ahead, drifted[0].replica_ahead), (1, 1));
+        assert!(!drifted[0].updated);
+        assert_eq!(
+            main_head(&Repository::open_bare(&replica_dir).unwrap()),
+            replica_head
+        );
+
+        // A repository only on the mirror is reported too.
+        fs::remove_dir_all(
+            record
+                .coordination_uuid
+                .sharded_dir(&cfg.coordination_dir()),
+        )
+        .unwrap();
+        let report = service.drift().unwrap();
+        let coordination = report
+            .repositories
+            .iter()
+            .find(|r| r.kind == VprRepositoryKind::Coordination)
+            .unwrap();
+        assert_eq!(coordination.status, ReplicaStatus::PrimaryMissing);
+        assert_eq!(coordination.primary_head, None);
+    }
+
+    #[test]
+    fn mirror_root_accepts_absolute_paths_and_file_urls_only() {
+        assert_eq!(mirror_root_from_env_value(None).unwrap(), None);
+        assert_eq!(mirror_root_from_env_value(Some("  ")).unwrap(), None);
+        assert_eq!(
+            mirror_root_from_env_value(Some("/srv/vpr-mirror")).unwrap(),
+            Some(PathBuf::from("/srv/vpr-mirror"))
+        );
+        assert_eq!(
+            mirror_root_from_env_value(Some("file:///srv/vpr-mirror")).unwrap(),
+            Some(PathBuf::from("/srv/vpr-mirror"))
+        );
+        for value in ["ssh://replica/srv/vpr-mirror", "vpr-mirror"] {
+            assert!(matches!(
+                mirror_root_from_env_value(Some(value)),
+                Err(PatientError::InvalidInput(_))
+            ));
+        }
+    }
+
+    fn commit_on_main(repo: &Repository, message: &str) {
+        let head = repo.find_commit(main_head(repo).unwrap()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(
+            Some(MAIN_REF),
+            &sig,
+            &sig,
+            message,
+            &head.tree().unwrap(),
+            &[&head],
+        )
+        .unwrap();
+    }
+}
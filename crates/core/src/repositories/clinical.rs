@@ -11,26 +11,39 @@ use crate::author::Author;
 use crate::config::CoreConfig;
 use crate::constants::{CLINICAL_DIR_NAME, DEFAULT_GITIGNORE};
 use crate::error::{PatientError, PatientResult};
+use crate::lock::{parse_commit_id, RepositoryLock};
 use crate::paths::{
-    clinical::{ehr_status::EhrStatusFile, letter::LetterPaths},
+    clinical::{
+        common::CorrespondenceDir,
+        ehr_status::EhrStatusFile,
+        letter::{AttachmentsDir, LetterDir, LetterPaths},
+    },
     common::GitIgnoreFile,
 };
-use crate::repositories::shared::create_uuid_and_shard_dir;
+use crate::repositories::redaction::{
+    RedactedItem, RedactionReason, RedactionRequest, RedactionService, RetainedFile,
+};
+use crate::repositories::revocation::RevocationService;
+use crate::repositories::shared::{create_uuid_and_shard_dir, page_limit, timestamp_id_order_key};
 use crate::NonEmptyText;
 
 // TODO: need to check if this is really needed
 #[cfg(test)]
 use crate::repositories::shared::create_uuid_and_shard_dir_with_source;
 use crate::versioned_files::{
-    ClinicalDomain::Record, FileToWrite, VersionedFileService, VprCommitAction, VprCommitDomain,
-    VprCommitMessage,
+    ClinicalDomain::{Correction, Record},
+    FileToRemove, FileToWrite, SignatureChainReport, VersionedFileService, VprCommitAction,
+    VprCommitDomain, VprCommitHistoryEntry, VprCommitMessage, VprRepositoryKind,
 };
 use crate::ShardableUuid;
+use chrono::{DateTime, Utc};
+use git2::Oid;
 use openehr::{
     extract_rm_version, validate_namespace_uri_safe, ClinicalList, EhrId, EhrStatus,
     ExternalReference, Letter, LetterData,
 };
 use std::{
+    collections::HashSet,
     fs,
     path::{Path, PathBuf},
     sync::Arc,
@@ -66,21 +79,31 @@ pub struct Uninitialised;
 /// # Fields
 ///
 /// The clinical ID is stored privately and accessed via the
-/// [`clinical_id()`](ClinicalService::clinical_id) method.
+/// [`clinical_id()`](ClinicalService::clinical_id) method. The commit that writes expect
+/// `main` to be at, if any, is set with
+/// [`with_expected_commit()`](ClinicalService::with_expected_commit).
 #[derive(Clone, Copy, Debug)]
 pub struct Initialised {
     clinical_id: Uuid,
+    expected_commit: Option<Oid>,
 }
 
 /// Result of reading an existing letter.
 ///
-/// Contains the body content and parsed composition metadata.
+/// Contains the body content and parsed composition metadata of one letter version,
+/// along with the letter's version chain.
 #[derive(Debug, Clone)]
 pub struct ReadLetterResult {
     /// The Markdown content from body.md
     pub body_content: NonEmptyText,
     /// Parsed composition metadata from composition.yaml
     pub letter_data: LetterData,
+    /// Timestamp IDs of every version of the letter, oldest first. The last entry is the
+    /// current version.
+    pub versions: Vec<TimestampId>,
+    /// Commit `main` pointed to when the letter was read, for conditional writes (see
+    /// [`ClinicalService::with_expected_commit`]).
+    pub commit_id: String,
 }
 
 /// Metadata for a file attachment in a clinical letter.
@@ -115,6 +138,95 @@ pub struct LetterAttachment {
     pub content: Vec<u8>,
 }
 
+/// Filters and paging options for [`ClinicalService::list_letters`].
+///
+/// Letters are ordered by timestamp ID. Date-range filters apply to the letter's
+/// timestamp ID, which records when the letter was created.
+#[derive(Debug, Clone, Default)]
+pub struct ListLettersQuery {
+    /// Return only letters after this timestamp ID (the `next_cursor` of the previous page).
+    pub cursor: Option<TimestampId>,
+    /// Maximum number of letters to return. Defaults to
+    /// [`DEFAULT_PAGE_SIZE`](crate::constants::DEFAULT_PAGE_SIZE).
+    pub limit: Option<usize>,
+    /// Return only letters created at or after this time.
+    pub from: Option<DateTime<Utc>>,
+    /// Return only letters created before this time.
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl ListLettersQuery {
+    /// Returns whether a letter with the given timestamp ID falls within this query.
+    fn matches(&self, timestamp_id: &TimestampId) -> bool {
+        if let Some(cursor) = &self.cursor {
+            if timestamp_id_order_key(timestamp_id) <= timestamp_id_order_key(cursor) {
+                return false;
+            }
+        }
+        if self
+            .from
+            .is_some_and(|from| timestamp_id.timestamp() < from)
+        {
+            return false;
+        }
+        if self.to.is_some_and(|to| timestamp_id.timestamp() >= to) {
+            return false;
+        }
+        true
+    }
+}
+
+/// Summary of one letter version, as returned by [`ClinicalService::list_letters`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LetterSummary {
+    /// Timestamp ID of the letter version
+    pub uid: TimestampId,
+    /// Name of the letter's composer
+    pub composer_name: String,
+    /// Role of the letter's composer
+    pub composer_role: String,
+    /// Start time of the letter's clinical context
+    pub start_time: DateTime<Utc>,
+    /// Whether the letter has a body.md
+    pub has_body: bool,
+    /// Number of attachments on the letter
+    pub attachment_count: usize,
+    /// The later version that replaces this letter, if any
+    pub superseded_by: Option<TimestampId>,
+}
+
+impl From<LetterData> for LetterSummary {
+    fn from(letter_data: LetterData) -> Self {
+        Self {
+            uid: letter_data.uid,
+            composer_name: letter_data.composer_name,
+            composer_role: letter_data.composer_role,
+            start_time: letter_data.start_time,
+            has_body: letter_data.has_body,
+            attachment_count: letter_data.attachments.len(),
+            superseded_by: letter_data.superseded_by,
+        }
+    }
+}
+
+/// One page of letters from [`ClinicalService::list_letters`].
+#[derive(Debug, Clone)]
+pub struct LetterPage {
+    /// Letters in this page, oldest first
+    pub letters: Vec<LetterSummary>,
+    /// Cursor for the next page, or `None` when this is the last page
+    pub next_cursor: Option<TimestampId>,
+}
+
+/// Content for a new letter or letter version.
+struct LetterContent<'a> {
+    body_content: Option<NonEmptyText>,
+    attachment_files: &'a [PathBuf],
+    clinical_lists: Option<&'a [ClinicalList]>,
+    /// The version this letter replaces, if it is a new version of an existing letter.
+    supersedes: Option<&'a TimestampId>,
+}
+
 /// Service for managing clinical record operations.
 ///
 /// This service uses the type-state pattern to enforce correct usage at compile time.
@@ -178,7 +290,10 @@ impl ClinicalService<Initialised> {
     pub fn with_id(cfg: Arc<CoreConfig>, clinical_id: Uuid) -> Self {
         Self {
             cfg,
-            state: Initialised { clinical_id },
+            state: Initialised {
+                clinical_id,
+                expected_commit: None,
+            },
         }
     }
 
@@ -190,6 +305,21 @@ impl ClinicalService<Initialised> {
     pub fn clinical_id(&self) -> Uuid {
         self.state.clinical_id
     }
+
+    /// Makes writes through this service conditional on `main` being at `commit_id`.
+    ///
+    /// Pass the commit id returned by a read (such as [`ReadLetterResult::commit_id`]) so that
+    /// a write fails with [`PatientError::ConcurrentModification`] rather than overwriting
+    /// changes made since. `None` makes writes unconditional. Every write moves `main`, so
+    /// a service with an expected commit accepts at most one write.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::InvalidInput` if `commit_id` is not a full hex commit id.
+    pub fn with_expected_commit(mut self, commit_id: Option<&str>) -> PatientResult<Self> {
+        self.state.expected_commit = commit_id.map(parse_commit_id).transpose()?;
+        Ok(self)
+    }
 }
 
 impl ClinicalService<Uninitialised> {
@@ -266,12 +396,20 @@ impl ClinicalService<Uninitialised> {
             },
         ];
 
-        VersionedFileService::init_and_commit(&patient_dir, &author, &commit_message, &files)?;
+        VersionedFileService::init_and_commit(
+            &patient_dir,
+            &author,
+            &commit_message,
+            &files,
+            self.cfg.timestamp_authority(),
+            self.cfg.mirror(),
+        )?;
 
         Ok(ClinicalService {
             cfg: self.cfg,
             state: Initialised {
                 clinical_id: clinical_uuid.uuid(),
+                expected_commit: None,
             },
         })
     }
@@ -346,6 +484,7 @@ impl ClinicalService<Initialised> {
         validate_namespace_uri_safe(namespace)?;
 
         let patient_dir = self.clinical_patient_dir(&clinical_uuid);
+        let lock = self.write_lock(&patient_dir)?;
         let filename = patient_dir.join("ehr_status.yaml");
 
         if !filename.exists() {
@@ -368,7 +507,7 @@ impl ClinicalService<Initialised> {
             EhrStatus::render(rm_version, Some(&previous_data), None, external_reference)?;
 
         VersionedFileService::write_and_commit_files(
-            &patient_dir,
+            &lock,
             author,
             &msg,
             &[FileToWrite {
@@ -376,7 +515,11 @@ impl ClinicalService<Initialised> {
                 content: &yaml_content,
                 old_content: Some(&previous_data),
             }],
-        )
+            self.cfg.timestamp_authority(),
+            self.cfg.mirror(),
+        )?;
+
+        Ok(())
     }
 
     /// Creates a new clinical letter with optional body and/or attachments.
@@ -436,144 +579,21 @@ impl ClinicalService<Initialised> {
         )?;
 
         let timestamp_id = TimestampIdGenerator::generate(None)?;
-        let letter_paths = LetterPaths::new(&timestamp_id);
-
         let clinical_uuid = ShardableUuid::parse(&self.clinical_id().simple().to_string())?;
-        let patient_dir = self.clinical_patient_dir(&clinical_uuid);
-
-        // Process attachments if provided
-        let mut attachment_metadata_list = Vec::new();
-        let mut attachment_files_to_write = Vec::new();
-
-        if !attachment_files.is_empty() {
-            let clinical_dir = self.clinical_dir();
-            let files_service = vpr_files::FilesService::new(&clinical_dir, clinical_uuid.clone())
-                .map_err(|e| {
-                    PatientError::InvalidInput(format!("Failed to initialize files service: {}", e))
-                })?;
-
-            for (index, file_path) in attachment_files.iter().enumerate() {
-                // Add file to storage
-                let file_metadata = files_service.add(file_path).map_err(|e| {
-                    PatientError::InvalidInput(format!("Failed to add attachment file: {}", e))
-                })?;
-
-                // Create attachment metadata
-                let attachment_filename = format!("attachment_{}.yaml", index + 1);
-                let attachment_metadata = AttachmentMetadata {
-                    metadata_filename: NonEmptyText::new(&attachment_filename).map_err(|e| {
-                        PatientError::InvalidInput(format!("Invalid attachment filename: {}", e))
-                    })?,
-                    hash: Sha256Hash::parse(file_metadata.hash.as_str()).map_err(|e| {
-                        PatientError::InvalidInput(format!("Invalid SHA-256 hash: {}", e))
-                    })?,
-                    file_storage_path: NonEmptyText::new(file_metadata.relative_path.as_str())
-                        .map_err(|e| {
-                            PatientError::InvalidInput(format!("Invalid file storage path: {}", e))
-                        })?,
-                    size_bytes: file_metadata.size_bytes,
-                    media_type: file_metadata
-                        .media_type
-                        .as_ref()
-                        .map(|mt| NonEmptyText::new(mt.as_str()))
-                        .transpose()
-                        .map_err(|e| {
-                            PatientError::InvalidInput(format!("Invalid media type: {}", e))
-                        })?,
-                    original_filename: NonEmptyText::new(&file_metadata.original_filename)
-                        .map_err(|e| {
-                            PatientError::InvalidInput(format!("Invalid original filename: {}", e))
-                        })?,
-                };
-
-                // Serialize attachment metadata to YAML
-                let attachment_yaml = serde_yaml::to_string(&attachment_metadata).map_err(|e| {
-                    PatientError::InvalidInput(format!(
-                        "Failed to serialize attachment metadata: {}",
-                        e
-                    ))
-                })?;
-
-                // Add to files to write
-                let attachment_path = letter_paths.attachment(&attachment_filename);
-                attachment_files_to_write.push((attachment_path, attachment_yaml));
-                attachment_metadata_list.push(attachment_metadata);
-            }
-        }
-
-        // Generate composition.yaml content
-        let rm_version = self.cfg.rm_system_version();
-        let start_time = timestamp_id.timestamp();
-
-        // Build attachment references for the composition
-        let attachment_refs: Vec<openehr::AttachmentReference> = attachment_metadata_list
-            .iter()
-            .map(|meta| openehr::AttachmentReference {
-                path: format!("./attachments/{}", meta.metadata_filename),
-            })
-            .collect();
-
-        // Construct LetterData
-        let letter_data = openehr::LetterData {
-            rm_version,
-            uid: timestamp_id.clone(),
-            composer_name: author.name.to_string(),
-            composer_role: "Clinical Practitioner".to_string(),
-            start_time,
-            clinical_lists: clinical_lists
-                .map(|lists| lists.to_vec())
-                .unwrap_or_default(),
-            has_body: body_content.is_some(),
-            attachments: attachment_refs,
-        };
-
-        let composition_content =
-            Letter::composition_render(rm_version, &letter_data).map_err(|e| {
-                PatientError::InvalidInput(format!("Failed to create letter composition: {}", e))
-            })?;
-
-        let composition_yaml_relative_path = letter_paths.composition_yaml();
-
-        // Build list of all files to write
-        let mut files_to_write_vec = vec![FileToWrite {
-            relative_path: &composition_yaml_relative_path,
-            content: &composition_content,
-            old_content: None,
-        }];
-
-        // Add body.md if provided
-        let body_md_relative_path;
-        if let Some(ref body) = body_content {
-            body_md_relative_path = letter_paths.body_md();
-            files_to_write_vec.push(FileToWrite {
-                relative_path: &body_md_relative_path,
-                content: body.as_str(),
-                old_content: None,
-            });
-        }
-
-        // Add attachment metadata files
-        let attachment_paths: Vec<PathBuf> = attachment_files_to_write
-            .iter()
-            .map(|(path, _)| path.clone())
-            .collect();
-
-        for (path, content) in attachment_paths
-            .iter()
-            .zip(attachment_files_to_write.iter())
-        {
-            files_to_write_vec.push(FileToWrite {
-                relative_path: path,
-                content: content.1.as_str(),
-                old_content: None,
-            });
-        }
+        let lock = self.write_lock(&self.clinical_patient_dir(&clinical_uuid))?;
 
-        VersionedFileService::write_and_commit_files(
-            &patient_dir,
+        self.write_letter(
+            &lock,
             author,
             &commit_message,
-            &files_to_write_vec,
+            &timestamp_id,
+            LetterContent {
+                body_content,
+                attachment_files,
+                clinical_lists,
+                supersedes: None,
+            },
+            &[],
         )?;
 
         Ok(timestamp_id)
@@ -658,6 +678,8 @@ impl ClinicalService<Initialised> {
                 .unwrap_or_default(),
             has_body: true,
             attachments: vec![],
+            supersedes: None,
+            superseded_by: None,
         };
 
         let composition_content =
@@ -678,110 +700,289 @@ impl ClinicalService<Initialised> {
             },
         ];
 
-        VersionedFileService::write_and_commit_files(&patient_dir, author, &msg, &files_to_write)?;
+        let lock = self.write_lock(&patient_dir)?;
+        VersionedFileService::write_and_commit_files(
+            &lock,
+            author,
+            &msg,
+            &files_to_write,
+            self.cfg.timestamp_authority(),
+            self.cfg.mirror(),
+        )?;
 
         Ok(timestamp_id)
     }
 
-    /// Reads an existing clinical letter.
+    /// Issues a new version of an existing clinical letter.
+    ///
+    /// The new version is written as a new letter (with its own timestamp ID) whose
+    /// composition records a `supersedes` link to the previous version. The previous
+    /// version's composition gains a matching `superseded_by` link; its body.md and
+    /// attachments are left untouched, so every version stays readable via
+    /// [`read_letter_version`](Self::read_letter_version). Both changes are committed
+    /// together as a `correction:superseded` commit.
     ///
-    /// This function reads both the body.md and composition.yaml files for a letter,
-    /// parses the composition metadata, and returns them together.
+    /// Only the current version of a letter can be superseded. The new version does not
+    /// inherit content from the previous one: body, attachments and clinical lists are
+    /// taken from the arguments, as for [`create_letter`](Self::create_letter).
     ///
     /// # Arguments
     ///
-    /// * `timestamp_id` - The timestamp ID of the letter to read.
+    /// * `author` - The author information for the Git commit.
+    /// * `care_location` - High-level organisational location for the commit.
+    /// * `timestamp_id` - The timestamp ID of the letter version being superseded.
+    /// * `body_content` - Optional Markdown content for the new version's body.
+    /// * `attachment_files` - Paths to files to attach to the new version. Can be empty.
+    /// * `clinical_lists` - Optional clinical lists to include in the new version.
     ///
     /// # Returns
     ///
-    /// Returns a `ReadLetterResult` containing both the body content and parsed letter data.
+    /// Returns the timestamp ID of the new version on success.
     ///
     /// # Errors
     ///
     /// Returns a `PatientError` if:
-    /// - The timestamp ID cannot be parsed
-    /// - The clinical UUID cannot be parsed
-    /// - The body.md or composition.yaml file does not exist
-    /// - Reading either file fails
-    /// - Parsing the composition.yaml fails
-    pub fn read_letter(&self, timestamp_id: &str) -> PatientResult<ReadLetterResult> {
-        let timestamp_id: vpr_uuid::TimestampId = timestamp_id
-            .parse()
-            .map_err(|e| PatientError::InvalidInput(format!("Invalid timestamp ID: {}", e)))?;
-        let letter_paths = LetterPaths::new(&timestamp_id);
+    /// - Both `body_content` and `attachment_files` are empty/None
+    /// - The author or care location is invalid
+    /// - The timestamp ID cannot be parsed or the letter does not exist
+    /// - The letter has already been superseded
+    /// - Any attachment file cannot be read or stored
+    /// - Rendering either composition, writing files or committing to Git fails
+    pub fn amend_letter(
+        &self,
+        author: &Author,
+        care_location: NonEmptyText,
+        timestamp_id: &str,
+        body_content: Option<NonEmptyText>,
+        attachment_files: &[PathBuf],
+        clinical_lists: Option<&[ClinicalList]>,
+    ) -> PatientResult<TimestampId> {
+        if body_content.is_none() && attachment_files.is_empty() {
+            return Err(PatientError::InvalidInput(
+                "Letter must have either body content or attachments (or both)".to_string(),
+            ));
+        }
 
-        let clinical_uuid = ShardableUuid::parse(&self.clinical_id().simple().to_string())?;
-        let patient_dir = self.clinical_patient_dir(&clinical_uuid);
+        author.validate_commit_author()?;
 
-        let body_md_path = patient_dir.join(letter_paths.body_md());
-        let composition_yaml_path = patient_dir.join(letter_paths.composition_yaml());
+        let commit_message = VprCommitMessage::new(
+            VprCommitDomain::Clinical(Correction),
+            VprCommitAction::Superseded,
+            "Issued new letter version",
+            care_location,
+        )?;
 
-        // Check that both files exist
-        if !body_md_path.exists() {
-            return Err(PatientError::InvalidInput(format!(
-                "Letter body file not found: {}",
-                body_md_path.display()
-            )));
-        }
+        let previous_id: TimestampId = timestamp_id
+            .parse()
+            .map_err(|e| PatientError::InvalidInput(format!("Invalid timestamp ID: {}", e)))?;
+        let clinical_uuid = ShardableUuid::parse(&self.clinical_id().simple().to_string())?;
+        let lock = self.write_lock(&self.clinical_patient_dir(&clinical_uuid))?;
+        let (previous_path, previous_composition, mut previous_data) =
+            self.letter_composition_read(&previous_id)?;
 
-        if !composition_yaml_path.exists() {
+        if let Some(superseded_by) = &previous_data.superseded_by {
             return Err(PatientError::InvalidInput(format!(
-                "Letter composition file not found: {}",
-                composition_yaml_path.display()
+                "Letter {} has already been superseded by {}",
+                previous_id, superseded_by
             )));
         }
 
-        // Read the body content
-        let body_content_str = fs::read_to_string(&body_md_path).map_err(PatientError::FileRead)?;
-        let body_content = NonEmptyText::new(&body_content_str).map_err(|_| {
-            PatientError::InvalidInput(format!(
-                "Letter body is empty for timestamp ID: {}",
-                timestamp_id
-            ))
-        })?;
-
-        // Read and parse the composition
-        let composition_yaml =
-            fs::read_to_string(&composition_yaml_path).map_err(PatientError::FileRead)?;
+        let timestamp_id = TimestampIdGenerator::generate(Some(&previous_id.to_string()))?;
 
-        let rm_version = extract_rm_version(&composition_yaml)?;
+        previous_data.superseded_by = Some(timestamp_id.clone());
+        let updated_composition =
+            Letter::composition_render(previous_data.rm_version, &previous_data)?;
 
-        let letter_data = Letter::composition_parse(rm_version, &composition_yaml)
-            .map_err(PatientError::Openehr)?;
+        self.write_letter(
+            &lock,
+            author,
+            &commit_message,
+            &timestamp_id,
+            LetterContent {
+                body_content,
+                attachment_files,
+                clinical_lists,
+                supersedes: Some(&previous_id),
+            },
+            &[FileToWrite {
+                relative_path: &previous_path,
+                content: &updated_composition,
+                old_content: Some(&previous_composition),
+            }],
+        )?;
 
-        Ok(ReadLetterResult {
-            body_content,
-            letter_data,
-        })
+        Ok(timestamp_id)
     }
 
-    /// Retrieves all attachments for a clinical letter.
+    /// Reads the current version of a clinical letter.
     ///
-    /// This function reads all attachment metadata files from the letter's attachments directory
-    /// and retrieves the actual file content from storage.
+    /// `timestamp_id` may identify any version of the letter; superseded versions are
+    /// followed to the version that replaced them. Use
+    /// [`read_letter_version`](Self::read_letter_version) to read a specific version.
     ///
     /// # Arguments
     ///
-    /// * `timestamp_id` - The timestamp ID of the letter.
+    /// * `timestamp_id` - The timestamp ID of any version of the letter.
     ///
     /// # Returns
     ///
-    /// Returns a vector of `LetterAttachment` containing metadata and file content for each attachment.
+    /// Returns a `ReadLetterResult` with the current version's body and parsed
+    /// composition, and the full version chain.
     ///
     /// # Errors
     ///
     /// Returns a `PatientError` if:
     /// - The timestamp ID cannot be parsed
     /// - The clinical UUID cannot be parsed
-    /// - The attachments directory does not exist or cannot be read
-    /// - Any attachment metadata file cannot be read or parsed
-    /// - Any file cannot be retrieved from storage
-    pub fn get_letter_attachments(
-        &self,
-        timestamp_id: &str,
-    ) -> PatientResult<Vec<LetterAttachment>> {
-        let timestamp_id: vpr_uuid::TimestampId = timestamp_id
-            .parse()
+    /// - The body.md or composition.yaml file of any version does not exist
+    /// - Reading either file fails
+    /// - Parsing the composition.yaml fails
+    pub fn read_letter(&self, timestamp_id: &str) -> PatientResult<ReadLetterResult> {
+        let timestamp_id: TimestampId = timestamp_id
+            .parse()
+            .map_err(|e| PatientError::InvalidInput(format!("Invalid timestamp ID: {}", e)))?;
+
+        // Read `main` first: if a write lands while reading, the id is stale rather than new.
+        let commit_id = self.main_commit_id()?;
+        let versions = self.letter_versions(&timestamp_id)?;
+        let current = versions
+            .last()
+            .cloned()
+            .unwrap_or_else(|| timestamp_id.clone());
+
+        self.letter_read(&current, versions, commit_id)
+    }
+
+    /// Reads a specific version of a clinical letter.
+    ///
+    /// Unlike [`read_letter`](Self::read_letter), superseded versions are returned as
+    /// they are rather than resolved to the current version.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp_id` - The timestamp ID of the letter version to read.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `ReadLetterResult` for the requested version, and the full version chain.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PatientError` under the same conditions as [`read_letter`](Self::read_letter).
+    pub fn read_letter_version(&self, timestamp_id: &str) -> PatientResult<ReadLetterResult> {
+        let timestamp_id: TimestampId = timestamp_id
+            .parse()
+            .map_err(|e| PatientError::InvalidInput(format!("Invalid timestamp ID: {}", e)))?;
+
+        let commit_id = self.main_commit_id()?;
+        let versions = self.letter_versions(&timestamp_id)?;
+        self.letter_read(&timestamp_id, versions, commit_id)
+    }
+
+    /// Lists the letters in this clinical record.
+    ///
+    /// Walks `correspondence/letter/` and returns a summary of each letter version,
+    /// ordered by timestamp ID. Superseded versions are included; their `superseded_by`
+    /// field points at the version that replaced them. Letter directories without a
+    /// composition (for example after redaction) are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Cursor, page size and date-range filters.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `LetterPage` holding up to `query.limit` letters and, when more letters
+    /// match, the cursor to pass for the next page.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PatientError` if:
+    /// - The limit is zero or greater than [`MAX_PAGE_SIZE`](crate::constants::MAX_PAGE_SIZE)
+    /// - `from` is not before `to`
+    /// - The letters directory cannot be read
+    /// - A letter's composition.yaml cannot be read or parsed
+    pub fn list_letters(&self, query: &ListLettersQuery) -> PatientResult<LetterPage> {
+        let limit = page_limit(query.limit)?;
+        if let (Some(from), Some(to)) = (query.from, query.to) {
+            if from >= to {
+                return Err(PatientError::InvalidInput(
+                    "Date range start must be before its end".into(),
+                ));
+            }
+        }
+
+        let clinical_uuid = ShardableUuid::parse(&self.clinical_id().simple().to_string())?;
+        let patient_dir = self.clinical_patient_dir(&clinical_uuid);
+        let letters_dir = patient_dir
+            .join(CorrespondenceDir::NAME)
+            .join(LetterDir::NAME);
+
+        let mut timestamp_ids = Vec::new();
+        if letters_dir.exists() {
+            for entry in fs::read_dir(&letters_dir).map_err(PatientError::FileRead)? {
+                let entry = entry.map_err(PatientError::FileRead)?;
+                let Some(timestamp_id) = entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|name| name.parse::<TimestampId>().ok())
+                else {
+                    continue;
+                };
+                if !query.matches(&timestamp_id)
+                    || !self.letter_composition_exists(&timestamp_id)?
+                {
+                    continue;
+                }
+                timestamp_ids.push(timestamp_id);
+            }
+        }
+
+        timestamp_ids.sort_by_key(timestamp_id_order_key);
+        let has_more = timestamp_ids.len() > limit;
+        timestamp_ids.truncate(limit);
+
+        let letters = timestamp_ids
+            .iter()
+            .map(|timestamp_id| {
+                let (_, _, letter_data) = self.letter_composition_read(timestamp_id)?;
+                Ok(LetterSummary::from(letter_data))
+            })
+            .collect::<PatientResult<Vec<_>>>()?;
+
+        Ok(LetterPage {
+            next_cursor: has_more.then(|| timestamp_ids.last().cloned()).flatten(),
+            letters,
+        })
+    }
+
+    /// Retrieves all attachments for a clinical letter.
+    ///
+    /// This function reads all attachment metadata files from the letter's attachments directory
+    /// and retrieves the actual file content from storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp_id` - The timestamp ID of the letter.
+    ///
+    /// # Returns
+    ///
+    /// Returns a vector of `LetterAttachment` containing metadata and file content for each attachment.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PatientError` if:
+    /// - The timestamp ID cannot be parsed
+    /// - The clinical UUID cannot be parsed
+    /// - The attachments directory does not exist or cannot be read
+    /// - Any attachment metadata file cannot be read or parsed
+    /// - Any file cannot be retrieved from storage
+    pub fn get_letter_attachments(
+        &self,
+        timestamp_id: &str,
+    ) -> PatientResult<Vec<LetterAttachment>> {
+        let timestamp_id: vpr_uuid::TimestampId = timestamp_id
+            .parse()
             .map_err(|e| PatientError::InvalidInput(format!("Invalid timestamp ID: {}", e)))?;
         let letter_paths = LetterPaths::new(&timestamp_id);
 
@@ -873,6 +1074,668 @@ impl ClinicalService<Initialised> {
             clinical_lists,
         )
     }
+
+    /// Redacts a clinical letter, moving it into the Redaction Retention Repository.
+    ///
+    /// The letter's composition, body and attachment metadata are removed from the clinical
+    /// record and retained in full in the RRR, along with any attachment files. A tombstone
+    /// is left under `redactions/`. Afterwards the letter can no longer be read through
+    /// [`read_letter`](Self::read_letter); see
+    /// [`RedactionService::read_redaction`] for authorised retrieval.
+    ///
+    /// # Arguments
+    ///
+    /// * `author` - The author performing the redaction.
+    /// * `care_location` - High-level organisational location for the commits.
+    /// * `timestamp_id` - The timestamp ID of the letter to redact.
+    /// * `reason` - Why the letter is being removed from routine view.
+    /// * `reason_detail` - Optional free-text detail, recorded in the RRR only.
+    ///
+    /// # Returns
+    ///
+    /// Returns the redaction ID on success.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PatientError` if:
+    /// - The timestamp ID cannot be parsed or the letter does not exist
+    /// - Any letter file or attachment metadata cannot be read or parsed
+    /// - Retaining the letter or committing either repository fails
+    pub fn redact_letter(
+        &self,
+        author: &Author,
+        care_location: NonEmptyText,
+        timestamp_id: &str,
+        reason: RedactionReason,
+        reason_detail: Option<NonEmptyText>,
+    ) -> PatientResult<TimestampId> {
+        let timestamp_id: TimestampId = timestamp_id
+            .parse()
+            .map_err(|e| PatientError::InvalidInput(format!("Invalid timestamp ID: {}", e)))?;
+        let letter_paths = LetterPaths::new(&timestamp_id);
+
+        let clinical_uuid = ShardableUuid::parse(&self.clinical_id().simple().to_string())?;
+        let patient_dir = self.clinical_patient_dir(&clinical_uuid);
+        let lock = self.write_lock(&patient_dir)?;
+
+        if !patient_dir.join(letter_paths.composition_yaml()).exists() {
+            return Err(PatientError::InvalidInput(format!(
+                "Letter not found: {}",
+                timestamp_id
+            )));
+        }
+
+        // Collect every file belonging to the letter
+        let mut relative_paths = vec![letter_paths.composition_yaml()];
+        if patient_dir.join(letter_paths.body_md()).exists() {
+            relative_paths.push(letter_paths.body_md());
+        }
+        relative_paths.extend(self.attachment_metadata_paths(&patient_dir, &letter_paths)?);
+
+        let mut retained = Vec::new();
+        let mut attachment_hashes = Vec::new();
+        for path in &relative_paths {
+            let content =
+                fs::read_to_string(patient_dir.join(path)).map_err(PatientError::FileRead)?;
+            if path.starts_with(letter_paths.attachments_dir()) {
+                attachment_hashes.push(attachment_metadata_parse(&content)?.hash);
+            }
+            retained.push(RetainedFile {
+                path: path.clone(),
+                content,
+            });
+        }
+
+        let removals: Vec<FileToRemove> = retained
+            .iter()
+            .map(|file| FileToRemove {
+                relative_path: &file.path,
+                old_content: &file.content,
+            })
+            .collect();
+
+        let files_service = self.files_service_for_redaction(&clinical_uuid, &attachment_hashes)?;
+
+        let redaction_id = RedactionService::with_id(self.cfg.clone(), self.clinical_id()).redact(
+            author,
+            care_location,
+            VprCommitDomain::Clinical(Record),
+            &lock,
+            RedactionRequest {
+                item: RedactedItem::Letter {
+                    letter_id: timestamp_id,
+                },
+                reason,
+                reason_detail,
+                retained: retained.clone(),
+                attachment_hashes: attachment_hashes.clone(),
+                source_files: files_service.as_ref(),
+                writes: &[],
+                removals: &removals,
+            },
+        )?;
+
+        if let Some(files_service) = files_service {
+            self.remove_unreferenced_files(&patient_dir, &files_service, &attachment_hashes);
+        }
+
+        Ok(redaction_id)
+    }
+
+    /// Redacts a single attachment of a clinical letter.
+    ///
+    /// The attachment metadata file and its stored file are moved into the Redaction
+    /// Retention Repository, and the letter's composition is rewritten without the
+    /// attachment reference. The rest of the letter remains in routine view.
+    ///
+    /// # Arguments
+    ///
+    /// * `author` - The author performing the redaction.
+    /// * `care_location` - High-level organisational location for the commits.
+    /// * `timestamp_id` - The timestamp ID of the letter.
+    /// * `metadata_filename` - The attachment metadata filename (e.g. `attachment_1.yaml`).
+    /// * `reason` - Why the attachment is being removed from routine view.
+    /// * `reason_detail` - Optional free-text detail, recorded in the RRR only.
+    ///
+    /// # Returns
+    ///
+    /// Returns the redaction ID on success.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PatientError` if:
+    /// - The timestamp ID cannot be parsed or the letter or attachment does not exist
+    /// - The attachment is the only content of the letter (redact the letter instead)
+    /// - The composition or attachment metadata cannot be read, parsed or rendered
+    /// - Retaining the attachment or committing either repository fails
+    pub fn redact_letter_attachment(
+        &self,
+        author: &Author,
+        care_location: NonEmptyText,
+        timestamp_id: &str,
+        metadata_filename: &str,
+        reason: RedactionReason,
+        reason_detail: Option<NonEmptyText>,
+    ) -> PatientResult<TimestampId> {
+        let timestamp_id: TimestampId = timestamp_id
+            .parse()
+            .map_err(|e| PatientError::InvalidInput(format!("Invalid timestamp ID: {}", e)))?;
+        let letter_paths = LetterPaths::new(&timestamp_id);
+
+        let attachment_name = NonEmptyText::new(metadata_filename)
+            .ok()
+            .filter(|name| {
+                Path::new(name.as_str()).file_name() == Some(std::ffi::OsStr::new(name.as_str()))
+            })
+            .ok_or_else(|| {
+                PatientError::InvalidInput(format!(
+                    "Invalid attachment filename: {}",
+                    metadata_filename
+                ))
+            })?;
+
+        let clinical_uuid = ShardableUuid::parse(&self.clinical_id().simple().to_string())?;
+        let patient_dir = self.clinical_patient_dir(&clinical_uuid);
+        let lock = self.write_lock(&patient_dir)?;
+
+        let attachment_path = letter_paths.attachment(attachment_name.as_str());
+        let composition_path = letter_paths.composition_yaml();
+
+        if !patient_dir.join(&attachment_path).exists() {
+            return Err(PatientError::InvalidInput(format!(
+                "Attachment {} not found for letter {}",
+                attachment_name, timestamp_id
+            )));
+        }
+
+        let attachment_yaml = fs::read_to_string(patient_dir.join(&attachment_path))
+            .map_err(PatientError::FileRead)?;
+        let attachment_hash = attachment_metadata_parse(&attachment_yaml)?.hash;
+
+        // Rewrite the composition without the attachment reference
+        let old_composition = fs::read_to_string(patient_dir.join(&composition_path))
+            .map_err(PatientError::FileRead)?;
+        let rm_version = extract_rm_version(&old_composition)?;
+        let mut letter_data = Letter::composition_parse(rm_version, &old_composition)?;
+
+        let reference = format!("./attachments/{}", attachment_name);
+        letter_data.attachments.retain(|a| a.path != reference);
+
+        if !letter_data.has_body && letter_data.attachments.is_empty() {
+            return Err(PatientError::InvalidInput(
+                "Cannot redact the only content of a letter; redact the letter instead".to_string(),
+            ));
+        }
+
+        let new_composition = Letter::composition_render(rm_version, &letter_data)?;
+
+        let writes = [FileToWrite {
+            relative_path: &composition_path,
+            content: &new_composition,
+            old_content: Some(&old_composition),
+        }];
+        let removals = [FileToRemove {
+            relative_path: &attachment_path,
+            old_content: &attachment_yaml,
+        }];
+
+        let attachment_hashes = vec![attachment_hash];
+        let files_service = self.files_service_for_redaction(&clinical_uuid, &attachment_hashes)?;
+
+        let redaction_id = RedactionService::with_id(self.cfg.clone(), self.clinical_id()).redact(
+            author,
+            care_location,
+            VprCommitDomain::Clinical(Record),
+            &lock,
+            RedactionRequest {
+                item: RedactedItem::LetterAttachment {
+                    letter_id: timestamp_id,
+                    attachment: attachment_name,
+                },
+                reason,
+                reason_detail,
+                retained: vec![RetainedFile {
+                    path: attachment_path.clone(),
+                    content: attachment_yaml.clone(),
+                }],
+                attachment_hashes: attachment_hashes.clone(),
+                source_files: files_service.as_ref(),
+                writes: &writes,
+                removals: &removals,
+            },
+        )?;
+
+        if let Some(files_service) = files_service {
+            self.remove_unreferenced_files(&patient_dir, &files_service, &attachment_hashes);
+        }
+
+        Ok(redaction_id)
+    }
+
+    /// Returns the commit history of this clinical record, newest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Optional path relative to the record root (e.g. `ehr_status.yaml`). When
+    ///   given, only commits that changed that file or directory are returned. A path that
+    ///   only held redacted content returns no commits; use the RRR history instead.
+    ///
+    /// # Returns
+    ///
+    /// One [`VprCommitHistoryEntry`] per commit on `refs/heads/main`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if the repository cannot be opened or walked. See
+    /// [`VersionedFileService::commit_history`].
+    pub fn commit_history(&self, path: Option<&Path>) -> PatientResult<Vec<VprCommitHistoryEntry>> {
+        if let Some(path) = path {
+            let clinical_uuid = ShardableUuid::parse(&self.clinical_id().simple().to_string())?;
+            let patient_dir = self.clinical_patient_dir(&clinical_uuid);
+            if RedactionService::with_id(self.cfg.clone(), self.clinical_id())
+                .is_redacted_path(&patient_dir, path)?
+            {
+                return Ok(Vec::new());
+            }
+        }
+
+        VersionedFileService::commit_history(
+            &self.clinical_dir(),
+            &self.clinical_id().simple().to_string(),
+            VprRepositoryKind::Clinical,
+            path,
+            self.cfg.trust_anchors(),
+            Some(&RevocationService::new(self.cfg.clone()).revocation_list()?),
+            self.cfg.timestamp_trust_anchors(),
+        )
+    }
+
+    /// Verifies the embedded signature of every commit in this clinical record.
+    ///
+    /// # Returns
+    ///
+    /// A [`SignatureChainReport`] listing each commit (newest first) as valid, unsigned,
+    /// invalid, or certificate/key mismatch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if the repository cannot be opened or walked. See
+    /// [`VersionedFileService::verify_signature_chain`].
+    pub fn verify_signature_chain(&self) -> PatientResult<SignatureChainReport> {
+        VersionedFileService::verify_signature_chain(
+            &self.clinical_dir(),
+            &self.clinical_id().simple().to_string(),
+            self.cfg.trust_anchors(),
+            Some(&RevocationService::new(self.cfg.clone()).revocation_list()?),
+            self.cfg.timestamp_trust_anchors(),
+        )
+    }
+}
+
+impl ClinicalService<Initialised> {
+    /// Takes the write lock on `patient_dir`, checking `main` against the expected commit.
+    fn write_lock(&self, patient_dir: &Path) -> PatientResult<RepositoryLock> {
+        let lock = RepositoryLock::acquire(patient_dir)?;
+        lock.expect_head(self.state.expected_commit)?;
+        Ok(lock)
+    }
+
+    /// Returns the id of the commit `main` points to.
+    fn main_commit_id(&self) -> PatientResult<String> {
+        let clinical_uuid = ShardableUuid::parse(&self.clinical_id().simple().to_string())?;
+        VersionedFileService::open(&self.clinical_patient_dir(&clinical_uuid))?.main_commit_id()
+    }
+
+    /// Returns the relative paths of all attachment metadata files of a letter.
+    fn attachment_metadata_paths(
+        &self,
+        patient_dir: &Path,
+        letter_paths: &LetterPaths,
+    ) -> PatientResult<Vec<PathBuf>> {
+        let attachments_dir = patient_dir.join(letter_paths.attachments_dir());
+        if !attachments_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(&attachments_dir).map_err(PatientError::FileRead)? {
+            let entry = entry.map_err(PatientError::FileRead)?;
+            if entry.path().extension().and_then(|s| s.to_str()) == Some("yaml") {
+                paths.push(letter_paths.attachment(&entry.file_name().to_string_lossy()));
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Writes a letter's files and commits them in a single commit.
+    ///
+    /// Stores any attachments, renders composition.yaml (with a `supersedes` link when
+    /// issuing a new version), and commits it together with body.md, the attachment
+    /// metadata and `extra_files`.
+    fn write_letter(
+        &self,
+        lock: &RepositoryLock,
+        author: &Author,
+        commit_message: &VprCommitMessage,
+        timestamp_id: &TimestampId,
+        content: LetterContent<'_>,
+        extra_files: &[FileToWrite],
+    ) -> PatientResult<()> {
+        let letter_paths = LetterPaths::new(timestamp_id);
+
+        let clinical_uuid = ShardableUuid::parse(&self.clinical_id().simple().to_string())?;
+
+        // Process attachments if provided
+        let mut attachment_metadata_list = Vec::new();
+        let mut attachment_files_to_write = Vec::new();
+
+        if !content.attachment_files.is_empty() {
+            let clinical_dir = self.clinical_dir();
+            let files_service = vpr_files::FilesService::new(&clinical_dir, clinical_uuid.clone())
+                .map_err(|e| {
+                    PatientError::InvalidInput(format!("Failed to initialize files service: {}", e))
+                })?;
+
+            for (index, file_path) in content.attachment_files.iter().enumerate() {
+                // Add file to storage
+                let file_metadata = files_service.add(file_path).map_err(|e| {
+                    PatientError::InvalidInput(format!("Failed to add attachment file: {}", e))
+                })?;
+
+                // Create attachment metadata
+                let attachment_filename = format!("attachment_{}.yaml", index + 1);
+                let attachment_metadata = AttachmentMetadata {
+                    metadata_filename: NonEmptyText::new(&attachment_filename).map_err(|e| {
+                        PatientError::InvalidInput(format!("Invalid attachment filename: {}", e))
+                    })?,
+                    hash: Sha256Hash::parse(file_metadata.hash.as_str()).map_err(|e| {
+                        PatientError::InvalidInput(format!("Invalid SHA-256 hash: {}", e))
+                    })?,
+                    file_storage_path: NonEmptyText::new(file_metadata.relative_path.as_str())
+                        .map_err(|e| {
+                            PatientError::InvalidInput(format!("Invalid file storage path: {}", e))
+                        })?,
+                    size_bytes: file_metadata.size_bytes,
+                    media_type: file_metadata
+                        .media_type
+                        .as_ref()
+                        .map(|mt| NonEmptyText::new(mt.as_str()))
+                        .transpose()
+                        .map_err(|e| {
+                            PatientError::InvalidInput(format!("Invalid media type: {}", e))
+                        })?,
+                    original_filename: NonEmptyText::new(&file_metadata.original_filename)
+                        .map_err(|e| {
+                            PatientError::InvalidInput(format!("Invalid original filename: {}", e))
+                        })?,
+                };
+
+                // Serialize attachment metadata to YAML
+                let attachment_yaml = serde_yaml::to_string(&attachment_metadata).map_err(|e| {
+                    PatientError::InvalidInput(format!(
+                        "Failed to serialize attachment metadata: {}",
+                        e
+                    ))
+                })?;
+
+                // Add to files to write
+                let attachment_path = letter_paths.attachment(&attachment_filename);
+                attachment_files_to_write.push((attachment_path, attachment_yaml));
+                attachment_metadata_list.push(attachment_metadata);
+            }
+        }
+
+        // Generate composition.yaml content
+        let rm_version = self.cfg.rm_system_version();
+        let start_time = timestamp_id.timestamp();
+
+        // Build attachment references for the composition
+        let attachment_refs: Vec<openehr::AttachmentReference> = attachment_metadata_list
+            .iter()
+            .map(|meta| openehr::AttachmentReference {
+                path: format!("./attachments/{}", meta.metadata_filename),
+            })
+            .collect();
+
+        // Construct LetterData
+        let letter_data = openehr::LetterData {
+            rm_version,
+            uid: timestamp_id.clone(),
+            composer_name: author.name.to_string(),
+            composer_role: "Clinical Practitioner".to_string(),
+            start_time,
+            clinical_lists: content
+                .clinical_lists
+                .map(|lists| lists.to_vec())
+                .unwrap_or_default(),
+            has_body: content.body_content.is_some(),
+            attachments: attachment_refs,
+            supersedes: content.supersedes.cloned(),
+            superseded_by: None,
+        };
+
+        let composition_content =
+            Letter::composition_render(rm_version, &letter_data).map_err(|e| {
+                PatientError::InvalidInput(format!("Failed to create letter composition: {}", e))
+            })?;
+
+        let composition_yaml_relative_path = letter_paths.composition_yaml();
+
+        // Build list of all files to write
+        let mut files_to_write_vec = vec![FileToWrite {
+            relative_path: &composition_yaml_relative_path,
+            content: &composition_content,
+            old_content: None,
+        }];
+
+        // Add body.md if provided
+        let body_md_relative_path;
+        if let Some(ref body) = content.body_content {
+            body_md_relative_path = letter_paths.body_md();
+            files_to_write_vec.push(FileToWrite {
+                relative_path: &body_md_relative_path,
+                content: body.as_str(),
+                old_content: None,
+            });
+        }
+
+        // Add attachment metadata files
+        let attachment_paths: Vec<PathBuf> = attachment_files_to_write
+            .iter()
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        for (path, content) in attachment_paths
+            .iter()
+            .zip(attachment_files_to_write.iter())
+        {
+            files_to_write_vec.push(FileToWrite {
+                relative_path: path,
+                content: content.1.as_str(),
+                old_content: None,
+            });
+        }
+
+        files_to_write_vec.extend_from_slice(extra_files);
+
+        VersionedFileService::write_and_commit_files(
+            lock,
+            author,
+            commit_message,
+            &files_to_write_vec,
+            self.cfg.timestamp_authority(),
+            self.cfg.mirror(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Reads and parses a letter's composition.yaml.
+    ///
+    /// Returns the composition's relative path, raw content and parsed data.
+    fn letter_composition_read(
+        &self,
+        timestamp_id: &TimestampId,
+    ) -> PatientResult<(PathBuf, String, LetterData)> {
+        let clinical_uuid = ShardableUuid::parse(&self.clinical_id().simple().to_string())?;
+        let patient_dir = self.clinical_patient_dir(&clinical_uuid);
+
+        let composition_path = LetterPaths::new(timestamp_id).composition_yaml();
+        let full_path = patient_dir.join(&composition_path);
+
+        if !full_path.exists() {
+            return Err(PatientError::InvalidInput(format!(
+                "Letter composition file not found: {}",
+                full_path.display()
+            )));
+        }
+
+        let composition_yaml = fs::read_to_string(&full_path).map_err(PatientError::FileRead)?;
+        let rm_version = extract_rm_version(&composition_yaml)?;
+        let letter_data = Letter::composition_parse(rm_version, &composition_yaml)
+            .map_err(PatientError::Openehr)?;
+
+        Ok((composition_path, composition_yaml, letter_data))
+    }
+
+    /// Returns whether a letter version's composition.yaml exists.
+    fn letter_composition_exists(&self, timestamp_id: &TimestampId) -> PatientResult<bool> {
+        let clinical_uuid = ShardableUuid::parse(&self.clinical_id().simple().to_string())?;
+        let patient_dir = self.clinical_patient_dir(&clinical_uuid);
+        Ok(patient_dir
+            .join(LetterPaths::new(timestamp_id).composition_yaml())
+            .exists())
+    }
+
+    /// Returns every version of the letter containing `timestamp_id`, oldest first.
+    ///
+    /// Walks `supersedes` links back to the original letter and `superseded_by` links
+    /// forward to the current version. Versions that are no longer present (for example
+    /// because they were redacted) end the walk in that direction.
+    fn letter_versions(&self, timestamp_id: &TimestampId) -> PatientResult<Vec<TimestampId>> {
+        let mut seen = HashSet::from([timestamp_id.to_string()]);
+        let (_, _, letter_data) = self.letter_composition_read(timestamp_id)?;
+
+        let mut earlier = Vec::new();
+        let mut previous = letter_data.supersedes.clone();
+        while let Some(id) = previous {
+            if !seen.insert(id.to_string()) {
+                return Err(PatientError::InvalidInput(format!(
+                    "Letter version chain contains a cycle at {}",
+                    id
+                )));
+            }
+            if !self.letter_composition_exists(&id)? {
+                break;
+            }
+            previous = self.letter_composition_read(&id)?.2.supersedes;
+            earlier.push(id);
+        }
+
+        let mut versions: Vec<TimestampId> = earlier.into_iter().rev().collect();
+        versions.push(timestamp_id.clone());
+
+        let mut next = letter_data.superseded_by;
+        while let Some(id) = next {
+            if !seen.insert(id.to_string()) {
+                return Err(PatientError::InvalidInput(format!(
+                    "Letter version chain contains a cycle at {}",
+                    id
+                )));
+            }
+            if !self.letter_composition_exists(&id)? {
+                break;
+            }
+            next = self.letter_composition_read(&id)?.2.superseded_by;
+            versions.push(id);
+        }
+
+        Ok(versions)
+    }
+
+    /// Reads one letter version's body and composition.
+    fn letter_read(
+        &self,
+        timestamp_id: &TimestampId,
+        versions: Vec<TimestampId>,
+        commit_id: String,
+    ) -> PatientResult<ReadLetterResult> {
+        let letter_paths = LetterPaths::new(timestamp_id);
+
+        let clinical_uuid = ShardableUuid::parse(&self.clinical_id().simple().to_string())?;
+        let patient_dir = self.clinical_patient_dir(&clinical_uuid);
+
+        let body_md_path = patient_dir.join(letter_paths.body_md());
+
+        if !body_md_path.exists() {
+            return Err(PatientError::InvalidInput(format!(
+                "Letter body file not found: {}",
+                body_md_path.display()
+            )));
+        }
+
+        // Read the body content
+        let body_content_str = fs::read_to_string(&body_md_path).map_err(PatientError::FileRead)?;
+        let body_content = NonEmptyText::new(&body_content_str).map_err(|_| {
+            PatientError::InvalidInput(format!(
+                "Letter body is empty for timestamp ID: {}",
+                timestamp_id
+            ))
+        })?;
+
+        let (_, _, letter_data) = self.letter_composition_read(timestamp_id)?;
+
+        Ok(ReadLetterResult {
+            body_content,
+            letter_data,
+            versions,
+            commit_id,
+        })
+    }
+
+    /// Opens the files service when a redaction needs to move attachment files.
+    fn files_service_for_redaction(
+        &self,
+        clinical_uuid: &ShardableUuid,
+        attachment_hashes: &[Sha256Hash],
+    ) -> PatientResult<Option<vpr_files::FilesService>> {
+        if attachment_hashes.is_empty() {
+            return Ok(None);
+        }
+
+        vpr_files::FilesService::new(&self.clinical_dir(), clinical_uuid.clone())
+            .map(Some)
+            .map_err(|e| {
+                PatientError::InvalidInput(format!("Failed to initialize files service: {}", e))
+            })
+    }
+
+    /// Removes stored files that are no longer referenced by any letter attachment.
+    ///
+    /// Identical content is stored once, so a redacted attachment's file is only removed
+    /// if no remaining attachment metadata refers to the same hash. The redaction has
+    /// already been committed at this point, so failures are logged rather than returned.
+    fn remove_unreferenced_files(
+        &self,
+        patient_dir: &Path,
+        files_service: &vpr_files::FilesService,
+        hashes: &[Sha256Hash],
+    ) {
+        let referenced = match referenced_attachment_hashes(patient_dir) {
+            Ok(referenced) => referenced,
+            Err(e) => {
+                tracing::warn!("Skipping removal of redacted attachment files: {}", e);
+                return;
+            }
+        };
+
+        for hash in hashes.iter().filter(|hash| !referenced.contains(*hash)) {
+            if let Err(e) = files_service.remove(hash.as_str()) {
+                tracing::warn!("Failed to remove redacted attachment file {}: {}", hash, e);
+            }
+        }
+    }
 }
 
 impl<S> ClinicalService<S> {
@@ -909,6 +1772,46 @@ impl<S> ClinicalService<S> {
     }
 }
 
+/// Parses an attachment metadata YAML file.
+fn attachment_metadata_parse(content: &str) -> PatientResult<AttachmentMetadata> {
+    serde_yaml::from_str(content).map_err(|e| {
+        PatientError::InvalidInput(format!("Failed to parse attachment metadata: {}", e))
+    })
+}
+
+/// Collects the hashes referenced by every attachment metadata file in a clinical record.
+fn referenced_attachment_hashes(patient_dir: &Path) -> PatientResult<HashSet<Sha256Hash>> {
+    let letters_dir = patient_dir
+        .join(CorrespondenceDir::NAME)
+        .join(LetterDir::NAME);
+
+    let mut referenced = HashSet::new();
+    if !letters_dir.exists() {
+        return Ok(referenced);
+    }
+
+    for letter in fs::read_dir(&letters_dir).map_err(PatientError::FileRead)? {
+        let attachments_dir = letter
+            .map_err(PatientError::FileRead)?
+            .path()
+            .join(AttachmentsDir::NAME);
+        if !attachments_dir.exists() {
+            continue;
+        }
+
+        for entry in fs::read_dir(&attachments_dir).map_err(PatientError::FileRead)? {
+            let path = entry.map_err(PatientError::FileRead)?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("yaml") {
+                continue;
+            }
+            let content = fs::read_to_string(&path).map_err(PatientError::FileRead)?;
+            referenced.insert(attachment_metadata_parse(&content)?.hash);
+        }
+    }
+
+    Ok(referenced)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1213,6 +2116,66 @@ mod tests {
         assert!(ehr_status_file.exists(), "ehr_status.yaml should exist");
     }
 
+    #[test]
+    fn test_commit_history_lists_ehr_status_changes() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+        let author = Author {
+            name: NonEmptyText::new("Test Author").unwrap(),
+            role: NonEmptyText::new("Clinician").unwrap(),
+            email: EmailAddress::parse("test@example.com").unwrap(),
+            registrations: vec![],
+            signature: None,
+            certificate: None,
+        };
+
+        let cfg = test_cfg(temp_dir.path());
+        let care_location = NonEmptyText::new("Test Hospital").unwrap();
+        let service = ClinicalService::new(cfg)
+            .initialise(author.clone(), care_location.clone())
+            .expect("initialise should succeed");
+        service
+            .link_to_demographics(
+                &author,
+                care_location.clone(),
+                "12345678123412341234123456789abc",
+                None,
+            )
+            .expect("link_to_demographics should succeed");
+        service
+            .new_letter(
+                &author,
+                care_location,
+                NonEmptyText::new("Letter body").unwrap(),
+                None,
+            )
+            .expect("new_letter should succeed");
+
+        let history = service
+            .commit_history(None)
+            .expect("commit_history should succeed");
+        assert_eq!(history.len(), 3);
+
+        let ehr_status_history = service
+            .commit_history(Some(Path::new(EhrStatusFile::NAME)))
+            .expect("commit_history should succeed");
+        assert_eq!(ehr_status_history.len(), 2);
+        assert_eq!(
+            ehr_status_history[0].message.as_ref().unwrap().action(),
+            VprCommitAction::Update
+        );
+        assert_eq!(
+            ehr_status_history[1].message.as_ref().unwrap().action(),
+            VprCommitAction::Create
+        );
+        assert!(ehr_status_history
+            .iter()
+            .all(|entry| entry.message.as_ref().unwrap().domain()
+                == VprCommitDomain::Clinical(Record)
+                && entry.author.as_ref().unwrap().name.as_str() == "Test Author"
+                && entry.message.as_ref().unwrap().care_location() == "Test Hospital"));
+    }
+
     #[test]
     fn test_link_to_demographics_rejects_invalid_clinical_uuid() {
         let temp_dir = TempDir::new().expect("Failed to create temp dir");
@@ -2143,4 +3106,471 @@ mod tests {
         assert_eq!(deserialized.hash, metadata.hash);
         assert_eq!(deserialized.size_bytes, metadata.size_bytes);
     }
+
+    fn redaction_test_author() -> Author {
+        Author {
+            name: NonEmptyText::new("Dr. Test").unwrap(),
+            role: NonEmptyText::new("Consultant").unwrap(),
+            email: EmailAddress::parse("test@example.com").unwrap(),
+            registrations: vec![],
+            signature: None,
+            certificate: None,
+        }
+    }
+
+    #[test]
+    fn test_redact_letter_moves_letter_to_rrr() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let cfg = test_cfg(temp_dir.path());
+        let author = redaction_test_author();
+
+        let service = ClinicalService::new(cfg.clone())
+            .initialise(author.clone(), NonEmptyText::new("Test Hospital").unwrap())
+            .expect("initialise should succeed");
+
+        let test_file = temp_dir.path().join("scan.pdf");
+        fs::write(&test_file, b"wrong patient scan").expect("Failed to create test file");
+
+        let timestamp_id = service
+            .create_letter(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                Some(NonEmptyText::new("# Letter\n\nWrong patient.").unwrap()),
+                &[test_file],
+                None,
+            )
+            .expect("create_letter should succeed");
+        let hash = service
+            .get_letter_attachments(&timestamp_id.to_string())
+            .unwrap()[0]
+            .metadata
+            .hash
+            .clone();
+
+        let redaction_id = service
+            .redact_letter(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                &timestamp_id.to_string(),
+                RedactionReason::WrongPatient,
+                None,
+            )
+            .expect("redact_letter should succeed");
+
+        // Routine reads no longer see the letter or its stored file
+        assert!(service.read_letter(&timestamp_id.to_string()).is_err());
+        assert!(service
+            .get_letter_attachments(&timestamp_id.to_string())
+            .unwrap()
+            .is_empty());
+        let files_service = vpr_files::FilesService::new(
+            &service.clinical_dir(),
+            ShardableUuid::from_uuid(service.clinical_id()),
+        )
+        .unwrap();
+        assert!(files_service.read(hash.as_str()).is_err());
+
+        // The RRR holds everything
+        let retained = RedactionService::with_id(cfg, service.clinical_id())
+            .read_redaction(&redaction_id)
+            .expect("read_redaction should succeed");
+        let letter_paths = LetterPaths::new(&timestamp_id);
+        let retained_paths: Vec<_> = retained.files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(
+            retained_paths,
+            vec![
+                letter_paths.composition_yaml(),
+                letter_paths.body_md(),
+                letter_paths.attachment("attachment_1.yaml"),
+            ]
+        );
+        assert_eq!(retained.attachments.len(), 1);
+        assert_eq!(retained.attachments[0].content, b"wrong patient scan");
+
+        let history = service.commit_history(None).unwrap();
+        assert_eq!(
+            history[0].message.as_ref().unwrap().action(),
+            VprCommitAction::Redact
+        );
+    }
+
+    #[test]
+    fn test_redact_letter_attachment_keeps_rest_of_letter() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let cfg = test_cfg(temp_dir.path());
+        let author = redaction_test_author();
+
+        let service = ClinicalService::new(cfg.clone())
+            .initialise(author.clone(), NonEmptyText::new("Test Hospital").unwrap())
+            .expect("initialise should succeed");
+
+        let test_file1 = temp_dir.path().join("right.pdf");
+        let test_file2 = temp_dir.path().join("wrong.pdf");
+        fs::write(&test_file1, b"right patient").unwrap();
+        fs::write(&test_file2, b"wrong patient").unwrap();
+
+        let timestamp_id = service
+            .new_letter_with_attachments(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                &[test_file1, test_file2],
+                None,
+            )
+            .expect("new_letter_with_attachments should succeed");
+
+        let redaction_id = service
+            .redact_letter_attachment(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                &timestamp_id.to_string(),
+                "attachment_2.yaml",
+                RedactionReason::Misfiled,
+                Some(NonEmptyText::new("Scan belongs to another patient").unwrap()),
+            )
+            .expect("redact_letter_attachment should succeed");
+
+        let attachments = service
+            .get_letter_attachments(&timestamp_id.to_string())
+            .unwrap();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].content, b"right patient");
+
+        let clinical_uuid = ShardableUuid::from_uuid(service.clinical_id());
+        let composition = fs::read_to_string(
+            service
+                .clinical_patient_dir(&clinical_uuid)
+                .join(LetterPaths::new(&timestamp_id).composition_yaml()),
+        )
+        .unwrap();
+        let letter_data = Letter::composition_parse(cfg.rm_system_version(), &composition).unwrap();
+        assert_eq!(
+            letter_data.attachments,
+            vec![openehr::AttachmentReference {
+                path: "./attachments/attachment_1.yaml".to_string()
+            }]
+        );
+
+        let retained = RedactionService::with_id(cfg, service.clinical_id())
+            .read_redaction(&redaction_id)
+            .unwrap();
+        assert_eq!(retained.attachments[0].content, b"wrong patient");
+        assert_eq!(
+            retained.record.reason_detail.unwrap().as_str(),
+            "Scan belongs to another patient"
+        );
+    }
+
+    #[test]
+    fn test_redact_letter_attachment_rejects_only_content() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let cfg = test_cfg(temp_dir.path());
+        let author = redaction_test_author();
+
+        let service = ClinicalService::new(cfg)
+            .initialise(author.clone(), NonEmptyText::new("Test Hospital").unwrap())
+            .expect("initialise should succeed");
+
+        let test_file = temp_dir.path().join("only.pdf");
+        fs::write(&test_file, b"only content").unwrap();
+
+        let timestamp_id = service
+            .new_letter_with_attachments(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                &[test_file],
+                None,
+            )
+            .unwrap();
+
+        for filename in ["attachment_1.yaml", "../composition.yaml"] {
+            let result = service.redact_letter_attachment(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                &timestamp_id.to_string(),
+                filename,
+                RedactionReason::EnteredInError,
+                None,
+            );
+            assert!(matches!(result, Err(PatientError::InvalidInput(_))));
+        }
+        assert_eq!(
+            service
+                .get_letter_attachments(&timestamp_id.to_string())
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_amend_letter_creates_version_chain() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let cfg = test_cfg(temp_dir.path());
+        let author = redaction_test_author();
+
+        let service = ClinicalService::new(cfg)
+            .initialise(author.clone(), NonEmptyText::new("Test Hospital").unwrap())
+            .expect("initialise should succeed");
+
+        let original = service
+            .new_letter(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                NonEmptyText::new("Original wording").unwrap(),
+                None,
+            )
+            .expect("new_letter should succeed");
+
+        let second = service
+            .amend_letter(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                &original.to_string(),
+                Some(NonEmptyText::new("Corrected wording").unwrap()),
+                &[],
+                None,
+            )
+            .expect("amend_letter should succeed");
+        let third = service
+            .amend_letter(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                &second.to_string(),
+                Some(NonEmptyText::new("Corrected again").unwrap()),
+                &[],
+                None,
+            )
+            .expect("amend_letter should succeed");
+
+        let chain: Vec<String> = [&original, &second, &third]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+
+        // Any version resolves to the current one by default
+        for id in &chain {
+            let result = service.read_letter(id).expect("read_letter should succeed");
+            assert_eq!(result.body_content.as_str(), "Corrected again");
+            assert_eq!(result.letter_data.uid.to_string(), chain[2]);
+            assert_eq!(
+                result
+                    .versions
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>(),
+                chain
+            );
+        }
+
+        // Earlier versions remain readable, with their links
+        let first = service
+            .read_letter_version(&chain[0])
+            .expect("read_letter_version should succeed");
+        assert_eq!(first.body_content.as_str(), "Original wording");
+        assert_eq!(first.letter_data.supersedes, None);
+        assert_eq!(
+            first.letter_data.superseded_by.map(|id| id.to_string()),
+            Some(chain[1].clone())
+        );
+
+        let middle = service.read_letter_version(&chain[1]).unwrap();
+        assert_eq!(middle.body_content.as_str(), "Corrected wording");
+        assert_eq!(
+            middle.letter_data.supersedes.map(|id| id.to_string()),
+            Some(chain[0].clone())
+        );
+
+        let history = service.commit_history(None).unwrap();
+        assert_eq!(
+            history[0].message.as_ref().unwrap().domain(),
+            VprCommitDomain::Clinical(Correction)
+        );
+        assert_eq!(
+            history[0].message.as_ref().unwrap().action(),
+            VprCommitAction::Superseded
+        );
+    }
+
+    #[test]
+    fn test_amend_letter_rejects_superseded_version() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let cfg = test_cfg(temp_dir.path());
+        let author = redaction_test_author();
+
+        let service = ClinicalService::new(cfg)
+            .initialise(author.clone(), NonEmptyText::new("Test Hospital").unwrap())
+            .expect("initialise should succeed");
+
+        let original = service
+            .new_letter(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                NonEmptyText::new("Original wording").unwrap(),
+                None,
+            )
+            .unwrap();
+        service
+            .amend_letter(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                &original.to_string(),
+                Some(NonEmptyText::new("Corrected wording").unwrap()),
+                &[],
+                None,
+            )
+            .unwrap();
+
+        let result = service.amend_letter(
+            &author,
+            NonEmptyText::new("Test Hospital").unwrap(),
+            &original.to_string(),
+            Some(NonEmptyText::new("Competing correction").unwrap()),
+            &[],
+            None,
+        );
+        assert!(matches!(result, Err(PatientError::InvalidInput(_))));
+
+        let missing = service.amend_letter(
+            &author,
+            NonEmptyText::new("Test Hospital").unwrap(),
+            "20260125T120000.000Z-550e8400-e29b-41d4-a716-446655440000",
+            Some(NonEmptyText::new("Correction").unwrap()),
+            &[],
+            None,
+        );
+        assert!(matches!(missing, Err(PatientError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_list_letters_pages_in_timestamp_order() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let cfg = test_cfg(temp_dir.path());
+        let author = redaction_test_author();
+
+        let service = ClinicalService::new(cfg)
+            .initialise(author.clone(), NonEmptyText::new("Test Hospital").unwrap())
+            .expect("initialise should succeed");
+
+        let empty = service
+            .list_letters(&ListLettersQuery::default())
+            .expect("list_letters should succeed without letters");
+        assert!(empty.letters.is_empty());
+        assert!(empty.next_cursor.is_none());
+
+        let mut created = Vec::new();
+        for body in ["First", "Second", "Third"] {
+            created.push(
+                service
+                    .new_letter(
+                        &author,
+                        NonEmptyText::new("Test Hospital").unwrap(),
+                        NonEmptyText::new(body).unwrap(),
+                        None,
+                    )
+                    .expect("new_letter should succeed")
+                    .to_string(),
+            );
+        }
+        let amended = service
+            .amend_letter(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                &created[0],
+                Some(NonEmptyText::new("First, corrected").unwrap()),
+                &[],
+                None,
+            )
+            .expect("amend_letter should succeed")
+            .to_string();
+        created.push(amended.clone());
+
+        let all = service
+            .list_letters(&ListLettersQuery::default())
+            .expect("list_letters should succeed");
+        assert_eq!(all.letters.len(), 4);
+        assert!(all.next_cursor.is_none());
+        assert!(all.letters.windows(2).all(
+            |pair| timestamp_id_order_key(&pair[0].uid) < timestamp_id_order_key(&pair[1].uid)
+        ));
+
+        let original = all
+            .letters
+            .iter()
+            .find(|letter| letter.uid.to_string() == created[0])
+            .expect("original letter should be listed");
+        assert_eq!(
+            original.superseded_by.as_ref().map(|id| id.to_string()),
+            Some(amended)
+        );
+        assert!(original.has_body);
+        assert_eq!(original.attachment_count, 0);
+        assert_eq!(original.composer_name, "Dr. Test");
+
+        // Paging through two at a time yields the same letters in the same order
+        let mut paged = Vec::new();
+        let mut query = ListLettersQuery {
+            limit: Some(2),
+            ..Default::default()
+        };
+        loop {
+            let page = service.list_letters(&query).expect("page should load");
+            assert!(page.letters.len() <= 2);
+            paged.extend(page.letters);
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(paged, all.letters);
+    }
+
+    #[test]
+    fn test_list_letters_filters_by_date_range() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let cfg = test_cfg(temp_dir.path());
+        let author = redaction_test_author();
+
+        let service = ClinicalService::new(cfg)
+            .initialise(author.clone(), NonEmptyText::new("Test Hospital").unwrap())
+            .expect("initialise should succeed");
+        let letter = service
+            .new_letter(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                NonEmptyText::new("Dated letter").unwrap(),
+                None,
+            )
+            .expect("new_letter should succeed");
+        let created_at = letter.timestamp();
+
+        let within = service
+            .list_letters(&ListLettersQuery {
+                from: Some(created_at - chrono::Duration::days(1)),
+                to: Some(created_at + chrono::Duration::days(1)),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(within.letters.len(), 1);
+
+        let before = service
+            .list_letters(&ListLettersQuery {
+                to: Some(created_at - chrono::Duration::days(1)),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(before.letters.is_empty());
+
+        let reversed = service.list_letters(&ListLettersQuery {
+            from: Some(created_at),
+            to: Some(created_at),
+            ..Default::default()
+        });
+        assert!(matches!(reversed, Err(PatientError::InvalidInput(_))));
+
+        let zero_limit = service.list_letters(&ListLettersQuery {
+            limit: Some(0),
+            ..Default::default()
+        });
+        assert!(matches!(zero_limit, Err(PatientError::InvalidInput(_))));
+    }
 }
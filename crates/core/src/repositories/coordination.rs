@@ -49,13 +49,19 @@ use crate::constants::{
     COORDINATION_DIR_NAME, DEFAULT_GITIGNORE, THREAD_FILENAME, THREAD_LEDGER_FILENAME,
 };
 use crate::error::{PatientError, PatientResult};
+use crate::lock::{parse_commit_id, RepositoryLock};
 use crate::markdown::{MarkdownService, Message, MessageMetadata};
 use crate::paths::common::GitIgnoreFile;
 use crate::paths::coordination::coordination_status::CoordinationStatusFile;
-use crate::repositories::shared::create_uuid_and_shard_dir;
+use crate::repositories::redaction::{
+    RedactedItem, RedactionReason, RedactionRequest, RedactionService, RetainedFile,
+};
+use crate::repositories::revocation::RevocationService;
+use crate::repositories::shared::{create_uuid_and_shard_dir, page_limit, timestamp_id_order_key};
 use crate::versioned_files::{
     CoordinationDomain::{Messaging, Record},
-    FileToWrite, VersionedFileService, VprCommitAction, VprCommitDomain, VprCommitMessage,
+    FileToWrite, SignatureChainReport, VersionedFileService, VprCommitAction, VprCommitDomain,
+    VprCommitHistoryEntry, VprCommitMessage, VprRepositoryKind,
 };
 use crate::NonEmptyText;
 use crate::ShardableUuid;
@@ -64,6 +70,7 @@ use fhir::{
     CoordinationStatus, CoordinationStatusData, LedgerData, LifecycleState, MessageAuthor,
     Messaging as FhirMessaging, SensitivityLevel, ThreadStatus as FhirThreadStatus,
 };
+use git2::Oid;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -88,6 +95,7 @@ pub struct Uninitialised;
 #[derive(Clone, Debug)]
 pub struct Initialised {
     coordination_id: ShardableUuid,
+    expected_commit: Option<Oid>,
 }
 
 // ============================================================================
@@ -189,12 +197,15 @@ impl CoordinationService<Uninitialised> {
             &commit_author,
             &commit_message,
             &files,
+            self.cfg.timestamp_authority(),
+            self.cfg.mirror(),
         )?;
 
         Ok(CoordinationService {
             cfg: self.cfg,
             state: Initialised {
                 coordination_id: coordination_uuid,
+                expected_commit: None,
             },
         })
     }
@@ -215,6 +226,7 @@ impl CoordinationService<Initialised> {
             cfg,
             state: Initialised {
                 coordination_id: ShardableUuid::from_uuid(coordination_id),
+                expected_commit: None,
             },
         }
     }
@@ -223,6 +235,21 @@ impl CoordinationService<Initialised> {
     pub fn coordination_id(&self) -> &ShardableUuid {
         &self.state.coordination_id
     }
+
+    /// Makes writes through this service conditional on `main` being at `commit_id`.
+    ///
+    /// Pass the commit id returned by [`read_communication`](Self::read_communication) so
+    /// that a write fails with [`PatientError::ConcurrentModification`] rather than
+    /// overwriting changes made since. `None` makes writes unconditional. Every write moves
+    /// `main`, so a service with an expected commit accepts at most one write.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::InvalidInput` if `commit_id` is not a full hex commit id.
+    pub fn with_expected_commit(mut self, commit_id: Option<&str>) -> PatientResult<Self> {
+        self.state.expected_commit = commit_id.map(parse_commit_id).transpose()?;
+        Ok(self)
+    }
 }
 
 // ============================================================================
@@ -283,7 +310,6 @@ impl CoordinationService<Initialised> {
         validate_communication_authors(&communication_authors)?;
 
         let communication_id = TimestampIdGenerator::generate(None)?;
-        let coordination_dir = self.coordination_dir(self.coordination_id());
 
         let now = Utc::now();
         let message_id = generate_message_id();
@@ -343,11 +369,14 @@ impl CoordinationService<Initialised> {
             },
         ];
 
+        let lock = self.write_lock()?;
         VersionedFileService::write_and_commit_files(
-            &coordination_dir,
+            &lock,
             commit_author,
             &commit_message,
             &files_to_write,
+            self.cfg.timestamp_authority(),
+            self.cfg.mirror(),
         )?;
 
         Ok(ledger.communication_id)
@@ -383,6 +412,7 @@ impl CoordinationService<Initialised> {
         thread_id: &TimestampId,
         new_message: MessageContent,
     ) -> PatientResult<Uuid> {
+        let lock = self.write_lock()?;
         self.file_exists(&["communications", &thread_id.to_string(), THREAD_FILENAME])?;
         self.file_exists(&[
             "communications",
@@ -436,7 +466,6 @@ impl CoordinationService<Initialised> {
         let new_ledger_raw = FhirMessaging::ledger_render(&new_ledger)?;
 
         // Write and commit
-        let coordination_dir = self.coordination_dir(self.coordination_id());
         let messages_relative =
             relative_path(&["communications", &thread_id.to_string(), THREAD_FILENAME]);
         let ledger_relative = relative_path(&[
@@ -459,10 +488,12 @@ impl CoordinationService<Initialised> {
         ];
 
         VersionedFileService::write_and_commit_files(
-            &coordination_dir,
+            &lock,
             commit_author,
             &commit_message,
             &files_to_write,
+            self.cfg.timestamp_authority(),
+            self.cfg.mirror(),
         )?;
 
         Ok(message_id)
@@ -489,6 +520,9 @@ impl CoordinationService<Initialised> {
     /// - File read operations fail
     /// - YAML or markdown parsing fails
     pub fn read_communication(&self, thread_id: &TimestampId) -> PatientResult<Communication> {
+        // Read `main` first: if a write lands while reading, the id is stale rather than new.
+        let commit_id = VersionedFileService::open(&self.coordination_dir(self.coordination_id()))?
+            .main_commit_id()?;
         let messages_raw = self.thread_file_read(thread_id, THREAD_FILENAME)?;
         let ledger_raw = self.thread_file_read(thread_id, THREAD_LEDGER_FILENAME)?;
 
@@ -500,6 +534,85 @@ impl CoordinationService<Initialised> {
             communication_id: thread_id.clone(),
             ledger,
             messages,
+            commit_id,
+        })
+    }
+
+    /// Lists the communications in this coordination record.
+    ///
+    /// Walks `communications/` and returns the ledger of each communication that
+    /// matches the query, ordered by communication ID. Directories without a
+    /// ledger.yaml are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Cursor, page size and filters
+    ///
+    /// # Returns
+    ///
+    /// A page of ledgers and, when more communications match, the cursor for the next page.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if:
+    /// - The limit is zero or too large
+    /// - The communications directory cannot be read
+    /// - A ledger.yaml cannot be read or parsed
+    pub fn list_communications(
+        &self,
+        query: &ListCommunicationsQuery,
+    ) -> PatientResult<CommunicationPage> {
+        let limit = page_limit(query.limit)?;
+        let communications_dir = self
+            .coordination_dir(self.coordination_id())
+            .join("communications");
+
+        let mut thread_ids = Vec::new();
+        if communications_dir.exists() {
+            for entry in fs::read_dir(&communications_dir).map_err(PatientError::FileRead)? {
+                let entry = entry.map_err(PatientError::FileRead)?;
+                let Some(thread_id) = entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|name| name.parse::<TimestampId>().ok())
+                else {
+                    continue;
+                };
+                if let Some(cursor) = &query.cursor {
+                    if timestamp_id_order_key(&thread_id) <= timestamp_id_order_key(cursor) {
+                        continue;
+                    }
+                }
+                if !entry.path().join(THREAD_LEDGER_FILENAME).exists() {
+                    continue;
+                }
+                thread_ids.push(thread_id);
+            }
+        }
+        thread_ids.sort_by_key(timestamp_id_order_key);
+
+        // Filters need the ledger, so ledgers are read in order until the page is full and
+        // one further match shows that another page exists.
+        let mut communications = Vec::new();
+        let mut last_thread_id = None;
+        let mut next_cursor = None;
+        for thread_id in thread_ids {
+            let ledger_raw = self.thread_file_read(&thread_id, THREAD_LEDGER_FILENAME)?;
+            let ledger = FhirMessaging::ledger_parse(ledger_raw.as_str())?;
+            if !query.matches(&ledger) {
+                continue;
+            }
+            if communications.len() == limit {
+                next_cursor = last_thread_id;
+                break;
+            }
+            communications.push(ledger);
+            last_thread_id = Some(thread_id);
+        }
+
+        Ok(CommunicationPage {
+            communications,
+            next_cursor,
         })
     }
 
@@ -539,6 +652,7 @@ impl CoordinationService<Initialised> {
             care_location,
         )?;
 
+        let lock = self.write_lock()?;
         self.file_exists(&[
             "communications",
             &thread_id.to_string(),
@@ -574,7 +688,6 @@ impl CoordinationService<Initialised> {
 
         let new_ledger_raw = FhirMessaging::ledger_render(&ledger_data)?;
 
-        let coordination_dir = self.coordination_dir(self.coordination_id());
         let ledger_relative = relative_path(&[
             "communications",
             &thread_id.to_string(),
@@ -588,10 +701,12 @@ impl CoordinationService<Initialised> {
         }];
 
         VersionedFileService::write_and_commit_files(
-            &coordination_dir,
+            &lock,
             commit_author,
             &msg,
             &files_to_write,
+            self.cfg.timestamp_authority(),
+            self.cfg.mirror(),
         )?;
 
         Ok(())
@@ -630,6 +745,7 @@ impl CoordinationService<Initialised> {
             care_location,
         )?;
 
+        let lock = self.write_lock()?;
         self.file_exists(&[CoordinationStatusFile::NAME])?;
 
         // Read existing status
@@ -652,7 +768,6 @@ impl CoordinationService<Initialised> {
 
         let new_status_raw = CoordinationStatus::render(&status_data)?;
 
-        let coordination_dir = self.coordination_dir(self.coordination_id());
         let files_to_write = [FileToWrite {
             relative_path: Path::new(CoordinationStatusFile::NAME),
             content: &new_status_raw,
@@ -660,14 +775,187 @@ impl CoordinationService<Initialised> {
         }];
 
         VersionedFileService::write_and_commit_files(
-            &coordination_dir,
+            &lock,
             commit_author,
             &commit_message,
             &files_to_write,
+            self.cfg.timestamp_authority(),
+            self.cfg.mirror(),
         )?;
 
         Ok(())
     }
+
+    /// Redacts a single message from a thread, moving it into the Redaction Retention
+    /// Repository.
+    ///
+    /// thread.md is rewritten without the message and the ledger's `last_updated_at` is
+    /// refreshed. The RRR retains the message as a single-message thread.md at the
+    /// thread's original path. Messages that correct the redacted message are left in
+    /// place.
+    ///
+    /// # Arguments
+    ///
+    /// * `commit_author` - Author performing the redaction (validated for commit permissions)
+    /// * `care_location` - Care location context for the Git commits
+    /// * `thread_id` - ID of the thread containing the message
+    /// * `message_id` - ID of the message to redact
+    /// * `reason` - Why the message is being removed from routine view
+    /// * `reason_detail` - Optional free-text detail, recorded in the RRR only
+    ///
+    /// # Returns
+    ///
+    /// The redaction ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if:
+    /// - Thread or message does not exist
+    /// - The message is the only message in the thread
+    /// - File read, write, or Git commit operations fail in either repository
+    /// - YAML serialisation or parsing fails
+    pub fn redact_message(
+        &self,
+        commit_author: &Author,
+        care_location: NonEmptyText,
+        thread_id: &TimestampId,
+        message_id: Uuid,
+        reason: RedactionReason,
+        reason_detail: Option<NonEmptyText>,
+    ) -> PatientResult<TimestampId> {
+        let lock = self.write_lock()?;
+        self.file_exists(&["communications", &thread_id.to_string(), THREAD_FILENAME])?;
+        self.file_exists(&[
+            "communications",
+            &thread_id.to_string(),
+            THREAD_LEDGER_FILENAME,
+        ])?;
+
+        // Split the thread into the redacted message and the remainder
+        let old_thread_raw = self.thread_file_read(thread_id, THREAD_FILENAME)?;
+        let markdown_service = MarkdownService::new();
+        let (redacted, remaining): (Vec<Message>, Vec<Message>) = markdown_service
+            .thread_parse(old_thread_raw.as_str())?
+            .into_iter()
+            .partition(|message| message.metadata.message_id == message_id);
+
+        if redacted.is_empty() {
+            return Err(PatientError::InvalidInput(format!(
+                "Message {} not found in thread {}",
+                message_id, thread_id
+            )));
+        }
+        if remaining.is_empty() {
+            return Err(PatientError::InvalidInput(
+                "Cannot redact the only message in a thread".to_string(),
+            ));
+        }
+
+        let retained_thread_raw = markdown_service.thread_render(&redacted)?;
+        let new_thread_raw = markdown_service.thread_render(&remaining)?;
+
+        // Update ledger last_updated_at
+        let old_ledger_raw = self.thread_file_read(thread_id, THREAD_LEDGER_FILENAME)?;
+        let mut new_ledger = FhirMessaging::ledger_parse(old_ledger_raw.as_str())?;
+        new_ledger.last_updated_at = Utc::now();
+        let new_ledger_raw = FhirMessaging::ledger_render(&new_ledger)?;
+
+        let messages_relative =
+            relative_path(&["communications", &thread_id.to_string(), THREAD_FILENAME]);
+        let ledger_relative = relative_path(&[
+            "communications",
+            &thread_id.to_string(),
+            THREAD_LEDGER_FILENAME,
+        ]);
+
+        let files_to_write = [
+            FileToWrite {
+                relative_path: &messages_relative,
+                content: new_thread_raw.as_str(),
+                old_content: Some(old_thread_raw.as_str()),
+            },
+            FileToWrite {
+                relative_path: &ledger_relative,
+                content: &new_ledger_raw,
+                old_content: Some(old_ledger_raw.as_str()),
+            },
+        ];
+
+        RedactionService::with_id(self.cfg.clone(), self.coordination_id().uuid()).redact(
+            commit_author,
+            care_location,
+            VprCommitDomain::Coordination(Messaging),
+            &lock,
+            RedactionRequest {
+                item: RedactedItem::Message {
+                    communication_id: thread_id.clone(),
+                    message_id,
+                },
+                reason,
+                reason_detail,
+                retained: vec![RetainedFile {
+                    path: messages_relative.clone(),
+                    content: retained_thread_raw.to_string(),
+                }],
+                attachment_hashes: vec![],
+                source_files: None,
+                writes: &files_to_write,
+                removals: &[],
+            },
+        )
+    }
+
+    /// Returns the commit history of this coordination record, newest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Optional path relative to the record root (e.g. `COORDINATION_STATUS.yaml`
+    ///   or `communications/<id>`). When given, only commits touching that path are returned.
+    ///   A path that only held redacted content returns no commits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if the repository cannot be opened or walked.
+    pub fn commit_history(&self, path: Option<&Path>) -> PatientResult<Vec<VprCommitHistoryEntry>> {
+        if let Some(path) = path {
+            if RedactionService::with_id(self.cfg.clone(), self.coordination_id().uuid())
+                .is_redacted_path(&self.coordination_dir(self.coordination_id()), path)?
+            {
+                return Ok(Vec::new());
+            }
+        }
+
+        VersionedFileService::commit_history(
+            &self.coordination_root_dir(),
+            &self.coordination_id().to_string(),
+            VprRepositoryKind::Coordination,
+            path,
+            self.cfg.trust_anchors(),
+            Some(&RevocationService::new(self.cfg.clone()).revocation_list()?),
+            self.cfg.timestamp_trust_anchors(),
+        )
+    }
+
+    /// Verifies the embedded signature of every commit in this coordination record.
+    ///
+    /// # Returns
+    ///
+    /// A [`SignatureChainReport`] listing each commit (newest first) as valid, unsigned,
+    /// invalid, or certificate/key mismatch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if the repository cannot be opened or walked. See
+    /// [`VersionedFileService::verify_signature_chain`].
+    pub fn verify_signature_chain(&self) -> PatientResult<SignatureChainReport> {
+        VersionedFileService::verify_signature_chain(
+            &self.coordination_root_dir(),
+            &self.coordination_id().to_string(),
+            self.cfg.trust_anchors(),
+            Some(&RevocationService::new(self.cfg.clone()).revocation_list()?),
+            self.cfg.timestamp_trust_anchors(),
+        )
+    }
 }
 
 // ============================================================================
@@ -733,6 +1021,47 @@ pub struct Communication {
     pub communication_id: TimestampId,
     pub ledger: LedgerData,
     pub messages: Vec<Message>,
+    /// Commit `main` pointed to when the thread was read, for conditional writes (see
+    /// [`CoordinationService::with_expected_commit`]).
+    pub commit_id: String,
+}
+
+/// Filters and paging options for [`CoordinationService::list_communications`].
+///
+/// Communications are ordered by their timestamp ID. Filters are combined, so a
+/// communication must match every filter that is set.
+#[derive(Clone, Debug, Default)]
+pub struct ListCommunicationsQuery {
+    /// Return only communications after this ID (the `next_cursor` of the previous page).
+    pub cursor: Option<TimestampId>,
+    /// Maximum number of communications to return.
+    pub limit: Option<usize>,
+    /// Return only communications with this status.
+    pub status: Option<FhirThreadStatus>,
+    /// Return only communications that include this participant.
+    pub participant_id: Option<Uuid>,
+    /// Return only communications with this sensitivity level.
+    pub sensitivity: Option<SensitivityLevel>,
+}
+
+impl ListCommunicationsQuery {
+    /// Returns whether a communication's ledger matches this query's filters.
+    fn matches(&self, ledger: &LedgerData) -> bool {
+        self.status.as_ref().is_none_or(|s| *s == ledger.status)
+            && self
+                .participant_id
+                .is_none_or(|id| ledger.participants.iter().any(|p| p.id == id))
+            && self.sensitivity.is_none_or(|s| s == ledger.sensitivity)
+    }
+}
+
+/// One page of communication ledgers.
+#[derive(Clone, Debug)]
+pub struct CommunicationPage {
+    /// Ledgers of the communications in this page, oldest first
+    pub communications: Vec<LedgerData>,
+    /// Cursor for the next page, or `None` when this is the last page
+    pub next_cursor: Option<TimestampId>,
 }
 
 /// Update to apply to a thread ledger.
@@ -781,6 +1110,14 @@ impl<S> CoordinationService<S> {
 }
 
 impl CoordinationService<Initialised> {
+    /// Takes the write lock on this coordination record, checking `main` against the
+    /// expected commit.
+    fn write_lock(&self) -> PatientResult<RepositoryLock> {
+        let lock = RepositoryLock::acquire(&self.coordination_dir(self.coordination_id()))?;
+        lock.expect_head(self.state.expected_commit)?;
+        Ok(lock)
+    }
+
     /// Returns the path to a specific thread directory.
     ///
     /// Constructs the absolute path by combining the coordination directory with
@@ -1186,6 +1523,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_concurrent_message_adds_are_all_kept() {
+        let (_temp, cfg, author) = setup_test_env();
+
+        let service = CoordinationService::new(cfg.clone())
+            .initialise(
+                author.clone(),
+                NonEmptyText::new("Test Location").unwrap(),
+                Uuid::new_v4(),
+            )
+            .unwrap();
+
+        let participants = create_test_participants();
+        let thread_id = service
+            .communication_create(
+                &author,
+                NonEmptyText::new("Test Location").unwrap(),
+                participants.clone(),
+                MessageContent::new(
+                    participants[0].clone(),
+                    NonEmptyText::new("First message").unwrap(),
+                    None,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+        // Without the repository lock, writers racing on thread.md would drop messages.
+        std::thread::scope(|scope| {
+            for i in 0..4 {
+                let (service, author, thread_id) = (&service, &author, &thread_id);
+                let message = MessageContent::new(
+                    participants[1].clone(),
+                    NonEmptyText::new(format!("Reply {i}")).unwrap(),
+                    None,
+                )
+                .unwrap();
+                scope.spawn(move || {
+                    service
+                        .message_add(
+                            author,
+                            NonEmptyText::new("Test Location").unwrap(),
+                            thread_id,
+                            message,
+                        )
+                        .unwrap();
+                });
+            }
+        });
+
+        let thread = service.read_communication(&thread_id).unwrap();
+        assert_eq!(thread.messages.len(), 5);
+        let history = service.commit_history(None).unwrap();
+        assert_eq!(history.len(), 6);
+    }
+
     #[test]
     fn test_message_add_with_correction() {
         let (_temp, cfg, author) = setup_test_env();
@@ -1488,6 +1881,91 @@ mod tests {
         assert_eq!(thread.ledger.status, FhirThreadStatus::Closed);
     }
 
+    #[test]
+    fn test_update_communication_ledger_with_stale_expected_commit_is_rejected() {
+        let (_temp, cfg, author) = setup_test_env();
+        let clinical_id = Uuid::new_v4();
+
+        let service = CoordinationService::new(cfg.clone())
+            .initialise(
+                author.clone(),
+                NonEmptyText::new("Test Location").unwrap(),
+                clinical_id,
+            )
+            .unwrap();
+
+        let participants = create_test_participants();
+        let initial_message = MessageContent::new(
+            participants[0].clone(),
+            NonEmptyText::new("Test").unwrap(),
+            None,
+        )
+        .unwrap();
+
+        let thread_id = service
+            .communication_create(
+                &author,
+                NonEmptyText::new("Test Location").unwrap(),
+                participants.clone(),
+                initial_message,
+            )
+            .unwrap();
+        let read = service.read_communication(&thread_id).unwrap();
+
+        // Another client adds a message after the ledger was read.
+        service
+            .message_add(
+                &author,
+                NonEmptyText::new("Test Location").unwrap(),
+                &thread_id,
+                MessageContent::new(
+                    participants[1].clone(),
+                    NonEmptyText::new("Reply").unwrap(),
+                    None,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+        let close = |commit_id: &str| {
+            CoordinationService::with_id(cfg.clone(), service.coordination_id().uuid())
+                .with_expected_commit(Some(commit_id))
+                .unwrap()
+                .update_communication_ledger(
+                    &author,
+                    NonEmptyText::new("Test Location").unwrap(),
+                    &thread_id,
+                    LedgerUpdate {
+                        set_status: Some(FhirThreadStatus::Closed),
+                        ..Default::default()
+                    },
+                )
+        };
+        assert!(matches!(
+            close(&read.commit_id),
+            Err(PatientError::ConcurrentModification { .. })
+        ));
+        assert_eq!(
+            service
+                .read_communication(&thread_id)
+                .unwrap()
+                .ledger
+                .status,
+            FhirThreadStatus::Open
+        );
+
+        let reread = service.read_communication(&thread_id).unwrap();
+        close(&reread.commit_id).unwrap();
+        assert_eq!(
+            service
+                .read_communication(&thread_id)
+                .unwrap()
+                .ledger
+                .status,
+            FhirThreadStatus::Closed
+        );
+    }
+
     #[test]
     fn test_update_communication_ledger_change_visibility() {
         let (_temp, cfg, author) = setup_test_env();
@@ -1656,4 +2134,237 @@ Corrected message body
         let id2 = generate_message_id();
         assert_ne!(id1, id2);
     }
+
+    #[test]
+    fn test_redact_message_moves_message_to_rrr() {
+        let (_temp, cfg, author) = setup_test_env();
+
+        let service = CoordinationService::new(cfg.clone())
+            .initialise(
+                author.clone(),
+                NonEmptyText::new("Test Location").unwrap(),
+                Uuid::new_v4(),
+            )
+            .unwrap();
+
+        let participants = create_test_participants();
+        let thread_id = service
+            .communication_create(
+                &author,
+                NonEmptyText::new("Test Location").unwrap(),
+                participants.clone(),
+                MessageContent::new(
+                    participants[0].clone(),
+                    NonEmptyText::new("First message").unwrap(),
+                    None,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+        // Redacting the only message would leave an empty thread
+        let only_id = service.read_communication(&thread_id).unwrap().messages[0]
+            .metadata
+            .message_id;
+        let result = service.redact_message(
+            &author,
+            NonEmptyText::new("Test Location").unwrap(),
+            &thread_id,
+            only_id,
+            RedactionReason::WrongPatient,
+            None,
+        );
+        assert!(matches!(result, Err(PatientError::InvalidInput(_))));
+
+        let message_id = service
+            .message_add(
+                &author,
+                NonEmptyText::new("Test Location").unwrap(),
+                &thread_id,
+                MessageContent::new(
+                    participants[1].clone(),
+                    NonEmptyText::new("Meant for another patient").unwrap(),
+                    None,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+        let redaction_id = service
+            .redact_message(
+                &author,
+                NonEmptyText::new("Test Location").unwrap(),
+                &thread_id,
+                message_id,
+                RedactionReason::WrongPatient,
+                None,
+            )
+            .unwrap();
+
+        let thread = service.read_communication(&thread_id).unwrap();
+        assert_eq!(thread.messages.len(), 1);
+        assert_eq!(thread.messages[0].body.as_str(), "First message");
+
+        let retained = RedactionService::with_id(cfg, service.coordination_id().uuid())
+            .read_redaction(&redaction_id)
+            .unwrap();
+        let retained_messages = MarkdownService::new()
+            .thread_parse(&retained.files[0].content)
+            .unwrap();
+        assert_eq!(retained_messages.len(), 1);
+        assert_eq!(retained_messages[0].metadata.message_id, message_id);
+        assert_eq!(
+            retained_messages[0].body.as_str(),
+            "Meant for another patient"
+        );
+
+        let unknown = service.redact_message(
+            &author,
+            NonEmptyText::new("Test Location").unwrap(),
+            &thread_id,
+            message_id,
+            RedactionReason::WrongPatient,
+            None,
+        );
+        assert!(matches!(unknown, Err(PatientError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_list_communications_filters_and_pages() {
+        let (_temp, cfg, author) = setup_test_env();
+        let service = CoordinationService::new(cfg)
+            .initialise(
+                author.clone(),
+                NonEmptyText::new("Test Location").unwrap(),
+                Uuid::new_v4(),
+            )
+            .unwrap();
+
+        let empty = service
+            .list_communications(&ListCommunicationsQuery::default())
+            .unwrap();
+        assert!(empty.communications.is_empty());
+        assert!(empty.next_cursor.is_none());
+
+        let shared_participant = create_test_participants().remove(0);
+        let mut thread_ids = Vec::new();
+        for _ in 0..3 {
+            let mut participants = create_test_participants();
+            participants[0] = shared_participant.clone();
+            let initial_message = MessageContent::new(
+                participants[1].clone(),
+                NonEmptyText::new("Hello").unwrap(),
+                None,
+            )
+            .unwrap();
+            thread_ids.push(
+                service
+                    .communication_create(
+                        &author,
+                        NonEmptyText::new("Test Location").unwrap(),
+                        participants,
+                        initial_message,
+                    )
+                    .unwrap(),
+            );
+        }
+
+        service
+            .update_communication_ledger(
+                &author,
+                NonEmptyText::new("Test Location").unwrap(),
+                &thread_ids[0],
+                LedgerUpdate {
+                    set_status: Some(FhirThreadStatus::Closed),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        service
+            .update_communication_ledger(
+                &author,
+                NonEmptyText::new("Test Location").unwrap(),
+                &thread_ids[1],
+                LedgerUpdate {
+                    set_visibility: Some((SensitivityLevel::Confidential, false)),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let all = service
+            .list_communications(&ListCommunicationsQuery::default())
+            .unwrap();
+        assert_eq!(all.communications.len(), 3);
+
+        let open = service
+            .list_communications(&ListCommunicationsQuery {
+                status: Some(FhirThreadStatus::Open),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(open.communications.len(), 2);
+        assert!(open
+            .communications
+            .iter()
+            .all(|ledger| ledger.status == FhirThreadStatus::Open));
+
+        let confidential = service
+            .list_communications(&ListCommunicationsQuery {
+                sensitivity: Some(SensitivityLevel::Confidential),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(confidential.communications.len(), 1);
+        assert_eq!(
+            confidential.communications[0].communication_id.to_string(),
+            thread_ids[1].to_string()
+        );
+
+        let shared = service
+            .list_communications(&ListCommunicationsQuery {
+                participant_id: Some(shared_participant.id),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(shared.communications.len(), 3);
+        let stranger = service
+            .list_communications(&ListCommunicationsQuery {
+                participant_id: Some(Uuid::new_v4()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(stranger.communications.is_empty());
+
+        // Paging one at a time visits every communication once, in order
+        let mut paged = Vec::new();
+        let mut query = ListCommunicationsQuery {
+            limit: Some(1),
+            ..Default::default()
+        };
+        loop {
+            let page = service.list_communications(&query).unwrap();
+            paged.extend(
+                page.communications
+                    .iter()
+                    .map(|ledger| ledger.communication_id.to_string()),
+            );
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        let expected: Vec<String> = all
+            .communications
+            .iter()
+            .map(|ledger| ledger.communication_id.to_string())
+            .collect();
+        assert_eq!(paged, expected);
+
+        let zero_limit = service.list_communications(&ListCommunicationsQuery {
+            limit: Some(0),
+            ..Default::default()
+        });
+        assert!(matches!(zero_limit, Err(PatientError::InvalidInput(_))));
+    }
 }
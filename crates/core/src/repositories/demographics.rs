@@ -7,6 +7,7 @@
 //! - Storage in a sharded directory structure under `patient_data/demographics/`
 //! - Version control using Git with signed commits
 //! - Updates to patient name and birth date information
+//! - Updates to patient identifiers, gender, contact points and addresses
 //!
 //! ## Storage Layout
 //!
@@ -34,19 +35,26 @@ use crate::author::Author;
 use crate::config::CoreConfig;
 use crate::constants::{DEFAULT_GITIGNORE, DEMOGRAPHICS_DIR_NAME};
 use crate::error::{PatientError, PatientResult};
+use crate::lock::{parse_commit_id, RepositoryLock};
 use crate::paths::common::GitIgnoreFile;
 use crate::paths::demographics::patient::PatientFile;
+use crate::repositories::revocation::RevocationService;
 use crate::versioned_files::{
-    DemographicsDomain::Record, FileToWrite, VersionedFileService, VprCommitAction,
-    VprCommitDomain, VprCommitMessage,
+    DemographicsDomain::Record, FileToWrite, SignatureChainReport, VersionedFileService,
+    VprCommitAction, VprCommitDomain, VprCommitHistoryEntry, VprCommitMessage, VprRepositoryKind,
 };
 use crate::NonEmptyText;
 use crate::ShardableUuid;
 use api_shared::pb;
 use chrono::Utc;
-use fhir::{NameUse, Patient, PatientData};
+use fhir::{
+    Address, AddressType, AddressUse, AdministrativeGender, ContactPoint, ContactPointSystem,
+    ContactPointUse, Deceased, FhirDateTime, FhirError, HumanName, Identifier, IdentifierUse,
+    NameUse, Patient, PatientContact, PatientData, Period, Reference,
+};
+use git2::Oid;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 // ============================================================================
@@ -67,6 +75,31 @@ pub struct Uninitialised;
 #[derive(Clone, Debug)]
 pub struct Initialised {
     demographics_id: ShardableUuid,
+    expected_commit: Option<Oid>,
+}
+
+/// Identification and contact details of a patient, replaced as a whole by
+/// [`DemographicsService::update_details`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PatientDetails {
+    /// Identifiers such as the NHS number and local hospital numbers.
+    pub identifiers: Vec<Identifier>,
+    /// Administrative gender.
+    pub gender: Option<AdministrativeGender>,
+    /// Phone numbers, email addresses and other contact points.
+    pub telecom: Vec<ContactPoint>,
+    /// Postal and physical addresses.
+    pub addresses: Vec<Address>,
+}
+
+/// Result of reading a demographics record.
+#[derive(Clone, Debug)]
+pub struct ReadDemographicsResult {
+    /// The patient resource from `patient.yaml`.
+    pub patient: PatientData,
+    /// Commit `main` pointed to when the record was read, for conditional writes (see
+    /// [`DemographicsService::with_expected_commit`]).
+    pub commit_id: String,
 }
 
 // ============================================================================
@@ -156,14 +189,8 @@ impl DemographicsService<Uninitialised> {
         let patient_dir = demographics_uuid.sharded_dir(&demographics_dir);
         let created_at = Utc::now();
 
-        let patient_data = PatientData {
-            id: demographics_uuid.clone(),
-            use_type: None,
-            family: None,
-            given: vec![],
-            birth_date: None,
-            last_updated: Some(created_at),
-        };
+        let mut patient_data = PatientData::new(demographics_uuid.clone());
+        patient_data.last_updated = Some(created_at);
 
         let patient_data_raw = Patient::render(&patient_data)?;
 
@@ -180,12 +207,20 @@ impl DemographicsService<Uninitialised> {
             },
         ];
 
-        VersionedFileService::init_and_commit(&patient_dir, &author, &commit_message, &files)?;
+        VersionedFileService::init_and_commit(
+            &patient_dir,
+            &author,
+            &commit_message,
+            &files,
+            self.cfg.timestamp_authority(),
+            self.cfg.mirror(),
+        )?;
 
         Ok(DemographicsService {
             cfg: self.cfg,
             state: Initialised {
                 demographics_id: demographics_uuid,
+                expected_commit: None,
             },
         })
     }
@@ -207,6 +242,7 @@ impl DemographicsService<Initialised> {
             cfg,
             state: Initialised {
                 demographics_id: demographics_uuid,
+                expected_commit: None,
             },
         })
     }
@@ -215,17 +251,59 @@ impl DemographicsService<Initialised> {
     pub fn demographics_id(&self) -> &ShardableUuid {
         &self.state.demographics_id
     }
+
+    /// Makes writes through this service conditional on `main` being at `commit_id`.
+    ///
+    /// Pass the commit id returned by [`read`](Self::read) so that a write fails with
+    /// [`PatientError::ConcurrentModification`] rather than overwriting changes made since.
+    /// `None` makes writes unconditional. Every write moves `main`, so a service with an
+    /// expected commit accepts at most one write.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::InvalidInput` if `commit_id` is not a full hex commit id.
+    pub fn with_expected_commit(mut self, commit_id: Option<&str>) -> PatientResult<Self> {
+        self.state.expected_commit = commit_id.map(parse_commit_id).transpose()?;
+        Ok(self)
+    }
 }
 
 impl DemographicsService<Initialised> {
+    /// Reads the patient resource of this demographics record.
+    ///
+    /// # Returns
+    ///
+    /// The parsed `patient.yaml` and the commit `main` pointed to when it was read.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if the repository cannot be opened, or if `patient.yaml`
+    /// cannot be read or parsed.
+    pub fn read(&self) -> PatientResult<ReadDemographicsResult> {
+        let patient_dir = self.patient_dir();
+
+        // Read `main` first: if a write lands while reading, the id is stale rather than new.
+        let commit_id = VersionedFileService::open(&patient_dir)?.main_commit_id()?;
+        let yaml = fs::read_to_string(patient_dir.join(PatientFile::NAME))
+            .map_err(PatientError::FileRead)?;
+
+        Ok(ReadDemographicsResult {
+            patient: Patient::parse(&yaml)?,
+            commit_id,
+        })
+    }
+
     /// Updates the demographics of an existing patient.
     ///
-    /// Reads the existing patient YAML file, updates the name and birth date fields,
-    /// and writes the changes back to the file. This operation does not create a new
-    /// Git commit—callers must commit changes separately if needed.
+    /// Reads the existing patient YAML file, replaces the current official name and the
+    /// birth date,
+    /// and writes the changes back as a single Git commit
+    /// (`record:update`, signed if the author provides a signing key).
     ///
     /// # Arguments
     ///
+    /// * `author` - Author information for the Git commit
+    /// * `care_location` - High-level organisational location for the commit (e.g., hospital name)
     /// * `given_names` - Vector of given names for the patient
     /// * `last_name` - Family/last name of the patient
     /// * `birth_date` - Birth date of the patient as a string
@@ -233,41 +311,175 @@ impl DemographicsService<Initialised> {
     /// # Errors
     ///
     /// Returns `PatientError` if:
-    /// - `patient.yaml` file cannot be read, deserialised, serialised, or written
+    /// - Author validation fails
+    /// - `last_name` is empty or `birth_date` is not a valid date ([`PatientError::InvalidInput`])
+    /// - `patient.yaml` file cannot be read, deserialised or serialised
+    /// - The file write or Git commit fails
+    ///
+    /// # Safety & Rollback
+    ///
+    /// If the write or commit fails, `patient.yaml` is restored to its previous contents.
     pub fn update(
         &self,
+        author: &Author,
+        care_location: NonEmptyText,
         given_names: Vec<NonEmptyText>,
         last_name: &str,
         birth_date: &str,
     ) -> PatientResult<()> {
-        let data_dir = self.cfg.patient_data_dir();
-        let demographics_dir = data_dir.join(DEMOGRAPHICS_DIR_NAME);
+        self.write_patient(author, care_location, |patient_data| {
+            // Update only the specified fields: the current official name is replaced, while
+            // other names (usual, maiden, closed official names) and all other elements are
+            // preserved.
+            let official_name = HumanName {
+                use_type: Some(NameUse::Official),
+                family: Some(
+                    NonEmptyText::new(last_name)
+                        .map_err(|e| PatientError::InvalidInput(e.to_string()))?,
+                ),
+                given: given_names,
+                ..Default::default()
+            };
+            patient_data.names.retain(|n| {
+                n.use_type != Some(NameUse::Official)
+                    || n.period.as_ref().is_some_and(|p| p.end.is_some())
+            });
+            patient_data.names.insert(0, official_name);
+            patient_data.birth_date =
+                Some(birth_date.parse().map_err(|e| {
+                    PatientError::InvalidInput(format!("Invalid birth date: {}", e))
+                })?);
+            Ok(())
+        })
+    }
+
+    /// Replaces the identifiers, gender, contact points and addresses of an existing patient.
+    ///
+    /// Each of the four elements is replaced as a whole, so an empty list (or `None` gender)
+    /// clears it; names, birth date and all other elements are preserved. The change is
+    /// written as a single Git commit (`record:update`), as for [`update`](Self::update).
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if:
+    /// - Author validation fails
+    /// - `patient.yaml` file cannot be read, deserialised or serialised
+    /// - The file write or Git commit fails
+    ///
+    /// # Safety & Rollback
+    ///
+    /// If the write or commit fails, `patient.yaml` is restored to its previous contents.
+    pub fn update_details(
+        &self,
+        author: &Author,
+        care_location: NonEmptyText,
+        details: PatientDetails,
+    ) -> PatientResult<()> {
+        self.write_patient(author, care_location, |patient_data| {
+            patient_data.identifiers = details.identifiers;
+            patient_data.gender = details.gender;
+            patient_data.telecom = details.telecom;
+            patient_data.addresses = details.addresses;
+            Ok(())
+        })
+    }
 
-        let patient_dir = self.demographics_id().sharded_dir(&demographics_dir);
+    /// Applies `change` to `patient.yaml` and commits the result under the write lock.
+    fn write_patient(
+        &self,
+        author: &Author,
+        care_location: NonEmptyText,
+        change: impl FnOnce(&mut PatientData) -> PatientResult<()>,
+    ) -> PatientResult<()> {
+        author.validate_commit_author()?;
+
+        let commit_message = VprCommitMessage::new(
+            VprCommitDomain::Demographics(Record),
+            VprCommitAction::Update,
+            "Demographics record updated",
+            care_location,
+        )?;
+
+        let patient_dir = self.patient_dir();
+        let lock = RepositoryLock::acquire(&patient_dir)?;
+        lock.expect_head(self.state.expected_commit)?;
         let filename = patient_dir.join(PatientFile::NAME);
 
         // Read existing patient.yaml
         let existing_yaml = fs::read_to_string(&filename).map_err(PatientError::FileRead)?;
         let mut patient_data = Patient::parse(&existing_yaml)?;
 
-        // Update only the specified fields
-        patient_data.use_type = Some(NameUse::Official);
-        patient_data.family = Some(
-            NonEmptyText::new(last_name).map_err(|e| PatientError::InvalidInput(e.to_string()))?,
-        );
-        patient_data.given = given_names;
-        patient_data.birth_date = Some(
-            birth_date
-                .parse()
-                .map_err(|e| PatientError::InvalidInput(format!("Invalid birth date: {}", e)))?,
-        );
+        change(&mut patient_data)?;
+        patient_data.last_updated = Some(Utc::now());
 
-        // Write back the updated YAML
         let yaml = Patient::render(&patient_data)?;
-        fs::write(&filename, yaml).map_err(PatientError::FileWrite)?;
+
+        let files = [FileToWrite {
+            relative_path: Path::new(PatientFile::NAME),
+            content: &yaml,
+            old_content: Some(&existing_yaml),
+        }];
+
+        VersionedFileService::write_and_commit_files(
+            &lock,
+            author,
+            &commit_message,
+            &files,
+            self.cfg.timestamp_authority(),
+            self.cfg.mirror(),
+        )?;
 
         Ok(())
     }
+
+    /// Returns the commit history of this demographics record, newest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Optional path relative to the record root (e.g. `patient.yaml`). When given,
+    ///   only commits that changed that path are returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if the repository cannot be opened or walked.
+    pub fn commit_history(&self, path: Option<&Path>) -> PatientResult<Vec<VprCommitHistoryEntry>> {
+        VersionedFileService::commit_history(
+            &self.cfg.demographics_dir(),
+            &self.demographics_id().to_string(),
+            VprRepositoryKind::Demographics,
+            path,
+            self.cfg.trust_anchors(),
+            Some(&RevocationService::new(self.cfg.clone()).revocation_list()?),
+            self.cfg.timestamp_trust_anchors(),
+        )
+    }
+
+    /// Verifies the embedded signature of every commit in this demographics record.
+    ///
+    /// # Returns
+    ///
+    /// A [`SignatureChainReport`] listing each commit (newest first) as valid, unsigned,
+    /// invalid, or certificate/key mismatch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if the repository cannot be opened or walked. See
+    /// [`VersionedFileService::verify_signature_chain`].
+    pub fn verify_signature_chain(&self) -> PatientResult<SignatureChainReport> {
+        VersionedFileService::verify_signature_chain(
+            &self.cfg.demographics_dir(),
+            &self.demographics_id().to_string(),
+            self.cfg.trust_anchors(),
+            Some(&RevocationService::new(self.cfg.clone()).revocation_list()?),
+            self.cfg.timestamp_trust_anchors(),
+        )
+    }
+
+    /// Returns the directory of this demographics record.
+    fn patient_dir(&self) -> PathBuf {
+        self.demographics_id()
+            .sharded_dir(&self.cfg.patient_data_dir().join(DEMOGRAPHICS_DIR_NAME))
+    }
 }
 
 // ============================================================================
@@ -338,31 +550,7 @@ impl<S> DemographicsService<S> {
                     if let Ok(contents) = fs::read_to_string(&patient_path) {
                         match Patient::parse(&contents) {
                             Ok(patient_data) => {
-                                let id = patient_data.id.to_string();
-
-                                // Extract name information from flat structure
-                                let first_name = patient_data
-                                    .given
-                                    .first()
-                                    .map(|n| n.to_string())
-                                    .unwrap_or_else(|| String::from(""));
-                                let last_name = patient_data
-                                    .family
-                                    .as_ref()
-                                    .map(|n| n.to_string())
-                                    .unwrap_or_else(|| String::from(""));
-                                let created_at = patient_data
-                                    .last_updated
-                                    .map(|dt| dt.to_rfc3339())
-                                    .unwrap_or_default();
-
-                                patients.push(pb::Patient {
-                                    id,
-                                    first_name,
-                                    last_name,
-                                    created_at,
-                                    national_id: String::new(), // Not implemented in current demographics
-                                });
+                                patients.push(patient_to_pb(&patient_data));
                             }
                             Err(e) => {
                                 tracing::warn!(
@@ -381,6 +569,278 @@ impl<S> DemographicsService<S> {
     }
 }
 
+// ============================================================================
+// PROTOBUF TRANSLATION
+// ============================================================================
+
+/// Converts FHIR-aligned patient data into the protobuf `Patient` message.
+///
+/// `first_name`/`last_name` come from [`PatientData::primary_name`] and `national_id`
+/// holds the NHS number, so older clients keep working; the full FHIR elements are
+/// carried in the repeated fields alongside.
+pub fn patient_to_pb(data: &PatientData) -> pb::Patient {
+    let primary_name = data.primary_name();
+
+    pb::Patient {
+        id: data.id.to_string(),
+        first_name: primary_name
+            .and_then(|n| n.given.first())
+            .map(|n| n.to_string())
+            .unwrap_or_default(),
+        last_name: primary_name
+            .and_then(|n| n.family.as_ref())
+            .map(|n| n.to_string())
+            .unwrap_or_default(),
+        created_at: data
+            .last_updated
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default(),
+        national_id: data.nhs_number().map(|n| n.to_string()).unwrap_or_default(),
+        identifiers: data.identifiers.iter().map(identifier_to_pb).collect(),
+        names: data.names.iter().map(name_to_pb).collect(),
+        telecom: data.telecom.iter().map(contact_point_to_pb).collect(),
+        gender: data
+            .gender
+            .map(|g| g.as_str().to_string())
+            .unwrap_or_default(),
+        birth_date: data.birth_date.map(|d| d.to_string()).unwrap_or_default(),
+        deceased_boolean: match data.deceased {
+            Some(Deceased::Boolean(flag)) => Some(flag),
+            _ => None,
+        },
+        deceased_date_time: match data.deceased {
+            Some(Deceased::DateTime(dt)) => dt.to_string(),
+            _ => String::new(),
+        },
+        addresses: data.addresses.iter().map(address_to_pb).collect(),
+        contacts: data.contacts.iter().map(contact_to_pb).collect(),
+        general_practitioners: data
+            .general_practitioners
+            .iter()
+            .map(reference_to_pb)
+            .collect(),
+    }
+}
+
+/// Converts protobuf identifiers, gender, contact points and addresses into
+/// [`PatientDetails`] for [`DemographicsService::update_details`].
+///
+/// Empty strings mean "not recorded", and a `rank` of 0 means unranked.
+///
+/// # Errors
+///
+/// Returns `PatientError::InvalidInput` if a required value is empty, a code is not a valid
+/// FHIR code, or a period bound is not a valid FHIR `dateTime`.
+pub fn patient_details_from_pb(
+    identifiers: Vec<pb::Identifier>,
+    gender: &str,
+    telecom: Vec<pb::ContactPoint>,
+    addresses: Vec<pb::Address>,
+) -> PatientResult<PatientDetails> {
+    Ok(PatientDetails {
+        identifiers: identifiers
+            .into_iter()
+            .map(identifier_from_pb)
+            .collect::<PatientResult<_>>()?,
+        gender: code_from_pb(gender, AdministrativeGender::parse)?,
+        telecom: telecom
+            .into_iter()
+            .map(contact_point_from_pb)
+            .collect::<PatientResult<_>>()?,
+        addresses: addresses
+            .into_iter()
+            .map(address_from_pb)
+            .collect::<PatientResult<_>>()?,
+    })
+}
+
+fn text_from_pb(value: String, field: &str) -> PatientResult<NonEmptyText> {
+    NonEmptyText::new(value)
+        .map_err(|e| PatientError::InvalidInput(format!("Invalid {field}: {e}")))
+}
+
+fn optional_text_from_pb(value: String, field: &str) -> PatientResult<Option<NonEmptyText>> {
+    if value.is_empty() {
+        Ok(None)
+    } else {
+        text_from_pb(value, field).map(Some)
+    }
+}
+
+fn texts_from_pb(values: Vec<String>, field: &str) -> PatientResult<Vec<NonEmptyText>> {
+    values
+        .into_iter()
+        .map(|value| text_from_pb(value, field))
+        .collect()
+}
+
+fn code_from_pb<T>(
+    value: &str,
+    parse: impl Fn(&str) -> Result<T, FhirError>,
+) -> PatientResult<Option<T>> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    parse(value)
+        .map(Some)
+        .map_err(|e| PatientError::InvalidInput(e.to_string()))
+}
+
+fn period_from_pb(period: Option<pb::Period>) -> PatientResult<Option<Period>> {
+    let Some(period) = period else {
+        return Ok(None);
+    };
+    let start = code_from_pb(&period.start, FhirDateTime::parse)?;
+    let end = code_from_pb(&period.end, FhirDateTime::parse)?;
+    Ok((start.is_some() || end.is_some()).then_some(Period { start, end }))
+}
+
+fn identifier_from_pb(identifier: pb::Identifier) -> PatientResult<Identifier> {
+    Ok(Identifier {
+        use_type: code_from_pb(&identifier.r#use, IdentifierUse::parse)?,
+        system: optional_text_from_pb(identifier.system, "identifier system")?,
+        value: text_from_pb(identifier.value, "identifier value")?,
+        period: period_from_pb(identifier.period)?,
+    })
+}
+
+fn contact_point_from_pb(contact_point: pb::ContactPoint) -> PatientResult<ContactPoint> {
+    Ok(ContactPoint {
+        system: code_from_pb(&contact_point.system, ContactPointSystem::parse)?,
+        value: text_from_pb(contact_point.value, "contact point value")?,
+        use_type: code_from_pb(&contact_point.r#use, ContactPointUse::parse)?,
+        rank: (contact_point.rank != 0).then_some(contact_point.rank),
+        period: period_from_pb(contact_point.period)?,
+    })
+}
+
+fn address_from_pb(address: pb::Address) -> PatientResult<Address> {
+    Ok(Address {
+        use_type: code_from_pb(&address.r#use, AddressUse::parse)?,
+        address_type: code_from_pb(&address.r#type, AddressType::parse)?,
+        text: optional_text_from_pb(address.text, "address text")?,
+        line: texts_from_pb(address.line, "address line")?,
+        city: optional_text_from_pb(address.city, "city")?,
+        district: optional_text_from_pb(address.district, "district")?,
+        state: optional_text_from_pb(address.state, "state")?,
+        postal_code: optional_text_from_pb(address.postal_code, "postal code")?,
+        country: optional_text_from_pb(address.country, "country")?,
+        period: period_from_pb(address.period)?,
+    })
+}
+
+fn text_to_pb(text: &Option<NonEmptyText>) -> String {
+    text.as_ref().map(|t| t.to_string()).unwrap_or_default()
+}
+
+fn period_to_pb(period: &Option<Period>) -> Option<pb::Period> {
+    period.as_ref().map(|p| pb::Period {
+        start: p.start.map(|dt| dt.to_string()).unwrap_or_default(),
+        end: p.end.map(|dt| dt.to_string()).unwrap_or_default(),
+    })
+}
+
+fn name_to_pb(name: &HumanName) -> pb::HumanName {
+    pb::HumanName {
+        r#use: name
+            .use_type
+            .map(|u| u.as_str().to_string())
+            .unwrap_or_default(),
+        family: text_to_pb(&name.family),
+        given: name.given.iter().map(|g| g.to_string()).collect(),
+        prefix: name.prefix.iter().map(|p| p.to_string()).collect(),
+        suffix: name.suffix.iter().map(|s| s.to_string()).collect(),
+        period: period_to_pb(&name.period),
+    }
+}
+
+fn identifier_to_pb(identifier: &Identifier) -> pb::Identifier {
+    pb::Identifier {
+        r#use: identifier
+            .use_type
+            .map(|u| u.as_str().to_string())
+            .unwrap_or_default(),
+        system: text_to_pb(&identifier.system),
+        value: identifier.value.to_string(),
+        period: period_to_pb(&identifier.period),
+    }
+}
+
+fn contact_point_to_pb(contact_point: &ContactPoint) -> pb::ContactPoint {
+    pb::ContactPoint {
+        system: contact_point
+            .system
+            .map(|s| s.as_str().to_string())
+            .unwrap_or_default(),
+        value: contact_point.value.to_string(),
+        r#use: contact_point
+            .use_type
+            .map(|u| u.as_str().to_string())
+            .unwrap_or_default(),
+        rank: contact_point.rank.unwrap_or_default(),
+        period: period_to_pb(&contact_point.period),
+    }
+}
+
+fn address_to_pb(address: &Address) -> pb::Address {
+    pb::Address {
+        r#use: address
+            .use_type
+            .map(|u| u.as_str().to_string())
+            .unwrap_or_default(),
+        r#type: address
+            .address_type
+            .map(|t| t.as_str().to_string())
+            .unwrap_or_default(),
+        text: text_to_pb(&address.text),
+        line: address.line.iter().map(|l| l.to_string()).collect(),
+        city: text_to_pb(&address.city),
+        district: text_to_pb(&address.district),
+        state: text_to_pb(&address.state),
+        postal_code: text_to_pb(&address.postal_code),
+        country: text_to_pb(&address.country),
+        period: period_to_pb(&address.period),
+    }
+}
+
+fn reference_to_pb(reference: &Reference) -> pb::Reference {
+    pb::Reference {
+        reference: text_to_pb(&reference.reference),
+        identifier: reference.identifier.as_ref().map(identifier_to_pb),
+        display: text_to_pb(&reference.display),
+    }
+}
+
+fn contact_to_pb(contact: &PatientContact) -> pb::PatientContact {
+    pb::PatientContact {
+        relationship: contact
+            .relationship
+            .iter()
+            .map(|r| pb::CodeableConcept {
+                coding: r
+                    .coding
+                    .iter()
+                    .map(|c| pb::Coding {
+                        system: text_to_pb(&c.system),
+                        code: text_to_pb(&c.code),
+                        display: text_to_pb(&c.display),
+                    })
+                    .collect(),
+                text: text_to_pb(&r.text),
+            })
+            .collect(),
+        name: contact.name.as_ref().map(name_to_pb),
+        telecom: contact.telecom.iter().map(contact_point_to_pb).collect(),
+        address: contact.address.as_ref().map(address_to_pb),
+        gender: contact
+            .gender
+            .map(|g| g.as_str().to_string())
+            .unwrap_or_default(),
+        organization: contact.organization.as_ref().map(reference_to_pb),
+        period: period_to_pb(&contact.period),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -470,9 +930,7 @@ mod tests {
         let patient_data = Patient::parse(&yaml_content).expect("should parse patient.yaml");
 
         assert_eq!(patient_data.id.uuid(), demographics_id.uuid());
-        assert_eq!(patient_data.use_type, None);
-        assert_eq!(patient_data.family, None);
-        assert_eq!(patient_data.given.len(), 0);
+        assert!(patient_data.names.is_empty());
         assert_eq!(patient_data.birth_date, None);
         assert!(patient_data.last_updated.is_some());
     }
@@ -584,12 +1042,14 @@ mod tests {
 
         let author = test_author();
         let demographics_service = service
-            .initialise(author, NonEmptyText::new("Test Hospital").unwrap())
+            .initialise(author.clone(), NonEmptyText::new("Test Hospital").unwrap())
             .expect("initialise should succeed");
 
         // Update demographics
         demographics_service
             .update(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
                 vec![
                     NonEmptyText::new("John").unwrap(),
                     NonEmptyText::new("Paul").unwrap(),
@@ -608,13 +1068,12 @@ mod tests {
             .expect("should read patient.yaml");
         let patient_data = Patient::parse(&yaml_content).expect("should parse patient.yaml");
 
-        assert_eq!(patient_data.use_type, Some(NameUse::Official));
-        assert_eq!(
-            patient_data.family,
-            Some(NonEmptyText::new("Smith").unwrap())
-        );
+        assert_eq!(patient_data.names.len(), 1);
+        let name = &patient_data.names[0];
+        assert_eq!(name.use_type, Some(NameUse::Official));
+        assert_eq!(name.family, Some(NonEmptyText::new("Smith").unwrap()));
         assert_eq!(
-            patient_data.given,
+            name.given,
             vec![
                 NonEmptyText::new("John").unwrap(),
                 NonEmptyText::new("Paul").unwrap()
@@ -626,6 +1085,327 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_update_commits_patient_yaml() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let cfg = test_cfg(temp_dir.path());
+        let author = test_author();
+        let demographics_service = DemographicsService::new(cfg)
+            .initialise(author.clone(), NonEmptyText::new("Test Hospital").unwrap())
+            .expect("initialise should succeed");
+
+        demographics_service
+            .update(
+                &author,
+                NonEmptyText::new("Ward 7").unwrap(),
+                vec![NonEmptyText::new("Jane").unwrap()],
+                "Doe",
+                "1985-06-30",
+            )
+            .expect("update should succeed");
+
+        let history = demographics_service
+            .commit_history(Some(Path::new(PatientFile::NAME)))
+            .expect("commit_history should succeed");
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            history[0].message.as_ref().unwrap().domain(),
+            VprCommitDomain::Demographics(Record)
+        );
+        assert_eq!(
+            history[0].message.as_ref().unwrap().action(),
+            VprCommitAction::Update
+        );
+        assert_eq!(
+            history[0].message.as_ref().unwrap().care_location(),
+            "Ward 7"
+        );
+        assert_eq!(
+            history[0].author.as_ref().unwrap().name.as_str(),
+            "Test Author"
+        );
+
+        // The working tree must match the committed tree (no uncommitted changes left behind).
+        let demographics_dir = temp_dir.path().join(DEMOGRAPHICS_DIR_NAME);
+        let patient_dir = demographics_service
+            .demographics_id()
+            .sharded_dir(&demographics_dir);
+        let repo = git2::Repository::open(&patient_dir).expect("Failed to open Git repo");
+        let statuses = repo.statuses(None).expect("Failed to read statuses");
+        assert!(statuses.is_empty(), "update should leave a clean work tree");
+    }
+
+    #[test]
+    fn test_update_with_stale_expected_commit_is_rejected() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let cfg = test_cfg(temp_dir.path());
+        let author = test_author();
+        let demographics_service = DemographicsService::new(cfg.clone())
+            .initialise(author.clone(), NonEmptyText::new("Test Hospital").unwrap())
+            .expect("initialise should succeed");
+        let id = demographics_service.demographics_id().to_string();
+        let update = |commit_id: &str, last_name: &str| {
+            DemographicsService::with_id(cfg.clone(), &id)
+                .unwrap()
+                .with_expected_commit(Some(commit_id))
+                .unwrap()
+                .update(
+                    &author,
+                    NonEmptyText::new("Test Hospital").unwrap(),
+                    vec![NonEmptyText::new("Jane").unwrap()],
+                    last_name,
+                    "1985-06-30",
+                )
+        };
+
+        // Two clients read the same version; only the first write built on it succeeds.
+        let read = demographics_service.read().expect("read should succeed");
+        update(&read.commit_id, "Doe").expect("first update should succeed");
+        assert!(matches!(
+            update(&read.commit_id, "Roe"),
+            Err(PatientError::ConcurrentModification { .. })
+        ));
+
+        let reread = demographics_service.read().expect("read should succeed");
+        assert_ne!(reread.commit_id, read.commit_id);
+        assert_eq!(
+            reread.patient.names[0].family,
+            Some(NonEmptyText::new("Doe").unwrap())
+        );
+        update(&reread.commit_id, "Roe").expect("update after re-reading should succeed");
+    }
+
+    #[test]
+    fn test_update_preserves_other_fhir_elements() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let cfg = test_cfg(temp_dir.path());
+        let author = test_author();
+        let demographics_service = DemographicsService::new(cfg.clone())
+            .initialise(author.clone(), NonEmptyText::new("Test Hospital").unwrap())
+            .expect("initialise should succeed");
+
+        let demographics_dir = temp_dir.path().join(DEMOGRAPHICS_DIR_NAME);
+        let patient_file = demographics_service
+            .demographics_id()
+            .sharded_dir(&demographics_dir)
+            .join(PatientFile::NAME);
+        let pas_yaml = format!(
+            r#"resourceType: Patient
+id: {}
+identifier:
+  - system: https://fhir.nhs.uk/Id/nhs-number
+    value: "9434765919"
+name:
+  - use: official
+    family: Jones
+    given:
+      - Sarah
+  - use: maiden
+    family: Evans
+gender: female
+"#,
+            demographics_service.demographics_id()
+        );
+        fs::write(&patient_file, pas_yaml).expect("Failed to write patient.yaml");
+
+        demographics_service
+            .update(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                vec![NonEmptyText::new("Sarah").unwrap()],
+                "Williams",
+                "1992-03-20",
+            )
+            .expect("update should succeed");
+
+        let patient_data = Patient::parse(&fs::read_to_string(&patient_file).unwrap())
+            .expect("should parse patient.yaml");
+        assert_eq!(patient_data.names.len(), 2);
+        assert_eq!(
+            patient_data.names[0].family.as_ref().map(|f| f.as_str()),
+            Some("Williams")
+        );
+        assert_eq!(patient_data.names[1].use_type, Some(NameUse::Maiden));
+        assert_eq!(
+            patient_data.nhs_number().map(|n| n.as_str()),
+            Some("9434765919")
+        );
+
+        let patients = DemographicsService::new(cfg).list_patients();
+        assert_eq!(patients.len(), 1);
+        assert_eq!(patients[0].first_name, "Sarah");
+        assert_eq!(patients[0].last_name, "Williams");
+        assert_eq!(patients[0].national_id, "9434765919");
+        assert_eq!(patients[0].gender, "female");
+        assert_eq!(patients[0].birth_date, "1992-03-20");
+        assert_eq!(patients[0].names.len(), 2);
+        assert_eq!(patients[0].names[1].r#use, "maiden");
+    }
+
+    #[test]
+    fn test_update_details_replaces_details_and_preserves_names() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let cfg = test_cfg(temp_dir.path());
+        let author = test_author();
+        let demographics_service = DemographicsService::new(cfg.clone())
+            .initialise(author.clone(), NonEmptyText::new("Test Hospital").unwrap())
+            .expect("initialise should succeed");
+        demographics_service
+            .update(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                vec![NonEmptyText::new("Sarah").unwrap()],
+                "Williams",
+                "1992-03-20",
+            )
+            .expect("update should succeed");
+
+        let details = patient_details_from_pb(
+            vec![pb::Identifier {
+                r#use: "official".into(),
+                system: fhir::NHS_NUMBER_SYSTEM.into(),
+                value: "9434765919".into(),
+                period: Some(pb::Period {
+                    start: "2020-01".into(),
+                    end: String::new(),
+                }),
+            }],
+            "female",
+            vec![pb::ContactPoint {
+                system: "phone".into(),
+                value: "01632 960001".into(),
+                r#use: "mobile".into(),
+                rank: 1,
+                period: None,
+            }],
+            vec![pb::Address {
+                line: vec!["1 High Street".into()],
+                city: "Leeds".into(),
+                postal_code: "LS1 1AA".into(),
+                ..Default::default()
+            }],
+        )
+        .expect("details should convert");
+        demographics_service
+            .update_details(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                details,
+            )
+            .expect("update_details should succeed");
+
+        let patient = demographics_service
+            .read()
+            .expect("read should succeed")
+            .patient;
+        assert_eq!(
+            patient.names[0].family.as_ref().map(|f| f.as_str()),
+            Some("Williams")
+        );
+        assert_eq!(
+            patient.birth_date.map(|d| d.to_string()).as_deref(),
+            Some("1992-03-20")
+        );
+        assert_eq!(patient.nhs_number().map(|n| n.as_str()), Some("9434765919"));
+        assert_eq!(
+            patient.identifiers[0].period.as_ref().and_then(|p| p.start),
+            Some(FhirDateTime::YearMonth(2020, 1))
+        );
+        assert_eq!(patient.gender, Some(AdministrativeGender::Female));
+        assert_eq!(patient.telecom[0].rank, Some(1));
+        assert_eq!(
+            patient.addresses[0]
+                .postal_code
+                .as_ref()
+                .map(|p| p.as_str()),
+            Some("LS1 1AA")
+        );
+
+        // Each element is replaced as a whole, so empty values clear it.
+        demographics_service
+            .update_details(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                PatientDetails::default(),
+            )
+            .expect("update_details should succeed");
+        let patient = demographics_service
+            .read()
+            .expect("read should succeed")
+            .patient;
+        assert!(patient.identifiers.is_empty());
+        assert_eq!(patient.gender, None);
+        assert!(patient.telecom.is_empty());
+        assert!(patient.addresses.is_empty());
+        assert_eq!(patient.names.len(), 1);
+    }
+
+    #[test]
+    fn test_patient_details_from_pb_rejects_invalid_values() {
+        let identifier = |value: &str, start: &str| pb::Identifier {
+            value: value.into(),
+            period: Some(pb::Period {
+                start: start.into(),
+                end: String::new(),
+            }),
+            ..Default::default()
+        };
+
+        for (identifiers, gender) in [
+            (vec![identifier("", "")], ""),
+            (vec![identifier("123", "2020-13")], ""),
+            (vec![], "not-a-gender"),
+        ] {
+            assert!(matches!(
+                patient_details_from_pb(identifiers, gender, vec![], vec![]),
+                Err(PatientError::InvalidInput(_))
+            ));
+        }
+
+        let details = patient_details_from_pb(vec![identifier("123", "")], "", vec![], vec![])
+            .expect("empty optional values are not recorded");
+        assert_eq!(details.identifiers[0].period, None);
+        assert_eq!(details.gender, None);
+    }
+
+    #[test]
+    fn test_update_rolls_back_on_invalid_birth_date() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let cfg = test_cfg(temp_dir.path());
+        let author = test_author();
+        let demographics_service = DemographicsService::new(cfg)
+            .initialise(author.clone(), NonEmptyText::new("Test Hospital").unwrap())
+            .expect("initialise should succeed");
+
+        let demographics_dir = temp_dir.path().join(DEMOGRAPHICS_DIR_NAME);
+        let patient_file = demographics_service
+            .demographics_id()
+            .sharded_dir(&demographics_dir)
+            .join(PatientFile::NAME);
+        let before = fs::read_to_string(&patient_file).expect("should read patient.yaml");
+
+        let err = demographics_service
+            .update(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                vec![NonEmptyText::new("Jane").unwrap()],
+                "Doe",
+                "not-a-date",
+            )
+            .expect_err("update should fail");
+        assert!(matches!(err, PatientError::InvalidInput(_)));
+
+        let after = fs::read_to_string(&patient_file).expect("should read patient.yaml");
+        assert_eq!(before, after);
+        assert_eq!(
+            demographics_service
+                .commit_history(None)
+                .expect("commit_history should succeed")
+                .len(),
+            1
+        );
+    }
+
     #[test]
     fn test_list_patients_returns_empty_for_nonexistent_directory() {
         let temp_dir = TempDir::new().expect("Failed to create temp dir");
@@ -648,6 +1428,8 @@ mod tests {
             .expect("initialise should succeed");
         demographics_service1
             .update(
+                &test_author(),
+                NonEmptyText::new("Test Hospital").unwrap(),
                 vec![NonEmptyText::new("Alice").unwrap()],
                 "Smith",
                 "1990-01-15",
@@ -661,6 +1443,8 @@ mod tests {
             .expect("initialise should succeed");
         demographics_service2
             .update(
+                &test_author(),
+                NonEmptyText::new("Test Hospital").unwrap(),
                 vec![NonEmptyText::new("Bob").unwrap()],
                 "Jones",
                 "1985-06-20",
@@ -701,6 +1485,8 @@ mod tests {
             .expect("initialise should succeed");
         demographics_service1
             .update(
+                &test_author(),
+                NonEmptyText::new("Test Hospital").unwrap(),
                 vec![NonEmptyText::new("Valid").unwrap()],
                 "Patient",
                 "1990-01-15",
@@ -1,9 +1,12 @@
 //! Repository management modules.
 //!
 //! This module contains services for managing different types of patient repositories,
-//! including clinical records and demographics.
+//! including clinical records, demographics, care coordination, and the Redaction
+//! Retention Repository, as well as the certificate revocation repository.
 
 pub mod clinical;
 pub mod coordination;
 pub mod demographics;
+pub mod redaction;
+pub mod revocation;
 pub mod shared;
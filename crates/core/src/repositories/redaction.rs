@@ -0,0 +1,1079 @@
+//! Redaction Retention Repository (RRR) management.
+//!
+//! This module moves artefacts out of routine patient repositories (clinical, coordination)
+//! and retains them, in full, in a Redaction Retention Repository. See the design notes in
+//! `docs/src/technical/redaction` for the principles behind redaction.
+//!
+//! ## Architecture
+//!
+//! There is one RRR per routine repository, keyed by the same UUID. It is created lazily
+//! on the first redaction from that repository, and like every other VPR repository it is
+//! a Git repository with signed, structured commits.
+//!
+//! A redaction always produces two `Redact` commits carrying the same `Redaction-Id`
+//! trailer:
+//!
+//! 1. The RRR commit, which stores the redaction record and a copy of every removed file.
+//!    It also carries a `Redaction-Source` trailer naming the routine repository.
+//! 2. The routine commit, which removes (or rewrites) the artefact and writes a tombstone.
+//!    It also carries a `Redaction-Commit` trailer holding the RRR commit id.
+//!
+//! The RRR commit is made first, so a failure part-way through never loses content. A retry
+//! of a redaction whose routine commit failed reuses the retained record rather than
+//! creating a second one for the same item.
+//!
+//! Redaction removes an artefact from the routine working tree, not from routine Git
+//! history: older commits still hold its content. Routine `commit_history` queries for a
+//! redacted path return no commits, but anyone with direct access to the routine
+//! repository can still read the content from Git.
+//!
+//! ## Storage Layout
+//!
+//! ```text
+//! redaction/
+//!   <s1>/
+//!     <s2>/
+//!       <source-id>/
+//!         items/
+//!           <redaction_id>/
+//!             redaction.yaml      # What, from where, why and by whom
+//!             content/            # Removed files at their original relative paths
+//!         files/                  # Retained attachment blobs (gitignored)
+//!         .git/
+//! ```
+//!
+//! and in the routine repository:
+//!
+//! ```text
+//! redactions/
+//!   <redaction_id>.yaml           # Tombstone pointing at the RRR commit
+//! ```
+//!
+//! ## Pure Data Operations
+//!
+//! Redaction is initiated from the routine services (for example
+//! [`ClinicalService::redact_letter`](crate::repositories::clinical::ClinicalService::redact_letter)).
+//! Reading retained items is exposed here without access control; restricting RRR access
+//! to authorised roles belongs in the API layer.
+
+use crate::author::{Author, AuthorRegistration};
+use crate::config::CoreConfig;
+use crate::constants::DEFAULT_GITIGNORE;
+use crate::error::{PatientError, PatientResult};
+use crate::lock::RepositoryLock;
+use crate::paths::{
+    common::GitIgnoreFile,
+    redaction::{
+        item::{ItemsDir, RedactedItemPaths, RedactionYaml},
+        tombstone::tombstone_path,
+    },
+};
+use crate::repositories::revocation::RevocationService;
+use crate::versioned_files::{
+    FileToRemove, FileToWrite, RedactionDomain, SignatureChainReport, VersionedFileService,
+    VprCommitAction, VprCommitDomain, VprCommitHistoryEntry, VprCommitMessage, VprRepositoryKind,
+};
+use crate::NonEmptyText;
+use crate::ShardableUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+use uuid::Uuid;
+use vpr_uuid::{Sha256Hash, TimestampId, TimestampIdGenerator};
+
+// ============================================================================
+// DATA STRUCTURES
+// ============================================================================
+
+/// Why an artefact was removed from routine view.
+///
+/// Reasons are recorded in the RRR only; the tombstone left in the routine repository
+/// does not carry them.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionReason {
+    /// The artefact belongs to a different patient.
+    WrongPatient,
+    /// The artefact was filed in the wrong place within this patient's record.
+    Misfiled,
+    /// The artefact was entered in error.
+    EnteredInError,
+    /// Consent for the artefact to be held in routine view was withdrawn.
+    ConsentWithdrawn,
+    /// A jurisdictional or policy constraint applies.
+    PolicyConstraint,
+}
+
+impl RedactionReason {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::WrongPatient => "wrong_patient",
+            Self::Misfiled => "misfiled",
+            Self::EnteredInError => "entered_in_error",
+            Self::ConsentWithdrawn => "consent_withdrawn",
+            Self::PolicyConstraint => "policy_constraint",
+        }
+    }
+}
+
+impl FromStr for RedactionReason {
+    type Err = PatientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wrong_patient" => Ok(Self::WrongPatient),
+            "misfiled" => Ok(Self::Misfiled),
+            "entered_in_error" => Ok(Self::EnteredInError),
+            "consent_withdrawn" => Ok(Self::ConsentWithdrawn),
+            "policy_constraint" => Ok(Self::PolicyConstraint),
+            other => Err(PatientError::InvalidInput(format!(
+                "unknown redaction reason: {other}"
+            ))),
+        }
+    }
+}
+
+/// The artefact a redaction removed from routine view.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RedactedItem {
+    /// A whole clinical letter, including its attachments.
+    Letter { letter_id: TimestampId },
+    /// A single attachment of a clinical letter (the letter itself remains).
+    LetterAttachment {
+        letter_id: TimestampId,
+        /// Attachment metadata filename, e.g. `attachment_1.yaml`.
+        attachment: NonEmptyText,
+    },
+    /// A single message in a coordination thread.
+    Message {
+        communication_id: TimestampId,
+        message_id: Uuid,
+    },
+}
+
+impl RedactedItem {
+    /// Commit summary used on both sides of the redaction.
+    fn commit_summary(&self) -> &'static str {
+        match self {
+            Self::Letter { .. } => "Redacted letter",
+            Self::LetterAttachment { .. } => "Redacted letter attachment",
+            Self::Message { .. } => "Redacted message",
+        }
+    }
+
+    /// Returns `true` if both refer to the same artefact.
+    ///
+    /// IDs are compared in their stored form, which has millisecond precision, so an item
+    /// read back from a record matches the one it was built from.
+    fn is_same(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Letter { letter_id: a }, Self::Letter { letter_id: b }) => {
+                a.to_string() == b.to_string()
+            }
+            (
+                Self::LetterAttachment {
+                    letter_id: a,
+                    attachment: a_name,
+                },
+                Self::LetterAttachment {
+                    letter_id: b,
+                    attachment: b_name,
+                },
+            ) => a.to_string() == b.to_string() && a_name == b_name,
+            (
+                Self::Message {
+                    communication_id: a,
+                    message_id: a_message,
+                },
+                Self::Message {
+                    communication_id: b,
+                    message_id: b_message,
+                },
+            ) => a.to_string() == b.to_string() && a_message == b_message,
+            _ => false,
+        }
+    }
+}
+
+/// The record stored in the RRR for each redaction (`items/<redaction_id>/redaction.yaml`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactionRecord {
+    pub redaction_id: TimestampId,
+    pub redacted_at: DateTime<Utc>,
+    /// Kind of routine repository the item was removed from.
+    pub source: VprRepositoryKind,
+    /// UUID of the routine repository the item was removed from.
+    pub source_id: Uuid,
+    pub item: RedactedItem,
+    pub reason: RedactionReason,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason_detail: Option<NonEmptyText>,
+    /// Name of the author who performed the redaction.
+    pub redacted_by: NonEmptyText,
+    /// Professional registrations the author declared when performing the redaction.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redacted_by_registrations: Vec<AuthorRegistration>,
+    /// Retained files, relative to the routine repository root.
+    pub files: Vec<PathBuf>,
+    /// Attachment blobs copied into the RRR's file storage.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachment_hashes: Vec<Sha256Hash>,
+}
+
+/// The tombstone left in the routine repository (`redactions/<redaction_id>.yaml`).
+///
+/// Identifies what was removed and where it went, without saying why.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactionTombstone {
+    pub redaction_id: TimestampId,
+    pub redacted_at: DateTime<Utc>,
+    pub item: RedactedItem,
+    /// Id of the RRR commit that retained the item.
+    pub retention_commit: String,
+}
+
+/// A file retained by a redaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetainedFile {
+    /// Path relative to the routine repository root.
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// An attachment blob retained by a redaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetainedAttachment {
+    pub hash: Sha256Hash,
+    pub content: Vec<u8>,
+}
+
+/// A redaction read back from the RRR.
+#[derive(Clone, Debug)]
+pub struct RetainedRedaction {
+    pub record: RedactionRecord,
+    pub files: Vec<RetainedFile>,
+    pub attachments: Vec<RetainedAttachment>,
+}
+
+/// Everything a routine service hands over to perform a redaction.
+///
+/// `retained` and `attachment_hashes` are copied into the RRR; `writes` and `removals`
+/// are applied to the routine repository together with the tombstone.
+pub(crate) struct RedactionRequest<'a> {
+    pub item: RedactedItem,
+    pub reason: RedactionReason,
+    pub reason_detail: Option<NonEmptyText>,
+    pub retained: Vec<RetainedFile>,
+    pub attachment_hashes: Vec<Sha256Hash>,
+    pub source_files: Option<&'a vpr_files::FilesService>,
+    pub writes: &'a [FileToWrite<'a>],
+    pub removals: &'a [FileToRemove<'a>],
+}
+
+// ============================================================================
+// REDACTION SERVICE
+// ============================================================================
+
+/// Service for the Redaction Retention Repository of one routine repository.
+#[derive(Clone, Debug)]
+pub struct RedactionService {
+    cfg: Arc<CoreConfig>,
+    source_id: ShardableUuid,
+}
+
+impl RedactionService {
+    /// Creates a service for the RRR belonging to the given routine repository.
+    ///
+    /// The RRR does not need to exist yet; it is created by the first redaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `cfg` - Core configuration
+    /// * `source_id` - UUID of the routine (clinical or coordination) repository
+    pub fn with_id(cfg: Arc<CoreConfig>, source_id: Uuid) -> Self {
+        Self {
+            cfg,
+            source_id: ShardableUuid::from_uuid(source_id),
+        }
+    }
+
+    /// Returns the UUID of the routine repository this RRR belongs to.
+    pub fn source_id(&self) -> Uuid {
+        self.source_id.uuid()
+    }
+
+    /// Removes an item from a routine repository and retains it in the RRR.
+    ///
+    /// Commits the retained copy to the RRR (creating it if needed), then applies the
+    /// routine-side changes and tombstone in a single commit.
+    ///
+    /// If an earlier attempt retained the same content but its routine commit failed, that
+    /// redaction is completed instead, so retrying never leaves two records for one item.
+    ///
+    /// # Arguments
+    ///
+    /// * `author` - Author performing the redaction
+    /// * `care_location` - Care location for both commits
+    /// * `source_domain` - Commit domain for the routine-side commit
+    /// * `source_lock` - Write lock on the routine repository, taken before the request was built
+    /// * `request` - What to retain and how to change the routine repository
+    ///
+    /// # Returns
+    ///
+    /// The new redaction ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if:
+    /// - Author validation fails
+    /// - `source_domain` is not a routine repository domain
+    /// - Attachment blobs cannot be copied into the RRR
+    /// - Serialisation, file writes, or either Git commit fail
+    pub(crate) fn redact(
+        &self,
+        author: &Author,
+        care_location: NonEmptyText,
+        source_domain: VprCommitDomain,
+        source_lock: &RepositoryLock,
+        request: RedactionRequest<'_>,
+    ) -> PatientResult<TimestampId> {
+        author.validate_commit_author()?;
+
+        let source = match source_domain {
+            VprCommitDomain::Clinical(_) => VprRepositoryKind::Clinical,
+            VprCommitDomain::Coordination(_) => VprRepositoryKind::Coordination,
+            VprCommitDomain::Demographics(_) => VprRepositoryKind::Demographics,
+            VprCommitDomain::Redaction(_) => {
+                return Err(PatientError::InvalidInput(
+                    "Items cannot be redacted from a Redaction Retention Repository".to_string(),
+                ))
+            }
+            VprCommitDomain::Revocation(_) => {
+                return Err(PatientError::InvalidInput(
+                    "Items cannot be redacted from the revocation repository".to_string(),
+                ))
+            }
+        };
+
+        // A previous attempt may have retained this item and then failed before the routine
+        // commit. Finish that redaction rather than retaining the item a second time.
+        let (redaction_id, retention_commit) =
+            match self.unfinished_redaction(source_lock.repo_dir(), &request)? {
+                Some(unfinished) => unfinished,
+                None => self.retain(author, care_location.clone(), source, &request)?,
+            };
+        let summary = request.item.commit_summary();
+        let redacted_at = redaction_id.timestamp();
+
+        // Routine side: apply the removal and leave a tombstone.
+        let tombstone = RedactionTombstone {
+            redaction_id: redaction_id.clone(),
+            redacted_at,
+            item: request.item,
+            retention_commit: retention_commit.clone(),
+        };
+        let tombstone_yaml =
+            serde_yaml::to_string(&tombstone).map_err(PatientError::YamlSerialization)?;
+        let tombstone_relative = tombstone_path(&redaction_id);
+
+        let mut routine_writes = request.writes.to_vec();
+        routine_writes.push(FileToWrite {
+            relative_path: &tombstone_relative,
+            content: &tombstone_yaml,
+            old_content: None,
+        });
+
+        let routine_msg = VprCommitMessage::new(
+            source_domain,
+            VprCommitAction::Redact,
+            summary,
+            care_location,
+        )?
+        .with_trailer("Redaction-Id", redaction_id.to_string())?
+        .with_trailer("Redaction-Commit", retention_commit)?;
+
+        VersionedFileService::write_remove_and_commit_files(
+            source_lock,
+            author,
+            &routine_msg,
+            &routine_writes,
+            request.removals,
+            self.cfg.timestamp_authority(),
+            self.cfg.mirror(),
+        )?;
+
+        Ok(redaction_id)
+    }
+
+    /// Commits a new redaction record and the retained content to the RRR.
+    ///
+    /// The RRR commit is made before the routine commit so that nothing is lost if the
+    /// routine commit fails.
+    ///
+    /// # Returns
+    ///
+    /// The new redaction ID and the id of the RRR commit.
+    fn retain(
+        &self,
+        author: &Author,
+        care_location: NonEmptyText,
+        source: VprRepositoryKind,
+        request: &RedactionRequest<'_>,
+    ) -> PatientResult<(TimestampId, String)> {
+        let summary = request.item.commit_summary();
+        let redaction_id = TimestampIdGenerator::generate(None)?;
+        let redacted_at = redaction_id.timestamp();
+
+        let record = RedactionRecord {
+            redaction_id: redaction_id.clone(),
+            redacted_at,
+            source,
+            source_id: self.source_id(),
+            item: request.item.clone(),
+            reason: request.reason,
+            reason_detail: request.reason_detail.clone(),
+            redacted_by: author.name.clone(),
+            redacted_by_registrations: author.registrations.clone(),
+            files: request.retained.iter().map(|f| f.path.clone()).collect(),
+            attachment_hashes: request.attachment_hashes.clone(),
+        };
+        let record_yaml =
+            serde_yaml::to_string(&record).map_err(PatientError::YamlSerialization)?;
+
+        let redaction_dir = self.redaction_dir();
+        self.ensure_repository(author, care_location.clone())?;
+
+        if !request.attachment_hashes.is_empty() {
+            let source_files = request.source_files.ok_or_else(|| {
+                PatientError::InvalidInput(
+                    "Attachment redaction requires the source files service".to_string(),
+                )
+            })?;
+            let retained_files =
+                vpr_files::FilesService::new(&self.redaction_root_dir(), self.source_id.clone())
+                    .map_err(|e| {
+                        PatientError::InvalidInput(format!(
+                            "Failed to initialize files service: {}",
+                            e
+                        ))
+                    })?;
+            for hash in &request.attachment_hashes {
+                source_files
+                    .copy_to(hash.as_str(), &retained_files)
+                    .map_err(|e| {
+                        PatientError::InvalidInput(format!(
+                            "Failed to retain attachment file: {}",
+                            e
+                        ))
+                    })?;
+            }
+        }
+
+        let item_paths = RedactedItemPaths::new(&redaction_id);
+        let record_path = item_paths.redaction_yaml();
+        let content_paths: Vec<PathBuf> = request
+            .retained
+            .iter()
+            .map(|f| item_paths.content(&f.path))
+            .collect();
+
+        let mut retained_writes = vec![FileToWrite {
+            relative_path: &record_path,
+            content: &record_yaml,
+            old_content: None,
+        }];
+        for (path, file) in content_paths.iter().zip(request.retained.iter()) {
+            retained_writes.push(FileToWrite {
+                relative_path: path,
+                content: &file.content,
+                old_content: None,
+            });
+        }
+
+        let retention_msg = VprCommitMessage::new(
+            VprCommitDomain::Redaction(RedactionDomain::Record),
+            VprCommitAction::Redact,
+            summary,
+            care_location,
+        )?
+        .with_trailer("Redaction-Id", redaction_id.to_string())?
+        .with_trailer(
+            "Redaction-Source",
+            format!("{}/{}", source.as_str(), self.source_id),
+        )?;
+
+        let retention_lock = RepositoryLock::acquire(&redaction_dir)?;
+        let retention_commit = VersionedFileService::write_and_commit_files(
+            &retention_lock,
+            author,
+            &retention_msg,
+            &retained_writes,
+            self.cfg.timestamp_authority(),
+            self.cfg.mirror(),
+        )?;
+
+        Ok((redaction_id, retention_commit.to_string()))
+    }
+
+    /// Finds an earlier redaction of the same item whose routine commit never landed.
+    ///
+    /// A redaction is unfinished when its record is in the RRR but the routine repository
+    /// has no tombstone for it. It is only reused when it retained exactly the content in
+    /// `request`; otherwise the item has changed since and is retained afresh.
+    ///
+    /// # Returns
+    ///
+    /// The redaction ID and the id of the RRR commit that retained it, if one was found.
+    fn unfinished_redaction(
+        &self,
+        source_dir: &Path,
+        request: &RedactionRequest<'_>,
+    ) -> PatientResult<Option<(TimestampId, String)>> {
+        for record in self.list_redactions()?.into_iter().rev() {
+            if !record.item.is_same(&request.item)
+                || source_dir
+                    .join(tombstone_path(&record.redaction_id))
+                    .exists()
+                || record.attachment_hashes != request.attachment_hashes
+            {
+                continue;
+            }
+
+            let retained = self.read_redaction(&record.redaction_id)?;
+            if retained.files != request.retained {
+                continue;
+            }
+
+            let item_paths = RedactedItemPaths::new(&record.redaction_id);
+            let retention_commit = self
+                .commit_history(Some(item_paths.dir()))?
+                .pop()
+                .map(|entry| entry.commit_id)
+                .ok_or_else(|| {
+                    PatientError::InvalidInput(format!(
+                        "Redaction {} has no retention commit",
+                        record.redaction_id
+                    ))
+                })?;
+
+            return Ok(Some((record.redaction_id, retention_commit)));
+        }
+
+        Ok(None)
+    }
+
+    /// Lists all redactions retained for this routine repository, oldest first.
+    ///
+    /// Returns an empty list if nothing has been redacted yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if the items directory or any redaction record cannot be
+    /// read or parsed.
+    pub fn list_redactions(&self) -> PatientResult<Vec<RedactionRecord>> {
+        let items_dir = self.redaction_dir().join(ItemsDir::NAME);
+        if !items_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut records = Vec::new();
+        for entry in fs::read_dir(&items_dir).map_err(PatientError::FileRead)? {
+            let entry = entry.map_err(PatientError::FileRead)?;
+            let record_path = entry.path().join(RedactionYaml::NAME);
+            if !record_path.is_file() {
+                continue;
+            }
+            records.push(Self::record_read(&record_path)?);
+        }
+
+        records.sort_by_key(|record| record.redaction_id.timestamp());
+        Ok(records)
+    }
+
+    /// Reads a retained item back from the RRR, including its files and attachments.
+    ///
+    /// This is an unrestricted read; callers are responsible for checking that the
+    /// requester is authorised to see redacted content.
+    ///
+    /// # Arguments
+    ///
+    /// * `redaction_id` - ID returned when the item was redacted
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if:
+    /// - No redaction with this ID exists
+    /// - The record, a retained file, or a retained attachment cannot be read
+    pub fn read_redaction(&self, redaction_id: &TimestampId) -> PatientResult<RetainedRedaction> {
+        let redaction_dir = self.redaction_dir();
+        let item_paths = RedactedItemPaths::new(redaction_id);
+        let record_path = redaction_dir.join(item_paths.redaction_yaml());
+
+        if !record_path.exists() {
+            return Err(PatientError::InvalidInput(format!(
+                "Redaction not found: {}",
+                redaction_id
+            )));
+        }
+
+        let record = Self::record_read(&record_path)?;
+
+        let files = record
+            .files
+            .iter()
+            .map(|path| {
+                let content = fs::read_to_string(redaction_dir.join(item_paths.content(path)))
+                    .map_err(PatientError::FileRead)?;
+                Ok(RetainedFile {
+                    path: path.clone(),
+                    content,
+                })
+            })
+            .collect::<PatientResult<Vec<_>>>()?;
+
+        let mut attachments = Vec::new();
+        if !record.attachment_hashes.is_empty() {
+            let files_service =
+                vpr_files::FilesService::new(&self.redaction_root_dir(), self.source_id.clone())
+                    .map_err(|e| {
+                        PatientError::InvalidInput(format!(
+                            "Failed to initialize files service: {}",
+                            e
+                        ))
+                    })?;
+            for hash in &record.attachment_hashes {
+                let content = files_service.read(hash.as_str()).map_err(|e| {
+                    PatientError::InvalidInput(format!("Failed to read attachment file: {}", e))
+                })?;
+                attachments.push(RetainedAttachment {
+                    hash: hash.clone(),
+                    content,
+                });
+            }
+        }
+
+        Ok(RetainedRedaction {
+            record,
+            files,
+            attachments,
+        })
+    }
+
+    /// Returns the commit history of this RRR, newest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Optional path relative to the RRR root (e.g. `items/<redaction_id>`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if the repository cannot be opened or walked.
+    pub fn commit_history(&self, path: Option<&Path>) -> PatientResult<Vec<VprCommitHistoryEntry>> {
+        VersionedFileService::commit_history(
+            &self.redaction_root_dir(),
+            &self.source_id.to_string(),
+            VprRepositoryKind::Redaction,
+            path,
+            self.cfg.trust_anchors(),
+            Some(&RevocationService::new(self.cfg.clone()).revocation_list()?),
+            self.cfg.timestamp_trust_anchors(),
+        )
+    }
+
+    /// Returns `true` if `path` in the routine repository refers to redacted content.
+    ///
+    /// A path is redacted when it no longer exists in the routine working tree and it is,
+    /// or lies inside, or contains a file retained by one of this repository's redactions.
+    /// Routine history queries use this to avoid pointing at commits that hold redacted
+    /// content.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_dir` - Working tree of the routine repository
+    /// * `path` - Path relative to the routine repository root
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if the redaction records cannot be read.
+    pub(crate) fn is_redacted_path(&self, source_dir: &Path, path: &Path) -> PatientResult<bool> {
+        if source_dir.join(path).exists() {
+            return Ok(false);
+        }
+
+        Ok(self.list_redactions()?.iter().any(|record| {
+            record
+                .files
+                .iter()
+                .any(|file| file.starts_with(path) || path.starts_with(file))
+        }))
+    }
+
+    /// Verifies the embedded signature of every commit in this RRR.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError` if the repository cannot be opened or walked.
+    pub fn verify_signature_chain(&self) -> PatientResult<SignatureChainReport> {
+        VersionedFileService::verify_signature_chain(
+            &self.redaction_root_dir(),
+            &self.source_id.to_string(),
+            self.cfg.trust_anchors(),
+            Some(&RevocationService::new(self.cfg.clone()).revocation_list()?),
+            self.cfg.timestamp_trust_anchors(),
+        )
+    }
+
+    /// Creates the RRR for this routine repository if it does not exist yet.
+    fn ensure_repository(&self, author: &Author, care_location: NonEmptyText) -> PatientResult<()> {
+        let redaction_dir = self.redaction_dir();
+        if redaction_dir.join(".git").exists() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&redaction_dir).map_err(PatientError::PatientDirCreation)?;
+
+        let msg = VprCommitMessage::new(
+            VprCommitDomain::Redaction(RedactionDomain::Record),
+            VprCommitAction::Create,
+            "Created redaction retention repository",
+            care_location,
+        )?;
+
+        let files = [FileToWrite {
+            relative_path: Path::new(GitIgnoreFile::NAME),
+            content: DEFAULT_GITIGNORE,
+            old_content: None,
+        }];
+
+        VersionedFileService::init_and_commit(
+            &redaction_dir,
+            author,
+            &msg,
+            &files,
+            self.cfg.timestamp_authority(),
+            self.cfg.mirror(),
+        )
+    }
+
+    /// Returns the path to the RRR root directory (`{patient_data_dir}/redaction`).
+    fn redaction_root_dir(&self) -> PathBuf {
+        self.cfg.redaction_dir()
+    }
+
+    /// Returns the path to this RRR (`{redaction_root_dir}/{s1}/{s2}/{source_id}`).
+    fn redaction_dir(&self) -> PathBuf {
+        self.source_id.sharded_dir(&self.redaction_root_dir())
+    }
+
+    /// Reads and parses a `redaction.yaml` record.
+    fn record_read(path: &Path) -> PatientResult<RedactionRecord> {
+        let content = fs::read_to_string(path).map_err(PatientError::FileRead)?;
+        serde_yaml::from_str(&content).map_err(PatientError::YamlDeserialization)
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EmailAddress, NonEmptyText};
+    use tempfile::TempDir;
+
+    fn setup_test_env() -> (TempDir, Arc<CoreConfig>, Author) {
+        let temp_dir = TempDir::new().unwrap();
+
+        let cfg = Arc::new(
+            CoreConfig::new(
+                temp_dir.path().to_path_buf(),
+                openehr::RmVersion::rm_1_1_0,
+                NonEmptyText::new("test-namespace").unwrap(),
+            )
+            .unwrap(),
+        );
+
+        let author = Author {
+            name: NonEmptyText::new("Dr. Test").unwrap(),
+            role: NonEmptyText::new("Clinician").unwrap(),
+            email: EmailAddress::parse("test@example.com").unwrap(),
+            registrations: vec![],
+            signature: None,
+            certificate: None,
+        };
+
+        (temp_dir, cfg, author)
+    }
+
+    /// Creates a bare routine repository with one committed file.
+    fn routine_repo(cfg: &CoreConfig, author: &Author) -> (Uuid, PathBuf) {
+        let source_id = Uuid::new_v4();
+        let dir = ShardableUuid::from_uuid(source_id).sharded_dir(&cfg.clinical_dir());
+        fs::create_dir_all(&dir).unwrap();
+
+        let msg = VprCommitMessage::new(
+            VprCommitDomain::Clinical(crate::versioned_files::ClinicalDomain::Record),
+            VprCommitAction::Create,
+            "Created note",
+            NonEmptyText::new("Test Hospital").unwrap(),
+        )
+        .unwrap();
+        let files = [FileToWrite {
+            relative_path: Path::new("notes/note.md"),
+            content: "wrong patient",
+            old_content: None,
+        }];
+        VersionedFileService::init_and_commit(&dir, author, &msg, &files, None, None).unwrap();
+
+        (source_id, dir)
+    }
+
+    fn letter_item() -> RedactedItem {
+        RedactedItem::Letter {
+            letter_id: TimestampIdGenerator::generate(None).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_redaction_reason_round_trip() {
+        for reason in [
+            RedactionReason::WrongPatient,
+            RedactionReason::Misfiled,
+            RedactionReason::EnteredInError,
+            RedactionReason::ConsentWithdrawn,
+            RedactionReason::PolicyConstraint,
+        ] {
+            assert_eq!(reason.as_str().parse::<RedactionReason>().unwrap(), reason);
+        }
+        assert!("deleted".parse::<RedactionReason>().is_err());
+    }
+
+    #[test]
+    fn test_redact_moves_file_into_rrr_with_cross_referenced_commits() {
+        let (_temp_dir, cfg, author) = setup_test_env();
+        let (source_id, source_dir) = routine_repo(&cfg, &author);
+        let service = RedactionService::with_id(cfg.clone(), source_id);
+
+        assert!(service.list_redactions().unwrap().is_empty());
+
+        let note_path = Path::new("notes/note.md");
+        let removals = [FileToRemove {
+            relative_path: note_path,
+            old_content: "wrong patient",
+        }];
+        let redaction_id = service
+            .redact(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                VprCommitDomain::Clinical(crate::versioned_files::ClinicalDomain::Record),
+                &RepositoryLock::acquire(&source_dir).unwrap(),
+                RedactionRequest {
+                    item: letter_item(),
+                    reason: RedactionReason::WrongPatient,
+                    reason_detail: Some(NonEmptyText::new("Belongs to bed 4").unwrap()),
+                    retained: vec![RetainedFile {
+                        path: note_path.to_path_buf(),
+                        content: "wrong patient".to_string(),
+                    }],
+                    attachment_hashes: vec![],
+                    source_files: None,
+                    writes: &[],
+                    removals: &removals,
+                },
+            )
+            .unwrap();
+
+        // Routine side: file gone (with its now-empty directory), tombstone present.
+        assert!(!source_dir.join(note_path).exists());
+        assert!(!source_dir.join("notes").exists());
+        let tombstone: RedactionTombstone = serde_yaml::from_str(
+            &fs::read_to_string(source_dir.join(tombstone_path(&redaction_id))).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(tombstone.redaction_id.to_string(), redaction_id.to_string());
+
+        // RRR side: record and content retained.
+        let retained = service.read_redaction(&redaction_id).unwrap();
+        assert_eq!(retained.record.source, VprRepositoryKind::Clinical);
+        assert_eq!(retained.record.source_id, source_id);
+        assert_eq!(retained.record.reason, RedactionReason::WrongPatient);
+        assert_eq!(retained.files.len(), 1);
+        assert_eq!(retained.files[0].content, "wrong patient");
+        assert_eq!(service.list_redactions().unwrap(), vec![retained.record]);
+
+        // Both commits carry the same Redaction-Id and point at each other.
+        let rrr_history = service.commit_history(None).unwrap();
+        assert_eq!(rrr_history.len(), 2);
+        assert_eq!(
+            rrr_history[0].message.as_ref().unwrap().action(),
+            VprCommitAction::Redact
+        );
+        assert_eq!(rrr_history[0].commit_id, tombstone.retention_commit);
+
+        let routine_history = VersionedFileService::commit_history(
+            &cfg.clinical_dir(),
+            &source_id.simple().to_string(),
+            VprRepositoryKind::Clinical,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let routine_trailers = routine_history[0].message.as_ref().unwrap().trailers();
+        let trailer = |key: &str| {
+            routine_trailers
+                .iter()
+                .find(|t| t.key() == key)
+                .map(|t| t.value().to_string())
+        };
+        assert_eq!(
+            routine_history[0].message.as_ref().unwrap().action(),
+            VprCommitAction::Redact
+        );
+        assert_eq!(trailer("Redaction-Id"), Some(redaction_id.to_string()));
+        assert_eq!(
+            trailer("Redaction-Commit"),
+            Some(tombstone.retention_commit)
+        );
+        assert!(rrr_history[0]
+            .message
+            .as_ref()
+            .unwrap()
+            .trailers()
+            .iter()
+            .any(|t| t.key() == "Redaction-Source"
+                && t.value() == format!("clinical/{}", source_id.simple())));
+    }
+
+    /// Builds a request that redacts the note written by [`routine_repo`].
+    fn note_request<'a>(
+        item: RedactedItem,
+        removals: &'a [FileToRemove<'a>],
+    ) -> RedactionRequest<'a> {
+        RedactionRequest {
+            item,
+            reason: RedactionReason::WrongPatient,
+            reason_detail: None,
+            retained: vec![RetainedFile {
+                path: PathBuf::from("notes/note.md"),
+                content: "wrong patient".to_string(),
+            }],
+            attachment_hashes: vec![],
+            source_files: None,
+            writes: &[],
+            removals,
+        }
+    }
+
+    #[test]
+    fn test_redact_retry_after_failed_routine_commit_reuses_record() {
+        let (_temp_dir, cfg, mut author) = setup_test_env();
+        author.registrations = vec![AuthorRegistration::new("GMC", "1234567").unwrap()];
+        let (source_id, source_dir) = routine_repo(&cfg, &author);
+        let service = RedactionService::with_id(cfg.clone(), source_id);
+        let item = letter_item();
+        let removals = [FileToRemove {
+            relative_path: Path::new("notes/note.md"),
+            old_content: "wrong patient",
+        }];
+        let domain = VprCommitDomain::Clinical(crate::versioned_files::ClinicalDomain::Record);
+        let care_location = NonEmptyText::new("Test Hospital").unwrap();
+
+        // A file where the tombstone directory belongs makes the routine commit fail after
+        // the RRR commit has been made.
+        fs::write(source_dir.join("redactions"), "blocker").unwrap();
+        let result = service.redact(
+            &author,
+            care_location.clone(),
+            domain,
+            &RepositoryLock::acquire(&source_dir).unwrap(),
+            note_request(item.clone(), &removals),
+        );
+        assert!(result.is_err());
+        assert!(source_dir.join("notes/note.md").exists());
+        let records = service.list_redactions().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].redacted_by_registrations, author.registrations);
+
+        fs::remove_file(source_dir.join("redactions")).unwrap();
+        let redaction_id = service
+            .redact(
+                &author,
+                care_location,
+                domain,
+                &RepositoryLock::acquire(&source_dir).unwrap(),
+                note_request(item, &removals),
+            )
+            .unwrap();
+
+        // The retry completed the first redaction instead of retaining the item again.
+        assert_eq!(service.list_redactions().unwrap(), records);
+        assert_eq!(redaction_id, records[0].redaction_id);
+        assert_eq!(service.commit_history(None).unwrap().len(), 2);
+
+        let tombstone: RedactionTombstone = serde_yaml::from_str(
+            &fs::read_to_string(source_dir.join(tombstone_path(&redaction_id))).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            tombstone.retention_commit,
+            service.commit_history(None).unwrap()[0].commit_id
+        );
+        assert!(!source_dir.join("notes/note.md").exists());
+    }
+
+    #[test]
+    fn test_routine_history_excludes_redacted_paths() {
+        let (_temp_dir, cfg, author) = setup_test_env();
+        let (source_id, source_dir) = routine_repo(&cfg, &author);
+        let clinical =
+            crate::repositories::clinical::ClinicalService::with_id(cfg.clone(), source_id);
+        let removals = [FileToRemove {
+            relative_path: Path::new("notes/note.md"),
+            old_content: "wrong patient",
+        }];
+
+        assert_eq!(
+            clinical
+                .commit_history(Some(Path::new("notes/note.md")))
+                .unwrap()
+                .len(),
+            1
+        );
+
+        RedactionService::with_id(cfg.clone(), source_id)
+            .redact(
+                &author,
+                NonEmptyText::new("Test Hospital").unwrap(),
+                VprCommitDomain::Clinical(crate::versioned_files::ClinicalDomain::Record),
+                &RepositoryLock::acquire(&source_dir).unwrap(),
+                note_request(letter_item(), &removals),
+            )
+            .unwrap();
+
+        for path in ["notes/note.md", "notes"] {
+            assert!(clinical
+                .commit_history(Some(Path::new(path)))
+                .unwrap()
+                .is_empty());
+        }
+        assert_eq!(clinical.commit_history(None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_read_redaction_unknown_id() {
+        let (_temp_dir, cfg, _author) = setup_test_env();
+        let service = RedactionService::with_id(cfg, Uuid::new_v4());
+
+        let result = service.read_redaction(&TimestampIdGenerator::generate(None).unwrap());
+        assert!(matches!(result, Err(PatientError::InvalidInput(_))));
+    }
+}
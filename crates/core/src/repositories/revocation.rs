@@ -0,0 +1,325 @@
+//! Certificate revocation repository management.
+//!
+//! This module keeps the certificate revocation lists (CRLs) that signature verification
+//! checks commit signing certificates against. See [`crate::revocation`] for how the lists
+//! are applied.
+//!
+//! ## Architecture
+//!
+//! There is one revocation repository per VPR instance. Like every other VPR repository it is
+//! a Git repository with signed, structured commits, so each change to the revocation lists
+//! records who made it and when. It is created by the first published CRL.
+//!
+//! Each issuing CA has exactly one CRL, replaced as a whole whenever the CA revokes another
+//! certificate. CRLs are produced and signed by `vpr-certificates`; this repository only
+//! stores them.
+//!
+//! ## Storage Layout
+//!
+//! ```text
+//! revocation/
+//!   crls/
+//!     <issuer-hash>.crl.pem    # SHA-256 of the DER issuer name
+//!   .git/
+//! ```
+
+use crate::author::Author;
+use crate::config::CoreConfig;
+use crate::error::{PatientError, PatientResult};
+use crate::lock::RepositoryLock;
+use crate::revocation::RevocationList;
+use crate::versioned_files::{
+    FileToWrite, RevocationDomain, VersionedFileService, VprCommitAction, VprCommitDomain,
+    VprCommitMessage,
+};
+use crate::NonEmptyText;
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use vpr_uuid::Sha256Hash;
+use x509_parser::prelude::*;
+
+/// Directory (in the revocation repository) holding one CRL per issuing CA.
+const CRLS_DIR: &str = "crls";
+
+/// Service for the certificate revocation repository.
+#[derive(Clone, Debug)]
+pub struct RevocationService {
+    cfg: Arc<CoreConfig>,
+}
+
+impl RevocationService {
+    /// Creates a service for the revocation repository.
+    ///
+    /// The repository does not need to exist yet; it is created by the first published CRL.
+    pub fn new(cfg: Arc<CoreConfig>) -> Self {
+        Self { cfg }
+    }
+
+    /// Returns the stored CRL for the CA with the given certificate, if it has published one.
+    ///
+    /// # Arguments
+    ///
+    /// * `ca_cert_pem` - PEM certificate of the issuing CA
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::InvalidInput` if the certificate cannot be parsed, or
+    /// `PatientError::FileRead` if the stored CRL cannot be read.
+    pub fn crl_for_issuer(&self, ca_cert_pem: &str) -> PatientResult<Option<String>> {
+        let (_, pem) = parse_x509_pem(ca_cert_pem.as_bytes())
+            .map_err(|e| PatientError::InvalidInput(format!("invalid CA certificate: {e}")))?;
+        let (_, ca) = parse_x509_certificate(&pem.contents)
+            .map_err(|e| PatientError::InvalidInput(format!("invalid CA certificate: {e}")))?;
+
+        let path = self.repository_dir().join(crl_path(ca.subject().as_raw()));
+        match fs::read_to_string(&path) {
+            Ok(crl) => Ok(Some(crl)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(PatientError::FileRead(e)),
+        }
+    }
+
+    /// Stores a CRL, replacing any earlier CRL from the same issuer, and commits it.
+    ///
+    /// The new CRL must have a higher CRL number than the one it replaces and must still list
+    /// every certificate the earlier CRL revoked, so revocations cannot be rolled back by
+    /// publishing a stale list.
+    ///
+    /// # Arguments
+    ///
+    /// * `author` - Author of the commit
+    /// * `care_location` - Organisational location recorded in the commit
+    /// * `crl_pem` - Signed CRL, as produced by `vpr-certificates`
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::InvalidInput` if the CRL cannot be parsed or would roll back an
+    /// earlier CRL, and the errors of the underlying Git operations otherwise.
+    pub fn publish_crl(
+        &self,
+        author: &Author,
+        care_location: NonEmptyText,
+        crl_pem: &str,
+    ) -> PatientResult<()> {
+        let new = parse_crl(crl_pem)?;
+        let relative_path = crl_path(&new.issuer);
+        let repository_dir = self.repository_dir();
+        let lock = if repository_dir.join(".git").exists() {
+            Some(RepositoryLock::acquire(&repository_dir)?)
+        } else {
+            None
+        };
+
+        let old_content = match fs::read_to_string(repository_dir.join(&relative_path)) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(PatientError::FileRead(e)),
+        };
+        if let Some(old_content) = &old_content {
+            let old = parse_crl(old_content)?;
+            if new.number <= old.number {
+                return Err(PatientError::InvalidInput(format!(
+                    "CRL number {} does not follow stored CRL number {}",
+                    new.number, old.number
+                )));
+            }
+            if old
+                .serials
+                .iter()
+                .any(|serial| !new.serials.contains(serial))
+            {
+                return Err(PatientError::InvalidInput(
+                    "CRL omits certificates revoked by the stored CRL".into(),
+                ));
+            }
+        }
+
+        let files = [FileToWrite {
+            relative_path: &relative_path,
+            content: crl_pem,
+            old_content: old_content.as_deref(),
+        }];
+
+        if let Some(lock) = &lock {
+            let msg = VprCommitMessage::new(
+                VprCommitDomain::Revocation(RevocationDomain::Record),
+                VprCommitAction::Update,
+                "Updated certificate revocation list",
+                care_location,
+            )?;
+            VersionedFileService::write_and_commit_files(
+                lock,
+                author,
+                &msg,
+                &files,
+                self.cfg.timestamp_authority(),
+                self.cfg.mirror(),
+            )?;
+        } else {
+            fs::create_dir_all(&repository_dir).map_err(PatientError::PatientDirCreation)?;
+            let msg = VprCommitMessage::new(
+                VprCommitDomain::Revocation(RevocationDomain::Record),
+                VprCommitAction::Create,
+                "Published certificate revocation list",
+                care_location,
+            )?;
+            VersionedFileService::init_and_commit(
+                &repository_dir,
+                author,
+                &msg,
+                &files,
+                self.cfg.timestamp_authority(),
+                self.cfg.mirror(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns every revocation that applies to signature verification.
+    ///
+    /// This is the local CRL configured in [`CoreConfig::revocation_list`], if any, together
+    /// with every CRL in the revocation repository. The repository is read on each call, so
+    /// newly published revocations take effect without a restart.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::FileRead` if a stored CRL cannot be read, or
+    /// `PatientError::InvalidInput` if one cannot be parsed.
+    pub fn revocation_list(&self) -> PatientResult<RevocationList> {
+        let mut list = self.cfg.revocation_list().cloned().unwrap_or_default();
+
+        let crls_dir = self.repository_dir().join(CRLS_DIR);
+        let entries = match fs::read_dir(&crls_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(list),
+            Err(e) => return Err(PatientError::FileRead(e)),
+        };
+        for entry in entries {
+            let path = entry.map_err(PatientError::FileRead)?.path();
+            if path.extension().is_some_and(|ext| ext == "pem") {
+                list.extend(&RevocationList::from_file(&path)?);
+            }
+        }
+
+        Ok(list)
+    }
+
+    /// Returns the path to the revocation repository (`{patient_data_dir}/revocation`).
+    fn repository_dir(&self) -> PathBuf {
+        self.cfg.revocation_dir()
+    }
+}
+
+/// The parts of a stored CRL checked before it is replaced.
+struct ParsedCrl {
+    issuer: Vec<u8>,
+    number: u64,
+    serials: Vec<Vec<u8>>,
+}
+
+fn parse_crl(crl_pem: &str) -> PatientResult<ParsedCrl> {
+    let invalid = |e: String| PatientError::InvalidInput(format!("invalid CRL: {e}"));
+
+    let (_, pem) = parse_x509_pem(crl_pem.as_bytes()).map_err(|e| invalid(e.to_string()))?;
+    let (_, crl) = parse_x509_crl(&pem.contents).map_err(|e| invalid(e.to_string()))?;
+    let number = crl
+        .crl_number()
+        .and_then(|n| u64::try_from(n).ok())
+        .ok_or_else(|| invalid("missing CRL number".into()))?;
+
+    Ok(ParsedCrl {
+        issuer: crl.issuer().as_raw().to_vec(),
+        number,
+        serials: crl
+            .iter_revoked_certificates()
+            .map(|revoked| revoked.serial().to_bytes_be())
+            .collect(),
+    })
+}
+
+/// Returns the repository-relative path of the CRL for `issuer` (a DER-encoded name).
+fn crl_path(issuer: impl AsRef<[u8]>) -> PathBuf {
+    let digest: [u8; 32] = Sha256::digest(issuer.as_ref()).into();
+    Path::new(CRLS_DIR).join(format!("{}.crl.pem", Sha256Hash::from_bytes(&digest)))
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EmailAddress;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+    use vpr_certificates::CertificateAuthority;
+
+    fn setup_test_env() -> (TempDir, Arc<CoreConfig>, Author) {
+        let temp_dir = TempDir::new().unwrap();
+
+        let cfg = Arc::new(
+            CoreConfig::new(
+                temp_dir.path().to_path_buf(),
+                openehr::RmVersion::rm_1_1_0,
+                NonEmptyText::new("test-namespace").unwrap(),
+            )
+            .unwrap(),
+        );
+
+        let author = Author {
+            name: NonEmptyText::new("Security Officer").unwrap(),
+            role: NonEmptyText::new("Administrator").unwrap(),
+            email: EmailAddress::parse("security@example.com").unwrap(),
+            registrations: vec![],
+            signature: None,
+            certificate: None,
+        };
+
+        (temp_dir, cfg, author)
+    }
+
+    fn location() -> NonEmptyText {
+        NonEmptyText::new("Test Hospital").unwrap()
+    }
+
+    #[test]
+    fn published_crls_are_stored_per_issuer_and_read_back() {
+        let (_temp_dir, cfg, author) = setup_test_env();
+        let service = RevocationService::new(cfg);
+        let ca = CertificateAuthority::create_root("VPR Root CA").unwrap();
+        let (first, _) = ca.issue("Dr Jane Smith", "GMC", "1234567").unwrap();
+        let (second, _) = ca.issue("Dr John Doe", "GMC", "7654321").unwrap();
+
+        assert!(service.revocation_list().unwrap().is_empty());
+        assert_eq!(service.crl_for_issuer(ca.cert_pem()).unwrap(), None);
+
+        let crl = ca.revoke(None, &first, SystemTime::now()).unwrap();
+        service.publish_crl(&author, location(), &crl).unwrap();
+        assert_eq!(
+            service.crl_for_issuer(ca.cert_pem()).unwrap(),
+            Some(crl.clone())
+        );
+
+        let updated = ca.revoke(Some(&crl), &second, SystemTime::now()).unwrap();
+        service.publish_crl(&author, location(), &updated).unwrap();
+
+        let list = service.revocation_list().unwrap();
+        let now = chrono::Utc::now() + chrono::Duration::seconds(1);
+        for cert in [&first, &second] {
+            assert_eq!(
+                list.status(cert.as_bytes(), now),
+                crate::revocation::RevocationStatus::RevokedBeforeSigning
+            );
+        }
+
+        // Republishing the older CRL would un-revoke the second certificate.
+        let err = service.publish_crl(&author, location(), &crl).unwrap_err();
+        assert!(matches!(err, PatientError::InvalidInput(_)));
+    }
+}
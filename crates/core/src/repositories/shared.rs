@@ -8,14 +8,20 @@
 //! - **Directory Operations**: Utilities for creating unique patient directories
 //!   (`create_uuid_and_shard_dir`) and recursive copying (`copy_dir_recursive`)
 //! - **Git Integration**: Functions for adding files to Git index (`add_directory_to_index`)
+//! - **Listing**: Ordering and page-size helpers shared by listing operations, and
+//!   enumeration of the sharded repositories of one type (`sharded_repository_dirs`)
 
+use crate::constants::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
 use crate::error::{PatientError, PatientResult};
 use crate::ShardableUuid;
+use chrono::{DateTime, Utc};
 use std::{
     fs,
     io::{self, ErrorKind},
     path::{Path, PathBuf},
 };
+use uuid::Uuid;
+use vpr_uuid::TimestampId;
 
 /// Creates a unique sharded directory within the base records directory.
 ///
@@ -94,6 +100,27 @@ pub(crate) fn create_uuid_and_shard_dir(
     create_uuid_and_shard_dir_with_source(base_dir, ShardableUuid::new)
 }
 
+/// Sort key that orders timestamp IDs chronologically, using the UUID as a tie-breaker.
+pub(crate) fn timestamp_id_order_key(timestamp_id: &TimestampId) -> (DateTime<Utc>, Uuid) {
+    (timestamp_id.timestamp(), *timestamp_id.uuid())
+}
+
+/// Resolves a requested page size, applying the default and rejecting out-of-range values.
+///
+/// # Errors
+///
+/// Returns `PatientError::InvalidInput` if the limit is zero or greater than [`MAX_PAGE_SIZE`].
+pub(crate) fn page_limit(limit: Option<usize>) -> PatientResult<usize> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(PatientError::InvalidInput(format!(
+            "Page limit must be between 1 and {}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    Ok(limit)
+}
+
 /// Recursively copies a directory and its contents to a destination.
 ///
 /// This function creates the destination directory if it doesn't exist and
@@ -130,6 +157,36 @@ pub fn copy_dir_recursive(src: &Path, dst: &Path) -> std::io::Result<()> {
     Ok(())
 }
 
+/// Lists the `<s1>/<s2>/<id>` directories under a repository type directory, sorted.
+pub(crate) fn sharded_repository_dirs(type_dir: &Path) -> PatientResult<Vec<PathBuf>> {
+    let mut dirs = Vec::new();
+    for s1 in subdirectories(type_dir)? {
+        for s2 in subdirectories(&s1)? {
+            dirs.extend(subdirectories(&s2)?);
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Lists the subdirectories of `dir`, or nothing if it does not exist.
+fn subdirectories(dir: &Path) -> PatientResult<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(PatientError::FileRead(e)),
+    };
+
+    let mut dirs = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(PatientError::FileRead)?.path();
+        if path.is_dir() {
+            dirs.push(path);
+        }
+    }
+    Ok(dirs)
+}
+
 /// Adds all files in a directory to a Git index recursively.
 ///
 /// This function traverses the directory tree and adds all files to the Git index,
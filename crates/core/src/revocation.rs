@@ -0,0 +1,217 @@
+//! Revocation checks for commit signing certificates.
+//!
+//! ## Purpose
+//! A signing key that has been compromised can still produce valid signatures. This module
+//! reads X.509 certificate revocation lists (CRLs) and reports whether a commit's signing
+//! certificate was revoked, and if so whether the commit was signed before or after the
+//! revocation took effect.
+//!
+//! ## Intended use
+//! Revocations come from two places, which are merged before verification:
+//! - a local CRL file configured at startup (see
+//!   [`crate::config::revocation_list_from_env_value`]), and
+//! - the CRLs kept in the VPR-managed revocation repository (see
+//!   [`crate::repositories::revocation::RevocationService`]).
+//!
+//! Signature verification in [`crate::versioned_files`] reports the outcome as
+//! [`crate::versioned_files::CommitSignatureStatus::RevokedBeforeSigning`] or
+//! [`crate::versioned_files::CommitSignatureStatus::RevokedAfterSigning`].
+//!
+//! CRL signatures are not checked here: a CRL can only withdraw trust, and the revocation
+//! repository only accepts CRLs through signed commits.
+
+use crate::error::{PatientError, PatientResult};
+use chrono::{DateTime, Utc};
+use std::path::Path;
+use x509_parser::prelude::*;
+
+/// Whether a signing certificate had been revoked when a commit was signed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RevocationStatus {
+    /// The certificate is not listed in any CRL.
+    NotRevoked,
+    /// The certificate was revoked, but only after the commit was signed.
+    RevokedAfterSigning,
+    /// The certificate had already been revoked when the commit was signed.
+    RevokedBeforeSigning,
+}
+
+/// One revoked certificate, identified by its issuer and serial number.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct RevokedEntry {
+    /// DER encoding of the issuer name.
+    issuer: Vec<u8>,
+    /// Serial number without leading zero bytes.
+    serial: Vec<u8>,
+    revoked_at: DateTime<Utc>,
+}
+
+/// Revoked certificates gathered from one or more CRLs.
+#[derive(Clone, Debug, Default)]
+pub struct RevocationList {
+    entries: Vec<RevokedEntry>,
+}
+
+impl RevocationList {
+    /// Parses CRLs from PEM (one or more `X509 CRL` blocks) or a single DER CRL.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::InvalidInput` if a CRL cannot be parsed, or if PEM input holds
+    /// no CRLs.
+    pub fn from_pem(bytes: &[u8]) -> PatientResult<Self> {
+        let invalid = || PatientError::InvalidInput("revocation list could not be parsed".into());
+
+        let ders = if is_pem(bytes) {
+            let mut ders = Vec::new();
+            for pem in Pem::iter_from_buffer(bytes) {
+                let pem = pem.map_err(|_| invalid())?;
+                if pem.label == "X509 CRL" {
+                    ders.push(pem.contents);
+                }
+            }
+            if ders.is_empty() {
+                return Err(PatientError::InvalidInput(
+                    "revocation list holds no CRLs".into(),
+                ));
+            }
+            ders
+        } else {
+            vec![bytes.to_vec()]
+        };
+
+        let mut list = Self::default();
+        for der in &ders {
+            let (_, crl) = parse_x509_crl(der).map_err(|_| invalid())?;
+            for revoked in crl.iter_revoked_certificates() {
+                list.entries.push(RevokedEntry {
+                    issuer: crl.issuer().as_raw().to_vec(),
+                    serial: normalise_serial(revoked.raw_serial()),
+                    revoked_at: DateTime::from_timestamp(revoked.revocation_date.timestamp(), 0)
+                        .ok_or_else(invalid)?,
+                });
+            }
+        }
+        Ok(list)
+    }
+
+    /// Reads CRLs from `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::FileRead` if the file cannot be read, or the errors of
+    /// [`from_pem`](Self::from_pem).
+    pub fn from_file(path: &Path) -> PatientResult<Self> {
+        let bytes = std::fs::read(path).map_err(PatientError::FileRead)?;
+        Self::from_pem(&bytes)
+    }
+
+    /// Adds the revocations in `other` to this list.
+    pub fn extend(&mut self, other: &RevocationList) {
+        self.entries.extend(other.entries.iter().cloned());
+    }
+
+    /// Returns `true` if no certificates are revoked.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Checks a commit signing certificate against this list.
+    ///
+    /// # Arguments
+    ///
+    /// * `certificate` - Certificate bytes embedded in the commit (PEM or DER). Only the first
+    ///   certificate of a PEM chain is checked.
+    /// * `signed_at` - Commit time.
+    ///
+    /// # Returns
+    ///
+    /// [`RevocationStatus::RevokedBeforeSigning`] if the certificate was revoked at or before
+    /// `signed_at`. A certificate that cannot be parsed is reported as not revoked; it fails
+    /// the other signature checks instead.
+    pub fn status(&self, certificate: &[u8], signed_at: DateTime<Utc>) -> RevocationStatus {
+        let der = if is_pem(certificate) {
+            match parse_x509_pem(certificate) {
+                Ok((_, pem)) => pem.contents,
+                Err(_) => return RevocationStatus::NotRevoked,
+            }
+        } else {
+            certificate.to_vec()
+        };
+        let Ok((_, cert)) = X509Certificate::from_der(&der) else {
+            return RevocationStatus::NotRevoked;
+        };
+
+        let issuer = cert.issuer().as_raw();
+        let serial = normalise_serial(cert.raw_serial());
+        let revoked_at = self
+            .entries
+            .iter()
+            .filter(|entry| entry.issuer == issuer && entry.serial == serial)
+            .map(|entry| entry.revoked_at)
+            .min();
+
+        match revoked_at {
+            None => RevocationStatus::NotRevoked,
+            Some(revoked_at) if revoked_at <= signed_at => RevocationStatus::RevokedBeforeSigning,
+            Some(_) => RevocationStatus::RevokedAfterSigning,
+        }
+    }
+}
+
+fn is_pem(bytes: &[u8]) -> bool {
+    bytes
+        .windows("-----BEGIN".len())
+        .any(|w| w == b"-----BEGIN")
+}
+
+/// Strips the sign padding from a DER serial so serials compare by value.
+fn normalise_serial(raw: &[u8]) -> Vec<u8> {
+    let start = raw.iter().position(|&b| b != 0).unwrap_or(raw.len());
+    raw[start..].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use std::time::SystemTime;
+    use vpr_certificates::CertificateAuthority;
+
+    #[test]
+    fn reports_revocation_relative_to_signing_time() {
+        let ca = CertificateAuthority::create_root("VPR Root CA").unwrap();
+        let (revoked, _) = ca.issue("Dr Jane Smith", "GMC", "1234567").unwrap();
+        let (other, _) = ca.issue("Dr John Doe", "GMC", "7654321").unwrap();
+
+        let revoked_at = Utc::now() - Duration::days(1);
+        let crl = ca
+            .revoke(None, &revoked, SystemTime::from(revoked_at))
+            .unwrap();
+
+        let list = RevocationList::from_pem(crl.as_bytes()).unwrap();
+        assert!(!list.is_empty());
+        assert_eq!(
+            list.status(revoked.as_bytes(), revoked_at - Duration::hours(1)),
+            RevocationStatus::RevokedAfterSigning
+        );
+        assert_eq!(
+            list.status(revoked.as_bytes(), Utc::now()),
+            RevocationStatus::RevokedBeforeSigning
+        );
+        assert_eq!(
+            list.status(other.as_bytes(), Utc::now()),
+            RevocationStatus::NotRevoked
+        );
+
+        // Entries only match certificates from the CRL issuer.
+        let other_ca = CertificateAuthority::create_root("Other CA").unwrap();
+        let (foreign, _) = other_ca.issue("Dr Jane Smith", "GMC", "1234567").unwrap();
+        assert_eq!(
+            list.status(foreign.as_bytes(), Utc::now()),
+            RevocationStatus::NotRevoked
+        );
+
+        assert!(RevocationList::from_pem(revoked.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,532 @@
+//! Staged creation of records that span several repositories.
+//!
+//! ## Purpose
+//! A full patient record is a demographics, a clinical and a coordination repository that
+//! refer to each other. Creating them in place one after another leaves orphaned repositories
+//! behind if a later step fails or the process dies part way through. Instead, each
+//! repository is created under a private staging directory, and they are all moved into
+//! their sharded locations only once every step has succeeded.
+//!
+//! ## Layout
+//!
+//! ```text
+//! <patient_data_dir>/.staging/
+//!   <txn>.lock                          # Held by the process creating the record
+//!   <txn>/
+//!     journal.json                      # State of the transaction
+//!     demographics/<s1>/<s2>/<uuid>     # Staged repositories, laid out like the data directory
+//!     clinical/<s1>/<s2>/<uuid>
+//!     coordination/<s1>/<s2>/<uuid>
+//! ```
+//!
+//! The staging directory is inside the patient data directory, so moving a repository into
+//! place is a rename on the same filesystem.
+//!
+//! ## Journal
+//! The journal starts in the `staging` state. Once every repository exists it is replaced,
+//! atomically, by one in the `committing` state that lists the staged repositories; that is
+//! the commit point. Only then are the repositories moved into place and pushed to the
+//! mirror, after which the transaction directory is removed.
+//!
+//! ## Recovery
+//! [`recover`] deals with transactions left behind by a process that died. Those still
+//! `staging` (or without a journal) are deleted; those `committing` have their remaining
+//! repositories moved into place. A transaction whose lock file is still locked belongs to a
+//! live process and is left alone.
+
+use crate::config::CoreConfig;
+use crate::constants::JOURNAL_FILENAME;
+use crate::error::{PatientError, PatientResult};
+use crate::versioned_files::VprRepositoryKind;
+use crate::ShardableUuid;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Extension of the lock file held alongside each transaction directory.
+const LOCK_EXTENSION: &str = "lock";
+
+/// Progress of a staged transaction, as recorded in its journal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum JournalState {
+    /// Repositories are being created; the transaction can be discarded.
+    Staging,
+    /// Every repository exists; the transaction must be completed.
+    Committing,
+}
+
+/// Contents of `journal.json` in a transaction directory.
+#[derive(Debug, Serialize, Deserialize)]
+struct Journal {
+    id: Uuid,
+    state: JournalState,
+    repositories: Vec<StagedRepository>,
+}
+
+/// One repository created by a staged transaction.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct StagedRepository {
+    kind: VprRepositoryKind,
+    /// Repository UUID, as named on disk.
+    id: String,
+}
+
+/// Transactions found and finished by [`recover`].
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct RecoveryReport {
+    /// Transactions that had not reached their commit point, and were deleted.
+    pub rolled_back: Vec<Uuid>,
+    /// Transactions past their commit point, whose repositories were moved into place.
+    pub completed: Vec<Uuid>,
+}
+
+/// A transaction creating repositories under the staging directory.
+///
+/// Services given [`config`](Self::config) create their repositories inside the transaction.
+/// Nothing is visible under the patient data directory until [`commit`](Self::commit).
+#[derive(Debug)]
+pub(crate) struct StagedTransaction {
+    cfg: Arc<CoreConfig>,
+    staged_cfg: Arc<CoreConfig>,
+    id: Uuid,
+    dir: PathBuf,
+    // Holding the open file holds the OS lock that tells recovery the transaction is live.
+    _lock: File,
+}
+
+impl StagedTransaction {
+    /// Starts a transaction under the staging directory of `cfg`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::StorageDirCreation` if the transaction directory cannot be
+    /// created, and `PatientError::FileWrite` if its lock or journal cannot be written.
+    pub(crate) fn begin(cfg: Arc<CoreConfig>) -> PatientResult<Self> {
+        let staging_dir = cfg.staging_dir();
+        fs::create_dir_all(&staging_dir).map_err(PatientError::StorageDirCreation)?;
+
+        // Lock before creating the directory, so recovery never sees it unlocked.
+        let id = Uuid::new_v4();
+        let lock = open_lock(&staging_dir, id)?;
+        lock.try_lock()
+            .map_err(|e| PatientError::FileWrite(e.into()))?;
+
+        let dir = transaction_dir(&staging_dir, id);
+        fs::create_dir(&dir).map_err(PatientError::StorageDirCreation)?;
+        write_journal(
+            &dir,
+            &Journal {
+                id,
+                state: JournalState::Staging,
+                repositories: Vec::new(),
+            },
+        )?;
+
+        Ok(Self {
+            staged_cfg: Arc::new(cfg.staged(dir.clone())),
+            cfg,
+            id,
+            dir,
+            _lock: lock,
+        })
+    }
+
+    /// Returns the configuration that creates repositories inside this transaction.
+    pub(crate) fn config(&self) -> Arc<CoreConfig> {
+        self.staged_cfg.clone()
+    }
+
+    /// Returns the transaction directory.
+    pub(crate) fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Moves the staged `repositories` into their sharded locations and pushes them to the
+    /// mirror, if one is configured.
+    ///
+    /// If this fails after the commit point, the transaction is left for [`recover`] to
+    /// complete rather than discarded.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::FileWrite` if the journal cannot be updated,
+    /// `PatientError::StagedRepositoryMove` if a repository cannot be moved into place, and
+    /// I/O errors if the transaction directory cannot be removed afterwards.
+    pub(crate) fn commit(
+        self,
+        repositories: &[(VprRepositoryKind, &ShardableUuid)],
+    ) -> PatientResult<()> {
+        let journal = Journal {
+            id: self.id,
+            state: JournalState::Committing,
+            repositories: repositories
+                .iter()
+                .map(|(kind, id)| StagedRepository {
+                    kind: *kind,
+                    id: id.to_string(),
+                })
+                .collect(),
+        };
+        if let Err(e) = write_journal(&self.dir, &journal) {
+            // The journal still says `staging`, so nothing has been committed.
+            if let Err(cleanup_error) = self.abort() {
+                tracing::warn!("Failed to discard staged record: {}", cleanup_error);
+            }
+            return Err(e);
+        }
+
+        complete(&self.cfg, &self.dir, &journal)?;
+        remove_transaction(&self.cfg.staging_dir(), self.id).map_err(PatientError::FileWrite)
+    }
+
+    /// Discards the transaction and every repository created in it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if the transaction directory cannot be removed.
+    pub(crate) fn abort(self) -> io::Result<()> {
+        remove_transaction(&self.cfg.staging_dir(), self.id)
+    }
+}
+
+/// Finishes or discards every staged transaction left behind by a process that died.
+///
+/// Transactions still being worked on by a live process are skipped.
+///
+/// # Errors
+///
+/// Returns `PatientError::FileRead` or `PatientError::Deserialization` if a journal cannot
+/// be read, `PatientError::StagedRepositoryMove` if a repository cannot be moved into place,
+/// and `PatientError::FileWrite` if a transaction cannot be locked or removed.
+pub fn recover(cfg: &CoreConfig) -> PatientResult<RecoveryReport> {
+    let staging_dir = cfg.staging_dir();
+    let mut report = RecoveryReport::default();
+
+    let entries = match fs::read_dir(&staging_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(report),
+        Err(e) => return Err(PatientError::FileRead(e)),
+    };
+
+    // A transaction may have a directory, a lock file, or both, depending on where it stopped.
+    let mut ids = BTreeSet::new();
+    for entry in entries {
+        let path = entry.map_err(PatientError::FileRead)?.path();
+        if let Some(id) = path
+            .file_stem()
+            .and_then(|stem| Uuid::parse_str(&stem.to_string_lossy()).ok())
+        {
+            ids.insert(id);
+        }
+    }
+
+    for id in ids {
+        let lock = open_lock(&staging_dir, id)?;
+        match lock.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => continue,
+            Err(TryLockError::Error(e)) => return Err(PatientError::FileWrite(e)),
+        }
+
+        let dir = transaction_dir(&staging_dir, id);
+        if dir.exists() {
+            match read_journal(&dir)? {
+                Some(journal) if journal.state == JournalState::Committing => {
+                    complete(cfg, &dir, &journal)?;
+                    report.completed.push(id);
+                }
+                _ => report.rolled_back.push(id),
+            }
+        }
+        remove_transaction(&staging_dir, id).map_err(PatientError::FileWrite)?;
+    }
+
+    Ok(report)
+}
+
+/// Moves the repositories listed in a `committing` journal into place, then pushes them to
+/// the mirror. Repositories already moved by an earlier attempt are skipped.
+fn complete(cfg: &CoreConfig, dir: &Path, journal: &Journal) -> PatientResult<()> {
+    let mut targets = Vec::with_capacity(journal.repositories.len());
+    for repository in &journal.repositories {
+        let uuid = ShardableUuid::parse(&repository.id)?;
+        let kind = repository.kind.as_str();
+        let staged = uuid.sharded_dir(&dir.join(kind));
+        let target = uuid.sharded_dir(&cfg.patient_data_dir().join(kind));
+
+        if staged.exists() {
+            if target.exists() {
+                return Err(PatientError::StagedRepositoryMove(io::Error::new(
+                    ErrorKind::AlreadyExists,
+                    format!("{} already exists", target.display()),
+                )));
+            }
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(PatientError::StagedRepositoryMove)?;
+            }
+            fs::rename(&staged, &target).map_err(PatientError::StagedRepositoryMove)?;
+        }
+        targets.push(target);
+    }
+
+    if let Some(mirror) = cfg.mirror() {
+        for target in targets {
+            // As for any commit, a failed push is left for replication reconciliation.
+            if let Err(e) = Repository::open(&target)
+                .map_err(PatientError::GitOpen)
+                .and_then(|repo| mirror.push(&repo))
+            {
+                tracing::warn!("Failed to push {} to mirror: {}", target.display(), e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn transaction_dir(staging_dir: &Path, id: Uuid) -> PathBuf {
+    staging_dir.join(id.simple().to_string())
+}
+
+fn lock_path(staging_dir: &Path, id: Uuid) -> PathBuf {
+    transaction_dir(staging_dir, id).with_extension(LOCK_EXTENSION)
+}
+
+fn open_lock(staging_dir: &Path, id: Uuid) -> PatientResult<File> {
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path(staging_dir, id))
+        .map_err(PatientError::FileWrite)
+}
+
+/// Removes a transaction directory, then its lock file.
+fn remove_transaction(staging_dir: &Path, id: Uuid) -> io::Result<()> {
+    for result in [
+        fs::remove_dir_all(transaction_dir(staging_dir, id)),
+        fs::remove_file(lock_path(staging_dir, id)),
+    ] {
+        match result {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the journal in `dir` with `journal`, so a crash leaves either the old or the new one.
+fn write_journal(dir: &Path, journal: &Journal) -> PatientResult<()> {
+    let json = serde_json::to_vec_pretty(journal).map_err(PatientError::Serialization)?;
+    let path = dir.join(JOURNAL_FILENAME);
+    let tmp = path.with_extension("json.tmp");
+
+    let mut file = File::create(&tmp).map_err(PatientError::FileWrite)?;
+    file.write_all(&json).map_err(PatientError::FileWrite)?;
+    file.sync_all().map_err(PatientError::FileWrite)?;
+    fs::rename(&tmp, &path).map_err(PatientError::FileWrite)
+}
+
+/// Reads the journal in `dir`, or `None` if the transaction stopped before writing one.
+fn read_journal(dir: &Path) -> PatientResult<Option<Journal>> {
+    match fs::read(dir.join(JOURNAL_FILENAME)) {
+        Ok(json) => serde_json::from_slice(&json)
+            .map(Some)
+            .map_err(PatientError::Deserialization),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(PatientError::FileRead(e)),
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::author::Author;
+    use crate::patient::{FullRecord, PatientService};
+    use crate::repositories::shared::sharded_repository_dirs;
+    use crate::{EmailAddress, NonEmptyText};
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Arc<CoreConfig>, Author) {
+        let temp_dir = TempDir::new().unwrap();
+        let cfg = CoreConfig::new(
+            temp_dir.path().to_path_buf(),
+            openehr::RmVersion::rm_1_1_0,
+            NonEmptyText::new("test-namespace").unwrap(),
+        )
+        .unwrap();
+
+        let author = Author {
+            name: NonEmptyText::new("Test Author").unwrap(),
+            role: NonEmptyText::new("Clinician").unwrap(),
+            email: EmailAddress::parse("test@example.com").unwrap(),
+            registrations: vec![],
+            signature: None,
+            certificate: None,
+        };
+
+        (temp_dir, Arc::new(cfg), author)
+    }
+
+    fn create_full_record(
+        cfg: Arc<CoreConfig>,
+        author: &Author,
+        namespace: Option<&str>,
+    ) -> PatientResult<FullRecord> {
+        PatientService::new(cfg).initialise_full_record(
+            author.clone(),
+            NonEmptyText::new("Test Hospital").unwrap(),
+            vec![NonEmptyText::new("Ada").unwrap()],
+            NonEmptyText::new("Lovelace").unwrap(),
+            NaiveDate::from_ymd_opt(1815, 12, 10).unwrap(),
+            namespace.map(|ns| NonEmptyText::new(ns).unwrap()),
+        )
+    }
+
+    /// Stages a full record in `transaction` without moving it into place.
+    fn stage(transaction: &StagedTransaction, author: &Author) -> Vec<(VprRepositoryKind, String)> {
+        let staged = PatientService::new(transaction.config())
+            .create_full_record(
+                author.clone(),
+                NonEmptyText::new("Test Hospital").unwrap(),
+                vec![NonEmptyText::new("Ada").unwrap()],
+                NonEmptyText::new("Lovelace").unwrap(),
+                NaiveDate::from_ymd_opt(1815, 12, 10).unwrap(),
+                None,
+            )
+            .unwrap();
+        vec![
+            (
+                VprRepositoryKind::Demographics,
+                staged.demographics_uuid.to_string(),
+            ),
+            (
+                VprRepositoryKind::Clinical,
+                staged.clinical_uuid.to_string(),
+            ),
+            (
+                VprRepositoryKind::Coordination,
+                staged.coordination_uuid.to_string(),
+            ),
+        ]
+    }
+
+    fn repository_count(cfg: &CoreConfig) -> usize {
+        [
+            cfg.demographics_dir(),
+            cfg.clinical_dir(),
+            cfg.coordination_dir(),
+        ]
+        .iter()
+        .map(|dir| sharded_repository_dirs(dir).unwrap().len())
+        .sum()
+    }
+
+    fn staging_entries(cfg: &CoreConfig) -> usize {
+        fs::read_dir(cfg.staging_dir()).unwrap().count()
+    }
+
+    #[test]
+    fn full_record_is_moved_into_place_and_staging_is_cleared() {
+        let (_temp_dir, cfg, author) = setup();
+
+        let record = create_full_record(cfg.clone(), &author, None).unwrap();
+
+        assert!(record
+            .demographics_uuid
+            .sharded_dir(&cfg.demographics_dir())
+            .join(".git")
+            .exists());
+        assert!(record
+            .clinical_uuid
+            .sharded_dir(&cfg.clinical_dir())
+            .join(".git")
+            .exists());
+        assert!(record
+            .coordination_uuid
+            .sharded_dir(&cfg.coordination_dir())
+            .join(".git")
+            .exists());
+        assert_eq!(staging_entries(&cfg), 0);
+    }
+
+    #[test]
+    fn failed_step_leaves_no_orphaned_repositories() {
+        let (_temp_dir, cfg, author) = setup();
+
+        // Linking fails after the demographics and clinical repositories have been created.
+        let result = create_full_record(cfg.clone(), &author, Some("bad/namespace"));
+
+        assert!(matches!(result, Err(PatientError::Openehr(_))));
+        assert_eq!(repository_count(&cfg), 0);
+        assert_eq!(staging_entries(&cfg), 0);
+    }
+
+    #[test]
+    fn recover_completes_committing_and_discards_staging_transactions() {
+        let (_temp_dir, cfg, author) = setup();
+
+        // Crashed after the commit point, with one repository already moved.
+        let committing = StagedTransaction::begin(cfg.clone()).unwrap();
+        let committing_id = committing.id;
+        let repositories = stage(&committing, &author);
+        let journal = Journal {
+            id: committing_id,
+            state: JournalState::Committing,
+            repositories: repositories
+                .iter()
+                .map(|(kind, id)| StagedRepository {
+                    kind: *kind,
+                    id: id.clone(),
+                })
+                .collect(),
+        };
+        write_journal(committing.dir(), &journal).unwrap();
+        let (kind, id) = &repositories[0];
+        let uuid = ShardableUuid::parse(id).unwrap();
+        let target = uuid.sharded_dir(&cfg.patient_data_dir().join(kind.as_str()));
+        fs::create_dir_all(target.parent().unwrap()).unwrap();
+        fs::rename(
+            uuid.sharded_dir(&committing.dir().join(kind.as_str())),
+            &target,
+        )
+        .unwrap();
+        drop(committing);
+
+        // Crashed before the commit point.
+        let staging = StagedTransaction::begin(cfg.clone()).unwrap();
+        let staging_id = staging.id;
+        stage(&staging, &author);
+        drop(staging);
+
+        // Still being worked on.
+        let live = StagedTransaction::begin(cfg.clone()).unwrap();
+
+        let report = recover(&cfg).unwrap();
+
+        assert_eq!(report.completed, vec![committing_id]);
+        assert_eq!(report.rolled_back, vec![staging_id]);
+        assert_eq!(repository_count(&cfg), 3);
+        for (kind, id) in &repositories {
+            let dir = ShardableUuid::parse(id)
+                .unwrap()
+                .sharded_dir(&cfg.patient_data_dir().join(kind.as_str()));
+            assert!(dir.join(".git").exists());
+        }
+        assert!(live.dir().join(JOURNAL_FILENAME).exists());
+
+        live.abort().unwrap();
+        assert_eq!(staging_entries(&cfg), 0);
+    }
+}
@@ -0,0 +1,758 @@
+//! RFC 3161 trusted timestamps for commits.
+//!
+//! ## Purpose
+//! A commit's time is set by the machine that creates it, so on its own it proves nothing. A
+//! timestamp token from a Time-Stamping Authority (TSA) is an independent, signed statement that
+//! the commit existed at a given time. Verification uses that time, rather than the commit time,
+//! to decide whether a signing certificate had already been revoked, and reports commits whose
+//! commit time disagrees with their token.
+//!
+//! ## Intended use
+//! Configure a TSA at startup (see [`crate::config::timestamp_authority_from_env_value`]) and
+//! attach it to [`crate::CoreConfig`]. Every commit then carries a token over its unsigned commit
+//! buffer in the `vpr-timestamp` commit header. The header is added before the commit is signed,
+//! so the commit signature covers the token as well.
+//!
+//! Tokens are checked by
+//! [`crate::versioned_files::VersionedFileService::verify_signature_chain`]. When TSA trust
+//! anchors are configured (see [`crate::CoreConfig::with_timestamp_trust_anchors`]) the TSA
+//! certificate must also chain to one of them.
+
+use crate::error::{PatientError, PatientResult};
+use crate::trust::{CertificateTrustError, TrustAnchors};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use cms::cert::CertificateChoices;
+use cms::content_info::ContentInfo;
+use cms::signed_data::{SignedData, SignerIdentifier};
+use der::asn1::{Any, Int, ObjectIdentifier, OctetString, Uint};
+use der::{
+    Decode, DecodeValue, Encode, EncodeValue, FixedTag, Header, Length, Reader, Sequence, Tag,
+    Writer,
+};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::borrow::Cow;
+use std::fmt;
+use std::io::Read;
+use std::time::Duration;
+use thiserror::Error;
+use x509_cert::ext::pkix::SubjectKeyIdentifier;
+use x509_cert::spki::AlgorithmIdentifierOwned;
+use x509_cert::Certificate;
+use x509_parser::der_parser::asn1_rs::{BitString, Oid};
+use x509_parser::prelude::{FromDer, X509Certificate};
+use x509_parser::x509::AlgorithmIdentifier;
+
+/// Commit header holding the base64 DER timestamp token.
+pub(crate) const TIMESTAMP_HEADER: &str = "vpr-timestamp";
+
+/// Largest accepted difference, in seconds, between a commit's time and its token's time.
+///
+/// The token is requested as the commit is made, so a larger gap means the commit time was
+/// misrepresented.
+pub(crate) const MAX_CLOCK_SKEW_SECONDS: i64 = 300;
+
+/// How long to wait for a TSA to answer.
+const TSA_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest TSA response read, in bytes.
+const MAX_RESPONSE_BYTES: u64 = 1024 * 1024;
+
+const ID_SHA_256: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.1");
+const ID_SHA_384: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.2");
+const ID_SHA_512: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.3");
+const ID_SIGNED_DATA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.7.2");
+const ID_CT_TST_INFO: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.1.4");
+const ID_CONTENT_TYPE: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.3");
+const ID_MESSAGE_DIGEST: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.4");
+const ID_SUBJECT_KEY_IDENTIFIER: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.29.14");
+const RSA_ENCRYPTION: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+const SHA_256_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.11");
+const SHA_384_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.12");
+const SHA_512_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.13");
+
+/// A Time-Stamping Authority that answers RFC 3161 requests.
+pub trait TimestampAuthority: fmt::Debug + Send + Sync {
+    /// Sends a DER-encoded `TimeStampReq` and returns the DER-encoded `TimeStampResp`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::TimestampAuthority` if the TSA cannot be reached or refuses the
+    /// request.
+    fn request(&self, request: &[u8]) -> PatientResult<Vec<u8>>;
+}
+
+/// A TSA reached over HTTP or HTTPS using the RFC 3161 HTTP transport.
+#[derive(Clone, Debug)]
+pub struct HttpTimestampAuthority {
+    url: String,
+}
+
+impl HttpTimestampAuthority {
+    /// Creates a client for the TSA at `url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::InvalidInput` if `url` is not an `http://` or `https://` URL.
+    pub fn new(url: &str) -> PatientResult<Self> {
+        let url = url.trim();
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(PatientError::InvalidInput(format!(
+                "timestamp authority URL must use http or https: {url}"
+            )));
+        }
+        Ok(Self {
+            url: url.to_string(),
+        })
+    }
+
+    /// The TSA endpoint.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl TimestampAuthority for HttpTimestampAuthority {
+    fn request(&self, request: &[u8]) -> PatientResult<Vec<u8>> {
+        let failed = |e: String| PatientError::TimestampAuthority(format!("{}: {e}", self.url));
+
+        let response = ureq::post(&self.url)
+            .timeout(TSA_TIMEOUT)
+            .set("Content-Type", "application/timestamp-query")
+            .send_bytes(request)
+            .map_err(|e| failed(e.to_string()))?;
+
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .take(MAX_RESPONSE_BYTES)
+            .read_to_end(&mut body)
+            .map_err(|e| failed(e.to_string()))?;
+        Ok(body)
+    }
+}
+
+/// Reasons a timestamp token is rejected.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TimestampError {
+    #[error("timestamp token could not be parsed")]
+    Malformed,
+    #[error("timestamp token does not cover the commit")]
+    ImprintMismatch,
+    #[error("timestamp token signature does not verify")]
+    InvalidSignature,
+    #[error("timestamp authority is not trusted: {0}")]
+    Untrusted(CertificateTrustError),
+}
+
+/// Obtains a timestamp token over `message` from `tsa`.
+///
+/// The response is checked before it is returned: it must carry a token whose signature
+/// verifies, which covers `message`, and which echoes the request nonce.
+///
+/// # Returns
+///
+/// The DER-encoded token (a CMS `ContentInfo`).
+///
+/// # Errors
+///
+/// Returns `PatientError::TimestampAuthority` if the TSA cannot be reached, refuses the
+/// request, or answers with a token that fails these checks.
+pub(crate) fn request_token(
+    tsa: &dyn TimestampAuthority,
+    message: &[u8],
+) -> PatientResult<Vec<u8>> {
+    let failed = |e: &str| PatientError::TimestampAuthority(e.to_string());
+
+    let nonce =
+        Uint::new(&rand::random::<u64>().to_be_bytes()).map_err(|e| failed(&e.to_string()))?;
+    let request = TimeStampReq {
+        version: 1,
+        message_imprint: MessageImprint::sha256(message),
+        req_policy: None,
+        nonce: Some(nonce.clone()),
+        cert_req: true,
+    };
+    let request = request.to_der().map_err(|e| failed(&e.to_string()))?;
+
+    let response = tsa.request(&request)?;
+    let response = TimeStampResp::from_der(&response)
+        .map_err(|_| failed("timestamp response could not be parsed"))?;
+    // 0 = granted, 1 = granted with modifications.
+    if response.status.status > 1 {
+        let reason = response
+            .status
+            .status_string
+            .map(|text| text.join("; "))
+            .unwrap_or_default();
+        return Err(PatientError::TimestampAuthority(format!(
+            "request rejected with status {}: {reason}",
+            response.status.status
+        )));
+    }
+
+    let token = response
+        .time_stamp_token
+        .ok_or_else(|| failed("timestamp response holds no token"))?
+        .to_der()
+        .map_err(|e| failed(&e.to_string()))?;
+    let (tst_info, _) = verify_token_signature(&token).map_err(|e| failed(&e.to_string()))?;
+    if !tst_info.message_imprint.covers(message) || tst_info.nonce != Some(nonce) {
+        return Err(failed("timestamp token does not match the request"));
+    }
+
+    Ok(token)
+}
+
+/// Verifies a timestamp token over `message` and returns the time it asserts.
+///
+/// # Arguments
+///
+/// * `token` - DER-encoded token, as returned by [`request_token`].
+/// * `message` - The data the token must cover.
+/// * `trust_anchors` - When given, the TSA certificate must chain to one of these anchors, be
+///   valid at the asserted time and permit time stamping (see
+///   [`TrustAnchors::verify_timestamping`]).
+///
+/// # Errors
+///
+/// Returns the first [`TimestampError`] found.
+pub(crate) fn verify_token(
+    token: &[u8],
+    message: &[u8],
+    trust_anchors: Option<&TrustAnchors>,
+) -> Result<DateTime<Utc>, TimestampError> {
+    let (tst_info, certificates) = verify_token_signature(token)?;
+    if !tst_info.message_imprint.covers(message) {
+        return Err(TimestampError::ImprintMismatch);
+    }
+
+    let time = tst_info.gen_time.0;
+    if let Some(trust_anchors) = trust_anchors {
+        trust_anchors
+            .verify_timestamping(&certificates, time)
+            .map_err(TimestampError::Untrusted)?;
+    }
+
+    Ok(time)
+}
+
+/// Checks the CMS signature of a token.
+///
+/// # Returns
+///
+/// The signed `TSTInfo` and the DER certificates carried in the token, the TSA signing
+/// certificate first.
+fn verify_token_signature(token: &[u8]) -> Result<(TstInfo, Vec<Vec<u8>>), TimestampError> {
+    let malformed = |_| TimestampError::Malformed;
+
+    let content_info = ContentInfo::from_der(token).map_err(malformed)?;
+    if content_info.content_type != ID_SIGNED_DATA {
+        return Err(TimestampError::Malformed);
+    }
+    let signed_data: SignedData = content_info.content.decode_as().map_err(malformed)?;
+
+    let encapsulated = &signed_data.encap_content_info;
+    if encapsulated.econtent_type != ID_CT_TST_INFO {
+        return Err(TimestampError::Malformed);
+    }
+    let tst_der = encapsulated
+        .econtent
+        .as_ref()
+        .ok_or(TimestampError::Malformed)?
+        .decode_as::<OctetString>()
+        .map_err(malformed)?
+        .into_bytes();
+    let tst_info = TstInfo::from_der(&tst_der).map_err(malformed)?;
+
+    let signer = match signed_data.signer_infos.0.as_slice() {
+        [signer] => signer,
+        _ => return Err(TimestampError::Malformed),
+    };
+    let signed_attrs = signer
+        .signed_attrs
+        .as_ref()
+        .ok_or(TimestampError::Malformed)?;
+    let attribute = |oid: ObjectIdentifier| {
+        signed_attrs
+            .iter()
+            .find(|attr| attr.oid == oid)
+            .and_then(|attr| match attr.values.as_slice() {
+                [value] => Some(value),
+                _ => None,
+            })
+    };
+    let content_type = attribute(ID_CONTENT_TYPE)
+        .and_then(|value| value.decode_as::<ObjectIdentifier>().ok())
+        .ok_or(TimestampError::Malformed)?;
+    if content_type != ID_CT_TST_INFO {
+        return Err(TimestampError::Malformed);
+    }
+    let message_digest = attribute(ID_MESSAGE_DIGEST)
+        .and_then(|value| value.decode_as::<OctetString>().ok())
+        .ok_or(TimestampError::Malformed)?;
+    if digest(&signer.digest_alg.oid, &tst_der).as_deref() != Some(message_digest.as_bytes()) {
+        return Err(TimestampError::InvalidSignature);
+    }
+
+    let mut certificates: Vec<&Certificate> = signed_data
+        .certificates
+        .iter()
+        .flat_map(|set| set.0.iter())
+        .filter_map(|choice| match choice {
+            CertificateChoices::Certificate(cert) => Some(cert),
+            _ => None,
+        })
+        .collect();
+    let signer_index = certificates
+        .iter()
+        .position(|cert| identifies(&signer.sid, cert))
+        .ok_or(TimestampError::InvalidSignature)?;
+    let signer_cert = certificates.remove(signer_index);
+    certificates.insert(0, signer_cert);
+    let certificates = certificates
+        .into_iter()
+        .map(|cert| cert.to_der())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(malformed)?;
+
+    let (_, cert) =
+        X509Certificate::from_der(&certificates[0]).map_err(|_| TimestampError::Malformed)?;
+    let signed_bytes = signed_attrs.to_der().map_err(malformed)?;
+    let algorithm = signature_algorithm(signer.signature_algorithm.oid, signer.digest_alg.oid);
+    let algorithm = AlgorithmIdentifier {
+        algorithm: Oid::new(Cow::Borrowed(algorithm.as_bytes())),
+        parameters: None,
+    };
+    x509_parser::verify::verify_signature(
+        cert.public_key(),
+        &algorithm,
+        &BitString::new(0, signer.signature.as_bytes()),
+        &signed_bytes,
+    )
+    .map_err(|_| TimestampError::InvalidSignature)?;
+
+    Ok((tst_info, certificates))
+}
+
+/// Returns `true` if `cert` is the certificate named by a CMS signer identifier.
+fn identifies(sid: &SignerIdentifier, cert: &Certificate) -> bool {
+    let tbs = &cert.tbs_certificate;
+    match sid {
+        SignerIdentifier::IssuerAndSerialNumber(id) => {
+            tbs.issuer == id.issuer && tbs.serial_number == id.serial_number
+        }
+        SignerIdentifier::SubjectKeyIdentifier(ski) => tbs
+            .extensions
+            .iter()
+            .flatten()
+            .filter(|ext| ext.extn_id == ID_SUBJECT_KEY_IDENTIFIER)
+            .any(|ext| {
+                SubjectKeyIdentifier::from_der(ext.extn_value.as_bytes()).is_ok_and(|id| id == *ski)
+            }),
+    }
+}
+
+/// Maps a CMS signature algorithm to the X.509 signature algorithm `x509-parser` verifies.
+///
+/// CMS allows plain `rsaEncryption` with the hash named by the digest algorithm.
+fn signature_algorithm(signature: ObjectIdentifier, digest: ObjectIdentifier) -> ObjectIdentifier {
+    match (signature, digest) {
+        (RSA_ENCRYPTION, ID_SHA_256) => SHA_256_WITH_RSA,
+        (RSA_ENCRYPTION, ID_SHA_384) => SHA_384_WITH_RSA,
+        (RSA_ENCRYPTION, ID_SHA_512) => SHA_512_WITH_RSA,
+        _ => signature,
+    }
+}
+
+/// Hashes `data` with the digest algorithm `oid`, or returns `None` if it is unsupported.
+fn digest(oid: &ObjectIdentifier, data: &[u8]) -> Option<Vec<u8>> {
+    match *oid {
+        ID_SHA_256 => Some(Sha256::digest(data).to_vec()),
+        ID_SHA_384 => Some(Sha384::digest(data).to_vec()),
+        ID_SHA_512 => Some(Sha512::digest(data).to_vec()),
+        _ => None,
+    }
+}
+
+// ============================================================================
+// RFC 3161 STRUCTURES
+// ============================================================================
+
+/// `MessageImprint ::= SEQUENCE { hashAlgorithm AlgorithmIdentifier, hashedMessage OCTET STRING }`
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+struct MessageImprint {
+    hash_algorithm: AlgorithmIdentifierOwned,
+    hashed_message: OctetString,
+}
+
+impl MessageImprint {
+    /// The SHA-256 imprint of `message`.
+    fn sha256(message: &[u8]) -> Self {
+        Self {
+            hash_algorithm: AlgorithmIdentifierOwned {
+                oid: ID_SHA_256,
+                parameters: None,
+            },
+            hashed_message: OctetString::new(Sha256::digest(message).to_vec())
+                .expect("a SHA-256 digest is a valid OCTET STRING"),
+        }
+    }
+
+    /// Returns `true` if this is the imprint of `message`.
+    fn covers(&self, message: &[u8]) -> bool {
+        digest(&self.hash_algorithm.oid, message).as_deref() == Some(self.hashed_message.as_bytes())
+    }
+}
+
+/// `TimeStampReq`, without the request extensions VPR never sends.
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+struct TimeStampReq {
+    version: u8,
+    message_imprint: MessageImprint,
+    #[asn1(optional = "true")]
+    req_policy: Option<ObjectIdentifier>,
+    #[asn1(optional = "true")]
+    nonce: Option<Uint>,
+    #[asn1(default = "Default::default")]
+    cert_req: bool,
+}
+
+/// `PKIStatusInfo`, the outcome of a timestamp request.
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+struct PkiStatusInfo {
+    status: u32,
+    #[asn1(optional = "true")]
+    status_string: Option<Vec<String>>,
+    #[asn1(optional = "true")]
+    fail_info: Option<der::asn1::BitString>,
+}
+
+/// `TimeStampResp ::= SEQUENCE { status PKIStatusInfo, timeStampToken TimeStampToken OPTIONAL }`
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+struct TimeStampResp {
+    status: PkiStatusInfo,
+    #[asn1(optional = "true")]
+    time_stamp_token: Option<ContentInfo>,
+}
+
+/// `TSTInfo`, the content signed by the TSA.
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+struct TstInfo {
+    version: u8,
+    policy: ObjectIdentifier,
+    message_imprint: MessageImprint,
+    serial_number: Int,
+    gen_time: GenTime,
+    #[asn1(optional = "true")]
+    accuracy: Option<Accuracy>,
+    #[asn1(default = "Default::default")]
+    ordering: bool,
+    #[asn1(optional = "true")]
+    nonce: Option<Uint>,
+    #[asn1(context_specific = "0", tag_mode = "EXPLICIT", optional = "true")]
+    tsa: Option<Any>,
+    #[asn1(context_specific = "1", tag_mode = "IMPLICIT", optional = "true")]
+    extensions: Option<x509_cert::ext::Extensions>,
+}
+
+/// `Accuracy` of a `TSTInfo` time.
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+struct Accuracy {
+    #[asn1(optional = "true")]
+    seconds: Option<u32>,
+    #[asn1(context_specific = "0", tag_mode = "IMPLICIT", optional = "true")]
+    millis: Option<u16>,
+    #[asn1(context_specific = "1", tag_mode = "IMPLICIT", optional = "true")]
+    micros: Option<u16>,
+}
+
+/// A `GeneralizedTime` that may carry fractional seconds.
+///
+/// TSAs commonly include fractions, which `der::asn1::GeneralizedTime` rejects.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct GenTime(DateTime<Utc>);
+
+impl GenTime {
+    fn encoded(&self) -> String {
+        self.0.format("%Y%m%d%H%M%SZ").to_string()
+    }
+}
+
+impl FixedTag for GenTime {
+    const TAG: Tag = Tag::GeneralizedTime;
+}
+
+impl<'a> DecodeValue<'a> for GenTime {
+    fn decode_value<R: Reader<'a>>(reader: &mut R, header: Header) -> der::Result<Self> {
+        let bytes = reader.read_vec(header.length)?;
+        std::str::from_utf8(&bytes)
+            .ok()
+            .and_then(|s| s.strip_suffix('Z'))
+            .and_then(|s| NaiveDateTime::parse_from_str(s, "%Y%m%d%H%M%S%.f").ok())
+            .map(|time| Self(time.and_utc()))
+            .ok_or_else(|| Self::TAG.value_error())
+    }
+}
+
+impl EncodeValue for GenTime {
+    fn value_len(&self) -> der::Result<Length> {
+        Length::try_from(self.encoded().len())
+    }
+
+    fn encode_value(&self, writer: &mut impl Writer) -> der::Result<()> {
+        writer.write(self.encoded().as_bytes())
+    }
+}
+
+// ============================================================================
+// LOCAL TSA (TESTS)
+// ============================================================================
+
+/// An in-process TSA for tests, with its own root CA.
+#[cfg(test)]
+#[derive(Debug)]
+pub(crate) struct LocalTimestampAuthority {
+    ca_pem: String,
+    certificate: Certificate,
+    signing_key: p256::ecdsa::SigningKey,
+    clock_offset: chrono::Duration,
+}
+
+#[cfg(test)]
+impl LocalTimestampAuthority {
+    /// Creates a TSA whose signing certificate is issued by a fresh root CA.
+    pub(crate) fn new() -> Self {
+        use p256::pkcs8::DecodePrivateKey;
+        use rcgen::{
+            BasicConstraints, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa, KeyPair,
+            KeyUsagePurpose,
+        };
+
+        let ca_key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::default();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "Local TSA Root");
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.key_usages = vec![KeyUsagePurpose::KeyCertSign];
+        let ca = params.self_signed(&ca_key).unwrap();
+
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::default();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "Local TSA");
+        params.key_usages = vec![KeyUsagePurpose::DigitalSignature];
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::TimeStamping];
+        let cert = params.signed_by(&key, &ca, &ca_key).unwrap();
+
+        Self {
+            ca_pem: ca.pem(),
+            certificate: Certificate::from_der(cert.der()).unwrap(),
+            signing_key: p256::ecdsa::SigningKey::from_pkcs8_pem(&key.serialize_pem()).unwrap(),
+            clock_offset: chrono::Duration::zero(),
+        }
+    }
+
+    /// Returns this TSA with a clock that runs `offset` ahead of the real time.
+    pub(crate) fn with_clock_offset(mut self, offset: chrono::Duration) -> Self {
+        self.clock_offset = offset;
+        self
+    }
+
+    /// PEM certificate of the root CA that issued the TSA certificate.
+    pub(crate) fn ca_pem(&self) -> &str {
+        &self.ca_pem
+    }
+
+    fn token(&self, request: &TimeStampReq) -> der::Result<ContentInfo> {
+        use cms::cert::IssuerAndSerialNumber;
+        use cms::content_info::CmsVersion;
+        use cms::signed_data::{CertificateSet, EncapsulatedContentInfo, SignerInfo, SignerInfos};
+        use der::asn1::SetOfVec;
+        use p256::ecdsa::signature::Signer;
+        use x509_cert::attr::Attribute;
+
+        let sha256 = AlgorithmIdentifierOwned {
+            oid: ID_SHA_256,
+            parameters: None,
+        };
+        let tst_info = TstInfo {
+            version: 1,
+            policy: ObjectIdentifier::new_unwrap("1.3.6.1.4.1.99999.1"),
+            message_imprint: request.message_imprint.clone(),
+            serial_number: Int::new(&rand::random::<u64>().to_be_bytes())?,
+            gen_time: GenTime(Utc::now() + self.clock_offset),
+            accuracy: None,
+            ordering: false,
+            nonce: request.nonce.clone(),
+            tsa: None,
+            extensions: None,
+        }
+        .to_der()?;
+
+        let attribute = |oid, value: Any| -> der::Result<Attribute> {
+            Ok(Attribute {
+                oid,
+                values: SetOfVec::try_from(vec![value])?,
+            })
+        };
+        let signed_attrs = SetOfVec::try_from(vec![
+            attribute(ID_CONTENT_TYPE, Any::encode_from(&ID_CT_TST_INFO)?)?,
+            attribute(
+                ID_MESSAGE_DIGEST,
+                Any::encode_from(&OctetString::new(Sha256::digest(&tst_info).to_vec())?)?,
+            )?,
+        ])?;
+        let signature: p256::ecdsa::Signature = self.signing_key.sign(&signed_attrs.to_der()?);
+
+        let signer = SignerInfo {
+            version: CmsVersion::V1,
+            sid: SignerIdentifier::IssuerAndSerialNumber(IssuerAndSerialNumber {
+                issuer: self.certificate.tbs_certificate.issuer.clone(),
+                serial_number: self.certificate.tbs_certificate.serial_number.clone(),
+            }),
+            digest_alg: sha256.clone(),
+            signed_attrs: Some(signed_attrs),
+            signature_algorithm: AlgorithmIdentifierOwned {
+                oid: ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.2"),
+                parameters: None,
+            },
+            signature: OctetString::new(signature.to_der().as_bytes())?,
+            unsigned_attrs: None,
+        };
+        let signed_data = SignedData {
+            version: CmsVersion::V3,
+            digest_algorithms: SetOfVec::try_from(vec![sha256])?,
+            encap_content_info: EncapsulatedContentInfo {
+                econtent_type: ID_CT_TST_INFO,
+                econtent: Some(Any::encode_from(&OctetString::new(tst_info)?)?),
+            },
+            certificates: Some(CertificateSet(SetOfVec::try_from(vec![
+                CertificateChoices::Certificate(self.certificate.clone()),
+            ])?)),
+            crls: None,
+            signer_infos: SignerInfos(SetOfVec::try_from(vec![signer])?),
+        };
+
+        Ok(ContentInfo {
+            content_type: ID_SIGNED_DATA,
+            content: Any::encode_from(&signed_data)?,
+        })
+    }
+}
+
+#[cfg(test)]
+impl TimestampAuthority for LocalTimestampAuthority {
+    fn request(&self, request: &[u8]) -> PatientResult<Vec<u8>> {
+        let failed = |e: der::Error| PatientError::TimestampAuthority(e.to_string());
+        let request = TimeStampReq::from_der(request).map_err(failed)?;
+        TimeStampResp {
+            status: PkiStatusInfo {
+                status: 0,
+                status_string: None,
+                fail_info: None,
+            },
+            time_stamp_token: Some(self.token(&request).map_err(failed)?),
+        }
+        .to_der()
+        .map_err(failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_cover_the_message_and_chain_to_the_tsa_root() {
+        let tsa = LocalTimestampAuthority::new();
+        let message = b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n";
+
+        let before = Utc::now() - chrono::Duration::seconds(1);
+        let token = request_token(&tsa, message).unwrap();
+        let anchors = TrustAnchors::from_pem(tsa.ca_pem().as_bytes()).unwrap();
+        let time = verify_token(&token, message, Some(&anchors)).unwrap();
+        assert!(time >= before && time <= Utc::now());
+
+        assert_eq!(
+            verify_token(&token, b"another commit", Some(&anchors)),
+            Err(TimestampError::ImprintMismatch)
+        );
+        let other = LocalTimestampAuthority::new();
+        let other_anchors = TrustAnchors::from_pem(other.ca_pem().as_bytes()).unwrap();
+        assert_eq!(
+            verify_token(&token, message, Some(&other_anchors)),
+            Err(TimestampError::Untrusted(
+                CertificateTrustError::UnknownIssuer
+            ))
+        );
+
+        let mut tampered = token.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 0x01;
+        assert!(verify_token(&tampered, message, None).is_err());
+    }
+
+    #[test]
+    fn generalized_time_accepts_fractional_seconds() {
+        let der = [&[0x18, 0x13][..], b"20240102030405.123Z"].concat();
+        let time = GenTime::from_der(&der).unwrap();
+        assert_eq!(
+            time.0,
+            DateTime::parse_from_rfc3339("2024-01-02T03:04:05.123Z").unwrap()
+        );
+        assert!(GenTime::from_der(&[&[0x18, 0x0e][..], b"20240102030405"].concat()).is_err());
+    }
+
+    #[test]
+    fn http_authority_posts_timestamp_queries() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::TcpListener;
+
+        assert!(HttpTimestampAuthority::new("ftp://tsa.example.com").is_err());
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/tsr", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let tsa = LocalTimestampAuthority::new();
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut content_length = 0;
+            let mut content_type = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(": ") {
+                    match name.to_ascii_lowercase().as_str() {
+                        "content-length" => content_length = value.parse().unwrap(),
+                        "content-type" => content_type = value.to_string(),
+                        _ => {}
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            let response = tsa.request(&body).unwrap();
+            let mut stream = reader.into_inner();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/timestamp-reply\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                response.len()
+            )
+            .unwrap();
+            stream.write_all(&response).unwrap();
+            content_type
+        });
+
+        let tsa = HttpTimestampAuthority::new(&url).unwrap();
+        let token = request_token(&tsa, b"commit").unwrap();
+        assert!(verify_token(&token, b"commit", None).is_ok());
+        assert_eq!(server.join().unwrap(), "application/timestamp-query");
+    }
+}
@@ -0,0 +1,523 @@
+//! Trust-anchor validation of commit signing certificates.
+//!
+//! ## Purpose
+//! A valid commit signature only proves that the commit was signed by the key embedded with it.
+//! This module checks that the certificate embedded alongside the key was issued by a configured
+//! trust anchor, was valid when the commit was made, permits digital signatures, and names the
+//! same professional registrations as the commit's `Author-Registration` trailers.
+//!
+//! ## Intended use
+//! Load [`TrustAnchors`] once at startup (see [`crate::config::trust_anchors_from_env_value`])
+//! and attach them to [`crate::CoreConfig`]. Signature verification in
+//! [`crate::versioned_files`] then reports commits whose certificates fail these checks as
+//! [`crate::versioned_files::CommitSignatureStatus::Untrusted`] or
+//! [`crate::versioned_files::CommitSignatureStatus::RegistrationMismatch`].
+
+use crate::author::AuthorRegistration;
+use crate::error::{PatientError, PatientResult};
+use chrono::{DateTime, Utc};
+use std::path::Path;
+use thiserror::Error;
+use x509_parser::prelude::*;
+
+/// Maximum number of intermediate certificates followed when building a chain.
+const MAX_CHAIN_DEPTH: usize = 8;
+
+/// Scheme of the subjectAltName URI carrying a professional registration.
+const REGISTRATION_URI_PREFIX: &str = "vpr://";
+
+/// Reasons a commit signing certificate is not trusted.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CertificateTrustError {
+    #[error("commit carries no signing certificate")]
+    MissingCertificate,
+    #[error("signing certificate could not be parsed")]
+    MalformedCertificate,
+    #[error("signing certificate does not chain to a trust anchor")]
+    UnknownIssuer,
+    #[error("certificate '{0}' was not valid at commit time")]
+    NotValidAtCommitTime(String),
+    #[error("signing certificate does not permit digital signatures")]
+    MissingDigitalSignatureUsage,
+    #[error("certificate '{0}' is not a CA but appears as an issuer")]
+    IssuerNotCa(String),
+    #[error("certificate '{0}' does not permit certificate signing")]
+    IssuerMissingCertSignUsage(String),
+    #[error("certificate '{0}' issues a chain longer than its path length constraint")]
+    PathLengthExceeded(String),
+    #[error("signing certificate is a CA certificate")]
+    LeafIsCa,
+    #[error("signing certificate registrations do not match the Author-Registration trailers")]
+    RegistrationMismatch,
+    #[error("timestamp authority certificate does not permit time stamping")]
+    MissingTimestampingUsage,
+}
+
+/// The set of CA certificates that commit signing certificates must chain to.
+///
+/// Every certificate in the set is trusted as an issuer, so a bundle may hold root and
+/// intermediate CAs. A commit may also embed intermediates after its signing certificate; those
+/// are used to build the chain but are not trusted on their own.
+#[derive(Clone, Debug, Default)]
+pub struct TrustAnchors {
+    certificates: Vec<Vec<u8>>,
+}
+
+impl TrustAnchors {
+    /// Parses a PEM bundle of trust anchor certificates.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::InvalidInput` if the bundle holds no certificates or a certificate
+    /// cannot be parsed.
+    pub fn from_pem(pem: &[u8]) -> PatientResult<Self> {
+        let certificates = parse_certificate_bytes(pem).map_err(|_| {
+            PatientError::InvalidInput("trust anchor bundle could not be parsed".into())
+        })?;
+        if certificates.is_empty() {
+            return Err(PatientError::InvalidInput(
+                "trust anchor bundle holds no certificates".into(),
+            ));
+        }
+        Ok(Self { certificates })
+    }
+
+    /// Reads a PEM bundle of trust anchor certificates from `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::FileRead` if the file cannot be read, or the errors of
+    /// [`from_pem`](Self::from_pem).
+    pub fn from_file(path: &Path) -> PatientResult<Self> {
+        let pem = std::fs::read(path).map_err(PatientError::FileRead)?;
+        Self::from_pem(&pem)
+    }
+
+    /// Checks a commit signing certificate against these anchors.
+    ///
+    /// # Arguments
+    ///
+    /// * `certificate` - Certificate bytes embedded in the commit (PEM or DER). A PEM may be
+    ///   followed by intermediate CA certificates.
+    /// * `signed_at` - Commit time; every certificate in the chain must be valid at this time.
+    /// * `registrations` - Registrations declared in the commit's `Author-Registration` trailers.
+    ///   These must be exactly the `vpr://` registrations in the signing certificate, so a
+    ///   commit cannot claim a registration its certificate does not vouch for.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`CertificateTrustError`] found.
+    pub fn verify(
+        &self,
+        certificate: &[u8],
+        signed_at: DateTime<Utc>,
+        registrations: &[AuthorRegistration],
+    ) -> Result<(), CertificateTrustError> {
+        let presented = parse_certificate_bytes(certificate)
+            .map_err(|_| CertificateTrustError::MalformedCertificate)?;
+        let (leaf_der, intermediates) = presented
+            .split_first()
+            .ok_or(CertificateTrustError::MalformedCertificate)?;
+        let leaf = parse_der(leaf_der)?;
+        let at = ASN1Time::from_timestamp(signed_at.timestamp())
+            .map_err(|_| CertificateTrustError::MalformedCertificate)?;
+
+        check_valid_at(&leaf, at)?;
+        let digital_signature = leaf
+            .key_usage()
+            .ok()
+            .flatten()
+            .is_some_and(|usage| usage.value.digital_signature());
+        if !digital_signature {
+            return Err(CertificateTrustError::MissingDigitalSignatureUsage);
+        }
+
+        self.build_chain(&leaf, intermediates, at)?;
+
+        let mut certified = certificate_registrations(&leaf);
+        let mut declared: Vec<(String, String)> = registrations
+            .iter()
+            .map(|r| (r.authority.to_string(), r.number.to_string()))
+            .collect();
+        certified.sort();
+        declared.sort();
+        if certified.is_empty() || certified != declared {
+            return Err(CertificateTrustError::RegistrationMismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Checks the certificate of an RFC 3161 timestamp authority against these anchors.
+    ///
+    /// # Arguments
+    ///
+    /// * `certificates` - DER certificates from the timestamp token, the TSA signing
+    ///   certificate first and any intermediate CA certificates after it.
+    /// * `timestamped_at` - Time asserted by the token; every certificate in the chain must be
+    ///   valid at this time.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`CertificateTrustError`] found, including
+    /// [`CertificateTrustError::MissingTimestampingUsage`] if the signing certificate lacks the
+    /// `timeStamping` extended key usage.
+    pub fn verify_timestamping(
+        &self,
+        certificates: &[Vec<u8>],
+        timestamped_at: DateTime<Utc>,
+    ) -> Result<(), CertificateTrustError> {
+        let (leaf_der, intermediates) = certificates
+            .split_first()
+            .ok_or(CertificateTrustError::MissingCertificate)?;
+        let leaf = parse_der(leaf_der)?;
+        let at = ASN1Time::from_timestamp(timestamped_at.timestamp())
+            .map_err(|_| CertificateTrustError::MalformedCertificate)?;
+
+        check_valid_at(&leaf, at)?;
+        let time_stamping = leaf
+            .extended_key_usage()
+            .ok()
+            .flatten()
+            .is_some_and(|usage| usage.value.time_stamping);
+        if !time_stamping {
+            return Err(CertificateTrustError::MissingTimestampingUsage);
+        }
+
+        self.build_chain(&leaf, intermediates, at)
+    }
+
+    /// Follows issuers from `leaf` until a trust anchor is reached.
+    ///
+    /// The leaf must not be a CA. Every issuer, the trust anchor included, must be a CA that
+    /// permits certificate signing and whose path length constraint allows the intermediate
+    /// CAs below it.
+    fn build_chain(
+        &self,
+        leaf: &X509Certificate<'_>,
+        intermediates: &[Vec<u8>],
+        at: ASN1Time,
+    ) -> Result<(), CertificateTrustError> {
+        let anchors = self
+            .certificates
+            .iter()
+            .map(|der| parse_der(der))
+            .collect::<Result<Vec<_>, _>>()?;
+        let intermediates = intermediates
+            .iter()
+            .map(|der| parse_der(der))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let issued = |child: &X509Certificate<'_>, issuer: &X509Certificate<'_>| {
+            child.issuer() == issuer.subject()
+                && child.verify_signature(Some(issuer.public_key())).is_ok()
+        };
+
+        if leaf.is_ca() {
+            return Err(CertificateTrustError::LeafIsCa);
+        }
+
+        // `depth` is the number of intermediate CAs between the leaf and the issuer found next.
+        let mut current = leaf;
+        for depth in 0..=MAX_CHAIN_DEPTH {
+            if let Some(anchor) = anchors.iter().find(|anchor| issued(current, anchor)) {
+                check_issuer(anchor, depth)?;
+                return check_valid_at(anchor, at);
+            }
+
+            let Some(next) = intermediates.iter().find(|cert| issued(current, cert)) else {
+                return Err(CertificateTrustError::UnknownIssuer);
+            };
+            check_issuer(next, depth)?;
+            check_valid_at(next, at)?;
+            current = next;
+        }
+
+        Err(CertificateTrustError::UnknownIssuer)
+    }
+}
+
+/// Checks that `issuer` may issue a chain with `ca_below` intermediate CAs beneath it.
+fn check_issuer(
+    issuer: &X509Certificate<'_>,
+    ca_below: usize,
+) -> Result<(), CertificateTrustError> {
+    let Some(constraints) = issuer
+        .basic_constraints()
+        .ok()
+        .flatten()
+        .map(|ext| ext.value)
+        .filter(|constraints| constraints.ca)
+    else {
+        return Err(CertificateTrustError::IssuerNotCa(
+            issuer.subject().to_string(),
+        ));
+    };
+    if constraints
+        .path_len_constraint
+        .is_some_and(|max| (max as usize) < ca_below)
+    {
+        return Err(CertificateTrustError::PathLengthExceeded(
+            issuer.subject().to_string(),
+        ));
+    }
+
+    let cert_sign = issuer
+        .key_usage()
+        .ok()
+        .flatten()
+        .is_some_and(|usage| usage.value.key_cert_sign());
+    if !cert_sign {
+        return Err(CertificateTrustError::IssuerMissingCertSignUsage(
+            issuer.subject().to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Parses PEM (one or more certificates) or a single DER certificate into DER blobs.
+fn parse_certificate_bytes(bytes: &[u8]) -> Result<Vec<Vec<u8>>, CertificateTrustError> {
+    let is_pem = bytes
+        .windows("-----BEGIN".len())
+        .any(|w| w == b"-----BEGIN");
+    if !is_pem {
+        parse_der(bytes)?;
+        return Ok(vec![bytes.to_vec()]);
+    }
+
+    let mut certificates = Vec::new();
+    for pem in Pem::iter_from_buffer(bytes) {
+        let pem = pem.map_err(|_| CertificateTrustError::MalformedCertificate)?;
+        if pem.label == "CERTIFICATE" {
+            parse_der(&pem.contents)?;
+            certificates.push(pem.contents);
+        }
+    }
+    Ok(certificates)
+}
+
+fn parse_der(der: &[u8]) -> Result<X509Certificate<'_>, CertificateTrustError> {
+    X509Certificate::from_der(der)
+        .map(|(_, cert)| cert)
+        .map_err(|_| CertificateTrustError::MalformedCertificate)
+}
+
+fn check_valid_at(cert: &X509Certificate<'_>, at: ASN1Time) -> Result<(), CertificateTrustError> {
+    if cert.validity().is_valid_at(at) {
+        Ok(())
+    } else {
+        Err(CertificateTrustError::NotValidAtCommitTime(
+            cert.subject().to_string(),
+        ))
+    }
+}
+
+/// Returns the `(authority, number)` pairs from `vpr://<authority>/<number>` subjectAltNames.
+fn certificate_registrations(cert: &X509Certificate<'_>) -> Vec<(String, String)> {
+    cert.subject_alternative_name()
+        .ok()
+        .flatten()
+        .into_iter()
+        .flat_map(|san| san.value.general_names.iter())
+        .filter_map(|name| match name {
+            GeneralName::URI(uri) => uri.strip_prefix(REGISTRATION_URI_PREFIX),
+            _ => None,
+        })
+        .filter_map(|rest| rest.split_once('/'))
+        .map(|(authority, number)| (authority.to_string(), number.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{
+        BasicConstraints, CertificateParams, DnType, IsCa, KeyPair, KeyUsagePurpose, SanType,
+    };
+
+    struct Ca {
+        cert: rcgen::Certificate,
+        key: KeyPair,
+    }
+
+    fn ca(name: &str) -> Ca {
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::default();
+        params.distinguished_name.push(DnType::CommonName, name);
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.key_usages = vec![KeyUsagePurpose::KeyCertSign];
+        Ca {
+            cert: params.self_signed(&key).unwrap(),
+            key,
+        }
+    }
+
+    fn leaf(issuer: &Ca, configure: impl FnOnce(&mut CertificateParams)) -> String {
+        let mut params = CertificateParams::default();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "Dr Jane Smith");
+        params.subject_alt_names.push(SanType::URI(
+            "vpr://GMC/1234567".to_string().try_into().unwrap(),
+        ));
+        params.key_usages = vec![KeyUsagePurpose::DigitalSignature];
+        configure(&mut params);
+        let key = KeyPair::generate().unwrap();
+        params
+            .signed_by(&key, &issuer.cert, &issuer.key)
+            .unwrap()
+            .pem()
+    }
+
+    fn gmc() -> Vec<AuthorRegistration> {
+        vec![AuthorRegistration::new("GMC", "1234567").unwrap()]
+    }
+
+    #[test]
+    fn accepts_certificate_issued_by_anchor() {
+        let root = ca("VPR Root CA");
+        let anchors = TrustAnchors::from_pem(root.cert.pem().as_bytes()).unwrap();
+        let cert = leaf(&root, |_| {});
+
+        assert_eq!(anchors.verify(cert.as_bytes(), Utc::now(), &gmc()), Ok(()));
+    }
+
+    fn sub_ca(name: &str, issuer: &Ca, constraints: BasicConstraints) -> Ca {
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::default();
+        params.distinguished_name.push(DnType::CommonName, name);
+        params.is_ca = IsCa::Ca(constraints);
+        params.key_usages = vec![KeyUsagePurpose::KeyCertSign];
+        Ca {
+            cert: params.signed_by(&key, &issuer.cert, &issuer.key).unwrap(),
+            key,
+        }
+    }
+
+    #[test]
+    fn follows_embedded_intermediates() {
+        let root = ca("VPR Root CA");
+        let intermediate = sub_ca("VPR Issuing CA", &root, BasicConstraints::Constrained(0));
+        let anchors = TrustAnchors::from_pem(root.cert.pem().as_bytes()).unwrap();
+        let cert = leaf(&intermediate, |_| {});
+
+        assert_eq!(
+            anchors.verify(cert.as_bytes(), Utc::now(), &gmc()),
+            Err(CertificateTrustError::UnknownIssuer)
+        );
+        let bundle = format!("{cert}{}", intermediate.cert.pem());
+        assert_eq!(
+            anchors.verify(bundle.as_bytes(), Utc::now(), &gmc()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn rejects_untrusted_expired_and_mismatched_certificates() {
+        let root = ca("VPR Root CA");
+        let anchors = TrustAnchors::from_pem(root.cert.pem().as_bytes()).unwrap();
+
+        let stranger = leaf(&ca("Other CA"), |_| {});
+        assert_eq!(
+            anchors.verify(stranger.as_bytes(), Utc::now(), &gmc()),
+            Err(CertificateTrustError::UnknownIssuer)
+        );
+
+        let expired = leaf(&root, |params| {
+            params.not_before = rcgen::date_time_ymd(2020, 1, 1);
+            params.not_after = rcgen::date_time_ymd(2021, 1, 1);
+        });
+        assert!(matches!(
+            anchors.verify(expired.as_bytes(), Utc::now(), &gmc()),
+            Err(CertificateTrustError::NotValidAtCommitTime(_))
+        ));
+        let signed_in_2020 = DateTime::parse_from_rfc3339("2020-06-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            anchors.verify(expired.as_bytes(), signed_in_2020, &gmc()),
+            Ok(())
+        );
+
+        let no_signing = leaf(&root, |params| {
+            params.key_usages = vec![KeyUsagePurpose::KeyEncipherment];
+        });
+        assert_eq!(
+            anchors.verify(no_signing.as_bytes(), Utc::now(), &gmc()),
+            Err(CertificateTrustError::MissingDigitalSignatureUsage)
+        );
+
+        let cert = leaf(&root, |_| {});
+        let other = vec![AuthorRegistration::new("NMC", "99A1234B").unwrap()];
+        assert_eq!(
+            anchors.verify(cert.as_bytes(), Utc::now(), &other),
+            Err(CertificateTrustError::RegistrationMismatch)
+        );
+        assert_eq!(
+            anchors.verify(cert.as_bytes(), Utc::now(), &[]),
+            Err(CertificateTrustError::RegistrationMismatch)
+        );
+    }
+
+    #[test]
+    fn enforces_issuer_constraints() {
+        let root = ca("VPR Root CA");
+        let anchors = TrustAnchors::from_pem(root.cert.pem().as_bytes()).unwrap();
+
+        // A sub-CA minted under an issuing CA that may only sign end-entity certificates.
+        let intermediate = sub_ca("VPR Issuing CA", &root, BasicConstraints::Constrained(0));
+        let rogue = sub_ca("Rogue CA", &intermediate, BasicConstraints::Unconstrained);
+        let cert = leaf(&rogue, |_| {});
+        let bundle = format!("{cert}{}{}", rogue.cert.pem(), intermediate.cert.pem());
+        assert!(matches!(
+            anchors.verify(bundle.as_bytes(), Utc::now(), &gmc()),
+            Err(CertificateTrustError::PathLengthExceeded(name)) if name.contains("VPR Issuing CA")
+        ));
+
+        let unconstrained = sub_ca("VPR Policy CA", &root, BasicConstraints::Unconstrained);
+        let issuing = sub_ca(
+            "VPR Issuing CA",
+            &unconstrained,
+            BasicConstraints::Constrained(0),
+        );
+        let cert = leaf(&issuing, |_| {});
+        let bundle = format!("{cert}{}{}", issuing.cert.pem(), unconstrained.cert.pem());
+        assert_eq!(
+            anchors.verify(bundle.as_bytes(), Utc::now(), &gmc()),
+            Ok(())
+        );
+
+        let ca_leaf = leaf(&root, |params| {
+            params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+            params.key_usages = vec![
+                KeyUsagePurpose::DigitalSignature,
+                KeyUsagePurpose::KeyCertSign,
+            ];
+        });
+        assert_eq!(
+            anchors.verify(ca_leaf.as_bytes(), Utc::now(), &gmc()),
+            Err(CertificateTrustError::LeafIsCa)
+        );
+
+        let no_cert_sign = {
+            let key = KeyPair::generate().unwrap();
+            let mut params = CertificateParams::default();
+            params
+                .distinguished_name
+                .push(DnType::CommonName, "VPR Signing-only CA");
+            params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+            params.key_usages = vec![KeyUsagePurpose::DigitalSignature];
+            Ca {
+                cert: params.signed_by(&key, &root.cert, &root.key).unwrap(),
+                key,
+            }
+        };
+        let cert = leaf(&no_cert_sign, |_| {});
+        let bundle = format!("{cert}{}", no_cert_sign.cert.pem());
+        assert!(matches!(
+            anchors.verify(bundle.as_bytes(), Utc::now(), &gmc()),
+            Err(CertificateTrustError::IssuerMissingCertSignUsage(_))
+        ));
+    }
+}
@@ -24,12 +24,16 @@
 //!
 //! ## Architecture
 //!
-//! The module provides four main components:
+//! The module provides five main components:
 //!
 //! - **File Operations**: [`FileToWrite`] struct for describing atomic file write operations
 //! - **Repository Management**: [`VersionedFileService`] for high-level Git operations
 //! - **Commit Messages**: [`VprCommitMessage`] with structured domains and actions
-//! - **Cryptographic Signing**: ECDSA P-256 signature creation and verification
+//! - **Cryptographic Signing**: ECDSA P-256, ECDSA P-384 and Ed25519 signature creation and
+//!   verification (see [`crate::signing`])
+//! - **History**: [`VersionedFileService::commit_history`] reads the audit trail back as
+//!   parsed [`VprCommitHistoryEntry`] values, optionally filtered by path, and
+//!   [`VersionedFileService::verify_signature_chain`] checks the signature of every commit
 //!
 //! ## Branch Policy
 //!
@@ -39,20 +43,50 @@
 //! movement and no `HEAD` update). For signed commits, this module explicitly updates
 //! `refs/heads/main` and points `HEAD` to it to maintain proper branch state.
 //!
+//! Commits move `refs/heads/main` only if it still points at the parent they were built on,
+//! and writes run under a [`crate::lock::RepositoryLock`], so concurrent writers are serialised
+//! and a `main` moved from outside VPR surfaces as `PatientError::ConcurrentModification`.
+//!
 //! ## Signature Format
 //!
-//! When `Author.signature` is present, VPR signs commits using ECDSA P-256.
+//! When `Author.signature` is present, VPR signs commits with the author's PKCS#8 private key,
+//! which may be ECDSA P-256, ECDSA P-384 or Ed25519.
 //!
 //! - Signed payload: the *unsigned commit buffer* produced by `Repository::commit_create_buffer`
-//! - Signature bytes: raw 64 bytes (`r || s`, not DER)
+//! - Signature bytes: raw fixed-size bytes (`r || s` for ECDSA, not DER)
 //! - Stored form: base64 of a deterministic JSON container passed to `commit_signed` and
 //!   written into the commit header field `gpgsig`
 //!
-//! The container embeds:
-//! - `signature`: base64 of raw 64-byte `r||s`
-//! - `public_key`: base64 of SEC1-encoded public key bytes
+//! New commits use version 2 of the container ([`VprCommitSignaturePayloadV2`]), which embeds:
+//! - `version`: `2`
+//! - `algorithm`: `ecdsa-p256`, `ecdsa-p384` or `ed25519`
+//! - `signature`: base64 of the raw signature bytes
+//! - `public_key`: base64 of the public key (SEC1 point for ECDSA, raw 32 bytes for Ed25519)
 //! - `certificate` (optional): base64 of the certificate bytes (PEM or DER)
 //!
+//! Version 1 containers have no `version` or `algorithm` field and are always ECDSA P-256.
+//! They are still verified, so existing history remains valid.
+//!
+//! When `Author.signature` is an OpenSSH private key, `gpgsig` instead holds a standard armored
+//! SSH signature in the `git` namespace, so `git log --show-signature` can verify the commit
+//! against an `allowed_signers` file. Such commits carry no certificate (see
+//! [`crate::signing`]). Verification in this module accepts both forms.
+//!
+//! ## Timestamps
+//!
+//! When a timestamp authority is passed to the commit functions, the unsigned commit buffer is
+//! first sent to it, and the RFC 3161 token it returns is added to the buffer as a
+//! `vpr-timestamp` header (base64 DER, folded like `gpgsig`). The commit is then signed over the
+//! buffer including that header, so signatures are verified over the stored commit without its
+//! `gpgsig` header rather than a reconstructed buffer. See [`crate::timestamp`].
+//!
+//! ## Mirroring
+//!
+//! When a [`Mirror`] is passed to the commit functions, `refs/heads/main` is pushed to the
+//! matching mirror repository after the commit is made. The push is fast-forward only, and a
+//! failed push is logged rather than returned, since the commit itself has succeeded. See
+//! [`crate::replication`].
+//!
 //! ## Safety and Immutability
 //!
 //! VPR maintains an immutable audit trail where nothing is ever truly deleted. The
@@ -64,18 +98,23 @@
 //! The verifier in clinical code (`ClinicalService::verify_commit_signature`) expects this
 //! exact scheme.
 
-use crate::author::Author;
+use crate::author::{Author, AuthorRegistration};
 use crate::error::{PatientError, PatientResult};
+use crate::lock::{self, RepositoryLock};
+use crate::replication::Mirror;
+use crate::revocation::{RevocationList, RevocationStatus};
+use crate::signing::{self, CommitSigningKey, SignatureAlgorithm, SshCommitSigningKey};
+use crate::timestamp::{self, TimestampAuthority, TimestampError};
+use crate::trust::{CertificateTrustError, TrustAnchors};
 use crate::NonEmptyText;
 use crate::ShardableUuid;
 use base64::{engine::general_purpose, Engine as _};
-use p256::ecdsa::signature::{Signer, Verifier};
-use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
-use p256::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use x509_parser::prelude::*;
 
 #[cfg(test)]
@@ -83,7 +122,10 @@ use std::collections::HashSet;
 #[cfg(test)]
 use std::sync::{LazyLock, Mutex};
 
-const MAIN_REF: &str = "refs/heads/main";
+pub(crate) const MAIN_REF: &str = "refs/heads/main";
+
+/// Version number written into [`VprCommitSignaturePayloadV2`].
+pub(crate) const SIGNATURE_PAYLOAD_VERSION_2: u32 = 2;
 
 /// Deterministic container for VPR commit signatures.
 ///
@@ -93,21 +135,29 @@ const MAIN_REF: &str = "refs/heads/main";
 ///
 /// The container ensures that all signature metadata is embedded directly in the Git commit,
 /// making signatures self-contained and verifiable without external dependencies.
+///
+/// Version 2 adds `version` and `algorithm` to the original version 1 container, which could
+/// only hold ECDSA P-256 signatures. See [`crate::author::extract_embedded_commit_signature`]
+/// for how both versions are read.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
-struct VprCommitSignaturePayloadV1 {
-    /// Base64 of raw 64-byte ECDSA P-256 signature (`r || s`).
-    signature: String,
-    /// Base64 of SEC1-encoded public key bytes.
-    public_key: String,
+pub(crate) struct VprCommitSignaturePayloadV2 {
+    /// Always [`SIGNATURE_PAYLOAD_VERSION_2`].
+    pub(crate) version: u32,
+    /// Algorithm of `signature` and `public_key`.
+    pub(crate) algorithm: SignatureAlgorithm,
+    /// Base64 of the raw signature bytes.
+    pub(crate) signature: String,
+    /// Base64 of the public key bytes.
+    pub(crate) public_key: String,
     /// Base64 of X.509 certificate bytes (PEM or DER), if provided.
     #[serde(skip_serializing_if = "Option::is_none")]
-    certificate: Option<String>,
+    pub(crate) certificate: Option<String>,
 }
 
-/// Extract the SEC1-encoded public key bytes from an X.509 certificate.
+/// Extract the public key bytes from an X.509 certificate.
 ///
 /// Accepts both PEM and DER certificate formats. The certificate is parsed to extract
-/// the public key, which is then encoded in SEC1 format for use with ECDSA verification.
+/// the `subjectPublicKey` bytes: the SEC1 point for ECDSA keys and the raw key for Ed25519.
 ///
 /// This function is used during commit signing to validate that the author's certificate
 /// matches their signing key.
@@ -118,7 +168,7 @@ struct VprCommitSignaturePayloadV1 {
 ///
 /// # Returns
 ///
-/// SEC1-encoded public key bytes on success.
+/// The public key bytes on success.
 ///
 /// # Errors
 ///
@@ -149,7 +199,7 @@ fn extract_cert_public_key_sec1(cert_bytes: &[u8]) -> PatientResult<Vec<u8>> {
 /// These represent different types of clinical data being modified.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
-pub(crate) enum ClinicalDomain {
+pub enum ClinicalDomain {
     Record,
     Observation,
     Diagnosis,
@@ -160,8 +210,7 @@ pub(crate) enum ClinicalDomain {
 }
 
 impl ClinicalDomain {
-    #[allow(dead_code)]
-    pub(crate) const fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Record => "record",
             Self::Observation => "observation",
@@ -174,19 +223,37 @@ impl ClinicalDomain {
     }
 }
 
+impl FromStr for ClinicalDomain {
+    type Err = PatientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "record" => Ok(Self::Record),
+            "observation" => Ok(Self::Observation),
+            "diagnosis" => Ok(Self::Diagnosis),
+            "treatment" => Ok(Self::Treatment),
+            "administration" => Ok(Self::Administration),
+            "correction" => Ok(Self::Correction),
+            "metadata" => Ok(Self::Metadata),
+            other => Err(PatientError::InvalidCommitMessage(format!(
+                "unknown clinical domain: {other}"
+            ))),
+        }
+    }
+}
+
 /// Coordination domain categories for commit messages.
 ///
 /// These represent different types of care coordination activities.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
-pub(crate) enum CoordinationDomain {
+pub enum CoordinationDomain {
     Record,
     Messaging,
 }
 
 impl CoordinationDomain {
-    #[allow(dead_code)]
-    pub(crate) const fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Record => "record",
             Self::Messaging => "messaging",
@@ -194,43 +261,180 @@ impl CoordinationDomain {
     }
 }
 
+impl FromStr for CoordinationDomain {
+    type Err = PatientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "record" => Ok(Self::Record),
+            "messaging" => Ok(Self::Messaging),
+            other => Err(PatientError::InvalidCommitMessage(format!(
+                "unknown coordination domain: {other}"
+            ))),
+        }
+    }
+}
+
 /// Demographics domain categories for commit messages.
 ///
 /// These represent different types of demographic data being modified.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
-pub(crate) enum DemographicsDomain {
+pub enum DemographicsDomain {
     Record,
 }
 
 impl DemographicsDomain {
-    #[allow(dead_code)]
-    pub(crate) const fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Record => "record",
+        }
+    }
+}
+
+impl FromStr for DemographicsDomain {
+    type Err = PatientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "record" => Ok(Self::Record),
+            other => Err(PatientError::InvalidCommitMessage(format!(
+                "unknown demographics domain: {other}"
+            ))),
+        }
+    }
+}
+
+/// Redaction Retention Repository domain categories for commit messages.
+///
+/// The RRR only ever records retention of redacted artefacts.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactionDomain {
+    Record,
+}
+
+impl RedactionDomain {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Record => "record",
+        }
+    }
+}
+
+impl FromStr for RedactionDomain {
+    type Err = PatientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "record" => Ok(Self::Record),
+            other => Err(PatientError::InvalidCommitMessage(format!(
+                "unknown redaction domain: {other}"
+            ))),
+        }
+    }
+}
+
+/// Revocation repository domain categories for commit messages.
+///
+/// The revocation repository only ever records certificate revocation lists.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RevocationDomain {
+    Record,
+}
+
+impl RevocationDomain {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Record => "record",
         }
     }
 }
 
+impl FromStr for RevocationDomain {
+    type Err = PatientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "record" => Ok(Self::Record),
+            other => Err(PatientError::InvalidCommitMessage(format!(
+                "unknown revocation domain: {other}"
+            ))),
+        }
+    }
+}
+
+/// The kind of VPR repository a commit belongs to.
+///
+/// Domain names are not unique across repository kinds (`record` exists in all of them), so
+/// parsing a commit subject back into a [`VprCommitDomain`] needs to know which kind of
+/// repository the commit was read from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VprRepositoryKind {
+    Clinical,
+    Coordination,
+    Demographics,
+    Redaction,
+    Revocation,
+}
+
+impl VprRepositoryKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Clinical => "clinical",
+            Self::Coordination => "coordination",
+            Self::Demographics => "demographics",
+            Self::Redaction => "redaction",
+            Self::Revocation => "revocation",
+        }
+    }
+}
+
 /// Controlled vocabulary for VPR commit message domains.
 ///
-/// Hierarchical structure organizing commits by repository type (Clinical, Coordination, Demographics)
-/// and specific domain within that repository.
+/// Hierarchical structure organizing commits by repository type (Clinical, Coordination,
+/// Demographics, Redaction) and specific domain within that repository.
 ///
 /// Safety/intent: Do not include patient identifiers or raw clinical data in commit messages.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-pub(crate) enum VprCommitDomain {
+pub enum VprCommitDomain {
     Clinical(ClinicalDomain),
     Coordination(CoordinationDomain),
     Demographics(DemographicsDomain),
+    Redaction(RedactionDomain),
+    Revocation(RevocationDomain),
 }
 
 impl VprCommitDomain {
-    pub(crate) const fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Clinical(subdomain) => subdomain.as_str(),
             Self::Coordination(subdomain) => subdomain.as_str(),
             Self::Demographics(subdomain) => subdomain.as_str(),
+            Self::Redaction(subdomain) => subdomain.as_str(),
+            Self::Revocation(subdomain) => subdomain.as_str(),
+        }
+    }
+
+    /// Parse a domain name as written in a commit subject line.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of repository the commit was read from
+    /// * `s` - The domain part of the subject line (e.g. `record`, `messaging`)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PatientError::InvalidCommitMessage`] if `s` is not a domain of `kind`.
+    pub fn parse(kind: VprRepositoryKind, s: &str) -> PatientResult<Self> {
+        match kind {
+            VprRepositoryKind::Clinical => s.parse().map(Self::Clinical),
+            VprRepositoryKind::Coordination => s.parse().map(Self::Coordination),
+            VprRepositoryKind::Demographics => s.parse().map(Self::Demographics),
+            VprRepositoryKind::Redaction => s.parse().map(Self::Redaction),
+            VprRepositoryKind::Revocation => s.parse().map(Self::Revocation),
         }
     }
 }
@@ -310,12 +514,17 @@ impl<'de> Deserialize<'de> for VprCommitDomain {
 ///
 /// - **`Redact`**: Used when data was entered into the wrong patient's repository
 ///   by mistake (can occur in clinical, demographics, or coordination repositories).
-///   The data is removed from the current view, encrypted, and stored in the
-///   Redaction Retention Repository with a tombstone/pointer remaining in the original
-///   repository's Git history. This maintains audit trail integrity while protecting
+///   The data is removed from the current view and moved into the Redaction Retention
+///   Repository (see [`crate::repositories::redaction`]) with a tombstone remaining in the
+///   original repository. Both sides record a `Redact` commit carrying the same
+///   `Redaction-Id` trailer. This maintains audit trail integrity while protecting
 ///   patient privacy. **This is the only action that removes data from active view**,
 ///   but even redacted data is preserved in secure storage for audit purposes.
 ///
+/// - **`Recover`**: Used for the marker commit made when a repository is restored from a
+///   backup (see [`crate::backup`]). It changes no files; its trailers record which backup
+///   the repository was restored from and the commit the backup held.
+///
 /// # What VPR Never Does
 ///
 /// VPR **never deletes data** from the version control history. Even redacted data is
@@ -332,20 +541,22 @@ impl<'de> Deserialize<'de> for VprCommitDomain {
 /// Use structured trailers for metadata only.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
-pub(crate) enum VprCommitAction {
+pub enum VprCommitAction {
     Create,
     Update,
     Superseded,
     Redact,
+    Recover,
 }
 
 impl VprCommitAction {
-    pub(crate) const fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Create => "create",
             Self::Update => "update",
             Self::Superseded => "superseded",
             Self::Redact => "redact",
+            Self::Recover => "recover",
         }
     }
 }
@@ -356,13 +567,30 @@ impl fmt::Display for VprCommitAction {
     }
 }
 
+impl FromStr for VprCommitAction {
+    type Err = PatientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "create" => Ok(Self::Create),
+            "update" => Ok(Self::Update),
+            "superseded" => Ok(Self::Superseded),
+            "redact" => Ok(Self::Redact),
+            "recover" => Ok(Self::Recover),
+            other => Err(PatientError::InvalidCommitMessage(format!(
+                "unknown commit action: {other}"
+            ))),
+        }
+    }
+}
+
 /// A single commit trailer line in standard Git trailer format.
 ///
 /// Renders as `Key: Value`. Trailers provide additional structured metadata
 /// beyond the main commit subject line. They follow Git's standard trailer
 /// conventions and are sorted deterministically in rendered output.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
-pub(crate) struct VprCommitTrailer {
+pub struct VprCommitTrailer {
     key: NonEmptyText,
     value: NonEmptyText,
 }
@@ -378,7 +606,6 @@ impl VprCommitTrailer {
     /// # Errors
     ///
     /// Returns `PatientError::InvalidInput` if validation fails.
-    #[allow(dead_code)]
     pub(crate) fn new(key: impl Into<String>, value: impl Into<String>) -> PatientResult<Self> {
         let key_str = key.into().trim().to_string();
         let value_str = value.into().trim().to_string();
@@ -405,12 +632,12 @@ impl VprCommitTrailer {
     }
 
     /// Get the trailer key.
-    pub(crate) fn key(&self) -> &str {
+    pub fn key(&self) -> &str {
         self.key.as_str()
     }
 
     /// Get the trailer value.
-    pub(crate) fn value(&self) -> &str {
+    pub fn value(&self) -> &str {
         self.value.as_str()
     }
 }
@@ -427,7 +654,7 @@ impl VprCommitTrailer {
 /// Safety/intent: Commit messages are labels and indexes; do not include patient identifiers or
 /// raw clinical data.
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub(crate) struct VprCommitMessage {
+pub struct VprCommitMessage {
     domain: VprCommitDomain,
     action: VprCommitAction,
     summary: NonEmptyText,
@@ -450,7 +677,6 @@ impl VprCommitMessage {
     /// Returns `PatientError::InvalidInput` if summary contains newlines or is empty.
     /// Returns `PatientError::MissingCareLocation` if care_location is empty.
     /// Returns `PatientError::InvalidCareLocation` if care_location contains newlines.
-    #[allow(dead_code)]
     pub(crate) fn new(
         domain: VprCommitDomain,
         action: VprCommitAction,
@@ -497,7 +723,6 @@ impl VprCommitMessage {
     /// Returns `PatientError::ReservedAuthorTrailerKey` for Author-* keys.
     /// Returns `PatientError::ReservedCareLocationTrailerKey` for Care-Location key.
     /// Returns `PatientError::InvalidInput` for invalid key/value format.
-    #[allow(dead_code)]
     pub(crate) fn with_trailer(
         mut self,
         key: impl Into<String>,
@@ -516,29 +741,131 @@ impl VprCommitMessage {
     }
 
     /// Get the commit domain.
-    #[allow(dead_code)]
-    pub(crate) fn domain(&self) -> VprCommitDomain {
+    pub fn domain(&self) -> VprCommitDomain {
         self.domain
     }
 
     /// Get the commit action.
-    #[allow(dead_code)]
-    pub(crate) fn action(&self) -> VprCommitAction {
+    pub fn action(&self) -> VprCommitAction {
         self.action
     }
 
     /// Get the commit summary.
-    #[allow(dead_code)]
-    pub(crate) fn summary(&self) -> &str {
+    pub fn summary(&self) -> &str {
         self.summary.as_str()
     }
 
     /// Get the commit trailers.
-    #[allow(dead_code)]
-    pub(crate) fn trailers(&self) -> &[VprCommitTrailer] {
+    pub fn trailers(&self) -> &[VprCommitTrailer] {
         &self.trailers
     }
 
+    /// Get the care location the change was made at.
+    pub fn care_location(&self) -> &str {
+        self.care_location.as_str()
+    }
+
+    /// Parse a commit message produced by [`render_with_author`](Self::render_with_author).
+    ///
+    /// This is the inverse of `render_with_author`: the subject line is split back into
+    /// domain, action and summary, the reserved `Author-*` and `Care-Location` trailers are
+    /// lifted into structured fields, and any remaining trailers are kept in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of repository the commit was read from (needed to resolve the domain)
+    /// * `raw` - The full commit message text
+    ///
+    /// # Returns
+    ///
+    /// The parsed message together with the author metadata recorded in its trailers.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::InvalidCommitMessage` if the subject or a trailer line is malformed.
+    /// Returns `PatientError::MissingAuthorName` / `PatientError::MissingAuthorRole` if the
+    /// mandatory author trailers are absent.
+    /// Returns `PatientError::MissingCareLocation` if the `Care-Location` trailer is absent.
+    /// Returns `PatientError::InvalidAuthorRegistration` if a registration trailer is malformed.
+    pub fn parse_with_author(
+        kind: VprRepositoryKind,
+        raw: &str,
+    ) -> PatientResult<(Self, VprCommitAuthor)> {
+        let mut lines = raw.lines();
+        let subject = lines
+            .next()
+            .ok_or_else(|| PatientError::InvalidCommitMessage("empty commit message".into()))?;
+
+        let (domain, rest) = subject.split_once(':').ok_or_else(|| {
+            PatientError::InvalidCommitMessage(format!("malformed subject line: {subject}"))
+        })?;
+        let (action, summary) = rest.split_once(": ").ok_or_else(|| {
+            PatientError::InvalidCommitMessage(format!("malformed subject line: {subject}"))
+        })?;
+
+        let domain = VprCommitDomain::parse(kind, domain.trim())?;
+        let action: VprCommitAction = action.trim().parse()?;
+        let summary = NonEmptyText::new(summary.trim()).map_err(|_| {
+            PatientError::InvalidCommitMessage("commit summary must be non-empty".into())
+        })?;
+
+        let mut author_name = None;
+        let mut author_role = None;
+        let mut registrations = Vec::new();
+        let mut care_location = None;
+        let mut trailers = Vec::new();
+
+        for line in lines.map(str::trim).filter(|l| !l.is_empty()) {
+            let (key, value) = line.split_once(": ").ok_or_else(|| {
+                PatientError::InvalidCommitMessage(format!("malformed trailer line: {line}"))
+            })?;
+
+            match key.trim() {
+                "Author-Name" => {
+                    author_name = Some(
+                        NonEmptyText::new(value.trim())
+                            .map_err(|_| PatientError::MissingAuthorName)?,
+                    )
+                }
+                "Author-Role" => {
+                    author_role = Some(
+                        NonEmptyText::new(value.trim())
+                            .map_err(|_| PatientError::MissingAuthorRole)?,
+                    )
+                }
+                "Author-Registration" => {
+                    let (authority, number) = value
+                        .trim()
+                        .split_once(' ')
+                        .ok_or(PatientError::InvalidAuthorRegistration)?;
+                    registrations.push(AuthorRegistration::new(authority, number)?);
+                }
+                "Care-Location" => {
+                    care_location = Some(
+                        NonEmptyText::new(value.trim())
+                            .map_err(|_| PatientError::MissingCareLocation)?,
+                    )
+                }
+                _ => trailers.push(VprCommitTrailer::new(key, value)?),
+            }
+        }
+
+        let message = Self {
+            domain,
+            action,
+            summary,
+            care_location: care_location.ok_or(PatientError::MissingCareLocation)?,
+            trailers,
+        };
+        let author = VprCommitAuthor {
+            name: author_name.ok_or(PatientError::MissingAuthorName)?,
+            role: author_role.ok_or(PatientError::MissingAuthorRole)?,
+            registrations,
+        };
+
+        Ok((message, author))
+    }
+
     /// Render the commit message without author information.
     ///
     /// Produces a standard Git commit message format with subject line and trailers.
@@ -690,6 +1017,178 @@ impl VprCommitMessage {
     }
 }
 
+/// Author metadata recovered from the reserved `Author-*` trailers of a commit message.
+///
+/// This mirrors the parts of [`Author`] that [`VprCommitMessage::render_with_author`] writes
+/// into the message. Email comes from the Git author signature rather than a trailer and is
+/// carried on [`VprCommitHistoryEntry`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VprCommitAuthor {
+    /// Value of the `Author-Name` trailer.
+    pub name: NonEmptyText,
+    /// Value of the `Author-Role` trailer.
+    pub role: NonEmptyText,
+    /// Values of the `Author-Registration` trailers, in rendered (sorted) order.
+    pub registrations: Vec<AuthorRegistration>,
+}
+
+/// Outcome of checking the embedded signature of a single commit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitSignatureStatus {
+    /// The commit has no `gpgsig` header.
+    Unsigned,
+    /// The embedded signature verifies against the reconstructed commit buffer.
+    Valid,
+    /// The signature payload is malformed or does not verify against the commit contents.
+    Invalid,
+    /// The embedded certificate does not contain the embedded signing public key.
+    CertificateMismatch,
+    /// The signature is valid, but the signing certificate is missing, does not chain to a
+    /// configured trust anchor, was not valid at commit time, or does not permit signing.
+    ///
+    /// Only reported when trust anchors are configured.
+    Untrusted,
+    /// The signature is valid and the certificate trusted, but the certificate's registrations
+    /// differ from the commit's `Author-Registration` trailers.
+    ///
+    /// Only reported when trust anchors are configured.
+    RegistrationMismatch,
+    /// The signature is valid, and the signing certificate was revoked after the commit was
+    /// made. The commit remains trustworthy.
+    RevokedAfterSigning,
+    /// The signature is valid, but the signing certificate had already been revoked when the
+    /// commit was made.
+    RevokedBeforeSigning,
+}
+
+/// Outcome of checking the RFC 3161 timestamp token of a single commit.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitTimestampStatus {
+    /// The commit has no `vpr-timestamp` header.
+    #[default]
+    Absent,
+    /// The token covers the commit, its signature verifies, and the time it asserts agrees
+    /// with the commit time.
+    Valid,
+    /// The token is valid, but the TSA certificate does not chain to a configured trust
+    /// anchor, was not valid at the asserted time, or does not permit time stamping.
+    ///
+    /// Only reported when timestamp trust anchors are configured.
+    Untrusted,
+    /// The token is malformed, does not cover the commit, has a signature that does not
+    /// verify, or asserts a time that disagrees with the commit time.
+    Invalid,
+}
+
+/// Signature status of one commit in a [`SignatureChainReport`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CommitSignatureReport {
+    /// Hex-encoded Git commit id.
+    pub commit_id: String,
+    /// Result of verifying the commit's embedded signature.
+    pub status: CommitSignatureStatus,
+    /// Result of verifying the commit's timestamp token.
+    #[serde(default)]
+    pub timestamp: CommitTimestampStatus,
+    /// Time asserted by the timestamp token, if it is valid.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamped_at: Option<DateTime<Utc>>,
+}
+
+/// Per-commit signature report for a whole repository.
+///
+/// Produced by [`VersionedFileService::verify_signature_chain`]. Commits are listed newest
+/// first, in the same order as [`VersionedFileService::commit_history`].
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SignatureChainReport {
+    pub commits: Vec<CommitSignatureReport>,
+}
+
+impl SignatureChainReport {
+    /// Returns `true` if every commit in the chain carries a valid signature, and no commit
+    /// carries an invalid or untrusted timestamp.
+    ///
+    /// Commits signed before their certificate was revoked count as valid. Commits without a
+    /// timestamp are not failures.
+    pub fn is_valid(&self) -> bool {
+        self.commits.iter().all(|c| {
+            matches!(
+                c.status,
+                CommitSignatureStatus::Valid | CommitSignatureStatus::RevokedAfterSigning
+            ) && !c.timestamp_failed()
+        })
+    }
+
+    /// Number of commits with the given status.
+    pub fn count(&self, status: CommitSignatureStatus) -> usize {
+        self.commits.iter().filter(|c| c.status == status).count()
+    }
+
+    /// Commits whose signature is invalid, or whose certificate does not match the signing key,
+    /// is not trusted, names different registrations, or was revoked before signing, and
+    /// commits whose timestamp is invalid or untrusted.
+    ///
+    /// Unsigned commits are not included; use [`count`](Self::count) or
+    /// [`is_valid`](Self::is_valid) when every commit is required to be signed.
+    pub fn failures(&self) -> impl Iterator<Item = &CommitSignatureReport> {
+        self.commits.iter().filter(|c| {
+            matches!(
+                c.status,
+                CommitSignatureStatus::Invalid
+                    | CommitSignatureStatus::CertificateMismatch
+                    | CommitSignatureStatus::Untrusted
+                    | CommitSignatureStatus::RegistrationMismatch
+                    | CommitSignatureStatus::RevokedBeforeSigning
+            ) || c.timestamp_failed()
+        })
+    }
+
+    /// Number of commits with the given timestamp status.
+    pub fn count_timestamps(&self, status: CommitTimestampStatus) -> usize {
+        self.commits
+            .iter()
+            .filter(|c| c.timestamp == status)
+            .count()
+    }
+}
+
+impl CommitSignatureReport {
+    fn timestamp_failed(&self) -> bool {
+        matches!(
+            self.timestamp,
+            CommitTimestampStatus::Invalid | CommitTimestampStatus::Untrusted
+        )
+    }
+}
+
+/// A single entry in the history of a patient repository.
+///
+/// Entries are produced by [`VersionedFileService::commit_history`] by walking
+/// `refs/heads/main` and parsing each commit message. Commits whose message VPR did not
+/// write (e.g. a manual repair commit) are still listed, with `message` and `author` set
+/// to `None`.
+#[derive(Clone, Debug)]
+pub struct VprCommitHistoryEntry {
+    /// Hex-encoded Git commit id.
+    pub commit_id: String,
+    /// The commit message exactly as stored in Git.
+    pub raw_message: String,
+    /// The structured commit message (domain, action, summary, care location, trailers),
+    /// or `None` if the message is not a valid VPR commit message.
+    pub message: Option<VprCommitMessage>,
+    /// Author metadata taken from the `Author-*` trailers, or `None` if the message is not
+    /// a valid VPR commit message.
+    pub author: Option<VprCommitAuthor>,
+    /// Email address from the Git author signature.
+    pub author_email: String,
+    /// Commit time (UTC).
+    pub committed_at: DateTime<Utc>,
+    /// Result of verifying the embedded commit signature, if any.
+    pub signature: CommitSignatureStatus,
+}
+
 /// Service for common Git operations on a repository rooted at `workdir`.
 ///
 /// This bundles the repository handle and its workdir to make workflows like “initialise repo
@@ -708,6 +1207,18 @@ pub struct FileToWrite<'a> {
     pub old_content: Option<&'a str>,
 }
 
+/// Represents a file to be removed and committed.
+///
+/// Used with [`VersionedFileService::write_remove_and_commit_files`]. Removal is only ever
+/// used to move content elsewhere (e.g. redaction); the file remains in Git history.
+#[derive(Debug, Clone)]
+pub struct FileToRemove<'a> {
+    /// The relative path to the file within the repository directory.
+    pub relative_path: &'a Path,
+    /// The current file content, restored if the operation is rolled back.
+    pub old_content: &'a str,
+}
+
 /// Service for managing versioned files with Git version control.
 ///
 /// `VersionedFileService` provides high-level operations for working with Git repositories
@@ -831,6 +1342,21 @@ impl VersionedFileService {
         self.repo
     }
 
+    /// Returns the hex id of the commit `refs/heads/main` points to.
+    ///
+    /// Reads return this so clients can make a later write conditional on the record not
+    /// having changed (see [`RepositoryLock::expect_head`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatientError::GitHead` if `main` does not exist.
+    pub(crate) fn main_commit_id(&self) -> PatientResult<String> {
+        self.repo
+            .refname_to_id(MAIN_REF)
+            .map(|oid| oid.to_string())
+            .map_err(PatientError::GitHead)
+    }
+
     /// Ensure `HEAD` points at `refs/heads/main`.
     ///
     /// Sets the repository's HEAD reference to point to the main branch. For newly
@@ -859,19 +1385,30 @@ impl VersionedFileService {
     /// - absolute paths under the repo workdir (they will be normalised to relative paths).
     ///
     /// Paths containing `..` are rejected.
+    ///
+    /// When `timestamp_authority` is given, the commit carries a timestamp token from it, and
+    /// when `mirror` is given, the commit is pushed to it (see the module documentation).
     pub(crate) fn commit_paths(
         &self,
         author: &Author,
         message: &VprCommitMessage,
         relative_paths: &[PathBuf],
+        timestamp_authority: Option<&dyn TimestampAuthority>,
+        mirror: Option<&Mirror>,
     ) -> PatientResult<git2::Oid> {
         let rendered = message.render_with_author(author)?;
-        self.commit_paths_rendered(author, &rendered, relative_paths)
+        self.commit_paths_rendered(
+            author,
+            &rendered,
+            relative_paths,
+            timestamp_authority,
+            mirror,
+        )
     }
 
     /// Writes multiple files and commits them to Git with rollback on failure.
     ///
-    /// Opens an existing Git repository, creates any necessary parent directories,
+    /// Opens the locked Git repository, creates any necessary parent directories,
     /// writes all files, and commits them in a single Git commit. All operations are
     /// wrapped in a closure to enable automatic rollback if any operation fails. On error:
     /// - Files that previously existed are restored to their previous state
@@ -880,37 +1417,100 @@ impl VersionedFileService {
     ///
     /// # Arguments
     ///
-    /// * `repo_path` - Path to the existing Git repository (patient directory).
+    /// * `lock` - Write lock on the existing Git repository (patient directory), taken before
+    ///   the caller read the files it is replacing.
     /// * `author` - The author information for the Git commit.
     /// * `msg` - The commit message structure containing domain, action, and location.
     /// * `files` - Slice of [`FileToWrite`] structs describing files to write.
+    /// * `timestamp_authority` - When given, the commit carries an RFC 3161 timestamp token
+    ///   from this authority (see [`crate::timestamp`]).
+    /// * `mirror` - When given, the commit is pushed to this mirror (see
+    ///   [`crate::replication`]).
     ///
     /// # Returns
     ///
-    /// Returns `Ok(())` if repository opening, directory creation, all file writes,
-    /// and Git commit succeed.
+    /// Returns the id of the new commit if repository opening, directory creation, all
+    /// file writes, and Git commit succeed.
     ///
     /// # Errors
     ///
     /// Returns a `PatientError` if:
     /// - Repository opening fails (various Git-related error variants)
+    /// - `refs/heads/main` moved after the lock was taken
+    ///   ([`PatientError::ConcurrentModification`])
     /// - Parent directory creation fails ([`PatientError::FileWrite`])
     /// - Any file write fails ([`PatientError::FileWrite`])
+    /// - No timestamp token can be obtained ([`PatientError::TimestampAuthority`])
     /// - The Git commit fails (various Git-related error variants)
     ///
     /// On error, attempts to rollback all files and any newly created directories.
     pub(crate) fn write_and_commit_files(
-        repo_path: &Path,
+        lock: &RepositoryLock,
         author: &Author,
         msg: &VprCommitMessage,
         files: &[FileToWrite],
-    ) -> PatientResult<()> {
-        let repo = Self::open(repo_path)?;
+        timestamp_authority: Option<&dyn TimestampAuthority>,
+        mirror: Option<&Mirror>,
+    ) -> PatientResult<git2::Oid> {
+        Self::write_remove_and_commit_files(
+            lock,
+            author,
+            msg,
+            files,
+            &[],
+            timestamp_authority,
+            mirror,
+        )
+    }
+
+    /// Writes and removes files, committing all changes in a single Git commit.
+    ///
+    /// This behaves like [`write_and_commit_files`](Self::write_and_commit_files), and
+    /// additionally removes each file in `removals` from the working tree and stages the
+    /// deletion. Directories left empty by a removal are pruned. On error, removed files
+    /// are restored from their `old_content` alongside the usual write rollback.
+    ///
+    /// # Arguments
+    ///
+    /// * `lock` - Write lock on the existing Git repository (patient directory).
+    /// * `author` - The author information for the Git commit.
+    /// * `msg` - The commit message structure containing domain, action, and location.
+    /// * `files` - Slice of [`FileToWrite`] structs describing files to write.
+    /// * `removals` - Slice of [`FileToRemove`] structs describing files to remove.
+    /// * `timestamp_authority` - When given, the commit carries an RFC 3161 timestamp token
+    ///   from this authority.
+    /// * `mirror` - When given, the commit is pushed to this mirror.
+    ///
+    /// # Returns
+    ///
+    /// Returns the id of the new commit.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PatientError` if:
+    /// - Repository opening fails (various Git-related error variants)
+    /// - `refs/heads/main` moved after the lock was taken
+    ///   ([`PatientError::ConcurrentModification`])
+    /// - Parent directory creation fails ([`PatientError::FileWrite`])
+    /// - Any file write or removal fails ([`PatientError::FileWrite`])
+    /// - The Git commit fails (various Git-related error variants)
+    pub(crate) fn write_remove_and_commit_files(
+        lock: &RepositoryLock,
+        author: &Author,
+        msg: &VprCommitMessage,
+        files: &[FileToWrite],
+        removals: &[FileToRemove],
+        timestamp_authority: Option<&dyn TimestampAuthority>,
+        mirror: Option<&Mirror>,
+    ) -> PatientResult<git2::Oid> {
+        let repo = Self::open(lock.repo_dir())?;
+        lock.check_head(&repo.repo)?;
 
         let mut created_dirs: Vec<PathBuf> = Vec::new();
         let mut written_files: Vec<(PathBuf, Option<String>)> = Vec::new();
+        let mut removed_files: Vec<(PathBuf, String)> = Vec::new();
 
-        let result: PatientResult<()> = (|| {
+        let result: PatientResult<git2::Oid> = (|| {
             // Collect all unique parent directories needed
             let mut dirs_needed = std::collections::HashSet::new();
             for file in files {
@@ -947,19 +1547,42 @@ impl VersionedFileService {
                 written_files.push((full_path, old_content));
             }
 
+            // Remove files, pruning any directories they leave empty
+            for removal in removals {
+                let full_path = repo.workdir.join(removal.relative_path);
+
+                std::fs::remove_file(&full_path).map_err(PatientError::FileWrite)?;
+                removed_files.push((full_path.clone(), removal.old_content.to_string()));
+
+                let mut current = full_path.parent();
+                while let Some(dir) = current {
+                    if dir == repo.workdir || std::fs::remove_dir(dir).is_err() {
+                        break;
+                    }
+                    current = dir.parent();
+                }
+            }
+
             // Commit all files in a single commit
             let paths: Vec<PathBuf> = files
                 .iter()
                 .map(|f| f.relative_path.to_path_buf())
+                .chain(removals.iter().map(|r| r.relative_path.to_path_buf()))
                 .collect();
-            repo.commit_paths(author, msg, &paths)?;
-
-            Ok(())
+            repo.commit_paths(author, msg, &paths, timestamp_authority, mirror)
         })();
 
         match result {
-            Ok(()) => Ok(()),
+            Ok(oid) => Ok(oid),
             Err(write_error) => {
+                // Restore removed files (recreating any pruned directories)
+                for (full_path, old_content) in removed_files.iter().rev() {
+                    if let Some(parent) = full_path.parent() {
+                        let _ = std::fs::create_dir_all(parent);
+                    }
+                    let _ = std::fs::write(full_path, old_content);
+                }
+
                 // Rollback file changes (in reverse order)
                 for (full_path, old_content) in written_files.iter().rev() {
                     match old_content {
@@ -1000,6 +1623,9 @@ impl VersionedFileService {
     /// * `author` - The author information for the initial Git commit.
     /// * `message` - The commit message structure containing domain, action, and location.
     /// * `files` - Slice of [`FileToWrite`] structs describing initial files to write.
+    /// * `timestamp_authority` - When given, the initial commit carries an RFC 3161 timestamp
+    ///   token from this authority.
+    /// * `mirror` - When given, the initial commit is pushed to this mirror.
     ///
     /// # Returns
     ///
@@ -1030,6 +1656,8 @@ impl VersionedFileService {
     ///     &author,
     ///     &commit_message,
     ///     &files,
+    ///     cfg.timestamp_authority(),
+    ///     cfg.mirror(),
     /// )?;
     /// ```
     pub(crate) fn init_and_commit(
@@ -1037,10 +1665,20 @@ impl VersionedFileService {
         author: &Author,
         message: &VprCommitMessage,
         files: &[FileToWrite],
+        timestamp_authority: Option<&dyn TimestampAuthority>,
+        mirror: Option<&Mirror>,
     ) -> PatientResult<()> {
         let result: PatientResult<()> = (|| {
             let _repo = Self::init(patient_dir)?;
-            Self::write_and_commit_files(patient_dir, author, message, files)?;
+            let lock = RepositoryLock::acquire(patient_dir)?;
+            Self::write_and_commit_files(
+                &lock,
+                author,
+                message,
+                files,
+                timestamp_authority,
+                mirror,
+            )?;
             Ok(())
         })();
 
@@ -1071,6 +1709,8 @@ impl VersionedFileService {
     /// * `author` - Author information for commit signature
     /// * `message` - Pre-rendered commit message string
     /// * `relative_paths` - Paths to commit (will be normalised if absolute)
+    /// * `timestamp_authority` - Optional authority to timestamp the commit
+    /// * `mirror` - Optional mirror to push the commit to; a failed push is only logged
     ///
     /// # Errors
     ///
@@ -1084,6 +1724,8 @@ impl VersionedFileService {
         author: &Author,
         message: &str,
         relative_paths: &[PathBuf],
+        timestamp_authority: Option<&dyn TimestampAuthority>,
+        mirror: Option<&Mirror>,
     ) -> PatientResult<git2::Oid> {
         self.ensure_main_head()?;
         let mut index = self.repo.index().map_err(PatientError::GitIndex)?;
@@ -1111,10 +1753,32 @@ impl VersionedFileService {
                 ));
             }
 
-            index.add_path(&rel).map_err(PatientError::GitAdd)?;
+            // Paths that no longer exist in the working tree are staged as deletions.
+            if self.workdir.join(&rel).exists() {
+                index.add_path(&rel).map_err(PatientError::GitAdd)?;
+            } else {
+                index.remove_path(&rel).map_err(PatientError::GitIndex)?;
+            }
+        }
+
+        let oid = self.commit_from_index(author, message, &mut index, timestamp_authority)?;
+
+        // Persist the index so the next commit (possibly from another handle) builds on this
+        // tree rather than on a stale on-disk index.
+        index.write().map_err(PatientError::GitIndex)?;
+
+        if let Some(mirror) = mirror {
+            if let Err(e) = mirror.push(&self.repo) {
+                tracing::warn!(
+                    "Failed to push commit {} in {} to mirror: {}",
+                    oid,
+                    self.workdir.display(),
+                    e
+                );
+            }
         }
 
-        self.commit_from_index(author, message, &mut index)
+        Ok(oid)
     }
 
     /// Create a commit from the current Git index state.
@@ -1123,23 +1787,27 @@ impl VersionedFileService {
     /// writes the index as a tree, and creates either a signed or unsigned commit depending
     /// on whether the author has a signature key.
     ///
-    /// For signed commits, this method:
+    /// For signed or timestamped commits, this method:
     /// 1. Creates the unsigned commit buffer with correct parent list
-    /// 2. Signs the buffer using ECDSA P-256
-    /// 3. Validates certificate matches signing key (if certificate provided)
-    /// 4. Creates the signed commit and manually updates refs
+    /// 2. Adds a `vpr-timestamp` header holding a token over that buffer (if a timestamp
+    ///    authority is given)
+    /// 3. Signs the resulting buffer (if the author has a signature key)
+    /// 4. Validates certificate matches signing key (if certificate provided)
+    /// 5. Writes the commit object and manually updates refs
     ///
     /// # Arguments
     ///
     /// * `author` - Validated author information
     /// * `message` - Complete commit message text
     /// * `index` - Git index containing staged changes
+    /// * `timestamp_authority` - Optional authority to timestamp the commit
     ///
     /// # Errors
     ///
     /// Returns `PatientError` if:
     /// - Author validation fails
     /// - Tree write or lookup fails
+    /// - No timestamp token can be obtained
     /// - Signature creation fails
     /// - Certificate/key mismatch detected
     /// - Commit creation or ref update fails
@@ -1148,6 +1816,7 @@ impl VersionedFileService {
         author: &Author,
         message: &str,
         index: &mut git2::Index,
+        timestamp_authority: Option<&dyn TimestampAuthority>,
     ) -> PatientResult<git2::Oid> {
         // Ensure author metadata is valid before creating any commit buffers or signatures.
         author.validate_commit_author()?;
@@ -1161,73 +1830,115 @@ impl VersionedFileService {
         let sig = git2::Signature::now(author.name.as_str(), author.email.as_str())
             .map_err(PatientError::GitSignature)?;
 
-        if let Some(private_key_pem) = &author.signature {
-            // Create the canonical unsigned commit buffer with correct parent list.
-            let parents = self.resolve_head_parents()?;
-            let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        let parents = self.resolve_head_parents()?;
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
 
-            let buf = self
+        let parent_id = parents.first().map(git2::Commit::id);
+        if author.signature.is_none() && timestamp_authority.is_none() {
+            // Normal commit updates HEAD (and underlying ref), failing if it has moved from
+            // the parent.
+            return self
                 .repo
-                .commit_create_buffer(&sig, &sig, message, &tree, &parent_refs)
-                .map_err(PatientError::GitCommitBuffer)?;
-            let buf_str = String::from_utf8(buf.as_ref().to_vec())
-                .map_err(PatientError::CommitBufferToString)?;
+                .commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)
+                .map_err(|e| self.ref_update_error(e, parent_id, PatientError::GitCommit));
+        }
+
+        // Create the canonical unsigned commit buffer with correct parent list.
+        let buf = self
+            .repo
+            .commit_create_buffer(&sig, &sig, message, &tree, &parent_refs)
+            .map_err(PatientError::GitCommitBuffer)?;
+        let mut buf_str =
+            String::from_utf8(buf.as_ref().to_vec()).map_err(PatientError::CommitBufferToString)?;
+
+        if let Some(timestamp_authority) = timestamp_authority {
+            let token = timestamp::request_token(timestamp_authority, buf_str.as_bytes())?;
+            buf_str = with_timestamp_header(&buf_str, &token);
+        }
 
+        let oid = if let Some(private_key_pem) = &author.signature {
             let private_key_str = std::str::from_utf8(private_key_pem)
                 .map_err(|e| PatientError::EcdsaPrivateKeyParse(Box::new(e)))?;
             let key_pem = Self::load_private_key_pem(private_key_str)?;
-            let signing_key = SigningKey::from_pkcs8_pem(&key_pem)
-                .map_err(|e| PatientError::EcdsaPrivateKeyParse(Box::new(e)))?;
-
-            let public_key_bytes = signing_key
-                .verifying_key()
-                .to_encoded_point(false)
-                .as_bytes()
-                .to_vec();
-
-            if let Some(cert_bytes) = author.certificate.as_deref() {
-                let cert_public_key = extract_cert_public_key_sec1(cert_bytes)?;
-                if cert_public_key != public_key_bytes {
-                    return Err(PatientError::AuthorCertificatePublicKeyMismatch);
+            let signature_str = if signing::is_openssh_private_key(&key_pem) {
+                // SSH mode: a standard SSH signature that stock git can verify. It has no
+                // room for a certificate, so refuse rather than silently drop one.
+                if author.certificate.is_some() {
+                    return Err(PatientError::InvalidInput(
+                        "an X.509 certificate cannot be embedded in an SSH commit signature".into(),
+                    ));
                 }
-            }
+                SshCommitSigningKey::from_openssh(&key_pem)?.sign(buf_str.as_bytes())?
+            } else {
+                let signing_key = CommitSigningKey::from_pkcs8_pem(&key_pem)?;
+                let public_key_bytes = signing_key.public_key_bytes();
 
-            // Sign the unsigned commit buffer. Signature is raw 64-byte (r||s), base64-encoded.
-            let signature: Signature = signing_key.sign(buf_str.as_bytes());
+                if let Some(cert_bytes) = author.certificate.as_deref() {
+                    let cert_public_key = extract_cert_public_key_sec1(cert_bytes)?;
+                    if cert_public_key != public_key_bytes {
+                        return Err(PatientError::AuthorCertificatePublicKeyMismatch);
+                    }
+                }
 
-            let payload = VprCommitSignaturePayloadV1 {
-                signature: general_purpose::STANDARD.encode(signature.to_bytes()),
-                public_key: general_purpose::STANDARD.encode(&public_key_bytes),
-                certificate: author
-                    .certificate
-                    .as_deref()
-                    .map(|b| general_purpose::STANDARD.encode(b)),
+                // Sign the unsigned commit buffer. Signature is raw bytes, base64-encoded.
+                let signature = signing_key.sign(buf_str.as_bytes());
+
+                let payload = VprCommitSignaturePayloadV2 {
+                    version: SIGNATURE_PAYLOAD_VERSION_2,
+                    algorithm: signing_key.algorithm(),
+                    signature: general_purpose::STANDARD.encode(signature),
+                    public_key: general_purpose::STANDARD.encode(&public_key_bytes),
+                    certificate: author
+                        .certificate
+                        .as_deref()
+                        .map(|b| general_purpose::STANDARD.encode(b)),
+                };
+
+                let payload_json =
+                    serde_json::to_vec(&payload).map_err(PatientError::Serialization)?;
+                general_purpose::STANDARD.encode(payload_json)
             };
 
-            let payload_json = serde_json::to_vec(&payload).map_err(PatientError::Serialization)?;
-            let signature_str = general_purpose::STANDARD.encode(payload_json);
-
-            let oid = self
-                .repo
-                .commit_signed(&buf_str, &signature_str, None)
-                .map_err(PatientError::GitCommitSigned)?;
-
-            // `commit_signed` creates the object but does not move refs.
             self.repo
-                .reference(MAIN_REF, oid, true, "signed commit")
-                .map_err(PatientError::GitReference)?;
+                .commit_signed(&buf_str, &signature_str, None)
+                .map_err(PatientError::GitCommitSigned)?
+        } else {
             self.repo
-                .set_head(MAIN_REF)
-                .map_err(PatientError::GitSetHead)?;
+                .odb()
+                .and_then(|odb| odb.write(git2::ObjectType::Commit, buf_str.as_bytes()))
+                .map_err(PatientError::GitCommit)?
+        };
+
+        // Neither `commit_signed` nor a raw object write moves refs. Move `main` only if it
+        // still points at the parent, as `Repository::commit` does.
+        match parent_id {
+            Some(parent_id) => {
+                self.repo
+                    .reference_matching(MAIN_REF, oid, true, parent_id, "vpr commit")
+            }
+            None => self.repo.reference(MAIN_REF, oid, false, "vpr commit"),
+        }
+        .map_err(|e| self.ref_update_error(e, parent_id, PatientError::GitReference))?;
+        self.repo
+            .set_head(MAIN_REF)
+            .map_err(PatientError::GitSetHead)?;
 
-            Ok(oid)
+        Ok(oid)
+    }
+
+    /// Map a failed update of `main` to [`PatientError::ConcurrentModification`] if `main`
+    /// no longer points at `parent_id`, or to `other` otherwise.
+    fn ref_update_error(
+        &self,
+        error: git2::Error,
+        parent_id: Option<git2::Oid>,
+        other: fn(git2::Error) -> PatientError,
+    ) -> PatientError {
+        let actual = self.repo.refname_to_id(MAIN_REF).ok();
+        if actual != parent_id {
+            lock::concurrent_modification(parent_id, actual)
         } else {
-            let parents = self.resolve_head_parents()?;
-            let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
-            // Normal commit updates HEAD (and underlying ref).
-            self.repo
-                .commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)
-                .map_err(PatientError::GitCommit)
+            other(error)
         }
     }
 
@@ -1257,7 +1968,7 @@ impl VersionedFileService {
         }
     }
 
-    /// Load an ECDSA private key in PKCS#8 PEM format.
+    /// Load a commit signing private key in PKCS#8 PEM (or OpenSSH) format.
     ///
     /// This method accepts private keys in three formats for compatibility:
     /// 1. Direct PEM string (contains `-----BEGIN` marker)
@@ -1290,10 +2001,10 @@ impl VersionedFileService {
         }
     }
 
-    /// Verifies the ECDSA signature of the latest commit in a patient's Git repository.
+    /// Verifies the signature of the latest commit in a patient's Git repository.
     ///
-    /// VPR uses `git2::Repository::commit_signed` with an ECDSA P-256 signature over the
-    /// *unsigned commit buffer* produced by `commit_create_buffer`.
+    /// VPR uses `git2::Repository::commit_signed` with an ECDSA P-256, ECDSA P-384 or Ed25519
+    /// signature over the *unsigned commit buffer* produced by `commit_create_buffer`.
     ///
     /// The signature, signing public key, and optional X.509 certificate are embedded directly
     /// in the commit object's `gpgsig` header as a base64-encoded JSON container.
@@ -1317,7 +2028,6 @@ impl VersionedFileService {
     /// - the UUID cannot be parsed,
     /// - the Git repository cannot be opened or the latest commit cannot be read,
     /// - `public_key_pem` is provided but cannot be parsed as a public key or X.509 certificate.
-    #[allow(dead_code)]
     pub fn verify_commit_signature(
         base_dir: &Path,
         uuid: &str,
@@ -1330,32 +2040,435 @@ impl VersionedFileService {
         let head = repo.repo.head().map_err(PatientError::GitHead)?;
         let commit = head.peel_to_commit().map_err(PatientError::GitPeel)?;
 
-        let embedded = match crate::author::extract_embedded_commit_signature(&commit) {
-            Ok(v) => v,
-            Err(_) => return Ok(false),
+        let signer_public_key = match repo.verify_embedded_signature(&commit) {
+            Some(Ok(signed)) => signed.public_key,
+            _ => return Ok(false),
         };
 
-        let signature = match Signature::from_slice(embedded.signature.as_slice()) {
-            Ok(s) => s,
-            Err(_) => return Ok(false),
-        };
-
-        let embedded_verifying_key =
-            match VerifyingKey::from_sec1_bytes(embedded.public_key.as_slice()) {
-                Ok(k) => k,
-                Err(_) => return Ok(false),
-            };
-
         // If a trusted key/cert was provided by the caller, it must match the embedded key.
         if !public_key_pem.trim().is_empty() {
-            let trusted_key = verifying_key_from_public_key_or_cert_pem(public_key_pem)?;
-            let trusted_pub_bytes = trusted_key.to_encoded_point(false).as_bytes().to_vec();
-            if trusted_pub_bytes != embedded.public_key {
+            let trusted_pub_bytes = public_key_from_public_key_or_cert_pem(public_key_pem)?;
+            if trusted_pub_bytes != signer_public_key {
                 return Ok(false);
             }
         }
 
-        // Recreate the unsigned commit buffer for this commit.
+        Ok(true)
+    }
+
+    /// Returns the history of a patient repository, newest commit first.
+    ///
+    /// Walks `refs/heads/main` of the repository for `uuid` under `base_dir` and parses every
+    /// commit message back into a [`VprCommitMessage`]. Each entry also carries the commit time
+    /// and the result of verifying its embedded signature (see [`CommitSignatureStatus`]).
+    /// A commit whose message cannot be parsed is still returned, with only its raw message.
+    ///
+    /// When `path` is given, only commits that changed that file or directory (relative to the
+    /// repository root) are returned, e.g. `Some(Path::new("ehr_status.yaml"))`.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_dir` - The base directory for the repository kind (e.g. clinical or demographics directory).
+    /// * `uuid` - The UUID of the patient repository as a string.
+    /// * `kind` - The kind of repository, used to resolve commit domains.
+    /// * `path` - Optional repo-relative path to filter on.
+    /// * `trust_anchors` - When given, signing certificates are also checked against these
+    ///   anchors (see [`TrustAnchors::verify`]).
+    /// * `revocations` - When given, signing certificates are also checked for revocation (see
+    ///   [`RevocationList::status`]).
+    /// * `timestamp_trust_anchors` - When given, a commit's timestamp is only used in place of
+    ///   its commit time if the TSA certificate chains to one of these anchors.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PatientError` if:
+    /// - the UUID cannot be parsed,
+    /// - the Git repository cannot be opened or walked ([`PatientError::GitOpen`], [`PatientError::GitRevwalk`]),
+    /// - `path` is absolute or contains `..` ([`PatientError::InvalidInput`]).
+    pub fn commit_history(
+        base_dir: &Path,
+        uuid: &str,
+        kind: VprRepositoryKind,
+        path: Option<&Path>,
+        trust_anchors: Option<&TrustAnchors>,
+        revocations: Option<&RevocationList>,
+        timestamp_trust_anchors: Option<&TrustAnchors>,
+    ) -> PatientResult<Vec<VprCommitHistoryEntry>> {
+        let uuid = ShardableUuid::parse(uuid)?;
+        let patient_dir = uuid.sharded_dir(base_dir);
+        let repo = Self::open(&patient_dir)?;
+        repo.history(
+            kind,
+            path,
+            trust_anchors,
+            revocations,
+            timestamp_trust_anchors,
+        )
+    }
+
+    /// Walk `refs/heads/main` and build history entries for this repository.
+    ///
+    /// See [`commit_history`](Self::commit_history) for details.
+    fn history(
+        &self,
+        kind: VprRepositoryKind,
+        path: Option<&Path>,
+        trust_anchors: Option<&TrustAnchors>,
+        revocations: Option<&RevocationList>,
+        timestamp_trust_anchors: Option<&TrustAnchors>,
+    ) -> PatientResult<Vec<VprCommitHistoryEntry>> {
+        if let Some(path) = path {
+            if path.is_absolute()
+                || path
+                    .components()
+                    .any(|c| matches!(c, std::path::Component::ParentDir))
+            {
+                return Err(PatientError::InvalidInput(
+                    "history path must be relative to the repository root and must not contain '..'"
+                        .into(),
+                ));
+            }
+        }
+
+        let mut entries = Vec::new();
+        for commit in self.main_commits()? {
+            let commit = commit?;
+            let oid = commit.id();
+
+            if let Some(path) = path {
+                if !Self::commit_touches_path(&commit, path)? {
+                    continue;
+                }
+            }
+
+            let raw_message = String::from_utf8_lossy(commit.message_bytes()).into_owned();
+            let (message, author) = match commit.message() {
+                Some(raw) => match VprCommitMessage::parse_with_author(kind, raw) {
+                    Ok((message, author)) => (Some(message), Some(author)),
+                    Err(e) => {
+                        tracing::warn!("commit {} has no VPR commit message: {}", oid, e);
+                        (None, None)
+                    }
+                },
+                None => {
+                    tracing::warn!("commit {} message is not valid UTF-8", oid);
+                    (None, None)
+                }
+            };
+            let committed_at = DateTime::<Utc>::from_timestamp(commit.time().seconds(), 0)
+                .ok_or(PatientError::InvalidTimestamp)?;
+            let (_, timestamped_at) =
+                self.commit_timestamp_status(&commit, timestamp_trust_anchors)?;
+
+            entries.push(VprCommitHistoryEntry {
+                commit_id: oid.to_string(),
+                raw_message,
+                message,
+                author,
+                author_email: commit.author().email().unwrap_or_default().to_string(),
+                committed_at,
+                signature: self.commit_signature_status(
+                    &commit,
+                    trust_anchors,
+                    revocations,
+                    timestamped_at,
+                ),
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Verifies the embedded signature of every commit in a patient repository.
+    ///
+    /// [`verify_commit_signature`](Self::verify_commit_signature) only checks `HEAD`. This walks
+    /// all of `refs/heads/main` and checks each commit's embedded signature payload against the
+    /// stored commit, so a tampered commit anywhere in history is reported rather than only one
+    /// at the tip. Each commit's timestamp token, if any, is checked as well.
+    ///
+    /// Signature and timestamp problems do not cause an error; they are reported per commit as
+    /// [`CommitSignatureStatus`] and [`CommitTimestampStatus`] values.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_dir` - The base directory for the repository kind (e.g. clinical or demographics directory).
+    /// * `uuid` - The UUID of the patient repository as a string.
+    /// * `trust_anchors` - When given, each signing certificate must also chain to one of these
+    ///   anchors, be valid at commit time, permit digital signatures and match the commit's
+    ///   `Author-Registration` trailers (see [`TrustAnchors::verify`]).
+    /// * `revocations` - When given, commits whose signing certificate was revoked are reported
+    ///   as revoked before or after signing (see [`RevocationList::status`]).
+    /// * `timestamp_trust_anchors` - When given, each timestamp authority certificate must
+    ///   chain to one of these anchors (see [`TrustAnchors::verify_timestamping`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PatientError` if:
+    /// - the UUID cannot be parsed,
+    /// - the Git repository cannot be opened or walked ([`PatientError::GitOpen`], [`PatientError::GitRevwalk`]),
+    /// - a commit buffer cannot be reconstructed ([`PatientError::GitCommitBuffer`]).
+    pub fn verify_signature_chain(
+        base_dir: &Path,
+        uuid: &str,
+        trust_anchors: Option<&TrustAnchors>,
+        revocations: Option<&RevocationList>,
+        timestamp_trust_anchors: Option<&TrustAnchors>,
+    ) -> PatientResult<SignatureChainReport> {
+        let uuid = ShardableUuid::parse(uuid)?;
+        let patient_dir = uuid.sharded_dir(base_dir);
+        let repo = Self::open(&patient_dir)?;
+
+        let mut report = SignatureChainReport::default();
+        for commit in repo.main_commits()? {
+            let commit = commit?;
+            let (timestamp, timestamped_at) =
+                repo.commit_timestamp_status(&commit, timestamp_trust_anchors)?;
+            report.commits.push(CommitSignatureReport {
+                commit_id: commit.id().to_string(),
+                status: repo.commit_signature_status(
+                    &commit,
+                    trust_anchors,
+                    revocations,
+                    timestamped_at,
+                ),
+                timestamp,
+                timestamped_at,
+            });
+        }
+
+        Ok(report)
+    }
+
+    /// Iterate over the commits reachable from `refs/heads/main`, newest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PatientError::GitRevwalk`] if the walk cannot be set up; errors while walking
+    /// are yielded by the iterator.
+    fn main_commits(
+        &self,
+    ) -> PatientResult<impl Iterator<Item = PatientResult<git2::Commit<'_>>> + '_> {
+        let mut revwalk = self.repo.revwalk().map_err(PatientError::GitRevwalk)?;
+        revwalk
+            .set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)
+            .map_err(PatientError::GitRevwalk)?;
+        revwalk
+            .push_ref(MAIN_REF)
+            .map_err(PatientError::GitRevwalk)?;
+
+        Ok(revwalk.map(move |oid| {
+            let oid = oid.map_err(PatientError::GitRevwalk)?;
+            self.repo.find_commit(oid).map_err(PatientError::GitRevwalk)
+        }))
+    }
+
+    /// Returns `true` if `commit` changed the file or directory at `path`.
+    ///
+    /// The entry at `path` is compared with the same entry in the first parent's tree. For the
+    /// root commit, the path counts as changed if it exists.
+    fn commit_touches_path(commit: &git2::Commit<'_>, path: &Path) -> PatientResult<bool> {
+        let entry_id = |tree: &git2::Tree<'_>| tree.get_path(path).ok().map(|e| e.id());
+
+        let tree = commit.tree().map_err(PatientError::GitFindTree)?;
+        let current = entry_id(&tree);
+
+        let previous = match commit.parents().next() {
+            Some(parent) => {
+                let parent_tree = parent.tree().map_err(PatientError::GitFindTree)?;
+                entry_id(&parent_tree)
+            }
+            None => None,
+        };
+
+        Ok(current != previous)
+    }
+
+    /// Check the embedded signature of a single commit.
+    ///
+    /// Unlike [`verify_commit_signature`](Self::verify_commit_signature), this only uses the
+    /// key material embedded in the commit and distinguishes unsigned commits from invalid ones.
+    /// When `trust_anchors` is given, a valid signature is further checked with
+    /// [`TrustAnchors::verify`] at the commit's time. When `revocations` is given, the signing
+    /// certificate is looked up with [`RevocationList::status`]; a certificate revoked before
+    /// the commit takes precedence over every other certificate check.
+    ///
+    /// When `timestamped_at` is given (the time of a valid timestamp token, see
+    /// [`commit_timestamp_status`](Self::commit_timestamp_status)), it is used instead of the
+    /// commit time, which the signer controls.
+    fn commit_signature_status(
+        &self,
+        commit: &git2::Commit<'_>,
+        trust_anchors: Option<&TrustAnchors>,
+        revocations: Option<&RevocationList>,
+        timestamped_at: Option<DateTime<Utc>>,
+    ) -> CommitSignatureStatus {
+        let embedded = match self.verify_embedded_signature(commit) {
+            None => return CommitSignatureStatus::Unsigned,
+            Some(Ok(signed)) => signed,
+            Some(Err(status)) => return status,
+        };
+
+        let signed_at =
+            timestamped_at.or_else(|| DateTime::<Utc>::from_timestamp(commit.time().seconds(), 0));
+        let revocation = match (revocations, embedded.certificate.as_deref(), signed_at) {
+            (Some(revocations), Some(certificate), Some(signed_at)) => {
+                revocations.status(certificate, signed_at)
+            }
+            _ => RevocationStatus::NotRevoked,
+        };
+        if revocation == RevocationStatus::RevokedBeforeSigning {
+            return CommitSignatureStatus::RevokedBeforeSigning;
+        }
+
+        if let Some(trust_anchors) = trust_anchors {
+            let (Some(certificate), Some(signed_at)) = (embedded.certificate.as_deref(), signed_at)
+            else {
+                return CommitSignatureStatus::Untrusted;
+            };
+            let registrations = registration_trailers(commit.message().unwrap_or_default());
+
+            match trust_anchors.verify(certificate, signed_at, &registrations) {
+                Ok(()) => {}
+                Err(CertificateTrustError::RegistrationMismatch) => {
+                    return CommitSignatureStatus::RegistrationMismatch
+                }
+                Err(e) => {
+                    tracing::debug!(
+                        "commit {} signing certificate not trusted: {}",
+                        commit.id(),
+                        e
+                    );
+                    return CommitSignatureStatus::Untrusted;
+                }
+            }
+        }
+
+        if revocation == RevocationStatus::RevokedAfterSigning {
+            CommitSignatureStatus::RevokedAfterSigning
+        } else {
+            CommitSignatureStatus::Valid
+        }
+    }
+
+    /// Check the timestamp token of a single commit.
+    ///
+    /// The token must cover the commit buffer as it was before the `vpr-timestamp` and
+    /// `gpgsig` headers were added, and the time it asserts must be within
+    /// [`timestamp::MAX_CLOCK_SKEW_SECONDS`] of the commit time.
+    ///
+    /// # Returns
+    ///
+    /// The status, and the time asserted by the token if it is valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PatientError` only if the commit buffer cannot be reconstructed; timestamp
+    /// problems are reported through the returned [`CommitTimestampStatus`].
+    fn commit_timestamp_status(
+        &self,
+        commit: &git2::Commit<'_>,
+        trust_anchors: Option<&TrustAnchors>,
+    ) -> PatientResult<(CommitTimestampStatus, Option<DateTime<Utc>>)> {
+        let Ok(header) = commit.header_field_bytes(timestamp::TIMESTAMP_HEADER) else {
+            return Ok((CommitTimestampStatus::Absent, None));
+        };
+        let encoded: Vec<u8> = header
+            .iter()
+            .copied()
+            .filter(|b| !b.is_ascii_whitespace())
+            .collect();
+        let Ok(token) = general_purpose::STANDARD.decode(encoded) else {
+            return Ok((CommitTimestampStatus::Invalid, None));
+        };
+
+        let buf_str = self.unsigned_commit_buffer(commit)?;
+        match timestamp::verify_token(&token, buf_str.as_bytes(), trust_anchors) {
+            Ok(time)
+                if (time.timestamp() - commit.time().seconds()).abs()
+                    <= timestamp::MAX_CLOCK_SKEW_SECONDS =>
+            {
+                Ok((CommitTimestampStatus::Valid, Some(time)))
+            }
+            Ok(time) => {
+                tracing::debug!(
+                    "commit {} time differs from its timestamp {}",
+                    commit.id(),
+                    time
+                );
+                Ok((CommitTimestampStatus::Invalid, None))
+            }
+            Err(TimestampError::Untrusted(e)) => {
+                tracing::debug!("commit {} timestamp not trusted: {}", commit.id(), e);
+                Ok((CommitTimestampStatus::Untrusted, None))
+            }
+            Err(e) => {
+                tracing::debug!("commit {} timestamp invalid: {}", commit.id(), e);
+                Ok((CommitTimestampStatus::Invalid, None))
+            }
+        }
+    }
+
+    /// Verifies the signature in a commit's `gpgsig` header.
+    ///
+    /// The signature is checked over the stored commit without its `gpgsig` header, which is
+    /// what was signed. Both the VPR signature container and SSH signatures are accepted; the
+    /// signature is checked against the public key embedded with it.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the commit is unsigned, the signer if the signature is valid, or
+    /// [`CommitSignatureStatus::CertificateMismatch`] or [`CommitSignatureStatus::Invalid`].
+    fn verify_embedded_signature(
+        &self,
+        commit: &git2::Commit<'_>,
+    ) -> Option<Result<VerifiedCommitSignature, CommitSignatureStatus>> {
+        let gpgsig = commit.header_field_bytes("gpgsig").ok()?;
+        let signed_data = match self.repo.extract_signature(&commit.id(), None) {
+            Ok((_, signed_data)) => signed_data,
+            Err(_) => return Some(Err(CommitSignatureStatus::Invalid)),
+        };
+
+        if signing::is_ssh_signature(&gpgsig) {
+            return Some(
+                signing::verify_ssh_signature(&gpgsig, &signed_data)
+                    .map(|public_key| VerifiedCommitSignature {
+                        public_key,
+                        certificate: None,
+                    })
+                    .ok_or(CommitSignatureStatus::Invalid),
+            );
+        }
+
+        let embedded = match crate::author::extract_embedded_commit_signature(commit) {
+            Ok(v) => v,
+            Err(PatientError::AuthorCertificatePublicKeyMismatch) => {
+                return Some(Err(CommitSignatureStatus::CertificateMismatch))
+            }
+            Err(_) => return Some(Err(CommitSignatureStatus::Invalid)),
+        };
+        if !signing::verify_signature(
+            embedded.algorithm,
+            &embedded.public_key,
+            &signed_data,
+            &embedded.signature,
+        ) {
+            return Some(Err(CommitSignatureStatus::Invalid));
+        }
+
+        Some(Ok(VerifiedCommitSignature {
+            public_key: embedded.public_key,
+            certificate: embedded.certificate,
+        }))
+    }
+
+    /// Recreate the commit buffer of `commit` as it was before any `vpr-timestamp` or `gpgsig`
+    /// header was added.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PatientError` if the commit tree cannot be read or the buffer cannot be
+    /// created or converted to UTF-8.
+    fn unsigned_commit_buffer(&self, commit: &git2::Commit<'_>) -> PatientResult<String> {
         let tree = commit.tree().map_err(PatientError::GitFindTree)?;
         let parents: Vec<git2::Commit> = commit.parents().collect();
         let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
@@ -1363,24 +2476,47 @@ impl VersionedFileService {
         let author = commit.author();
         let committer = commit.committer();
 
-        let buf = repo
+        let buf = self
             .repo
             .commit_create_buffer(&author, &committer, message, &tree, &parent_refs)
             .map_err(PatientError::GitCommitBuffer)?;
-        let buf_str =
-            String::from_utf8(buf.as_ref().to_vec()).map_err(PatientError::CommitBufferToString)?;
-
-        // Verify with the canonical payload.
-        Ok(embedded_verifying_key
-            .verify(buf_str.as_bytes(), &signature)
-            .is_ok())
+        String::from_utf8(buf.as_ref().to_vec()).map_err(PatientError::CommitBufferToString)
     }
 }
 
+/// Adds a `vpr-timestamp` header holding `token` to the end of the headers of a commit buffer.
+///
+/// The base64 token is folded over continuation lines that start with a space, as Git does for
+/// `gpgsig`.
+fn with_timestamp_header(buffer: &str, token: &[u8]) -> String {
+    let encoded = general_purpose::STANDARD.encode(token);
+    let folded = encoded
+        .as_bytes()
+        .chunks(64)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n ");
+
+    let (headers, message) = buffer.split_once("\n\n").unwrap_or((buffer, ""));
+    format!(
+        "{headers}\n{} {folded}\n\n{message}",
+        timestamp::TIMESTAMP_HEADER
+    )
+}
+
+/// The key material of a commit whose signature has been verified.
+struct VerifiedCommitSignature {
+    /// Public key bytes of the signer.
+    public_key: Vec<u8>,
+    /// Embedded X.509 certificate, if any; SSH signatures never carry one.
+    certificate: Option<Vec<u8>>,
+}
+
 /// Parse a public key from PEM format or extract it from an X.509 certificate.
 ///
-/// This function handles both raw ECDSA public keys in PEM format and X.509 certificates.
-/// It's used during signature verification to parse trusted public keys provided by callers.
+/// This function handles PEM public keys (P-256, P-384 or Ed25519), OpenSSH public key lines
+/// (`ssh-ed25519 AAAA...`) and X.509 certificates. It's used during signature verification to parse trusted public keys
+/// provided by callers.
 ///
 /// # Arguments
 ///
@@ -1388,28 +2524,33 @@ impl VersionedFileService {
 ///
 /// # Returns
 ///
-/// A `VerifyingKey` for ECDSA signature verification.
+/// The public key bytes, encoded as in the commit signature payload.
 ///
 /// # Errors
 ///
 /// Returns `PatientError::EcdsaPublicKeyParse` if parsing fails.
-fn verifying_key_from_public_key_or_cert_pem(pem_or_cert: &str) -> PatientResult<VerifyingKey> {
+fn public_key_from_public_key_or_cert_pem(pem_or_cert: &str) -> PatientResult<Vec<u8>> {
     if pem_or_cert.contains("-----BEGIN CERTIFICATE-----") {
-        let (_, pem) = x509_parser::pem::parse_x509_pem(pem_or_cert.as_bytes())
-            .map_err(|e| PatientError::EcdsaPublicKeyParse(Box::new(e)))?;
-        let (_, cert) = X509Certificate::from_der(pem.contents.as_ref())
-            .map_err(|e| PatientError::EcdsaPublicKeyParse(Box::new(e)))?;
-
-        let spk = cert.public_key();
-        let key_bytes = &spk.subject_public_key.data;
-        VerifyingKey::from_sec1_bytes(key_bytes.as_ref())
-            .map_err(|e| PatientError::EcdsaPublicKeyParse(Box::new(e)))
+        extract_cert_public_key_sec1(pem_or_cert.as_bytes())
+    } else if !pem_or_cert.contains("-----BEGIN") {
+        signing::public_key_bytes_from_openssh(pem_or_cert)
     } else {
-        VerifyingKey::from_public_key_pem(pem_or_cert)
-            .map_err(|e| PatientError::EcdsaPublicKeyParse(Box::new(e)))
+        signing::public_key_bytes_from_pem(pem_or_cert)
     }
 }
 
+/// Reads the `Author-Registration` trailers of a raw commit message.
+///
+/// Unlike [`VprCommitMessage::parse_with_author`] this does not need the repository kind and
+/// skips malformed trailers, which simply fail to match a certificate.
+fn registration_trailers(raw: &str) -> Vec<AuthorRegistration> {
+    raw.lines()
+        .filter_map(|line| line.trim().strip_prefix("Author-Registration: "))
+        .filter_map(|value| value.trim().split_once(' '))
+        .filter_map(|(authority, number)| AuthorRegistration::new(authority, number).ok())
+        .collect()
+}
+
 #[cfg(test)]
 static FORCE_CLEANUP_ERROR_FOR_THREADS: LazyLock<Mutex<HashSet<std::thread::ThreadId>>> =
     LazyLock::new(|| Mutex::new(HashSet::new()));
@@ -1435,6 +2576,7 @@ mod tests {
     use super::ClinicalDomain::*;
     use super::*;
     use crate::{EmailAddress, NonEmptyText};
+    use p256::ecdsa::SigningKey;
     use tempfile::TempDir;
 
     #[test]
@@ -1535,12 +2677,12 @@ mod tests {
             .expect("Failed to encode public key");
 
         // Parse it back
-        let parsed_key = verifying_key_from_public_key_or_cert_pem(&pem).unwrap();
+        let parsed_key = public_key_from_public_key_or_cert_pem(&pem).unwrap();
 
         // Verify they match
         assert_eq!(
             verifying_key.to_encoded_point(false).as_bytes(),
-            parsed_key.to_encoded_point(false).as_bytes()
+            parsed_key.as_slice()
         );
     }
 
@@ -1650,4 +2792,961 @@ mod tests {
         let err = VprCommitTrailer::new("Bad:Key", "Value").unwrap_err();
         assert!(matches!(err, PatientError::InvalidInput(_)));
     }
+
+    fn history_test_author() -> Author {
+        Author {
+            name: NonEmptyText::new("Test Author").unwrap(),
+            role: NonEmptyText::new("Clinician").unwrap(),
+            email: EmailAddress::parse("test@example.com").unwrap(),
+            registrations: vec![AuthorRegistration::new("GMC", "12345").unwrap()],
+            signature: None,
+            certificate: None,
+        }
+    }
+
+    #[test]
+    fn parse_with_author_round_trips_rendered_message() {
+        let author = history_test_author();
+        let msg = VprCommitMessage::new(
+            VprCommitDomain::Clinical(Correction),
+            VprCommitAction::Superseded,
+            "Letter superseded",
+            "St Elsewhere Hospital",
+        )
+        .unwrap()
+        .with_trailer("Change-Reason", "Correction")
+        .unwrap();
+
+        let rendered = msg.render_with_author(&author).unwrap();
+        let (parsed, parsed_author) =
+            VprCommitMessage::parse_with_author(VprRepositoryKind::Clinical, &rendered).unwrap();
+
+        assert_eq!(parsed, msg);
+        assert_eq!(parsed.care_location(), "St Elsewhere Hospital");
+        assert_eq!(parsed_author.name.as_str(), "Test Author");
+        assert_eq!(parsed_author.role.as_str(), "Clinician");
+        assert_eq!(parsed_author.registrations, author.registrations);
+    }
+
+    #[test]
+    fn parse_resolves_domain_by_repository_kind() {
+        let raw = "record:update: Demographics updated\n\nAuthor-Name: Test Author\nAuthor-Role: Clinician\nCare-Location: St Elsewhere Hospital";
+
+        let (clinical, _) =
+            VprCommitMessage::parse_with_author(VprRepositoryKind::Clinical, raw).unwrap();
+        let (demographics, _) =
+            VprCommitMessage::parse_with_author(VprRepositoryKind::Demographics, raw).unwrap();
+
+        assert_eq!(clinical.domain(), VprCommitDomain::Clinical(Record));
+        assert_eq!(
+            demographics.domain(),
+            VprCommitDomain::Demographics(DemographicsDomain::Record)
+        );
+
+        let err = VprCommitMessage::parse_with_author(
+            VprRepositoryKind::Demographics,
+            "messaging:create: Thread created\n\nAuthor-Name: A\nAuthor-Role: B\nCare-Location: C",
+        )
+        .unwrap_err();
+        assert!(matches!(err, PatientError::InvalidCommitMessage(_)));
+    }
+
+    #[test]
+    fn parse_rejects_missing_care_location() {
+        let err = VprCommitMessage::parse_with_author(
+            VprRepositoryKind::Clinical,
+            "record:create: Record created\n\nAuthor-Name: Test Author\nAuthor-Role: Clinician",
+        )
+        .unwrap_err();
+        assert!(matches!(err, PatientError::MissingCareLocation));
+    }
+
+    #[test]
+    fn history_filters_by_path_and_reports_signature_status() {
+        use p256::pkcs8::EncodePrivateKey;
+
+        let temp_dir = TempDir::new().unwrap();
+        let service = VersionedFileService::init(temp_dir.path()).unwrap();
+
+        let unsigned_author = history_test_author();
+        let mut signed_author = history_test_author();
+        let signing_key = SigningKey::random(&mut rand::thread_rng());
+        signed_author.signature = Some(
+            signing_key
+                .to_pkcs8_pem(p256::pkcs8::LineEnding::LF)
+                .unwrap()
+                .as_bytes()
+                .to_vec(),
+        );
+
+        let create = VprCommitMessage::new(
+            VprCommitDomain::Clinical(Record),
+            VprCommitAction::Create,
+            "Record created",
+            "St Elsewhere Hospital",
+        )
+        .unwrap();
+        VersionedFileService::write_and_commit_files(
+            &RepositoryLock::acquire(temp_dir.path()).unwrap(),
+            &unsigned_author,
+            &create,
+            &[FileToWrite {
+                relative_path: Path::new("ehr_status.yaml"),
+                content: "v1",
+                old_content: None,
+            }],
+            None,
+            None,
+        )
+        .unwrap();
+
+        let add_letter = VprCommitMessage::new(
+            VprCommitDomain::Clinical(Record),
+            VprCommitAction::Create,
+            "Letter created",
+            "Ward 7",
+        )
+        .unwrap();
+        VersionedFileService::write_and_commit_files(
+            &RepositoryLock::acquire(temp_dir.path()).unwrap(),
+            &signed_author,
+            &add_letter,
+            &[FileToWrite {
+                relative_path: Path::new("correspondence/letter/body.md"),
+                content: "Dear colleague",
+                old_content: None,
+            }],
+            None,
+            None,
+        )
+        .unwrap();
+
+        let all = service
+            .history(VprRepositoryKind::Clinical, None, None, None, None)
+            .unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].message.as_ref().unwrap().summary(), "Letter created");
+        assert_eq!(all[0].message.as_ref().unwrap().care_location(), "Ward 7");
+        assert_eq!(all[0].signature, CommitSignatureStatus::Valid);
+        assert_eq!(all[1].message.as_ref().unwrap().summary(), "Record created");
+        assert_eq!(all[1].signature, CommitSignatureStatus::Unsigned);
+        assert_eq!(all[1].author.as_ref().unwrap().name.as_str(), "Test Author");
+        assert_eq!(all[1].author_email, "test@example.com");
+
+        let ehr_status = service
+            .history(
+                VprRepositoryKind::Clinical,
+                Some(Path::new("ehr_status.yaml")),
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(ehr_status.len(), 1);
+        assert_eq!(ehr_status[0].commit_id, all[1].commit_id);
+
+        let correspondence = service
+            .history(
+                VprRepositoryKind::Clinical,
+                Some(Path::new("correspondence")),
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(correspondence.len(), 1);
+        assert_eq!(correspondence[0].commit_id, all[0].commit_id);
+
+        let err = service
+            .history(
+                VprRepositoryKind::Clinical,
+                Some(Path::new("../escape")),
+                None,
+                None,
+                None,
+            )
+            .unwrap_err();
+        assert!(matches!(err, PatientError::InvalidInput(_)));
+    }
+
+    #[test]
+    fn history_keeps_commits_with_non_vpr_messages() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = VersionedFileService::init(temp_dir.path()).unwrap();
+        let author = history_test_author();
+
+        let commit = |summary: &str, file: &str| {
+            let msg = VprCommitMessage::new(
+                VprCommitDomain::Clinical(Record),
+                VprCommitAction::Update,
+                summary,
+                "Ward 7",
+            )
+            .unwrap();
+            VersionedFileService::write_and_commit_files(
+                &RepositoryLock::acquire(temp_dir.path()).unwrap(),
+                &author,
+                &msg,
+                &[FileToWrite {
+                    relative_path: Path::new(file),
+                    content: summary,
+                    old_content: None,
+                }],
+                None,
+                None,
+            )
+            .unwrap();
+        };
+
+        commit("Record created", "ehr_status.yaml");
+
+        // A manual repair commit made with plain git.
+        let head = service.repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = git2::Signature::now("Ops", "ops@example.com").unwrap();
+        service
+            .repo
+            .commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                "Fix line endings\n",
+                &head.tree().unwrap(),
+                &[&head],
+            )
+            .unwrap();
+
+        commit("Letter created", "letter.md");
+
+        let history = service
+            .history(VprRepositoryKind::Clinical, None, None, None, None)
+            .unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(
+            history[0].message.as_ref().unwrap().summary(),
+            "Letter created"
+        );
+        assert!(history[1].message.is_none());
+        assert!(history[1].author.is_none());
+        assert_eq!(history[1].raw_message, "Fix line endings\n");
+        assert_eq!(history[1].author_email, "ops@example.com");
+        assert_eq!(
+            history[2].message.as_ref().unwrap().summary(),
+            "Record created"
+        );
+    }
+
+    #[test]
+    fn verify_signature_chain_flags_tampered_commit_below_head() {
+        use p256::pkcs8::EncodePrivateKey;
+
+        let temp_dir = TempDir::new().unwrap();
+        let uuid = ShardableUuid::new();
+        let patient_dir = uuid.sharded_dir(temp_dir.path());
+        std::fs::create_dir_all(&patient_dir).unwrap();
+        let service = VersionedFileService::init(&patient_dir).unwrap();
+
+        let mut author = history_test_author();
+        let signing_key = SigningKey::random(&mut rand::thread_rng());
+        author.signature = Some(
+            signing_key
+                .to_pkcs8_pem(p256::pkcs8::LineEnding::LF)
+                .unwrap()
+                .as_bytes()
+                .to_vec(),
+        );
+
+        let commit_file = |name: &str, summary: &str| {
+            let msg = VprCommitMessage::new(
+                VprCommitDomain::Clinical(Record),
+                VprCommitAction::Create,
+                summary,
+                "St Elsewhere Hospital",
+            )
+            .unwrap();
+            VersionedFileService::write_and_commit_files(
+                &RepositoryLock::acquire(&patient_dir).unwrap(),
+                &author,
+                &msg,
+                &[FileToWrite {
+                    relative_path: Path::new(name),
+                    content: summary,
+                    old_content: None,
+                }],
+                None,
+                None,
+            )
+            .unwrap();
+        };
+
+        commit_file("a.md", "First entry");
+        commit_file("b.md", "Second entry");
+
+        // Rewrite the second commit's message while keeping its original signature, then
+        // build a properly signed commit on top of the forged one.
+        let head = service.repo.head().unwrap().peel_to_commit().unwrap();
+        let (gpgsig, _) = service.repo.extract_signature(&head.id(), None).unwrap();
+        let tampered_message = head
+            .message()
+            .unwrap()
+            .replace("Second entry", "Forged entry");
+        let parents: Vec<git2::Commit> = head.parents().collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        let buf = service
+            .repo
+            .commit_create_buffer(
+                &head.author(),
+                &head.committer(),
+                &tampered_message,
+                &head.tree().unwrap(),
+                &parent_refs,
+            )
+            .unwrap();
+        let forged = service
+            .repo
+            .commit_signed(
+                std::str::from_utf8(&buf).unwrap(),
+                gpgsig.as_str().unwrap(),
+                None,
+            )
+            .unwrap();
+        service
+            .repo
+            .reference(MAIN_REF, forged, true, "tamper")
+            .unwrap();
+
+        commit_file("c.md", "Third entry");
+
+        let report = VersionedFileService::verify_signature_chain(
+            temp_dir.path(),
+            &uuid.to_string(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let statuses: Vec<CommitSignatureStatus> =
+            report.commits.iter().map(|c| c.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                CommitSignatureStatus::Valid,
+                CommitSignatureStatus::Invalid,
+                CommitSignatureStatus::Valid,
+            ]
+        );
+        assert!(!report.is_valid());
+        assert_eq!(report.count(CommitSignatureStatus::Valid), 2);
+
+        let failures: Vec<&CommitSignatureReport> = report.failures().collect();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].commit_id, forged.to_string());
+    }
+
+    #[test]
+    fn verify_signature_chain_reports_unsigned_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let uuid = ShardableUuid::new();
+        let patient_dir = uuid.sharded_dir(temp_dir.path());
+        std::fs::create_dir_all(&patient_dir).unwrap();
+        VersionedFileService::init(&patient_dir).unwrap();
+
+        let msg = VprCommitMessage::new(
+            VprCommitDomain::Clinical(Record),
+            VprCommitAction::Create,
+            "Record created",
+            "St Elsewhere Hospital",
+        )
+        .unwrap();
+        VersionedFileService::write_and_commit_files(
+            &RepositoryLock::acquire(&patient_dir).unwrap(),
+            &history_test_author(),
+            &msg,
+            &[FileToWrite {
+                relative_path: Path::new("ehr_status.yaml"),
+                content: "v1",
+                old_content: None,
+            }],
+            None,
+            None,
+        )
+        .unwrap();
+
+        let report = VersionedFileService::verify_signature_chain(
+            temp_dir.path(),
+            &uuid.to_string(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(report.commits.len(), 1);
+        assert_eq!(report.count(CommitSignatureStatus::Unsigned), 1);
+        assert_eq!(report.failures().count(), 0);
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn verify_signature_chain_accepts_each_algorithm_and_version_1_payloads() {
+        use p256::ecdsa::signature::Signer;
+        use vpr_certificates::{Certificate, KeyAlgorithm};
+
+        let temp_dir = TempDir::new().unwrap();
+        let uuid = ShardableUuid::new();
+        let patient_dir = uuid.sharded_dir(temp_dir.path());
+        std::fs::create_dir_all(&patient_dir).unwrap();
+        let service = VersionedFileService::init(&patient_dir).unwrap();
+
+        let algorithms = [
+            (KeyAlgorithm::EcdsaP256, SignatureAlgorithm::EcdsaP256),
+            (KeyAlgorithm::EcdsaP384, SignatureAlgorithm::EcdsaP384),
+            (KeyAlgorithm::Ed25519, SignatureAlgorithm::Ed25519),
+        ];
+        for (i, (key_algorithm, _)) in algorithms.iter().enumerate() {
+            let (cert_pem, key_pem) = Certificate::create_with_algorithm(
+                "Dr Jane Smith",
+                "GMC",
+                "1234567",
+                *key_algorithm,
+            )
+            .unwrap();
+            let mut author = history_test_author();
+            author.signature = Some(key_pem.into_bytes());
+            author.certificate = Some(cert_pem.into_bytes());
+
+            let summary = format!("Entry {i}");
+            let msg = VprCommitMessage::new(
+                VprCommitDomain::Clinical(Record),
+                VprCommitAction::Create,
+                &summary,
+                "St Elsewhere Hospital",
+            )
+            .unwrap();
+            VersionedFileService::write_and_commit_files(
+                &RepositoryLock::acquire(&patient_dir).unwrap(),
+                &author,
+                &msg,
+                &[FileToWrite {
+                    relative_path: Path::new(&format!("{i}.md")),
+                    content: &summary,
+                    old_content: None,
+                }],
+                None,
+                None,
+            )
+            .unwrap();
+        }
+
+        // A commit signed before payloads carried a version or algorithm.
+        let head = service.repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = git2::Signature::now("Legacy Author", "legacy@example.com").unwrap();
+        let buf = service
+            .repo
+            .commit_create_buffer(
+                &sig,
+                &sig,
+                "clinical:update: Legacy entry",
+                &head.tree().unwrap(),
+                &[&head],
+            )
+            .unwrap();
+        let buf = std::str::from_utf8(&buf).unwrap();
+        let signing_key = SigningKey::random(&mut rand::thread_rng());
+        let signature: p256::ecdsa::Signature = signing_key.sign(buf.as_bytes());
+        let v1_payload = serde_json::json!({
+            "signature": general_purpose::STANDARD.encode(signature.to_bytes()),
+            "public_key": general_purpose::STANDARD.encode(
+                signing_key.verifying_key().to_encoded_point(false).as_bytes()
+            ),
+        });
+        let legacy = service
+            .repo
+            .commit_signed(
+                buf,
+                &general_purpose::STANDARD.encode(v1_payload.to_string()),
+                None,
+            )
+            .unwrap();
+        service
+            .repo
+            .reference(MAIN_REF, legacy, true, "legacy commit")
+            .unwrap();
+
+        let report = VersionedFileService::verify_signature_chain(
+            temp_dir.path(),
+            &uuid.to_string(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(report.commits.len(), 4);
+        assert!(report.is_valid(), "{:?}", report.commits);
+
+        // Newest first: the legacy commit, then the algorithms in reverse order.
+        let embedded_algorithms: Vec<SignatureAlgorithm> = report
+            .commits
+            .iter()
+            .map(|c| {
+                let oid = git2::Oid::from_str(&c.commit_id).unwrap();
+                let commit = service.repo.find_commit(oid).unwrap();
+                crate::author::extract_embedded_commit_signature(&commit)
+                    .unwrap()
+                    .algorithm
+            })
+            .collect();
+        let mut expected: Vec<SignatureAlgorithm> =
+            algorithms.iter().rev().map(|(_, a)| *a).collect();
+        expected.insert(0, SignatureAlgorithm::EcdsaP256);
+        assert_eq!(embedded_algorithms, expected);
+    }
+
+    #[test]
+    fn ssh_signed_commits_verify_and_refuse_certificates() {
+        use ssh_key::{LineEnding, PrivateKey};
+
+        let temp_dir = TempDir::new().unwrap();
+        let uuid = ShardableUuid::new();
+        let patient_dir = uuid.sharded_dir(temp_dir.path());
+        std::fs::create_dir_all(&patient_dir).unwrap();
+        let service = VersionedFileService::init(&patient_dir).unwrap();
+
+        let private_key =
+            PrivateKey::random(&mut rand::thread_rng(), ssh_key::Algorithm::Ed25519).unwrap();
+        let mut author = history_test_author();
+        author.signature = Some(
+            private_key
+                .to_openssh(LineEnding::LF)
+                .unwrap()
+                .as_bytes()
+                .to_vec(),
+        );
+
+        let msg = VprCommitMessage::new(
+            VprCommitDomain::Clinical(Record),
+            VprCommitAction::Create,
+            "Record created",
+            "St Elsewhere Hospital",
+        )
+        .unwrap();
+        let files = [FileToWrite {
+            relative_path: Path::new("ehr_status.yaml"),
+            content: "v1",
+            old_content: None,
+        }];
+        VersionedFileService::write_and_commit_files(
+            &RepositoryLock::acquire(&patient_dir).unwrap(),
+            &author,
+            &msg,
+            &files,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let head = service.repo.head().unwrap().peel_to_commit().unwrap();
+        let gpgsig = head.header_field_bytes("gpgsig").unwrap();
+        assert!(gpgsig.starts_with(b"-----BEGIN SSH SIGNATURE-----"));
+
+        let report = VersionedFileService::verify_signature_chain(
+            temp_dir.path(),
+            &uuid.to_string(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(report.count(CommitSignatureStatus::Valid), 1);
+
+        let public_key = private_key.public_key().to_openssh().unwrap();
+        assert!(VersionedFileService::verify_commit_signature(
+            temp_dir.path(),
+            &uuid.to_string(),
+            &public_key,
+        )
+        .unwrap());
+        let other = PrivateKey::random(&mut rand::thread_rng(), ssh_key::Algorithm::Ed25519)
+            .unwrap()
+            .public_key()
+            .to_openssh()
+            .unwrap();
+        assert!(!VersionedFileService::verify_commit_signature(
+            temp_dir.path(),
+            &uuid.to_string(),
+            &other,
+        )
+        .unwrap());
+
+        let (cert_pem, _) =
+            vpr_certificates::Certificate::create("Dr Jane Smith", "GMC", "1234567").unwrap();
+        author.certificate = Some(cert_pem.into_bytes());
+        let err = VersionedFileService::write_and_commit_files(
+            &RepositoryLock::acquire(&patient_dir).unwrap(),
+            &author,
+            &msg,
+            &files,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, PatientError::InvalidInput(_)));
+    }
+
+    #[test]
+    fn writes_fail_cleanly_if_main_moved_after_the_lock_was_taken() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = VersionedFileService::init(temp_dir.path()).unwrap();
+        let author = history_test_author();
+        let msg = VprCommitMessage::new(
+            VprCommitDomain::Clinical(Record),
+            VprCommitAction::Update,
+            "Status updated",
+            "St Elsewhere Hospital",
+        )
+        .unwrap();
+        let write = |lock: &RepositoryLock, content, old_content| {
+            VersionedFileService::write_and_commit_files(
+                lock,
+                &author,
+                &msg,
+                &[FileToWrite {
+                    relative_path: Path::new("ehr_status.yaml"),
+                    content,
+                    old_content,
+                }],
+                None,
+                None,
+            )
+        };
+        write(
+            &RepositoryLock::acquire(temp_dir.path()).unwrap(),
+            "v1",
+            None,
+        )
+        .unwrap();
+
+        // A writer that does not take the lock moves `main` under a VPR writer.
+        let lock = RepositoryLock::acquire(temp_dir.path()).unwrap();
+        let head = service.repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = git2::Signature::now("Other", "other@example.com").unwrap();
+        let moved = service
+            .repo
+            .commit(
+                Some(MAIN_REF),
+                &sig,
+                &sig,
+                "outside VPR",
+                &head.tree().unwrap(),
+                &[&head],
+            )
+            .unwrap();
+
+        let err = write(&lock, "v2", Some("v1")).unwrap_err();
+        assert!(matches!(err, PatientError::ConcurrentModification { .. }));
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("ehr_status.yaml")).unwrap(),
+            "v1"
+        );
+        assert_eq!(service.repo.refname_to_id(MAIN_REF).unwrap(), moved);
+
+        // Retrying under a fresh lock builds on the moved `main`.
+        drop(lock);
+        let oid = write(
+            &RepositoryLock::acquire(temp_dir.path()).unwrap(),
+            "v2",
+            Some("v1"),
+        )
+        .unwrap();
+        let commit = service.repo.find_commit(oid).unwrap();
+        assert_eq!(commit.parent_id(0).unwrap(), moved);
+    }
+
+    #[test]
+    fn verify_signature_chain_checks_commit_timestamps() {
+        use crate::timestamp::LocalTimestampAuthority;
+        use p256::pkcs8::EncodePrivateKey;
+
+        let temp_dir = TempDir::new().unwrap();
+        let uuid = ShardableUuid::new();
+        let patient_dir = uuid.sharded_dir(temp_dir.path());
+        std::fs::create_dir_all(&patient_dir).unwrap();
+        let service = VersionedFileService::init(&patient_dir).unwrap();
+
+        let tsa = LocalTimestampAuthority::new();
+        let skewed_tsa =
+            LocalTimestampAuthority::new().with_clock_offset(chrono::Duration::hours(1));
+
+        let mut signed_author = history_test_author();
+        signed_author.signature = Some(
+            SigningKey::random(&mut rand::thread_rng())
+                .to_pkcs8_pem(p256::pkcs8::LineEnding::LF)
+                .unwrap()
+                .as_bytes()
+                .to_vec(),
+        );
+        let unsigned_author = history_test_author();
+
+        let commit_file = |author: &Author, name: &str, tsa: &dyn TimestampAuthority| {
+            let msg = VprCommitMessage::new(
+                VprCommitDomain::Clinical(Record),
+                VprCommitAction::Create,
+                "Entry added",
+                "St Elsewhere Hospital",
+            )
+            .unwrap();
+            let files = [FileToWrite {
+                relative_path: Path::new(name),
+                content: name,
+                old_content: None,
+            }];
+            VersionedFileService::write_and_commit_files(
+                &RepositoryLock::acquire(&patient_dir).unwrap(),
+                author,
+                &msg,
+                &files,
+                Some(tsa),
+                None,
+            )
+            .unwrap()
+        };
+
+        let signed = commit_file(&signed_author, "a.md", &tsa);
+        let unsigned = commit_file(&unsigned_author, "b.md", &tsa);
+        let skewed = commit_file(&signed_author, "c.md", &skewed_tsa);
+
+        let head = service.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.id(), skewed);
+        assert!(head.header_field_bytes(timestamp::TIMESTAMP_HEADER).is_ok());
+
+        let verify = |timestamp_trust_anchors: Option<&TrustAnchors>| {
+            VersionedFileService::verify_signature_chain(
+                temp_dir.path(),
+                &uuid.to_string(),
+                None,
+                None,
+                timestamp_trust_anchors,
+            )
+            .unwrap()
+        };
+
+        let report = verify(None);
+        let statuses: Vec<(String, CommitSignatureStatus, CommitTimestampStatus)> = report
+            .commits
+            .iter()
+            .map(|c| (c.commit_id.clone(), c.status, c.timestamp))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (
+                    skewed.to_string(),
+                    CommitSignatureStatus::Valid,
+                    CommitTimestampStatus::Invalid
+                ),
+                (
+                    unsigned.to_string(),
+                    CommitSignatureStatus::Unsigned,
+                    CommitTimestampStatus::Valid
+                ),
+                (
+                    signed.to_string(),
+                    CommitSignatureStatus::Valid,
+                    CommitTimestampStatus::Valid
+                ),
+            ]
+        );
+        assert!(report.commits[2].timestamped_at.is_some());
+        let failures: Vec<&CommitSignatureReport> = report.failures().collect();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].commit_id, skewed.to_string());
+
+        let anchors = TrustAnchors::from_pem(tsa.ca_pem().as_bytes()).unwrap();
+        let report = verify(Some(&anchors));
+        assert_eq!(report.count_timestamps(CommitTimestampStatus::Valid), 2);
+        assert_eq!(report.count_timestamps(CommitTimestampStatus::Untrusted), 1);
+
+        let other = LocalTimestampAuthority::new();
+        let anchors = TrustAnchors::from_pem(other.ca_pem().as_bytes()).unwrap();
+        let report = verify(Some(&anchors));
+        assert_eq!(report.count_timestamps(CommitTimestampStatus::Untrusted), 3);
+        assert!(!report.is_valid());
+
+        // History still parses commits carrying the extra header.
+        let history = service
+            .history(VprRepositoryKind::Clinical, None, None, None, None)
+            .unwrap();
+        assert_eq!(history.len(), 3);
+    }
+
+    #[test]
+    fn verify_signature_chain_checks_certificates_against_trust_anchors() {
+        use rcgen::{
+            BasicConstraints, CertificateParams, DnType, IsCa, KeyPair, KeyUsagePurpose, SanType,
+        };
+
+        let ca = |name: &str| {
+            let key = KeyPair::generate().unwrap();
+            let mut params = CertificateParams::default();
+            params.distinguished_name.push(DnType::CommonName, name);
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            params.key_usages = vec![KeyUsagePurpose::KeyCertSign];
+            (params.self_signed(&key).unwrap(), key)
+        };
+        let (root, root_key) = ca("VPR Root CA");
+        let (other, _) = ca("Other CA");
+
+        let signing_key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::default();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "Dr Jane Smith");
+        params.subject_alt_names.push(SanType::URI(
+            "vpr://GMC/1234567".to_string().try_into().unwrap(),
+        ));
+        params.key_usages = vec![KeyUsagePurpose::DigitalSignature];
+        let cert = params.signed_by(&signing_key, &root, &root_key).unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let uuid = ShardableUuid::new();
+        let patient_dir = uuid.sharded_dir(temp_dir.path());
+        std::fs::create_dir_all(&patient_dir).unwrap();
+        VersionedFileService::init(&patient_dir).unwrap();
+
+        let mut author = history_test_author();
+        author.signature = Some(signing_key.serialize_pem().into_bytes());
+        author.certificate = Some(cert.pem().into_bytes());
+
+        let commit_as = |author: &Author, name: &str| {
+            let msg = VprCommitMessage::new(
+                VprCommitDomain::Clinical(Record),
+                VprCommitAction::Create,
+                "Entry added",
+                "St Elsewhere Hospital",
+            )
+            .unwrap();
+            VersionedFileService::write_and_commit_files(
+                &RepositoryLock::acquire(&patient_dir).unwrap(),
+                author,
+                &msg,
+                &[FileToWrite {
+                    relative_path: Path::new(name),
+                    content: name,
+                    old_content: None,
+                }],
+                None,
+                None,
+            )
+            .unwrap();
+        };
+
+        // Trailers name a different registration to the certificate.
+        commit_as(&author, "a.md");
+        author.registrations = vec![AuthorRegistration::new("GMC", "1234567").unwrap()];
+        commit_as(&author, "b.md");
+        // Signed, but with no certificate to check.
+        author.certificate = None;
+        commit_as(&author, "c.md");
+
+        let statuses = |anchors: Option<&TrustAnchors>| -> Vec<CommitSignatureStatus> {
+            VersionedFileService::verify_signature_chain(
+                temp_dir.path(),
+                &uuid.to_string(),
+                anchors,
+                None,
+                None,
+            )
+            .unwrap()
+            .commits
+            .iter()
+            .map(|c| c.status)
+            .collect()
+        };
+
+        assert_eq!(statuses(None), vec![CommitSignatureStatus::Valid; 3]);
+
+        let trusted = TrustAnchors::from_pem(root.pem().as_bytes()).unwrap();
+        assert_eq!(
+            statuses(Some(&trusted)),
+            vec![
+                CommitSignatureStatus::Untrusted,
+                CommitSignatureStatus::Valid,
+                CommitSignatureStatus::RegistrationMismatch,
+            ]
+        );
+
+        let untrusted = TrustAnchors::from_pem(other.pem().as_bytes()).unwrap();
+        assert_eq!(
+            statuses(Some(&untrusted)),
+            vec![CommitSignatureStatus::Untrusted; 3]
+        );
+    }
+
+    #[test]
+    fn verify_signature_chain_reports_revoked_signing_certificates() {
+        use std::time::SystemTime;
+        use vpr_certificates::CertificateAuthority;
+
+        let ca = CertificateAuthority::create_root("VPR Root CA").unwrap();
+        let (cert_pem, key_pem) = ca.issue("Test Author", "GMC", "12345").unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let uuid = ShardableUuid::new();
+        let patient_dir = uuid.sharded_dir(temp_dir.path());
+        std::fs::create_dir_all(&patient_dir).unwrap();
+        VersionedFileService::init(&patient_dir).unwrap();
+
+        let mut author = history_test_author();
+        author.signature = Some(key_pem.into_bytes());
+        author.certificate = Some(cert_pem.clone().into_bytes());
+        let msg = VprCommitMessage::new(
+            VprCommitDomain::Clinical(Record),
+            VprCommitAction::Create,
+            "Entry added",
+            "St Elsewhere Hospital",
+        )
+        .unwrap();
+        VersionedFileService::write_and_commit_files(
+            &RepositoryLock::acquire(&patient_dir).unwrap(),
+            &author,
+            &msg,
+            &[FileToWrite {
+                relative_path: Path::new("a.md"),
+                content: "a",
+                old_content: None,
+            }],
+            None,
+            None,
+        )
+        .unwrap();
+
+        let report = |revoked_at: SystemTime| {
+            let crl = ca.revoke(None, &cert_pem, revoked_at).unwrap();
+            let revocations = RevocationList::from_pem(crl.as_bytes()).unwrap();
+            VersionedFileService::verify_signature_chain(
+                temp_dir.path(),
+                &uuid.to_string(),
+                None,
+                Some(&revocations),
+                None,
+            )
+            .unwrap()
+        };
+        let day = std::time::Duration::from_secs(86_400);
+
+        let later = report(SystemTime::now() + day);
+        assert_eq!(
+            later.commits[0].status,
+            CommitSignatureStatus::RevokedAfterSigning
+        );
+        assert!(later.is_valid());
+        assert_eq!(later.failures().count(), 0);
+
+        let earlier = report(SystemTime::now() - day);
+        assert_eq!(
+            earlier.commits[0].status,
+            CommitSignatureStatus::RevokedBeforeSigning
+        );
+        assert!(!earlier.is_valid());
+        assert_eq!(earlier.failures().count(), 1);
+    }
 }
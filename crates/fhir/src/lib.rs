@@ -2,7 +2,7 @@
 //!
 //! This crate provides **wire models** and **format/translation helpers** for on-disk,
 //! version-controlled coordination files:
-//! - YAML components (for example messaging thread ledgers)
+//! - YAML components (for example messaging thread ledgers and patient demographics)
 //!
 //! This crate focuses on:
 //! - FHIR semantic alignment (without FHIR JSON/REST transport)
@@ -24,7 +24,11 @@ pub use patient::Patient;
 // Re-export public domain-level types
 pub use coordination_status::{CoordinationStatusData, LifecycleState};
 pub use messaging::{AuthorRole, LedgerData, MessageAuthor, SensitivityLevel, ThreadStatus};
-pub use patient::{NameUse, PatientData};
+pub use patient::{
+    Address, AddressType, AddressUse, AdministrativeGender, CodeableConcept, Coding, ContactPoint,
+    ContactPointSystem, ContactPointUse, Deceased, FhirDateTime, HumanName, Identifier,
+    IdentifierUse, NameUse, PatientContact, PatientData, Period, Reference, NHS_NUMBER_SYSTEM,
+};
 
 // Re-export TimestampId from vpr_uuid crate
 pub use vpr_uuid::TimestampId;
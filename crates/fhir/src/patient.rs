@@ -9,16 +9,25 @@
 //! - Provide translation helpers between domain primitives and the wire model
 //! - Validate patient structure and enforce required fields
 //!
+//! Supported Patient elements: `identifier`, `name` (multiple, with periods), `telecom`,
+//! `gender`, `birthDate`, `deceased[x]`, `address`, `contact`, `generalPractitioner` and
+//! `meta.lastUpdated`.
+//!
 //! Notes:
 //! - This patient file is mutable and overwriteable
 //! - Changes should be git-audited where appropriate
 
 use crate::FhirError;
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveTime, SecondsFormat, TimeDelta, Utc,
+};
 use serde::{Deserialize, Serialize};
 use vpr_types::NonEmptyText;
 use vpr_uuid::ShardableUuid;
 
+/// Identifier system for NHS numbers (England and Wales).
+pub const NHS_NUMBER_SYSTEM: &str = "https://fhir.nhs.uk/Id/nhs-number";
+
 // ============================================================================
 // Public domain-level types
 // ============================================================================
@@ -69,18 +78,379 @@ impl NameUse {
             _ => None,
         }
     }
+
+    /// Returns the FHIR code for this name use.
+    pub fn as_str(&self) -> &'static str {
+        self.to_wire()
+    }
+}
+
+/// Administrative gender of a patient or contact.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdministrativeGender {
+    /// Male.
+    Male,
+    /// Female.
+    Female,
+    /// Other.
+    Other,
+    /// Unknown.
+    Unknown,
+}
+
+impl AdministrativeGender {
+    /// Parses a gender from its FHIR code (case-insensitive).
+    pub fn parse(s: &str) -> Result<Self, FhirError> {
+        match s.to_lowercase().as_str() {
+            "male" => Ok(Self::Male),
+            "female" => Ok(Self::Female),
+            "other" => Ok(Self::Other),
+            "unknown" => Ok(Self::Unknown),
+            _ => Err(FhirError::InvalidInput(format!("Invalid gender: {}", s))),
+        }
+    }
+
+    /// Returns the FHIR code for this gender.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Male => "male",
+            Self::Female => "female",
+            Self::Other => "other",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// Purpose of an identifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdentifierUse {
+    /// The identifier recommended for display and use in real-world interactions.
+    Usual,
+    /// The identifier considered most trusted for identification of this patient.
+    Official,
+    /// A temporary identifier.
+    Temp,
+    /// An identifier assigned in secondary use.
+    Secondary,
+    /// The identifier is no longer considered valid.
+    Old,
+}
+
+impl IdentifierUse {
+    /// Parses an identifier use from its FHIR code (case-insensitive).
+    pub fn parse(s: &str) -> Result<Self, FhirError> {
+        match s.to_lowercase().as_str() {
+            "usual" => Ok(Self::Usual),
+            "official" => Ok(Self::Official),
+            "temp" => Ok(Self::Temp),
+            "secondary" => Ok(Self::Secondary),
+            "old" => Ok(Self::Old),
+            _ => Err(FhirError::InvalidInput(format!(
+                "Invalid identifier use: {}",
+                s
+            ))),
+        }
+    }
+
+    /// Returns the FHIR code for this identifier use.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Usual => "usual",
+            Self::Official => "official",
+            Self::Temp => "temp",
+            Self::Secondary => "secondary",
+            Self::Old => "old",
+        }
+    }
+}
+
+/// Kind of communication channel for a contact point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContactPointSystem {
+    /// Telephone.
+    Phone,
+    /// Fax machine.
+    Fax,
+    /// Email address.
+    Email,
+    /// Pager.
+    Pager,
+    /// Web address.
+    Url,
+    /// SMS-capable number.
+    Sms,
+    /// Any other channel.
+    Other,
+}
+
+impl ContactPointSystem {
+    /// Parses a contact point system from its FHIR code (case-insensitive).
+    pub fn parse(s: &str) -> Result<Self, FhirError> {
+        match s.to_lowercase().as_str() {
+            "phone" => Ok(Self::Phone),
+            "fax" => Ok(Self::Fax),
+            "email" => Ok(Self::Email),
+            "pager" => Ok(Self::Pager),
+            "url" => Ok(Self::Url),
+            "sms" => Ok(Self::Sms),
+            "other" => Ok(Self::Other),
+            _ => Err(FhirError::InvalidInput(format!(
+                "Invalid contact point system: {}",
+                s
+            ))),
+        }
+    }
+
+    /// Returns the FHIR code for this contact point system.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Phone => "phone",
+            Self::Fax => "fax",
+            Self::Email => "email",
+            Self::Pager => "pager",
+            Self::Url => "url",
+            Self::Sms => "sms",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Purpose of a contact point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContactPointUse {
+    /// Home contact.
+    Home,
+    /// Work contact.
+    Work,
+    /// Temporary contact.
+    Temp,
+    /// No longer in use.
+    Old,
+    /// Mobile device.
+    Mobile,
+}
+
+impl ContactPointUse {
+    /// Parses a contact point use from its FHIR code (case-insensitive).
+    pub fn parse(s: &str) -> Result<Self, FhirError> {
+        match s.to_lowercase().as_str() {
+            "home" => Ok(Self::Home),
+            "work" => Ok(Self::Work),
+            "temp" => Ok(Self::Temp),
+            "old" => Ok(Self::Old),
+            "mobile" => Ok(Self::Mobile),
+            _ => Err(FhirError::InvalidInput(format!(
+                "Invalid contact point use: {}",
+                s
+            ))),
+        }
+    }
+
+    /// Returns the FHIR code for this contact point use.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Home => "home",
+            Self::Work => "work",
+            Self::Temp => "temp",
+            Self::Old => "old",
+            Self::Mobile => "mobile",
+        }
+    }
+}
+
+/// Purpose of an address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressUse {
+    /// Home address.
+    Home,
+    /// Work address.
+    Work,
+    /// Temporary address.
+    Temp,
+    /// No longer in use.
+    Old,
+    /// Billing address.
+    Billing,
+}
+
+impl AddressUse {
+    /// Parses an address use from its FHIR code (case-insensitive).
+    pub fn parse(s: &str) -> Result<Self, FhirError> {
+        match s.to_lowercase().as_str() {
+            "home" => Ok(Self::Home),
+            "work" => Ok(Self::Work),
+            "temp" => Ok(Self::Temp),
+            "old" => Ok(Self::Old),
+            "billing" => Ok(Self::Billing),
+            _ => Err(FhirError::InvalidInput(format!(
+                "Invalid address use: {}",
+                s
+            ))),
+        }
+    }
+
+    /// Returns the FHIR code for this address use.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Home => "home",
+            Self::Work => "work",
+            Self::Temp => "temp",
+            Self::Old => "old",
+            Self::Billing => "billing",
+        }
+    }
+}
+
+/// Distinguishes postal addresses from physical locations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressType {
+    /// Mailing address.
+    Postal,
+    /// Physical location that can be visited.
+    Physical,
+    /// Both postal and physical.
+    Both,
+}
+
+impl AddressType {
+    /// Parses an address type from its FHIR code (case-insensitive).
+    pub fn parse(s: &str) -> Result<Self, FhirError> {
+        match s.to_lowercase().as_str() {
+            "postal" => Ok(Self::Postal),
+            "physical" => Ok(Self::Physical),
+            "both" => Ok(Self::Both),
+            _ => Err(FhirError::InvalidInput(format!(
+                "Invalid address type: {}",
+                s
+            ))),
+        }
+    }
+
+    /// Returns the FHIR code for this address type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Postal => "postal",
+            Self::Physical => "physical",
+            Self::Both => "both",
+        }
+    }
 }
 
-/// Domain-level carrier for patient data (flat structure).
+/// A FHIR `dateTime`, kept at the precision it was recorded with.
 ///
-/// This struct represents patient demographics in a flat format suitable for
-/// direct use in APIs and services. The wire format supports multiple names,
-/// but this flat structure extracts the first (primary) name for simplicity.
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct PatientData {
-    /// Unique identifier for this patient record.
-    pub id: ShardableUuid,
+/// FHIR allows a year (`2020`), a year and month (`2020-01`), a date (`2020-01-01`) or a
+/// date and time with a UTC offset (`2020-01-01T09:30:00+01:00`). Each is rendered back at
+/// the same precision, so a date never gains a time of day. Date-times are rendered in RFC 3339
+/// form, with a zero offset written as `Z`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FhirDateTime {
+    /// A year only (`YYYY`).
+    Year(i32),
+    /// A year and month (`YYYY-MM`); the month is 1-based.
+    YearMonth(i32, u32),
+    /// A calendar date (`YYYY-MM-DD`).
+    Date(NaiveDate),
+    /// A date and time with a UTC offset.
+    DateTime(DateTime<FixedOffset>),
+}
+
+impl FhirDateTime {
+    /// Parses a FHIR `dateTime` at any of its precisions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FhirError::InvalidInput` if `value` is not a valid year, year-month, date or
+    /// RFC 3339 date-time.
+    pub fn parse(value: &str) -> Result<Self, FhirError> {
+        let invalid = || FhirError::InvalidInput(format!("Invalid dateTime: {value}"));
+        let bytes = value.as_bytes();
+        let digits = |range: std::ops::Range<usize>| bytes[range].iter().all(u8::is_ascii_digit);
+
+        match bytes.len() {
+            4 if digits(0..4) => value.parse().map(Self::Year).map_err(|_| invalid()),
+            7 if digits(0..4) && bytes[4] == b'-' && digits(5..7) => {
+                NaiveDate::parse_from_str(&format!("{value}-01"), "%Y-%m-%d")
+                    .map(|d| Self::YearMonth(d.year(), d.month()))
+                    .map_err(|_| invalid())
+            }
+            10 => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(Self::Date)
+                .map_err(|_| invalid()),
+            _ => DateTime::parse_from_rfc3339(value)
+                .map(Self::DateTime)
+                .map_err(|_| invalid()),
+        }
+    }
+
+    /// Returns the first instant this value covers. Dates without a time are read as UTC.
+    pub fn earliest(&self) -> DateTime<Utc> {
+        match self {
+            Self::Year(year) => first_day(*year, 1).and_time(NaiveTime::MIN).and_utc(),
+            Self::YearMonth(year, month) => {
+                first_day(*year, *month).and_time(NaiveTime::MIN).and_utc()
+            }
+            Self::Date(date) => date.and_time(NaiveTime::MIN).and_utc(),
+            Self::DateTime(dt) => dt.with_timezone(&Utc),
+        }
+    }
 
+    /// Returns the last instant this value covers, so that `2020-01-31` as the inclusive end
+    /// of a period includes the whole of that day. Dates without a time are read as UTC.
+    pub fn latest(&self) -> DateTime<Utc> {
+        let next = match self {
+            Self::Year(year) => first_day(year.saturating_add(1), 1),
+            Self::YearMonth(year, 12) => first_day(year.saturating_add(1), 1),
+            Self::YearMonth(year, month) => first_day(*year, month + 1),
+            Self::Date(date) => date.succ_opt().unwrap_or(NaiveDate::MAX),
+            Self::DateTime(dt) => return dt.with_timezone(&Utc),
+        };
+        next.and_time(NaiveTime::MIN).and_utc() - TimeDelta::nanoseconds(1)
+    }
+}
+
+impl std::fmt::Display for FhirDateTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Year(year) => write!(f, "{year:04}"),
+            Self::YearMonth(year, month) => write!(f, "{year:04}-{month:02}"),
+            Self::Date(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+            Self::DateTime(dt) => f.write_str(&dt.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+        }
+    }
+}
+
+/// Returns the first day of `month` in `year`, clamped to the representable range.
+fn first_day(year: i32, month: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month.clamp(1, 12), 1).unwrap_or(if year < 0 {
+        NaiveDate::MIN
+    } else {
+        NaiveDate::MAX
+    })
+}
+
+/// Time range during which an element is or was valid.
+///
+/// Either bound may be open. Each bound keeps the precision it was recorded with (see
+/// [`FhirDateTime`]), and both bounds are inclusive of their whole extent.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Period {
+    /// Start of the period (inclusive).
+    pub start: Option<FhirDateTime>,
+    /// End of the period (inclusive); `None` means ongoing.
+    pub end: Option<FhirDateTime>,
+}
+
+impl Period {
+    /// Returns `true` if `at` falls within this period.
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.start.is_none_or(|start| start.earliest() <= at)
+            && self.end.is_none_or(|end| at <= end.latest())
+    }
+}
+
+/// A human name, such as a patient's official name or a previous (maiden) name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HumanName {
     /// Purpose of the name (official, usual, nickname, etc.).
     pub use_type: Option<NameUse>,
 
@@ -90,43 +460,237 @@ pub struct PatientData {
     /// Given names (first name, middle names).
     pub given: Vec<NonEmptyText>,
 
-    /// Patient's date of birth (ISO 8601 date format: YYYY-MM-DD).
-    pub birth_date: Option<NaiveDate>,
+    /// Parts that come before the name (e.g. "Dr", "Mrs").
+    pub prefix: Vec<NonEmptyText>,
 
-    /// Last updated timestamp.
-    pub last_updated: Option<DateTime<Utc>>,
+    /// Parts that come after the name (e.g. "MBE").
+    pub suffix: Vec<NonEmptyText>,
+
+    /// Period during which the name was in use.
+    pub period: Option<Period>,
 }
 
-// ============================================================================
-// Internal nested types
-// ============================================================================
+/// An identifier for a patient, such as an NHS number or a local hospital number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Identifier {
+    /// Purpose of the identifier.
+    pub use_type: Option<IdentifierUse>,
 
-/// Internal nested representation of patient data.
-///
-/// This matches the FHIR wire format structure with nested name and meta objects.
-/// Used internally for wire format conversion.
+    /// Namespace URI for the identifier value (e.g. [`NHS_NUMBER_SYSTEM`]).
+    pub system: Option<NonEmptyText>,
+
+    /// The identifier value.
+    pub value: NonEmptyText,
+
+    /// Period during which the identifier was valid.
+    pub period: Option<Period>,
+}
+
+/// A contact detail such as a phone number or email address.
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct PatientDataNested {
-    pub id: ShardableUuid,
-    pub names: Vec<FullName>,
-    pub birth_date: Option<NaiveDate>,
-    pub meta: Option<PatientMeta>,
+pub struct ContactPoint {
+    /// Kind of communication channel.
+    pub system: Option<ContactPointSystem>,
+
+    /// The actual contact point details.
+    pub value: NonEmptyText,
+
+    /// Purpose of the contact point.
+    pub use_type: Option<ContactPointUse>,
+
+    /// Preferred order of use (1 = highest).
+    pub rank: Option<u32>,
+
+    /// Period during which the contact point was in use.
+    pub period: Option<Period>,
+}
+
+/// A postal or physical address.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Address {
+    /// Purpose of the address.
+    pub use_type: Option<AddressUse>,
+
+    /// Postal, physical, or both.
+    pub address_type: Option<AddressType>,
+
+    /// Full address as free text.
+    pub text: Option<NonEmptyText>,
+
+    /// Street name, number, flat, etc.
+    pub line: Vec<NonEmptyText>,
+
+    /// City, town or village.
+    pub city: Option<NonEmptyText>,
+
+    /// Sub-unit of a country (e.g. county).
+    pub district: Option<NonEmptyText>,
+
+    /// Sub-unit of a country with limited sovereignty.
+    pub state: Option<NonEmptyText>,
+
+    /// Postal code for the area.
+    pub postal_code: Option<NonEmptyText>,
+
+    /// Country (ISO 3166 code or name).
+    pub country: Option<NonEmptyText>,
+
+    /// Period during which the address was in use.
+    pub period: Option<Period>,
 }
 
-/// Human name representation (internal).
+/// Indicates whether, or when, a patient died.
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct FullName {
-    pub use_type: Option<NameUse>,
-    pub family: Option<NonEmptyText>,
-    pub given: Vec<NonEmptyText>,
+pub enum Deceased {
+    /// `deceasedBoolean`: death is known (or known not to have occurred) without a date.
+    Boolean(bool),
+    /// `deceasedDateTime`: date, or date and time, of death.
+    DateTime(FhirDateTime),
+}
+
+impl Deceased {
+    /// Returns `true` if this value records the patient as deceased.
+    pub fn is_deceased(&self) -> bool {
+        match self {
+            Deceased::Boolean(flag) => *flag,
+            Deceased::DateTime(_) => true,
+        }
+    }
+}
+
+/// A code from a terminology system.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Coding {
+    /// Terminology system URI.
+    pub system: Option<NonEmptyText>,
+    /// Code within the system.
+    pub code: Option<NonEmptyText>,
+    /// Human-readable representation of the code.
+    pub display: Option<NonEmptyText>,
 }
 
-/// Patient resource metadata (internal).
+/// A concept expressed as codes and/or free text.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CodeableConcept {
+    /// Codes defined by terminology systems.
+    pub coding: Vec<Coding>,
+    /// Plain text representation of the concept.
+    pub text: Option<NonEmptyText>,
+}
+
+/// A reference to another resource, such as a GP or GP practice.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Reference {
+    /// Literal reference (e.g. `Organization/A12345`).
+    pub reference: Option<NonEmptyText>,
+    /// Logical reference when no literal reference is available (e.g. an ODS code).
+    pub identifier: Option<Identifier>,
+    /// Text alternative for the referenced resource.
+    pub display: Option<NonEmptyText>,
+}
+
+/// A contact party for the patient, such as next of kin or a guardian.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PatientContact {
+    /// Relationship of the contact to the patient.
+    pub relationship: Vec<CodeableConcept>,
+    /// Name of the contact person.
+    pub name: Option<HumanName>,
+    /// Contact details for the person.
+    pub telecom: Vec<ContactPoint>,
+    /// Address of the contact person.
+    pub address: Option<Address>,
+    /// Administrative gender of the contact person.
+    pub gender: Option<AdministrativeGender>,
+    /// Organisation the contact is associated with.
+    pub organization: Option<Reference>,
+    /// Period during which this contact is valid.
+    pub period: Option<Period>,
+}
+
+/// Domain-level carrier for patient data.
+///
+/// This struct represents patient demographics in a format suitable for direct use in
+/// APIs and services. All names are kept, in wire order; use [`PatientData::primary_name`]
+/// when a single display name is needed.
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct PatientMeta {
+pub struct PatientData {
+    /// Unique identifier for this patient record.
+    pub id: ShardableUuid,
+
+    /// Business identifiers (NHS number, hospital numbers, etc.).
+    pub identifiers: Vec<Identifier>,
+
+    /// Names associated with the patient.
+    pub names: Vec<HumanName>,
+
+    /// Contact details (phone, email, etc.).
+    pub telecom: Vec<ContactPoint>,
+
+    /// Administrative gender.
+    pub gender: Option<AdministrativeGender>,
+
+    /// Patient's date of birth (ISO 8601 date format: YYYY-MM-DD).
+    pub birth_date: Option<NaiveDate>,
+
+    /// Whether, or when, the patient died.
+    pub deceased: Option<Deceased>,
+
+    /// Addresses for the patient.
+    pub addresses: Vec<Address>,
+
+    /// Contact parties (next of kin, guardians, etc.).
+    pub contacts: Vec<PatientContact>,
+
+    /// Nominated primary care providers (GP and/or GP practice).
+    pub general_practitioners: Vec<Reference>,
+
+    /// Last updated timestamp.
     pub last_updated: Option<DateTime<Utc>>,
 }
 
+impl PatientData {
+    /// Creates patient data with the given ID and no other elements populated.
+    pub fn new(id: ShardableUuid) -> Self {
+        Self {
+            id,
+            identifiers: vec![],
+            names: vec![],
+            telecom: vec![],
+            gender: None,
+            birth_date: None,
+            deceased: None,
+            addresses: vec![],
+            contacts: vec![],
+            general_practitioners: vec![],
+            last_updated: None,
+        }
+    }
+
+    /// Returns the name to use when a single name is needed.
+    ///
+    /// This is the first official name whose period covers the current time, falling
+    /// back to the first name of any kind.
+    pub fn primary_name(&self) -> Option<&HumanName> {
+        let now = Utc::now();
+        self.names
+            .iter()
+            .find(|n| {
+                n.use_type == Some(NameUse::Official)
+                    && n.period.as_ref().is_none_or(|p| p.contains(now))
+            })
+            .or_else(|| self.names.first())
+    }
+
+    /// Returns the patient's NHS number, if one is recorded.
+    pub fn nhs_number(&self) -> Option<&NonEmptyText> {
+        self.identifiers
+            .iter()
+            .find(|i| i.system.as_ref().map(|s| s.as_str()) == Some(NHS_NUMBER_SYSTEM))
+            .map(|i| &i.value)
+    }
+}
+
 // ============================================================================
 // Public Patient operations
 // ============================================================================
@@ -157,7 +721,9 @@ impl Patient {
     /// - the YAML does not represent a valid patient resource,
     /// - any field has an unexpected type,
     /// - any unknown keys are present (due to `#[serde(deny_unknown_fields)]`),
-    /// - resourceType is not "Patient".
+    /// - resourceType is not "Patient",
+    /// - a coded value (gender, identifier/telecom/address use, etc.) is not a known FHIR code,
+    /// - both `deceasedBoolean` and `deceasedDateTime` are present.
     pub fn parse(yaml_text: &str) -> Result<PatientData, FhirError> {
         let deserializer = serde_yaml::Deserializer::from_str(yaml_text);
 
@@ -211,44 +777,227 @@ impl Patient {
     }
 }
 
-// ============================================================================
-// Wire types (internal)
-// ============================================================================
+// ============================================================================
+// Wire types (internal)
+// ============================================================================
+
+/// Wire representation of a patient resource for on-disk YAML.
+///
+/// This is the exact structure that will be serialised to/from YAML.
+/// All fields use `#[serde(deny_unknown_fields)]` for strict validation.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct PatientWire {
+    #[serde(rename = "resourceType")]
+    pub resource_type: String,
+
+    pub id: String,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub identifier: Vec<IdentifierWire>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub name: Vec<FullNameWire>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub telecom: Vec<ContactPointWire>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gender: Option<String>,
+
+    #[serde(rename = "birthDate", skip_serializing_if = "Option::is_none")]
+    pub birth_date: Option<String>,
+
+    #[serde(rename = "deceasedBoolean", skip_serializing_if = "Option::is_none")]
+    pub deceased_boolean: Option<bool>,
+
+    #[serde(rename = "deceasedDateTime", skip_serializing_if = "Option::is_none")]
+    pub deceased_date_time: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub address: Vec<AddressWire>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contact: Vec<ContactWire>,
+
+    #[serde(
+        rename = "generalPractitioner",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub general_practitioner: Vec<ReferenceWire>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<PatientMetaWire>,
+}
+
+/// Wire representation of a human name.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct FullNameWire {
+    #[serde(rename = "use", skip_serializing_if = "Option::is_none")]
+    pub use_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub given: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prefix: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suffix: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<PeriodWire>,
+}
+
+/// Wire representation of a period.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct PeriodWire {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+}
+
+/// Wire representation of an identifier.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct IdentifierWire {
+    #[serde(rename = "use", skip_serializing_if = "Option::is_none")]
+    pub use_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+
+    pub value: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<PeriodWire>,
+}
+
+/// Wire representation of a contact point.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct ContactPointWire {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+
+    pub value: String,
+
+    #[serde(rename = "use", skip_serializing_if = "Option::is_none")]
+    pub use_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<PeriodWire>,
+}
+
+/// Wire representation of an address.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct AddressWire {
+    #[serde(rename = "use", skip_serializing_if = "Option::is_none")]
+    pub use_type: Option<String>,
+
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub address_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub line: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub district: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+
+    #[serde(rename = "postalCode", skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<PeriodWire>,
+}
+
+/// Wire representation of a coding.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct CodingWire {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+}
 
-/// Wire representation of a patient resource for on-disk YAML.
-///
-/// This is the exact structure that will be serialised to/from YAML.
-/// All fields use `#[serde(deny_unknown_fields)]` for strict validation.
+/// Wire representation of a codeable concept.
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
-struct PatientWire {
-    #[serde(rename = "resourceType")]
-    pub resource_type: String,
+struct CodeableConceptWire {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub coding: Vec<CodingWire>,
 
-    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
 
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub name: Vec<FullNameWire>,
+/// Wire representation of a reference.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct ReferenceWire {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
 
-    #[serde(rename = "birthDate", skip_serializing_if = "Option::is_none")]
-    pub birth_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<IdentifierWire>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub meta: Option<PatientMetaWire>,
+    pub display: Option<String>,
 }
 
-/// Wire representation of a human name.
+/// Wire representation of a patient contact.
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
-struct FullNameWire {
-    #[serde(rename = "use", skip_serializing_if = "Option::is_none")]
-    pub use_type: Option<String>,
+struct ContactWire {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relationship: Vec<CodeableConceptWire>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub family: Option<String>,
+    pub name: Option<FullNameWire>,
 
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub given: Vec<String>,
+    pub telecom: Vec<ContactPointWire>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<AddressWire>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gender: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization: Option<ReferenceWire>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<PeriodWire>,
 }
 
 /// Wire representation of patient metadata.
@@ -263,135 +1012,369 @@ struct PatientMetaWire {
 // Helper functions (internal)
 // ============================================================================
 
-/// Convert wire format to nested internal format.
-fn wire_to_nested(wire: PatientWire) -> Result<PatientDataNested, FhirError> {
-    let id = ShardableUuid::parse(&wire.id)
-        .map_err(|e| FhirError::Translation(format!("Invalid patient ID: {e}")))?;
+/// Convert an optional wire string into optional non-empty text.
+fn text_from_wire(value: Option<String>, field: &str) -> Result<Option<NonEmptyText>, FhirError> {
+    value
+        .map(|v| NonEmptyText::new(&v))
+        .transpose()
+        .map_err(|e| FhirError::Translation(format!("Invalid {field}: {e}")))
+}
 
-    let names = wire
-        .name
+/// Convert a list of wire strings into non-empty text values.
+fn texts_from_wire(values: Vec<String>, field: &str) -> Result<Vec<NonEmptyText>, FhirError> {
+    values
         .into_iter()
-        .map(|n| {
-            let use_type = n.use_type.as_deref().and_then(NameUse::from_wire);
-
-            let family = n
-                .family
-                .map(|f| NonEmptyText::new(&f))
-                .transpose()
-                .map_err(|e| FhirError::Translation(format!("Invalid family name: {}", e)))?;
-
-            let given = n
-                .given
-                .into_iter()
-                .map(|g| {
-                    NonEmptyText::new(&g)
-                        .map_err(|e| FhirError::Translation(format!("Invalid given name: {}", e)))
-                })
-                .collect::<Result<Vec<_>, FhirError>>()?;
-
-            Ok(FullName {
-                use_type,
-                family,
-                given,
+        .map(|v| {
+            NonEmptyText::new(&v)
+                .map_err(|e| FhirError::Translation(format!("Invalid {field}: {e}")))
+        })
+        .collect()
+}
+
+/// Convert a list of non-empty text values into wire strings.
+fn texts_to_wire(values: &[NonEmptyText]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+/// Parse a FHIR `dateTime` at any precision.
+fn datetime_from_wire(value: &str, field: &str) -> Result<FhirDateTime, FhirError> {
+    FhirDateTime::parse(value)
+        .map_err(|_| FhirError::Translation(format!("Invalid {field}: {value}")))
+}
+
+/// Translate a coded-value parse failure into a wire translation error.
+fn code_from_wire<T>(
+    value: Option<String>,
+    parse: impl Fn(&str) -> Result<T, FhirError>,
+) -> Result<Option<T>, FhirError> {
+    value
+        .map(|v| {
+            parse(&v).map_err(|e| match e {
+                FhirError::InvalidInput(msg) => FhirError::Translation(msg),
+                other => other,
             })
         })
-        .collect::<Result<Vec<_>, FhirError>>()?;
+        .transpose()
+}
 
-    let meta = wire.meta.map(|m| {
-        let last_updated = m
-            .last_updated
-            .as_ref()
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok());
-        PatientMeta { last_updated }
-    });
+/// Convert wire format to a period.
+fn period_from_wire(wire: Option<PeriodWire>) -> Result<Option<Period>, FhirError> {
+    wire.map(|p| {
+        Ok(Period {
+            start: p
+                .start
+                .map(|s| datetime_from_wire(&s, "period start"))
+                .transpose()?,
+            end: p
+                .end
+                .map(|s| datetime_from_wire(&s, "period end"))
+                .transpose()?,
+        })
+    })
+    .transpose()
+}
 
-    let birth_date = wire
-        .birth_date
-        .as_ref()
-        .map(|s| {
-            s.parse::<NaiveDate>()
-                .map_err(|e| FhirError::Translation(format!("Invalid birth date: {e}")))
+/// Convert a period to wire format.
+fn period_to_wire(period: &Option<Period>) -> Option<PeriodWire> {
+    period.as_ref().map(|p| PeriodWire {
+        start: p.start.map(|dt| dt.to_string()),
+        end: p.end.map(|dt| dt.to_string()),
+    })
+}
+
+/// Convert wire format to a human name.
+fn name_from_wire(wire: FullNameWire) -> Result<HumanName, FhirError> {
+    Ok(HumanName {
+        use_type: wire.use_type.as_deref().and_then(NameUse::from_wire),
+        family: text_from_wire(wire.family, "family name")?,
+        given: texts_from_wire(wire.given, "given name")?,
+        prefix: texts_from_wire(wire.prefix, "name prefix")?,
+        suffix: texts_from_wire(wire.suffix, "name suffix")?,
+        period: period_from_wire(wire.period)?,
+    })
+}
+
+/// Convert a human name to wire format.
+fn name_to_wire(name: &HumanName) -> FullNameWire {
+    FullNameWire {
+        use_type: name.use_type.map(|u| u.to_wire().to_string()),
+        family: name.family.as_ref().map(|f| f.to_string()),
+        given: texts_to_wire(&name.given),
+        prefix: texts_to_wire(&name.prefix),
+        suffix: texts_to_wire(&name.suffix),
+        period: period_to_wire(&name.period),
+    }
+}
+
+/// Convert wire format to an identifier.
+fn identifier_from_wire(wire: IdentifierWire) -> Result<Identifier, FhirError> {
+    Ok(Identifier {
+        use_type: code_from_wire(wire.use_type, IdentifierUse::parse)?,
+        system: text_from_wire(wire.system, "identifier system")?,
+        value: NonEmptyText::new(&wire.value)
+            .map_err(|e| FhirError::Translation(format!("Invalid identifier value: {e}")))?,
+        period: period_from_wire(wire.period)?,
+    })
+}
+
+/// Convert an identifier to wire format.
+fn identifier_to_wire(identifier: &Identifier) -> IdentifierWire {
+    IdentifierWire {
+        use_type: identifier.use_type.map(|u| u.as_str().to_string()),
+        system: identifier.system.as_ref().map(|s| s.to_string()),
+        value: identifier.value.to_string(),
+        period: period_to_wire(&identifier.period),
+    }
+}
+
+/// Convert wire format to a contact point.
+fn contact_point_from_wire(wire: ContactPointWire) -> Result<ContactPoint, FhirError> {
+    Ok(ContactPoint {
+        system: code_from_wire(wire.system, ContactPointSystem::parse)?,
+        value: NonEmptyText::new(&wire.value)
+            .map_err(|e| FhirError::Translation(format!("Invalid telecom value: {e}")))?,
+        use_type: code_from_wire(wire.use_type, ContactPointUse::parse)?,
+        rank: wire.rank,
+        period: period_from_wire(wire.period)?,
+    })
+}
+
+/// Convert a contact point to wire format.
+fn contact_point_to_wire(contact_point: &ContactPoint) -> ContactPointWire {
+    ContactPointWire {
+        system: contact_point.system.map(|s| s.as_str().to_string()),
+        value: contact_point.value.to_string(),
+        use_type: contact_point.use_type.map(|u| u.as_str().to_string()),
+        rank: contact_point.rank,
+        period: period_to_wire(&contact_point.period),
+    }
+}
+
+/// Convert wire format to an address.
+fn address_from_wire(wire: AddressWire) -> Result<Address, FhirError> {
+    Ok(Address {
+        use_type: code_from_wire(wire.use_type, AddressUse::parse)?,
+        address_type: code_from_wire(wire.address_type, AddressType::parse)?,
+        text: text_from_wire(wire.text, "address text")?,
+        line: texts_from_wire(wire.line, "address line")?,
+        city: text_from_wire(wire.city, "city")?,
+        district: text_from_wire(wire.district, "district")?,
+        state: text_from_wire(wire.state, "state")?,
+        postal_code: text_from_wire(wire.postal_code, "postal code")?,
+        country: text_from_wire(wire.country, "country")?,
+        period: period_from_wire(wire.period)?,
+    })
+}
+
+/// Convert an address to wire format.
+fn address_to_wire(address: &Address) -> AddressWire {
+    AddressWire {
+        use_type: address.use_type.map(|u| u.as_str().to_string()),
+        address_type: address.address_type.map(|t| t.as_str().to_string()),
+        text: address.text.as_ref().map(|t| t.to_string()),
+        line: texts_to_wire(&address.line),
+        city: address.city.as_ref().map(|t| t.to_string()),
+        district: address.district.as_ref().map(|t| t.to_string()),
+        state: address.state.as_ref().map(|t| t.to_string()),
+        postal_code: address.postal_code.as_ref().map(|t| t.to_string()),
+        country: address.country.as_ref().map(|t| t.to_string()),
+        period: period_to_wire(&address.period),
+    }
+}
+
+/// Convert wire format to a codeable concept.
+fn codeable_concept_from_wire(wire: CodeableConceptWire) -> Result<CodeableConcept, FhirError> {
+    let coding = wire
+        .coding
+        .into_iter()
+        .map(|c| {
+            Ok(Coding {
+                system: text_from_wire(c.system, "coding system")?,
+                code: text_from_wire(c.code, "coding code")?,
+                display: text_from_wire(c.display, "coding display")?,
+            })
         })
-        .transpose()?;
+        .collect::<Result<Vec<_>, FhirError>>()?;
 
-    Ok(PatientDataNested {
-        id,
-        names,
-        birth_date,
-        meta,
+    Ok(CodeableConcept {
+        coding,
+        text: text_from_wire(wire.text, "concept text")?,
     })
 }
 
-/// Convert nested internal format to wire format.
-fn nested_to_wire(nested: &PatientDataNested) -> PatientWire {
-    PatientWire {
-        resource_type: "Patient".to_string(),
-        id: nested.id.to_string(),
-        name: nested
-            .names
+/// Convert a codeable concept to wire format.
+fn codeable_concept_to_wire(concept: &CodeableConcept) -> CodeableConceptWire {
+    CodeableConceptWire {
+        coding: concept
+            .coding
             .iter()
-            .map(|n| FullNameWire {
-                use_type: n.use_type.map(|u| u.to_wire().to_string()),
-                family: n.family.as_ref().map(|f| f.to_string()),
-                given: n.given.iter().map(|g| g.to_string()).collect(),
+            .map(|c| CodingWire {
+                system: c.system.as_ref().map(|t| t.to_string()),
+                code: c.code.as_ref().map(|t| t.to_string()),
+                display: c.display.as_ref().map(|t| t.to_string()),
             })
             .collect(),
-        birth_date: nested.birth_date.map(|d| d.to_string()),
-        meta: nested.meta.as_ref().map(|m| PatientMetaWire {
-            last_updated: m.last_updated.map(|dt| dt.to_rfc3339()),
-        }),
+        text: concept.text.as_ref().map(|t| t.to_string()),
     }
 }
 
-/// Convert flat public PatientData to nested internal format.
-fn flat_to_nested(data: &PatientData) -> PatientDataNested {
-    let names = if data.use_type.is_some() || data.family.is_some() || !data.given.is_empty() {
-        vec![FullName {
-            use_type: data.use_type,
-            family: data.family.clone(),
-            given: data.given.clone(),
-        }]
-    } else {
-        vec![]
-    };
-
-    let meta = data.last_updated.map(|lu| PatientMeta {
-        last_updated: Some(lu),
-    });
+/// Convert wire format to a reference.
+fn reference_from_wire(wire: ReferenceWire) -> Result<Reference, FhirError> {
+    Ok(Reference {
+        reference: text_from_wire(wire.reference, "reference")?,
+        identifier: wire.identifier.map(identifier_from_wire).transpose()?,
+        display: text_from_wire(wire.display, "reference display")?,
+    })
+}
 
-    PatientDataNested {
-        id: data.id.clone(),
-        names,
-        birth_date: data.birth_date,
-        meta,
+/// Convert a reference to wire format.
+fn reference_to_wire(reference: &Reference) -> ReferenceWire {
+    ReferenceWire {
+        reference: reference.reference.as_ref().map(|t| t.to_string()),
+        identifier: reference.identifier.as_ref().map(identifier_to_wire),
+        display: reference.display.as_ref().map(|t| t.to_string()),
     }
 }
 
-/// Convert nested internal format to flat public PatientData.
-fn nested_to_flat(nested: PatientDataNested) -> PatientData {
-    // Extract first name if available
-    let first_name = nested.names.first();
+/// Convert wire format to a patient contact.
+fn contact_from_wire(wire: ContactWire) -> Result<PatientContact, FhirError> {
+    Ok(PatientContact {
+        relationship: wire
+            .relationship
+            .into_iter()
+            .map(codeable_concept_from_wire)
+            .collect::<Result<Vec<_>, FhirError>>()?,
+        name: wire.name.map(name_from_wire).transpose()?,
+        telecom: wire
+            .telecom
+            .into_iter()
+            .map(contact_point_from_wire)
+            .collect::<Result<Vec<_>, FhirError>>()?,
+        address: wire.address.map(address_from_wire).transpose()?,
+        gender: code_from_wire(wire.gender, AdministrativeGender::parse)?,
+        organization: wire.organization.map(reference_from_wire).transpose()?,
+        period: period_from_wire(wire.period)?,
+    })
+}
 
-    PatientData {
-        id: nested.id,
-        use_type: first_name.and_then(|n| n.use_type),
-        family: first_name.and_then(|n| n.family.clone()),
-        given: first_name.map(|n| n.given.clone()).unwrap_or_default(),
-        birth_date: nested.birth_date,
-        last_updated: nested.meta.and_then(|m| m.last_updated),
+/// Convert a patient contact to wire format.
+fn contact_to_wire(contact: &PatientContact) -> ContactWire {
+    ContactWire {
+        relationship: contact
+            .relationship
+            .iter()
+            .map(codeable_concept_to_wire)
+            .collect(),
+        name: contact.name.as_ref().map(name_to_wire),
+        telecom: contact.telecom.iter().map(contact_point_to_wire).collect(),
+        address: contact.address.as_ref().map(address_to_wire),
+        gender: contact.gender.map(|g| g.as_str().to_string()),
+        organization: contact.organization.as_ref().map(reference_to_wire),
+        period: period_to_wire(&contact.period),
     }
 }
 
-/// Convert wire format patient to flat domain type.
+/// Convert wire format patient to domain type.
 fn wire_to_domain(wire: PatientWire) -> Result<PatientData, FhirError> {
-    let nested = wire_to_nested(wire)?;
-    Ok(nested_to_flat(nested))
+    let id = ShardableUuid::parse(&wire.id)
+        .map_err(|e| FhirError::Translation(format!("Invalid patient ID: {e}")))?;
+
+    let birth_date = wire
+        .birth_date
+        .as_ref()
+        .map(|s| {
+            s.parse::<NaiveDate>()
+                .map_err(|e| FhirError::Translation(format!("Invalid birth date: {e}")))
+        })
+        .transpose()?;
+
+    let deceased = match (wire.deceased_boolean, wire.deceased_date_time) {
+        (Some(_), Some(_)) => {
+            return Err(FhirError::Translation(
+                "Only one of deceasedBoolean and deceasedDateTime may be present".into(),
+            ))
+        }
+        (Some(flag), None) => Some(Deceased::Boolean(flag)),
+        (None, Some(dt)) => Some(Deceased::DateTime(datetime_from_wire(
+            &dt,
+            "deceased date/time",
+        )?)),
+        (None, None) => None,
+    };
+
+    let last_updated = wire
+        .meta
+        .and_then(|m| m.last_updated)
+        .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+
+    Ok(PatientData {
+        id,
+        identifiers: wire
+            .identifier
+            .into_iter()
+            .map(identifier_from_wire)
+            .collect::<Result<Vec<_>, FhirError>>()?,
+        names: wire
+            .name
+            .into_iter()
+            .map(name_from_wire)
+            .collect::<Result<Vec<_>, FhirError>>()?,
+        telecom: wire
+            .telecom
+            .into_iter()
+            .map(contact_point_from_wire)
+            .collect::<Result<Vec<_>, FhirError>>()?,
+        gender: code_from_wire(wire.gender, AdministrativeGender::parse)?,
+        birth_date,
+        deceased,
+        addresses: wire
+            .address
+            .into_iter()
+            .map(address_from_wire)
+            .collect::<Result<Vec<_>, FhirError>>()?,
+        contacts: wire
+            .contact
+            .into_iter()
+            .map(contact_from_wire)
+            .collect::<Result<Vec<_>, FhirError>>()?,
+        general_practitioners: wire
+            .general_practitioner
+            .into_iter()
+            .map(reference_from_wire)
+            .collect::<Result<Vec<_>, FhirError>>()?,
+        last_updated,
+    })
 }
 
-/// Convert flat domain type to wire format patient.
+/// Convert domain type to wire format patient.
 fn domain_to_wire(data: &PatientData) -> PatientWire {
-    let nested = flat_to_nested(data);
-    nested_to_wire(&nested)
+    let (deceased_boolean, deceased_date_time) = match &data.deceased {
+        Some(Deceased::Boolean(flag)) => (Some(*flag), None),
+        Some(Deceased::DateTime(dt)) => (None, Some(dt.to_string())),
+        None => (None, None),
+    };
+
+    PatientWire {
+        resource_type: "Patient".to_string(),
+        id: data.id.to_string(),
+        identifier: data.identifiers.iter().map(identifier_to_wire).collect(),
+        name: data.names.iter().map(name_to_wire).collect(),
+        telecom: data.telecom.iter().map(contact_point_to_wire).collect(),
+        gender: data.gender.map(|g| g.as_str().to_string()),
+        birth_date: data.birth_date.map(|d| d.to_string()),
+        deceased_boolean,
+        deceased_date_time,
+        address: data.addresses.iter().map(address_to_wire).collect(),
+        contact: data.contacts.iter().map(contact_to_wire).collect(),
+        general_practitioner: data
+            .general_practitioners
+            .iter()
+            .map(reference_to_wire)
+            .collect(),
+        meta: data.last_updated.map(|lu| PatientMetaWire {
+            last_updated: Some(lu.to_rfc3339()),
+        }),
+    }
 }
 
 #[cfg(test)]
@@ -491,10 +1474,11 @@ id: 90a8d1ea318041d9adb070a834d4e0f6
 
         let result = Patient::parse(input).expect("should parse minimal patient");
         assert_eq!(result.id.to_string(), "90a8d1ea318041d9adb070a834d4e0f6");
-        assert!(result.use_type.is_none());
-        assert!(result.family.is_none());
-        assert!(result.given.is_empty());
+        assert!(result.names.is_empty());
+        assert!(result.identifiers.is_empty());
+        assert!(result.gender.is_none());
         assert!(result.birth_date.is_none());
+        assert!(result.deceased.is_none());
         assert!(result.last_updated.is_none());
     }
 
@@ -514,13 +1498,22 @@ name:
 "#;
 
         let result = Patient::parse(input).expect("should parse multiple names");
-        // Flat structure extracts only the first name
-        assert_eq!(result.use_type, Some(NameUse::Official));
-        assert_eq!(result.family.as_ref().map(|f| f.as_str()), Some("Williams"));
+        assert_eq!(result.names.len(), 2);
+
+        let primary = result.primary_name().expect("primary name");
+        assert_eq!(primary.use_type, Some(NameUse::Official));
         assert_eq!(
-            result.given.iter().map(|g| g.as_str()).collect::<Vec<_>>(),
+            primary.family.as_ref().map(|f| f.as_str()),
+            Some("Williams")
+        );
+        assert_eq!(
+            primary.given.iter().map(|g| g.as_str()).collect::<Vec<_>>(),
             vec!["Sarah", "Jane"]
         );
+
+        assert_eq!(result.names[1].use_type, Some(NameUse::Nickname));
+        assert!(result.names[1].family.is_none());
+        assert_eq!(result.names[1].given[0].as_str(), "Sally");
     }
 
     #[test]
@@ -560,17 +1553,18 @@ meta:
             .parse::<DateTime<Utc>>()
             .expect("valid datetime");
 
-        let data = PatientData {
-            id,
+        let mut data = PatientData::new(id);
+        data.names = vec![HumanName {
             use_type: Some(NameUse::Official),
             family: Some(NonEmptyText::new("Williams").unwrap()),
             given: vec![
                 NonEmptyText::new("Sarah").unwrap(),
                 NonEmptyText::new("Jane").unwrap(),
             ],
-            birth_date: Some(NaiveDate::from_ymd_opt(1992, 3, 20).unwrap()),
-            last_updated: Some(last_updated),
-        };
+            ..Default::default()
+        }];
+        data.birth_date = Some(NaiveDate::from_ymd_opt(1992, 3, 20).unwrap());
+        data.last_updated = Some(last_updated);
 
         let yaml = Patient::render(&data).expect("should render patient");
         assert!(yaml.contains("resourceType: Patient"));
@@ -592,14 +1586,7 @@ meta:
     fn renders_minimal_patient() {
         let id = ShardableUuid::parse("00000000000000000000000000000001").expect("valid uuid");
 
-        let data = PatientData {
-            id,
-            use_type: None,
-            family: None,
-            given: vec![],
-            birth_date: None,
-            last_updated: None,
-        };
+        let data = PatientData::new(id);
 
         let yaml = Patient::render(&data).expect("should render minimal patient");
         assert!(yaml.contains("resourceType: Patient"));
@@ -608,5 +1595,310 @@ meta:
         assert!(!yaml.contains("name:"));
         assert!(!yaml.contains("birthDate"));
         assert!(!yaml.contains("meta:"));
+        assert!(!yaml.contains("identifier:"));
+        assert!(!yaml.contains("deceased"));
+    }
+
+    const PAS_PATIENT_YAML: &str = r#"resourceType: Patient
+id: 90a8d1ea318041d9adb070a834d4e0f6
+identifier:
+  - use: official
+    system: https://fhir.nhs.uk/Id/nhs-number
+    value: "9434765919"
+  - system: https://example.nhs.uk/Id/hospital-number
+    value: RX1-123456
+name:
+  - use: official
+    family: Williams
+    given:
+      - Sarah
+    prefix:
+      - Mrs
+    period:
+      start: 2015-06-01
+  - use: maiden
+    family: Jones
+    given:
+      - Sarah
+    period:
+      end: 2015-05-31T23:59:59Z
+telecom:
+  - system: phone
+    value: 01632 960001
+    use: mobile
+    rank: 1
+  - system: email
+    value: sarah@example.com
+gender: female
+birthDate: 1992-03-20
+deceasedDateTime: 2026-01-02T03:04:05Z
+address:
+  - use: home
+    type: both
+    line:
+      - 1 High Street
+    city: Leeds
+    postalCode: LS1 1AA
+    country: GB
+contact:
+  - relationship:
+      - coding:
+          - system: http://terminology.hl7.org/CodeSystem/v2-0131
+            code: N
+            display: Next-of-Kin
+    name:
+      family: Williams
+      given:
+        - Tom
+    telecom:
+      - system: phone
+        value: 01632 960002
+    gender: male
+generalPractitioner:
+  - reference: Organization/B82005
+    identifier:
+      system: https://fhir.nhs.uk/Id/ods-organization-code
+      value: B82005
+    display: Example Surgery
+meta:
+  lastUpdated: 2026-01-23T13:58:04.099304Z
+"#;
+
+    #[test]
+    fn round_trips_full_pas_patient() {
+        let patient_data = Patient::parse(PAS_PATIENT_YAML).expect("parse yaml");
+
+        assert_eq!(
+            patient_data.nhs_number().map(|n| n.as_str()),
+            Some("9434765919")
+        );
+        assert_eq!(patient_data.identifiers.len(), 2);
+        assert_eq!(patient_data.gender, Some(AdministrativeGender::Female));
+        assert_eq!(patient_data.names.len(), 2);
+        assert_eq!(
+            patient_data.names[0].period.as_ref().and_then(|p| p.start),
+            Some(FhirDateTime::Date(
+                NaiveDate::from_ymd_opt(2015, 6, 1).unwrap()
+            ))
+        );
+        assert_eq!(patient_data.names[1].use_type, Some(NameUse::Maiden));
+        assert_eq!(
+            patient_data.telecom[0].system,
+            Some(ContactPointSystem::Phone)
+        );
+        assert_eq!(
+            patient_data.telecom[0].use_type,
+            Some(ContactPointUse::Mobile)
+        );
+        assert_eq!(patient_data.telecom[0].rank, Some(1));
+        assert_eq!(
+            patient_data.addresses[0]
+                .postal_code
+                .as_ref()
+                .map(|p| p.as_str()),
+            Some("LS1 1AA")
+        );
+        assert!(patient_data
+            .deceased
+            .as_ref()
+            .is_some_and(Deceased::is_deceased));
+        assert_eq!(
+            patient_data.contacts[0].gender,
+            Some(AdministrativeGender::Male)
+        );
+        assert_eq!(
+            patient_data.contacts[0].relationship[0].coding[0]
+                .code
+                .as_ref()
+                .map(|c| c.as_str()),
+            Some("N")
+        );
+        assert_eq!(
+            patient_data.general_practitioners[0]
+                .identifier
+                .as_ref()
+                .map(|i| i.value.as_str()),
+            Some("B82005")
+        );
+
+        let output = Patient::render(&patient_data).expect("render patient");
+        assert!(output.contains("generalPractitioner:"));
+        assert!(output.contains("deceasedDateTime:"));
+        assert!(output.contains("postalCode: LS1 1AA"));
+        let reparsed = Patient::parse(&output).expect("reparse yaml");
+        assert_eq!(patient_data, reparsed);
+    }
+
+    #[test]
+    fn primary_name_skips_ended_official_name() {
+        let input = r#"resourceType: Patient
+id: 90a8d1ea318041d9adb070a834d4e0f6
+name:
+  - use: official
+    family: Jones
+    period:
+      end: 2015-05-31
+  - use: official
+    family: Williams
+    period:
+      start: 2015-06-01
+"#;
+
+        let result = Patient::parse(input).expect("should parse names");
+        let primary = result.primary_name().expect("primary name");
+        assert_eq!(
+            primary.family.as_ref().map(|f| f.as_str()),
+            Some("Williams")
+        );
+    }
+
+    #[test]
+    fn round_trips_each_date_time_precision() {
+        let input = r#"resourceType: Patient
+id: 90a8d1ea318041d9adb070a834d4e0f6
+identifier:
+- value: RX1-123456
+  period:
+    start: '2020'
+    end: 2021-02
+name:
+- family: Williams
+  period:
+    start: 2020-01-01
+    end: 2020-06-30T17:00:00+01:00
+deceasedDateTime: 2026-01-02T03:04:05.250Z
+"#;
+
+        let result = Patient::parse(input).expect("should parse every precision");
+        let identifier_period = result.identifiers[0].period.as_ref().unwrap();
+        assert_eq!(identifier_period.start, Some(FhirDateTime::Year(2020)));
+        assert_eq!(
+            identifier_period.end,
+            Some(FhirDateTime::YearMonth(2021, 2))
+        );
+        let name_period = result.names[0].period.as_ref().unwrap();
+        assert_eq!(
+            name_period.start,
+            Some(FhirDateTime::Date(
+                NaiveDate::from_ymd_opt(2020, 1, 1).unwrap()
+            ))
+        );
+        assert!(matches!(name_period.end, Some(FhirDateTime::DateTime(_))));
+
+        let yaml = Patient::render(&result).expect("should render patient");
+        for value in [
+            "'2020'",
+            "2021-02",
+            "2020-01-01",
+            "2020-06-30T17:00:00+01:00",
+            "2026-01-02T03:04:05.250Z",
+        ] {
+            assert!(yaml.contains(value), "{value} missing from:\n{yaml}");
+        }
+        assert!(!yaml.contains("T00:00:00"));
+        assert_eq!(Patient::parse(&yaml).expect("should reparse"), result);
+    }
+
+    #[test]
+    fn date_time_values_render_as_parsed() {
+        for value in [
+            "2020",
+            "2020-01",
+            "2020-01-01",
+            "2020-01-01T09:30:00Z",
+            "2020-01-01T09:30:00-05:00",
+            "2020-01-01T09:30:00.123456Z",
+        ] {
+            let parsed = FhirDateTime::parse(value).expect("valid dateTime");
+            assert_eq!(parsed.to_string(), value);
+        }
+
+        for value in [
+            "",
+            "20",
+            "2020-13",
+            "2020-02-30",
+            "2020-01-01T09:30",
+            "2020/01",
+        ] {
+            assert!(FhirDateTime::parse(value).is_err(), "accepted {value}");
+        }
+    }
+
+    #[test]
+    fn period_bounds_cover_their_whole_extent() {
+        let at = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        let period = |start: &str, end: &str| Period {
+            start: Some(FhirDateTime::parse(start).unwrap()),
+            end: Some(FhirDateTime::parse(end).unwrap()),
+        };
+
+        let dates = period("2020-01-01", "2020-01-31");
+        assert!(dates.contains(at("2020-01-01T00:00:00Z")));
+        assert!(dates.contains(at("2020-01-31T23:59:59Z")));
+        assert!(!dates.contains(at("2020-02-01T00:00:00Z")));
+
+        let months = period("2019-12", "2020-02");
+        assert!(months.contains(at("2019-12-01T00:00:00Z")));
+        assert!(months.contains(at("2020-02-29T12:00:00Z")));
+        assert!(!months.contains(at("2020-03-01T00:00:00Z")));
+
+        let years = period("2019", "2019");
+        assert!(years.contains(at("2019-12-31T23:59:59Z")));
+        assert!(!years.contains(at("2018-12-31T23:59:59Z")));
+        assert!(!years.contains(at("2020-01-01T00:00:00Z")));
+    }
+
+    #[test]
+    fn round_trips_deceased_boolean() {
+        let input = r#"resourceType: Patient
+id: 90a8d1ea318041d9adb070a834d4e0f6
+deceasedBoolean: false
+"#;
+
+        let result = Patient::parse(input).expect("should parse deceasedBoolean");
+        assert_eq!(result.deceased, Some(Deceased::Boolean(false)));
+
+        let yaml = Patient::render(&result).expect("should render patient");
+        assert!(yaml.contains("deceasedBoolean: false"));
+        assert!(!yaml.contains("deceasedDateTime"));
+    }
+
+    #[test]
+    fn rejects_both_deceased_variants() {
+        let input = r#"resourceType: Patient
+id: 90a8d1ea318041d9adb070a834d4e0f6
+deceasedBoolean: true
+deceasedDateTime: 2026-01-02T03:04:05Z
+"#;
+
+        let err = Patient::parse(input).expect_err("should reject both deceased variants");
+        match err {
+            FhirError::Translation(msg) => assert!(msg.contains("deceased")),
+            other => panic!("expected Translation error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_codes() {
+        let input = r#"resourceType: Patient
+id: 90a8d1ea318041d9adb070a834d4e0f6
+gender: not-a-gender
+"#;
+
+        let err = Patient::parse(input).expect_err("should reject unknown gender");
+        match err {
+            FhirError::Translation(msg) => assert!(msg.contains("not-a-gender")),
+            other => panic!("expected Translation error, got {other:?}"),
+        }
+
+        let input = r#"resourceType: Patient
+id: 90a8d1ea318041d9adb070a834d4e0f6
+telecom:
+  - system: pigeon
+    value: "123"
+"#;
+
+        assert!(Patient::parse(input).is_err());
     }
 }
@@ -345,6 +345,67 @@ impl FilesService {
         })
     }
 
+    /// Copies a stored file into another repository's content-addressed storage
+    ///
+    /// This is used when content is relocated between repositories (for example when a
+    /// redacted attachment is moved into the Redaction Retention Repository). If the
+    /// target already holds a file with the same hash, the copy is skipped because the
+    /// content is identical by construction.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The SHA-256 hash (hexadecimal string) of the file to copy
+    /// * `target` - The service for the repository that should receive the file
+    ///
+    /// # Errors
+    ///
+    /// Returns `FilesError` if:
+    /// - The file does not exist in this repository's storage
+    /// - The file cannot be written to the target storage (I/O)
+    pub fn copy_to(&self, hash: &str, target: &FilesService) -> Result<(), FilesError> {
+        let storage_path = self.compute_storage_path(hash);
+
+        if !storage_path.exists() {
+            return Err(FilesError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("File not found for hash: {}", hash),
+            )));
+        }
+
+        match target.add(&storage_path) {
+            Ok(_) | Err(FilesError::FileAlreadyExists(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Removes a file from content-addressed storage
+    ///
+    /// Stored files are never modified, but they may be relocated out of a repository
+    /// (see [`Self::copy_to`]). Callers are responsible for ensuring that nothing in the
+    /// repository still references the hash, since identical content is stored once.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The SHA-256 hash (hexadecimal string) of the file to remove
+    ///
+    /// # Errors
+    ///
+    /// Returns `FilesError` if the file does not exist or cannot be removed (I/O).
+    pub fn remove(&self, hash: &str) -> Result<(), FilesError> {
+        let storage_path = self.compute_storage_path(hash);
+
+        fs::remove_file(&storage_path).map_err(|e| {
+            FilesError::Io(std::io::Error::new(
+                e.kind(),
+                format!(
+                    "Failed to remove file from {}: {}",
+                    storage_path.display(),
+                    e
+                ),
+            ))
+        })
+    }
+
     /// Computes the sharded storage path for a given hash
     ///
     /// This method constructs the absolute storage path by combining the repository root
@@ -948,4 +1009,35 @@ mod tests {
             assert_eq!(retrieved, content, "Round-trip failed for {}", filename);
         }
     }
+
+    #[test]
+    fn test_copy_to_and_remove() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("repositories");
+        fs::create_dir_all(&root).unwrap();
+
+        let source_uuid = ShardableUuid::new();
+        let target_uuid = ShardableUuid::new();
+        create_test_repo(&root, &source_uuid);
+        create_test_repo(&root, &target_uuid);
+
+        let source = FilesService::new(&root, source_uuid).unwrap();
+        let target = FilesService::new(&root, target_uuid).unwrap();
+
+        let source_file = temp.path().join("scan.pdf");
+        fs::write(&source_file, b"scanned letter").unwrap();
+        let metadata = source.add(&source_file).unwrap();
+        let hash = metadata.hash.as_str();
+
+        source.copy_to(hash, &target).unwrap();
+        // Copying identical content again is a no-op rather than an immutability error.
+        source.copy_to(hash, &target).unwrap();
+        assert_eq!(target.read(hash).unwrap(), b"scanned letter");
+
+        source.remove(hash).unwrap();
+        assert!(source.read(hash).is_err());
+        assert!(source.copy_to(hash, &target).is_err());
+        assert!(source.remove(hash).is_err());
+        assert_eq!(target.read(hash).unwrap(), b"scanned letter");
+    }
 }
@@ -24,6 +24,11 @@ use serde::{Deserialize, Serialize};
 /// - Attachments (external files) via `attachments` vector
 /// - Both body AND attachments
 /// - But never neither (at least one must be present)
+///
+/// # Versions
+///
+/// A letter can be superseded by a new version. The two compositions link to each
+/// other through `supersedes` and `superseded_by`, forming a version chain.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct LetterData {
     /// RM version for this letter.
@@ -51,6 +56,12 @@ pub struct LetterData {
     /// Attachment references. When present, these generate
     /// external_media narratives pointing to attachment metadata files.
     pub attachments: Vec<AttachmentReference>,
+
+    /// The earlier letter version this letter replaces, if any.
+    pub supersedes: Option<TimestampId>,
+
+    /// The later letter version that replaces this letter, if any.
+    pub superseded_by: Option<TimestampId>,
 }
 
 /// Reference to an attachment file in a letter composition.
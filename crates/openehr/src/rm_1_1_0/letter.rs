@@ -36,6 +36,8 @@ struct Composition {
     pub composer: Composer,
     pub context: Context,
     pub content: Vec<ContentItem>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<Link>,
 }
 
 /// Returns the archetype node ID for the letter Composition.
@@ -65,6 +67,51 @@ struct Context {
     pub start_time: DateTime<Utc>,
 }
 
+/// RM `LINK` representation, used to connect versions of a letter.
+///
+/// The target is the `uid` of the linked composition.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct Link {
+    pub meaning: DvText,
+    #[serde(rename = "type")]
+    pub type_: DvText,
+    pub target: String,
+}
+
+/// Link type for links between versions of the same letter.
+const LINK_TYPE_VERSION: &str = "version";
+
+/// Link meaning: this composition replaces the target.
+const LINK_MEANING_SUPERSEDES: &str = "supersedes";
+
+/// Link meaning: this composition is replaced by the target.
+const LINK_MEANING_SUPERSEDED_BY: &str = "superseded_by";
+
+impl Link {
+    fn version(meaning: &str, target: &TimestampId) -> Self {
+        Link {
+            meaning: DvText {
+                value: NonEmptyText::new(meaning).expect("link meaning is non-empty"),
+            },
+            type_: DvText {
+                value: NonEmptyText::new(LINK_TYPE_VERSION).expect("link type is non-empty"),
+            },
+            target: target.to_string(),
+        }
+    }
+}
+
+/// Returns the target of the version link with the given meaning, if present.
+fn version_link_target(links: &[Link], meaning: &str) -> Option<TimestampId> {
+    links
+        .iter()
+        .find(|link| {
+            link.type_.value.as_str() == LINK_TYPE_VERSION && link.meaning.value.as_str() == meaning
+        })
+        .and_then(|link| link.target.parse().ok())
+}
+
 /// Content item wrapper (can be a section).
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
@@ -342,6 +389,8 @@ impl From<Composition> for LetterData {
             clinical_lists,
             has_body,
             attachments,
+            supersedes: version_link_target(&comp.links, LINK_MEANING_SUPERSEDES),
+            superseded_by: version_link_target(&comp.links, LINK_MEANING_SUPERSEDED_BY),
         }
     }
 }
@@ -373,6 +422,8 @@ impl From<Composition> for LetterData {
 ///     start_time: Utc::now(),
 ///     clinical_lists: vec![],
 ///     attachments: vec![],
+///     supersedes: None,
+///     superseded_by: None,
 /// };
 ///
 /// let composition: Composition = (&letter_data).into();
@@ -380,7 +431,7 @@ impl From<Composition> for LetterData {
 /// ```
 impl From<&LetterData> for Composition {
     fn from(data: &LetterData) -> Self {
-        letter_init(
+        let mut composition = letter_init(
             data.rm_version.as_str(),
             &data.uid.to_string(),
             &data.composer_name,
@@ -392,7 +443,20 @@ impl From<&LetterData> for Composition {
                 attachments: &data.attachments,
             },
         )
-        .expect("letter_init should not fail with valid LetterData")
+        .expect("letter_init should not fail with valid LetterData");
+
+        composition.links = data
+            .supersedes
+            .iter()
+            .map(|id| Link::version(LINK_MEANING_SUPERSEDES, id))
+            .chain(
+                data.superseded_by
+                    .iter()
+                    .map(|id| Link::version(LINK_MEANING_SUPERSEDED_BY, id)),
+            )
+            .collect();
+
+        composition
     }
 }
 
@@ -419,7 +483,19 @@ pub fn composition_parse(yaml_text: &str) -> Result<LetterData, OpenEhrError> {
     let deserializer = serde_yaml::Deserializer::from_str(yaml_text);
 
     match serde_path_to_error::deserialize::<_, Composition>(deserializer) {
-        Ok(parsed) => Ok(LetterData::from(parsed)),
+        Ok(parsed) => {
+            if let Some(link) = parsed
+                .links
+                .iter()
+                .find(|link| link.target.parse::<TimestampId>().is_err())
+            {
+                return Err(OpenEhrError::Translation(format!(
+                    "Composition link target is not a valid letter uid: {}",
+                    link.target
+                )));
+            }
+            Ok(LetterData::from(parsed))
+        }
         Err(err) => {
             let path = err.path().to_string();
             let source = err.into_inner();
@@ -557,6 +633,7 @@ fn letter_init(
             role: composer_role.to_string(),
         },
         context: Context { start_time },
+        links: Vec::new(),
         content: vec![ContentItem {
             section: Section {
                 archetype_node_id: section_archetype_node_id().to_string(),
@@ -884,6 +961,8 @@ content:
             clinical_lists: vec![],
             has_body: true,
             attachments: vec![],
+            supersedes: None,
+            superseded_by: None,
         };
 
         let result_yaml =
@@ -926,6 +1005,8 @@ content:
                     path: "./attachments/attachment_2.yaml".to_string(),
                 },
             ],
+            supersedes: None,
+            superseded_by: None,
         };
 
         let yaml_string = composition_render(&letter_data).expect("composition_render should work");
@@ -990,6 +1071,8 @@ content:
             attachments: vec![crate::public_structs::letter::AttachmentReference {
                 path: "./attachments/attachment_1.yaml".to_string(),
             }],
+            supersedes: None,
+            superseded_by: None,
         };
 
         let yaml_string = composition_render(&letter_data).expect("composition_render should work");
@@ -1024,4 +1107,50 @@ content:
             "./attachments/attachment_1.yaml"
         );
     }
+
+    #[test]
+    fn letter_version_links_round_trip() {
+        let start_time = DateTime::parse_from_rfc3339("2026-01-12T10:00:00Z")
+            .expect("valid datetime")
+            .with_timezone(&Utc);
+        let previous: TimestampId = "20260112T100000.000Z-00000000-0000-0000-0000-000000000001"
+            .parse()
+            .expect("valid TimestampId");
+        let next: TimestampId = "20260112T110000.000Z-00000000-0000-0000-0000-000000000003"
+            .parse()
+            .expect("valid TimestampId");
+
+        let letter_data = LetterData {
+            rm_version: RmVersion::rm_1_1_0,
+            uid: "20260112T103000.000Z-00000000-0000-0000-0000-000000000002"
+                .parse()
+                .expect("valid TimestampId"),
+            composer_name: "Dr Test".to_string(),
+            composer_role: "Test Role".to_string(),
+            start_time,
+            clinical_lists: vec![],
+            has_body: true,
+            attachments: vec![],
+            supersedes: Some(previous.clone()),
+            superseded_by: Some(next.clone()),
+        };
+
+        let yaml_string = composition_render(&letter_data).expect("composition_render should work");
+        assert!(yaml_string.contains("links:"));
+        assert!(yaml_string.contains("value: supersedes"));
+        assert!(yaml_string.contains("value: superseded_by"));
+
+        let reparsed = composition_parse(&yaml_string).expect("should parse the generated YAML");
+        assert_eq!(reparsed.supersedes, Some(previous));
+        assert_eq!(reparsed.superseded_by, Some(next));
+
+        let invalid = yaml_string.replace(
+            "20260112T100000.000Z-00000000-0000-0000-0000-000000000001",
+            "not-a-letter",
+        );
+        assert!(matches!(
+            composition_parse(&invalid),
+            Err(OpenEhrError::Translation(_))
+        ));
+    }
 }
@@ -634,6 +634,27 @@ impl fmt::Display for TimestampId {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for TimestampId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TimestampId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 /// Generator for creating [`TimestampId`] values with monotonicity guarantees.
 ///
 /// `TimestampIdGenerator` is a stateless utility that encapsulates the clock access
@@ -1191,6 +1212,24 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_timestamp_id_serde_roundtrip() {
+        let id: TimestampId = "20260113T143522.045Z-550e8400-e29b-41d4-a716-446655440000"
+            .parse()
+            .unwrap();
+
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(
+            json,
+            "\"20260113T143522.045Z-550e8400-e29b-41d4-a716-446655440000\""
+        );
+
+        let deserialized: TimestampId = serde_json::from_str(&json).unwrap();
+        assert_eq!(id, deserialized);
+        assert!(serde_json::from_str::<TimestampId>("\"not-a-timestamp-id\"").is_err());
+    }
+
     #[test]
     fn test_debug_format() {
         let uuid = ShardableUuid::parse("550e8400e29b41d4a716446655440000").unwrap();
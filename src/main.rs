@@ -9,8 +9,9 @@
 
 use axum::{
     Router,
-    extract::State,
+    extract::{Extension, State},
     http::StatusCode,
+    middleware,
     response::Json,
     routing::{get, post},
 };
@@ -21,15 +22,27 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
-use api_grpc::{VprService, auth_interceptor};
+use api_grpc::{VprService, auth_interceptor, server_tls_config};
+use api_rest::auth::{
+    author_from_certificate, author_from_identity, authorize, require_authentication,
+};
+use api_rest::tls::serve_tls;
+use api_rest::write::{ApiError, blocking_write, write_error};
 use api_shared::HealthService;
+use api_shared::auth::{Principal, api_key_identity};
 use api_shared::pb;
 use api_shared::pb::vpr_server::VprServer;
+use api_shared::policy::{AccessPolicy, Operation};
+use api_shared::tls::TlsSettings;
 use std::path::Path;
 use std::sync::Arc;
 use vpr_core::{
-    Author, AuthorRegistration, CoreConfig, EmailAddress, NonEmptyText,
-    config::rm_system_version_from_env_value,
+    Author, CoreConfig, NonEmptyText, PatientService,
+    config::{
+        mirror_root_from_env_value, revocation_list_from_env_value,
+        rm_system_version_from_env_value, timestamp_authority_from_env_value,
+        trust_anchors_from_env_value,
+    },
     repositories::clinical::ClinicalService,
     repositories::demographics::{DemographicsService, Uninitialised as DemographicsUninitialised},
 };
@@ -47,6 +60,7 @@ type Patient = pb::Patient;
 #[derive(Clone)]
 struct AppState {
     cfg: Arc<CoreConfig>,
+    policy: Arc<AccessPolicy>,
     demographics_service: Arc<DemographicsService<DemographicsUninitialised>>,
 }
 
@@ -69,14 +83,29 @@ struct ApiDoc;
 /// - gRPC server on port 50051 (configurable via VPR_ADDR)
 /// - REST server on port 3000 (configurable via VPR_REST_ADDR)
 ///
-/// The gRPC server requires authentication via x-api-key header.
-/// The REST server provides open access to patient operations.
+/// Both servers require authentication via a bearer token, a verified client certificate or
+/// the x-api-key header. On the REST server, only `/health` and the Swagger UI are reachable
+/// without credentials. When TLS is configured, both servers use the same certificate and
+/// client CA bundle.
 ///
 /// # Environment Variables
 /// - `VPR_ADDR`: gRPC server address (default: "0.0.0.0:50051")
 /// - `VPR_REST_ADDR`: REST server address (default: "0.0.0.0:3000")
 /// - `PATIENT_DATA_DIR`: Directory for patient data storage (default: "/patient_data")
-/// - `API_KEY`: API key for gRPC authentication
+/// - `API_KEY`: API key for gRPC and REST authentication
+/// - `ACCESS_POLICY_PATH`: JSON file overriding the default role rules (optional)
+/// - `JWT_JWKS_PATH` / `JWT_PUBLIC_KEY_PATH`: Keys that verify bearer tokens (optional)
+/// - `JWT_ISSUER` / `JWT_AUDIENCE`: Required bearer token `iss` / `aud` claims (optional)
+/// - `VPR_TLS_CERT_PATH` / `VPR_TLS_KEY_PATH`: Server certificate and key; enables TLS (optional)
+/// - `VPR_TLS_CLIENT_CA_PATH`: CA bundle for client certificates; enables mutual TLS (optional)
+/// - `VPR_TLS_CLIENT_AUTH_REQUIRED`: Refuse clients without a certificate (default: "false")
+/// - `VPR_TRUST_ANCHORS_PATH`: CA bundle that commit signing certificates must chain to (optional)
+/// - `VPR_REVOCATION_CRL_PATH`: CRL of revoked commit signing certificates (optional)
+/// - `VPR_TSA_URL`: RFC 3161 timestamp authority that timestamps every commit (optional)
+/// - `VPR_TSA_TRUST_ANCHORS_PATH`: CA bundle that timestamp authority certificates must chain to
+///   (optional)
+/// - `VPR_MIRROR_URL`: Directory or `file://` URL of the bare repositories every commit is
+///   pushed to (optional)
 ///
 /// # Returns
 /// * `Ok(())` - If servers start and run successfully
@@ -129,17 +158,69 @@ async fn main() -> anyhow::Result<()> {
         .and_then(|s| vpr_core::NonEmptyText::new(s).ok())
         .unwrap_or_else(|| vpr_core::NonEmptyText::new("vpr.dev.1").unwrap());
 
-    let cfg = Arc::new(
-        CoreConfig::new(
-            patient_data_path.to_path_buf(),
-            rm_system_version,
-            vpr_namespace,
-        )
+    let mut cfg = CoreConfig::new(
+        patient_data_path.to_path_buf(),
+        rm_system_version,
+        vpr_namespace,
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("Error: Invalid core configuration ({})", e);
+        std::process::exit(1);
+    });
+    let trust_anchors =
+        trust_anchors_from_env_value(std::env::var("VPR_TRUST_ANCHORS_PATH").ok().as_deref())
+            .unwrap_or_else(|e| {
+                eprintln!("Error: Invalid trust anchors ({})", e);
+                std::process::exit(1);
+            });
+    if let Some(anchors) = trust_anchors {
+        cfg = cfg.with_trust_anchors(anchors);
+    }
+    let revocations =
+        revocation_list_from_env_value(std::env::var("VPR_REVOCATION_CRL_PATH").ok().as_deref())
+            .unwrap_or_else(|e| {
+                eprintln!("Error: Invalid revocation list ({})", e);
+                std::process::exit(1);
+            });
+    if let Some(revocations) = revocations {
+        cfg = cfg.with_revocation_list(revocations);
+    }
+    let tsa = timestamp_authority_from_env_value(std::env::var("VPR_TSA_URL").ok().as_deref())
+        .unwrap_or_else(|e| {
+            eprintln!("Error: Invalid timestamp authority ({})", e);
+            std::process::exit(1);
+        });
+    if let Some(tsa) = tsa {
+        cfg = cfg.with_timestamp_authority(tsa);
+    }
+    let tsa_anchors =
+        trust_anchors_from_env_value(std::env::var("VPR_TSA_TRUST_ANCHORS_PATH").ok().as_deref())
+            .unwrap_or_else(|e| {
+                eprintln!("Error: Invalid timestamp trust anchors ({})", e);
+                std::process::exit(1);
+            });
+    if let Some(anchors) = tsa_anchors {
+        cfg = cfg.with_timestamp_trust_anchors(anchors);
+    }
+    let mirror_root = mirror_root_from_env_value(std::env::var("VPR_MIRROR_URL").ok().as_deref())
         .unwrap_or_else(|e| {
-            eprintln!("Error: Invalid core configuration ({})", e);
+            eprintln!("Error: Invalid mirror location ({})", e);
             std::process::exit(1);
-        }),
-    );
+        });
+    if let Some(mirror_root) = mirror_root {
+        cfg = cfg.with_mirror(mirror_root);
+    }
+    let cfg = Arc::new(cfg);
+
+    let policy = Arc::new(AccessPolicy::from_env().unwrap_or_else(|e| {
+        eprintln!("Error: Invalid access policy ({})", e);
+        std::process::exit(1);
+    }));
+
+    let tls = TlsSettings::from_env().unwrap_or_else(|e| {
+        eprintln!("Error: Invalid TLS configuration ({})", e);
+        std::process::exit(1);
+    });
 
     // Ensure clinical subdirectory exists
     let clinical_dir = cfg.patient_data_dir().join("clinical");
@@ -168,6 +249,19 @@ async fn main() -> anyhow::Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    // Finish or discard full records whose creation was interrupted by a crash.
+    match PatientService::new(cfg.clone()).recover_interrupted_records() {
+        Ok(report) if !report.completed.is_empty() || !report.rolled_back.is_empty() => {
+            tracing::info!(
+                "Recovered interrupted records: {} completed, {} rolled back",
+                report.completed.len(),
+                report.rolled_back.len()
+            );
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to recover interrupted records: {}", e),
+    }
+
     let grpc_addr: SocketAddr = std::env::var("VPR_ADDR")
         .unwrap_or_else(|_| "0.0.0.0:50051".into())
         .parse()?;
@@ -179,6 +273,7 @@ async fn main() -> anyhow::Result<()> {
     // Start REST server
     let rest_state = AppState {
         cfg: cfg.clone(),
+        policy: policy.clone(),
         demographics_service: Arc::new(DemographicsService::new(cfg.clone())),
     };
 
@@ -187,18 +282,28 @@ async fn main() -> anyhow::Result<()> {
         .route("/patients", get(list_patients))
         .route("/patients", post(create_patient))
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
+        .layer(middleware::from_fn(require_authentication))
         .layer(CorsLayer::permissive())
         .with_state(rest_state);
 
+    let rest_tls = tls.clone();
     let rest_server = tokio::spawn(async move {
         let listener = tokio::net::TcpListener::bind(&rest_addr).await.unwrap();
-        axum::serve(listener, rest_app).await.unwrap();
+        match rest_tls {
+            Some(tls) => serve_tls(listener, rest_app, &tls).await.unwrap(),
+            None => axum::serve(listener, rest_app).await.unwrap(),
+        }
     });
 
     // Start gRPC server
-    let grpc_server = Server::builder()
+    let mut grpc_builder = Server::builder();
+    if let Some(tls) = &tls {
+        grpc_builder = grpc_builder.tls_config(server_tls_config(tls))?;
+        tracing::info!("++ TLS enabled for gRPC and REST");
+    }
+    let grpc_server = grpc_builder
         .add_service(VprServer::with_interceptor(
-            VprService::new(cfg),
+            VprService::new(cfg, policy.clone()),
             auth_interceptor,
         ))
         .serve(grpc_addr);
@@ -247,7 +352,10 @@ async fn health(State(_state): State<AppState>) -> Json<HealthRes> {
 /// * `Err((StatusCode, &str))` - Internal server error if listing fails
 async fn list_patients(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
 ) -> Result<Json<ListPatientsRes>, (StatusCode, &'static str)> {
+    authorize(&state.policy, &principal, Operation::ListPatients)?;
+
     let patients = state.demographics_service.list_patients();
     Ok(Json(ListPatientsRes { patients }))
 }
@@ -259,7 +367,8 @@ async fn list_patients(
     responses(
         (status = 201, description = "Patient created", body = CreatePatientRes),
         (status = 400, description = "Bad request"),
-        (status = 500, description = "Internal server error")
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Record busy, retry later")
     )
 )]
 /// Create a new patient record
@@ -274,46 +383,24 @@ async fn list_patients(
 ///
 /// # Returns
 /// * `Ok(Json<CreatePatientRes>)` - Initialised clinical with generated UUID
-/// * `Err((StatusCode, &str))` - Internal server error
+/// * `Err(ApiError)` - Error response
 ///
 /// # Errors
-/// Returns `500 Internal Server Error` if initialisation fails.
+/// Returns `503 Service Unavailable` if the repository write lock could not be taken in time,
+/// or `500 Internal Server Error` if initialisation fails.
 async fn create_patient(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Json(req): Json<CreatePatientReq>,
-) -> Result<Json<CreatePatientRes>, (StatusCode, &'static str)> {
-    let registrations: Vec<AuthorRegistration> = req
-        .author_registrations
-        .into_iter()
-        .map(|r| {
-            AuthorRegistration::new(r.authority, r.number).expect("valid registration from request")
-        })
-        .collect();
-
-    let name = NonEmptyText::new(&req.author_name)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid author name"))?;
-    let role = NonEmptyText::new(&req.author_role)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid author role"))?;
-    let email = EmailAddress::parse(&req.author_email)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid author email"))?;
-
-    let author = Author {
-        name,
-        role,
-        email,
-        registrations,
-        signature: if req.author_signature.is_empty() {
-            None
-        } else {
-            Some(req.author_signature.into_bytes())
-        },
-        certificate: None,
-    };
+) -> Result<Json<CreatePatientRes>, ApiError> {
+    authorize(&state.policy, &principal, Operation::CreateRecord)?;
+
+    let author = request_author(&principal, &req)?;
     let care_location = NonEmptyText::new(&req.care_location)
         .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid care_location"))?;
 
     let clinical_service = ClinicalService::new(state.cfg.clone());
-    match clinical_service.initialise(author, care_location) {
+    match blocking_write(move || clinical_service.initialise(author, care_location)).await? {
         Ok(service) => {
             let resp = CreatePatientRes {
                 filename: "".to_string(),
@@ -323,13 +410,63 @@ async fn create_patient(
                     last_name: "".to_string(),
                     created_at: "".to_string(),
                     national_id: "".to_string(),
+                    ..Default::default()
                 }),
             };
             Ok(Json(resp))
         }
-        Err(e) => {
-            tracing::error!("Initialise clinical error: {:?}", e);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal error"))
+        Err(e) => Err(write_error("Initialise clinical", e)),
+    }
+}
+
+/// Builds the commit author for a create-patient request.
+///
+/// Bearer-token callers are recorded as the user named in the token, certificate callers under
+/// the name and registration in their certificate, and API-key callers as the fixed service
+/// identity from [`api_key_identity`]. The author name and registrations in the body are never
+/// used.
+fn request_author(
+    principal: &Principal,
+    req: &CreatePatientReq,
+) -> Result<Author, (StatusCode, &'static str)> {
+    let signature = if req.author_signature.is_empty() {
+        None
+    } else {
+        Some(req.author_signature.clone().into_bytes())
+    };
+
+    match principal {
+        Principal::User(identity) => author_from_identity(identity, signature),
+        Principal::ApiKey => author_from_identity(&api_key_identity(), signature),
+        Principal::Certificate(identity) => {
+            author_from_certificate(identity, &req.author_role, &req.author_email, signature)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_key_author_ignores_body_author_fields() {
+        let req = CreatePatientReq {
+            author_name: "Mallory Spoof".into(),
+            author_email: "mallory@example.com".into(),
+            author_role: "Clinician".into(),
+            author_registrations: vec![pb::AuthorRegistration {
+                authority: "GMC".into(),
+                number: "".into(),
+            }],
+            care_location: "Ward 1".into(),
+            ..Default::default()
+        };
+
+        let author = request_author(&Principal::ApiKey, &req).expect("author");
+        let service = api_key_identity();
+        assert_eq!(author.name.as_str(), service.name);
+        assert_eq!(author.role.as_str(), service.role);
+        assert_eq!(author.email.as_str(), service.email);
+        assert!(author.registrations.is_empty());
+    }
+}